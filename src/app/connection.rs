@@ -154,6 +154,7 @@ impl Ultimate64Browser {
                     )));
                 }
                 self.status = status;
+                self.observe_firmware_update();
             }
             Err(e) => {
                 if self.remote_browser.is_transferring() {
@@ -14,7 +14,7 @@ impl Ultimate64Browser {
         // If a drop is already pending or one is in flight, ignore
         // the new one — the user can re-drop after dismissing the
         // dialog. Avoids the dialog stacking on accidental drops.
-        if self.pending_drop.is_none() && !self.drop_in_flight {
+        if self.pending_drop.is_none() && self.pending_drop_crt.is_none() && !self.drop_in_flight {
             log::info!("File dropped: {}", path.display());
             self.pending_drop = Some(path);
         }
@@ -28,6 +28,24 @@ impl Ultimate64Browser {
 
     pub(crate) fn handle_drop_action(&mut self, action: DropAction) -> Task<Message> {
         self.pending_drop = None;
+        // Cartridges are checked first; one with warnings waits for the
+        // user to confirm, like Run in the file browser.
+        if let DropAction::RunOnDevice {
+            path,
+            runner: "run_crt",
+        } = action
+        {
+            self.user_message = Some(UserMessage::Info("Checking cartridge…".into()));
+            return Task::perform(
+                crate::file_browser::check_crt_async(path.clone()),
+                move |result| Message::DropCrtChecked(path.clone(), result),
+            );
+        }
+        self.start_drop_action(action)
+    }
+
+    /// Run a drop action; cartridges arrive here once they have been checked.
+    fn start_drop_action(&mut self, action: DropAction) -> Task<Message> {
         self.user_message = Some(UserMessage::Info(format!("{}…", action.status_label())));
         let host_url = format!("http://{}", self.settings.connection.host);
         let password = self.settings.connection.password.clone();
@@ -43,6 +61,7 @@ impl Ultimate64Browser {
                     let bytes = tokio::fs::read(&path)
                         .await
                         .map_err(|e| format!("Read failed: {}", e))?;
+                    let bytes = crate::pc64::runnable_payload(&path, bytes)?;
                     tokio::time::timeout(
                         std::time::Duration::from_secs(30),
                         api::upload_runner_async(&host_url, runner, bytes, password.as_deref()),
@@ -150,6 +169,49 @@ impl Ultimate64Browser {
         }
     }
 
+    pub(crate) fn handle_drop_crt_checked(
+        &mut self,
+        path: PathBuf,
+        result: Result<Vec<String>, String>,
+    ) -> Task<Message> {
+        match result {
+            Ok(warnings) if warnings.is_empty() => self.run_dropped_crt(path),
+            Ok(warnings) => {
+                self.user_message = None;
+                self.pending_drop_crt = Some((path, warnings));
+                Task::none()
+            }
+            Err(e) => {
+                self.user_message = Some(UserMessage::Error(format!(
+                    "Not sending {}: {}",
+                    path.file_name().unwrap_or_default().to_string_lossy(),
+                    e
+                )));
+                Task::none()
+            }
+        }
+    }
+
+    pub(crate) fn handle_drop_confirm_crt(&mut self) -> Task<Message> {
+        match self.pending_drop_crt.take() {
+            Some((path, _)) => self.run_dropped_crt(path),
+            None => Task::none(),
+        }
+    }
+
+    pub(crate) fn handle_drop_cancel_crt(&mut self) -> Task<Message> {
+        self.pending_drop_crt = None;
+        self.user_message = Some(UserMessage::Info("Drop cancelled".into()));
+        Task::none()
+    }
+
+    fn run_dropped_crt(&mut self, path: PathBuf) -> Task<Message> {
+        self.start_drop_action(DropAction::RunOnDevice {
+            path,
+            runner: "run_crt",
+        })
+    }
+
     pub(crate) fn handle_drop_completed(
         &mut self,
         result: Result<String, String>,
@@ -0,0 +1,133 @@
+//! Firmware update dialog handlers. The steps and their checks live in
+//! `firmware_update`; this file moves the session between stages and hooks
+//! the status poll in to notice the new firmware after the reboot.
+
+use iced::Task;
+use std::path::PathBuf;
+use std::time::Instant;
+
+use crate::firmware_update::{FirmwareImage, Stage, UpdateSession};
+use crate::{Message, Ultimate64Browser, UserMessage};
+
+impl Ultimate64Browser {
+    pub(crate) fn handle_firmware_update_open(&mut self, path: PathBuf) -> Task<Message> {
+        let (Some(product), Some(firmware)) = (
+            self.status.product.clone(),
+            self.status.firmware_version.clone(),
+        ) else {
+            self.user_message = Some(UserMessage::Error(
+                "Connect to the device before updating its firmware".to_string(),
+            ));
+            return Task::none();
+        };
+        if !self.status.connected {
+            self.user_message = Some(UserMessage::Error("Not connected".to_string()));
+            return Task::none();
+        }
+        match FirmwareImage::read(&path) {
+            Ok(image) => {
+                self.firmware_update = Some(UpdateSession::new(image, product, firmware));
+            }
+            Err(e) => self.user_message = Some(UserMessage::Error(e)),
+        }
+        Task::none()
+    }
+
+    pub(crate) fn handle_firmware_update_proceed(&mut self) -> Task<Message> {
+        let Some(session) = self.firmware_update.as_mut() else {
+            return Task::none();
+        };
+        let Some(host_url) = self.host_url.clone() else {
+            session.stage = Stage::Failed("Not connected".to_string());
+            return Task::none();
+        };
+        session.stage = Stage::BackingUp;
+        let password = self.settings.connection.password.clone();
+        let from_version = session.from_version.clone();
+        Task::perform(
+            crate::firmware_update::backup_config(host_url, password, from_version),
+            Message::FirmwareBackupDone,
+        )
+    }
+
+    pub(crate) fn handle_firmware_backup_done(
+        &mut self,
+        result: Result<PathBuf, String>,
+    ) -> Task<Message> {
+        let Some(session) = self.firmware_update.as_mut() else {
+            return Task::none();
+        };
+        match result {
+            Ok(path) => {
+                log::info!("Pre-update configuration saved to {}", path.display());
+                session.backup = Some(path);
+                session.stage = Stage::Uploading;
+                let host = self.settings.connection.host.clone();
+                let password = self.settings.connection.password.clone();
+                Task::perform(
+                    crate::firmware_update::upload(host, session.image.path.clone(), password),
+                    Message::FirmwareUploadDone,
+                )
+            }
+            // Never upload without a backup to fall back on.
+            Err(e) => {
+                session.stage = Stage::Failed(format!("Configuration backup failed: {}", e));
+                Task::none()
+            }
+        }
+    }
+
+    pub(crate) fn handle_firmware_upload_done(
+        &mut self,
+        result: Result<String, String>,
+    ) -> Task<Message> {
+        let Some(session) = self.firmware_update.as_mut() else {
+            return Task::none();
+        };
+        match result {
+            Ok(remote_path) => {
+                session.stage = Stage::AwaitingStart { remote_path };
+                // Bring up the device menu so the user can start the update.
+                return self.handle_menu_button();
+            }
+            Err(e) => session.stage = Stage::Failed(format!("Upload failed: {}", e)),
+        }
+        Task::none()
+    }
+
+    pub(crate) fn handle_firmware_update_started(&mut self) -> Task<Message> {
+        if let Some(session) = self.firmware_update.as_mut() {
+            session.stage = Stage::Rebooting {
+                since: Instant::now(),
+            };
+        }
+        Task::none()
+    }
+
+    pub(crate) fn handle_firmware_update_close(&mut self) -> Task<Message> {
+        if self.firmware_update.as_ref().is_some_and(|s| !s.is_busy()) {
+            self.firmware_update = None;
+        }
+        Task::none()
+    }
+
+    pub(crate) fn handle_firmware_update_tick(&mut self) -> Task<Message> {
+        if let Some(session) = self.firmware_update.as_mut() {
+            session.tick(Instant::now());
+        }
+        Task::none()
+    }
+
+    /// Called for every successful status poll.
+    pub(crate) fn observe_firmware_update(&mut self) {
+        let (Some(session), Some(firmware)) = (
+            self.firmware_update.as_mut(),
+            self.status.firmware_version.as_deref(),
+        ) else {
+            return;
+        };
+        if session.observe(firmware) {
+            self.show_toast(format!("Firmware updated to {}", firmware));
+        }
+    }
+}
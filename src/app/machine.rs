@@ -1,8 +1,10 @@
-//! Machine-control handlers (reset / reboot / pause / resume / poweroff / menu)
-//! and the shared completion arm. Extracted from `main.rs::update`.
+//! Machine-control handlers (reset / reboot / pause / resume / poweroff / menu /
+//! snapshot) and the shared completion arm. Extracted from `main.rs::update`.
 
 use iced::Task;
+use std::path::PathBuf;
 
+use crate::machine_snapshot;
 use crate::net_utils::REST_TIMEOUT_SECS;
 use crate::{Message, Ultimate64Browser, UserMessage};
 
@@ -160,6 +162,40 @@ impl Ultimate64Browser {
         }
     }
 
+    pub(crate) fn handle_save_snapshot(&mut self) -> Task<Message> {
+        if self.connection.is_none() {
+            self.user_message = Some(UserMessage::Error("Not connected".to_string()));
+            return Task::none();
+        }
+        let default_name = format!(
+            "snapshot_{}.{}",
+            chrono::Local::now().format("%Y%m%d_%H%M%S"),
+            machine_snapshot::EXTENSION
+        );
+        Task::perform(
+            async move {
+                rfd::AsyncFileDialog::new()
+                    .set_file_name(&default_name)
+                    .add_filter("Machine snapshot", &[machine_snapshot::EXTENSION])
+                    .save_file()
+                    .await
+                    .map(|h| h.path().to_path_buf())
+            },
+            Message::SnapshotPathSelected,
+        )
+    }
+
+    pub(crate) fn handle_snapshot_path_selected(&mut self, path: Option<PathBuf>) -> Task<Message> {
+        let (Some(path), Some(conn)) = (path, &self.connection) else {
+            return Task::none();
+        };
+        self.user_message = Some(UserMessage::Info("Capturing snapshot...".to_string()));
+        Task::perform(
+            machine_snapshot::save_snapshot(conn.clone(), path),
+            Message::MachineCommandCompleted,
+        )
+    }
+
     pub(crate) fn handle_machine_command_completed(
         &mut self,
         result: Result<String, String>,
@@ -9,7 +9,10 @@ mod connection;
 mod copy;
 mod device;
 mod dragdrop;
+mod firmware;
 mod machine;
+mod network;
 mod settings;
 mod view;
 mod window_modals;
+mod workspace;
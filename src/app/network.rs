@@ -0,0 +1,73 @@
+//! Network rollback watchdog handlers. The checks and the revert live in
+//! `network_watchdog`; this file follows the device to a new address, or
+//! keeps the recovery dialog up until it is found again.
+
+use iced::Task;
+
+use crate::network_watchdog::{self, Outcome, Recovery};
+use crate::{Message, Tab, Ultimate64Browser, UserMessage};
+
+impl Ultimate64Browser {
+    pub(crate) fn handle_network_outcome(&mut self, outcome: Outcome) -> Task<Message> {
+        match outcome {
+            Outcome::Reachable(host) => {
+                self.network_recovery = None;
+                if host == self.settings.connection.host {
+                    return Task::none();
+                }
+                self.show_toast(format!("Device now answers at {} — reconnecting", host));
+                self.host_input = host;
+                self.handle_connect_pressed()
+            }
+            Outcome::Reverted(host) => {
+                self.network_recovery = None;
+                self.user_message = Some(UserMessage::Error(format!(
+                    "The device did not answer at its new address; previous network settings were restored at {}",
+                    host
+                )));
+                Task::none()
+            }
+            Outcome::Lost(guard) => {
+                if self.network_recovery.is_some() {
+                    self.show_toast("Still no answer from the device");
+                }
+                self.network_recovery = Some(Recovery::new(guard));
+                Task::none()
+            }
+        }
+    }
+
+    pub(crate) fn handle_network_recovery_retry(&mut self) -> Task<Message> {
+        let Some(recovery) = self.network_recovery.as_mut() else {
+            return Task::none();
+        };
+        if recovery.checking {
+            return Task::none();
+        }
+        recovery.checking = true;
+        let password = self.settings.connection.password.clone();
+        Task::perform(
+            network_watchdog::watch(recovery.guard.clone(), password),
+            Message::NetworkRecoveryRetried,
+        )
+    }
+
+    pub(crate) fn handle_network_recovery_search(&mut self) -> Task<Message> {
+        self.network_recovery = None;
+        self.active_tab = Tab::Settings;
+        self.handle_start_discovery()
+    }
+
+    pub(crate) fn handle_network_recovery_connect(&mut self, host: String) -> Task<Message> {
+        self.network_recovery = None;
+        self.host_input = host;
+        self.handle_connect_pressed()
+    }
+
+    pub(crate) fn handle_network_recovery_close(&mut self) -> Task<Message> {
+        if self.network_recovery.as_ref().is_some_and(|r| !r.checking) {
+            self.network_recovery = None;
+        }
+        Task::none()
+    }
+}
@@ -1,11 +1,11 @@
-//! Settings, profile CRUD, device discovery, templates, and directory
-//! pickers. Extracted from `main.rs::update`.
+//! Settings, profile CRUD, device discovery, templates and launch scripts,
+//! and directory pickers. Extracted from `main.rs::update`.
 
 use iced::Task;
 use std::path::PathBuf;
 
 use crate::discovery::{self, DiscoveredDevice};
-use crate::execute_template_commands;
+use crate::launch_script::{self, LaunchRequest, ScriptError};
 use crate::settings::ConnectionSettings;
 use crate::templates::DiskTemplate;
 use crate::{Message, Ultimate64Browser, UserMessage};
@@ -56,7 +56,10 @@ impl Ultimate64Browser {
         self.discovered_devices.clear();
         self.user_message = Some(UserMessage::Info("Scanning network...".to_string()));
 
-        Task::perform(discovery::discover_devices(), Message::DiscoveryComplete)
+        Task::perform(
+            discovery::discover_devices(self.settings.preferences.discovery_subnets.clone()),
+            Message::DiscoveryComplete,
+        )
     }
 
     pub(crate) fn handle_discovery_complete(
@@ -103,6 +106,7 @@ impl Ultimate64Browser {
                 .clone()
                 .unwrap_or_default();
             self.font_size_input = self.settings.preferences.font_size.to_string();
+            self.discovery_subnets_input = self.settings.preferences.discovery_subnets.join(", ");
             self.video_streaming
                 .set_stream_control_method(self.settings.connection.stream_control_method);
 
@@ -194,24 +198,101 @@ impl Ultimate64Browser {
     }
 
     pub(crate) fn handle_execute_template(&mut self) -> Task<Message> {
-        if let Some(template) = &self.selected_template {
-            if let Some(conn) = &self.connection {
-                let conn = conn.clone();
-                let commands = template.commands.clone();
-                return Task::perform(
-                    async move { execute_template_commands(conn, commands).await },
-                    |result| match result {
-                        Ok(_) => Message::RefreshStatus,
-                        Err(e) => Message::ShowError(e),
-                    },
-                );
-            } else {
-                self.user_message = Some(UserMessage::Error("Not connected".to_string()));
+        let Some(template) = &self.selected_template else {
+            return Task::none();
+        };
+        let request = LaunchRequest {
+            name: template.name.clone(),
+            source: template.script_source(),
+            script: None,
+            file: None,
+        };
+        self.handle_run_launch_script(request)
+    }
+
+    /// Validate a launch script and start stepping through it.
+    pub(crate) fn handle_run_launch_script(&mut self, request: LaunchRequest) -> Task<Message> {
+        let Some(conn) = &self.connection else {
+            self.user_message = Some(UserMessage::Error("Not connected".to_string()));
+            return Task::none();
+        };
+        if let Some(run) = &self.launch_run {
+            self.user_message = Some(UserMessage::Error(format!(
+                "'{}' is still running",
+                run.name
+            )));
+            return Task::none();
+        }
+        let env = launch_script::Env {
+            connection: conn.clone(),
+            host: self.settings.connection.host.clone(),
+            password: self.settings.connection.password.clone(),
+            script: request.script,
+            file: request.file,
+            cancel: Default::default(),
+        };
+        match launch_script::Stepper::new(request.name.clone(), env, &request.source) {
+            Ok(run) => {
+                self.launch_run = Some(run);
+                self.step_launch_script()
+            }
+            Err(errors) => {
+                let msg = format!("{}:\n{}", request.name, launch_script::describe(&errors));
+                Task::done(Message::ShowError(msg))
+            }
+        }
+    }
+
+    pub(crate) fn handle_launch_script_stepped(
+        &mut self,
+        id: u64,
+        result: Result<(), ScriptError>,
+    ) -> Task<Message> {
+        // A run stopped while its statement was in flight
+        if self.launch_run.as_ref().map(|r| r.id) != Some(id) {
+            return Task::none();
+        }
+        match result {
+            Ok(()) => self.step_launch_script(),
+            Err(e) => {
+                let name = self.launch_run.take().map(|r| r.name).unwrap_or_default();
+                Task::done(Message::ShowError(format!("{}: {}", name, e)))
             }
         }
+    }
+
+    pub(crate) fn handle_stop_launch_script(&mut self) -> Task<Message> {
+        if let Some(run) = self.launch_run.take() {
+            run.stop();
+            self.user_message = Some(UserMessage::Info(format!("Stopped '{}'", run.name)));
+        }
         Task::none()
     }
 
+    /// Start the next statement, or finish the run.
+    fn step_launch_script(&mut self) -> Task<Message> {
+        let Some(run) = &mut self.launch_run else {
+            return Task::none();
+        };
+        if let Some(statement) = run.current() {
+            self.user_message = Some(UserMessage::Info(format!(
+                "{} — line {}: {}",
+                run.name, statement.line, statement.source
+            )));
+        }
+        match run.step() {
+            Some(step) => {
+                let id = run.id;
+                Task::perform(step, move |result| Message::LaunchScriptStepped(id, result))
+            }
+            None => {
+                let name = self.launch_run.take().map(|r| r.name).unwrap_or_default();
+                self.user_message = Some(UserMessage::Info(format!("Finished '{}'", name)));
+                Task::done(Message::RefreshStatus)
+            }
+        }
+    }
+
     pub(crate) fn handle_default_song_duration_changed(&mut self, value: String) -> Task<Message> {
         if let Ok(duration) = value.parse::<u32>() {
             if duration > 0 && duration <= 3600 {
@@ -229,6 +310,24 @@ impl Ultimate64Browser {
         Task::none()
     }
 
+    pub(crate) fn handle_discovery_subnets_changed(&mut self, value: String) -> Task<Message> {
+        let subnets: Vec<String> = value
+            .split([',', ' '])
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+            .collect();
+        self.discovery_subnets_input = value;
+        self.profile_manager
+            .active_settings_mut()
+            .preferences
+            .discovery_subnets = subnets;
+        self.settings = self.profile_manager.active_settings().clone();
+        if let Err(e) = self.profile_manager.save() {
+            log::error!("Failed to save profiles: {}", e);
+        }
+        Task::none()
+    }
+
     pub(crate) fn handle_font_size_changed(&mut self, value: String) -> Task<Message> {
         self.font_size_input = value.clone();
         if let Ok(size) = value.parse::<u32>() {
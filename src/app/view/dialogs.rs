@@ -170,6 +170,70 @@ impl Ultimate64Browser {
         .on_press(Message::DropCancel)
         .into()
     }
+    pub(crate) fn view_drop_crt_dialog<'a>(
+        &'a self,
+        path: &'a std::path::Path,
+        warnings: &'a [String],
+    ) -> Element<'a, Message> {
+        let fs = crate::styles::FontSizes::from_base(self.settings.preferences.font_size);
+        let mut lines = column![text(format!(
+            "{} may not run:",
+            path.file_name().unwrap_or_default().to_string_lossy()
+        ))
+        .size(fs.large)]
+        .spacing(4);
+        for warning in warnings {
+            lines = lines.push(
+                text(format!("• {}", warning))
+                    .size(fs.small)
+                    .color(iced::Color::from_rgb(0.7, 0.7, 0.75)),
+            );
+        }
+
+        let dialog = container(
+            column![
+                lines,
+                Space::new().height(12),
+                column![
+                    button(text("▶ Send anyway").size(fs.normal))
+                        .on_press(Message::DropConfirmCrt)
+                        .padding([6, 14])
+                        .width(Length::Fill),
+                    button(text("Cancel").size(fs.normal))
+                        .on_press(Message::DropCancelCrt)
+                        .padding([6, 14])
+                        .width(Length::Fill)
+                        .style(iced::widget::button::text),
+                ]
+                .spacing(8),
+            ]
+            .spacing(6)
+            .padding(20),
+        )
+        .style(|_theme| container::Style {
+            background: Some(iced::Background::Color(iced::Color::from_rgba(
+                0.18, 0.20, 0.28, 0.98,
+            ))),
+            border: iced::Border {
+                color: iced::Color::from_rgba(0.85, 0.7, 0.3, 0.7),
+                width: 1.0,
+                radius: 6.0.into(),
+            },
+            ..Default::default()
+        })
+        .width(Length::Fixed(420.0));
+
+        iced::widget::mouse_area(
+            container(iced::widget::mouse_area(dialog).on_press(Message::Nop))
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .center_x(Length::Fill)
+                .center_y(Length::Fill)
+                .padding(20),
+        )
+        .on_press(Message::DropCancelCrt)
+        .into()
+    }
     pub(crate) fn view_eject_confirm_dialog(&self) -> Element<'_, Message> {
         let fs = crate::styles::FontSizes::from_base(self.settings.preferences.font_size);
 
@@ -221,6 +285,242 @@ impl Ultimate64Browser {
         .on_press(Message::EjectCancel)
         .into()
     }
+    pub(crate) fn view_firmware_update_dialog<'a>(
+        &'a self,
+        session: &'a crate::firmware_update::UpdateSession,
+    ) -> Element<'a, Message> {
+        use crate::firmware_update::Stage;
+        let fs = crate::styles::FontSizes::from_base(self.settings.preferences.font_size);
+        let dim = iced::Color::from_rgb(0.7, 0.7, 0.75);
+        let image = &session.image;
+
+        let mut details = column![
+            text(format!("Update firmware: {}", image.name)).size(fs.large),
+            text(format!(
+                "For {} · {} KB{}",
+                image.model.label(),
+                image.size / 1024,
+                image
+                    .version
+                    .as_ref()
+                    .map(|v| format!(" · {}", v))
+                    .unwrap_or_default()
+            ))
+            .size(fs.small)
+            .color(dim),
+            text(format!(
+                "Device: {} running {}",
+                session.product, session.from_version
+            ))
+            .size(fs.small)
+            .color(dim),
+        ]
+        .spacing(4);
+        if let Some(backup) = &session.backup {
+            details = details.push(
+                text(format!("Configuration backed up to {}", backup.display()))
+                    .size(fs.small)
+                    .color(dim),
+            );
+        }
+
+        let (status, color) = match &session.stage {
+            Stage::Ready => (
+                "The configuration is backed up first, then the image is uploaded to the device."
+                    .to_string(),
+                dim,
+            ),
+            Stage::BackingUp => ("Backing up the configuration...".to_string(), dim),
+            Stage::Uploading => ("Uploading the image...".to_string(), dim),
+            Stage::AwaitingStart { remote_path } => (
+                format!(
+                    "Uploaded to {}. In the device menu, select that file and choose Run Update, then press the button below.",
+                    remote_path
+                ),
+                iced::Color::from_rgb(0.95, 0.8, 0.4),
+            ),
+            Stage::Rebooting { since } => (
+                format!(
+                    "Waiting for the device to come back with new firmware ({}s)... Don't power it off.",
+                    since.elapsed().as_secs()
+                ),
+                iced::Color::from_rgb(0.95, 0.8, 0.4),
+            ),
+            Stage::Done { version } => (
+                format!("Done — the device now runs firmware {}.", version),
+                iced::Color::from_rgb(0.4, 0.85, 0.4),
+            ),
+            Stage::Failed(e) => (e.clone(), iced::Color::from_rgb(0.95, 0.45, 0.45)),
+        };
+
+        let primary: Option<Element<'_, Message>> = match &session.stage {
+            Stage::Ready => Some(
+                button(text("Back up & upload").size(fs.normal))
+                    .on_press(Message::FirmwareUpdateProceed)
+                    .padding([6, 14])
+                    .width(Length::Fill)
+                    .style(iced::widget::button::danger)
+                    .into(),
+            ),
+            Stage::AwaitingStart { .. } => Some(
+                column![
+                    button(text("Open device menu").size(fs.normal))
+                        .on_press(Message::MenuButton)
+                        .padding([6, 14])
+                        .width(Length::Fill),
+                    button(text("I started the update").size(fs.normal))
+                        .on_press(Message::FirmwareUpdateStarted)
+                        .padding([6, 14])
+                        .width(Length::Fill)
+                        .style(iced::widget::button::danger),
+                ]
+                .spacing(8)
+                .into(),
+            ),
+            _ => None,
+        };
+        let close_label = match session.stage {
+            Stage::Ready | Stage::AwaitingStart { .. } => "Cancel",
+            _ => "Close",
+        };
+        let mut buttons = column![].spacing(8);
+        if let Some(primary) = primary {
+            buttons = buttons.push(primary);
+        }
+        buttons = buttons.push(
+            button(text(close_label).size(fs.normal))
+                .on_press_maybe((!session.is_busy()).then_some(Message::FirmwareUpdateClose))
+                .padding([6, 14])
+                .width(Length::Fill)
+                .style(iced::widget::button::text),
+        );
+
+        let dialog = container(
+            column![
+                details,
+                Space::new().height(8),
+                text(status).size(fs.normal).color(color),
+                Space::new().height(12),
+                buttons,
+            ]
+            .spacing(6)
+            .padding(20),
+        )
+        .style(|_theme| container::Style {
+            background: Some(iced::Background::Color(iced::Color::from_rgba(
+                0.18, 0.20, 0.28, 0.98,
+            ))),
+            border: iced::Border {
+                color: iced::Color::from_rgba(0.85, 0.4, 0.4, 0.7),
+                width: 1.0,
+                radius: 6.0.into(),
+            },
+            ..Default::default()
+        })
+        .width(Length::Fixed(460.0));
+
+        container(dialog)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x(Length::Fill)
+            .center_y(Length::Fill)
+            .padding(20)
+            .into()
+    }
+    pub(crate) fn view_network_recovery_dialog<'a>(
+        &'a self,
+        recovery: &'a crate::network_watchdog::Recovery,
+    ) -> Element<'a, Message> {
+        use crate::network_watchdog::Expected;
+        let fs = crate::styles::FontSizes::from_base(self.settings.preferences.font_size);
+        let dim = iced::Color::from_rgb(0.7, 0.7, 0.75);
+        let guard = &recovery.guard;
+
+        let mut steps = column![].spacing(6);
+        for (i, step) in recovery.steps().into_iter().enumerate() {
+            steps = steps.push(
+                text(format!("{}. {}", i + 1, step))
+                    .size(fs.small)
+                    .color(dim),
+            );
+        }
+
+        let mut buttons = column![].spacing(8);
+        if let Expected::Address(ip) = &guard.expected {
+            buttons = buttons.push(
+                button(text(format!("Connect to {}", ip)).size(fs.normal))
+                    .on_press(Message::NetworkRecoveryConnect(ip.clone()))
+                    .padding([6, 14])
+                    .width(Length::Fill),
+            );
+        }
+        buttons = buttons
+            .push(
+                button(text("Search network").size(fs.normal))
+                    .on_press(Message::NetworkRecoverySearch)
+                    .padding([6, 14])
+                    .width(Length::Fill),
+            )
+            .push(
+                button(
+                    text(if recovery.checking {
+                        "Checking..."
+                    } else {
+                        "Check again"
+                    })
+                    .size(fs.normal),
+                )
+                .on_press_maybe((!recovery.checking).then_some(Message::NetworkRecoveryRetry))
+                .padding([6, 14])
+                .width(Length::Fill),
+            )
+            .push(
+                button(text("Close").size(fs.normal))
+                    .on_press_maybe((!recovery.checking).then_some(Message::NetworkRecoveryClose))
+                    .padding([6, 14])
+                    .width(Length::Fill)
+                    .style(iced::widget::button::text),
+            );
+
+        let dialog = container(
+            column![
+                text("Device not reachable after network change").size(fs.large),
+                text(format!(
+                    "The settings were saved, but the device no longer answers at {}.",
+                    guard.expected_label()
+                ))
+                .size(fs.small)
+                .color(iced::Color::from_rgb(0.95, 0.8, 0.4)),
+                Space::new().height(8),
+                steps,
+                Space::new().height(12),
+                buttons,
+            ]
+            .spacing(6)
+            .padding(20),
+        )
+        .style(|_theme| container::Style {
+            background: Some(iced::Background::Color(iced::Color::from_rgba(
+                0.18, 0.20, 0.28, 0.98,
+            ))),
+            border: iced::Border {
+                color: iced::Color::from_rgba(0.85, 0.4, 0.4, 0.7),
+                width: 1.0,
+                radius: 6.0.into(),
+            },
+            ..Default::default()
+        })
+        .width(Length::Fixed(460.0));
+
+        container(dialog)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x(Length::Fill)
+            .center_y(Length::Fill)
+            .padding(20)
+            .into()
+    }
+
     pub(crate) fn view_close_confirm_dialog(&self) -> Element<'_, Message> {
         let fs = crate::styles::FontSizes::from_base(self.settings.preferences.font_size);
 
@@ -271,10 +271,17 @@ impl Ultimate64Browser {
                 .placeholder("Template...")
                 .text_size(tiny)
                 .width(Length::Fixed(150.0)),
-                button(text("Exec").size(tiny))
-                    .on_press(Message::ExecuteTemplate)
-                    .padding([4, 8])
-                    .style(crate::styles::nav_button),
+                if self.launch_run.is_some() {
+                    button(text("Stop").size(tiny))
+                        .on_press(Message::StopLaunchScript)
+                        .padding([4, 8])
+                        .style(crate::styles::nav_button)
+                } else {
+                    button(text("Exec").size(tiny))
+                        .on_press(Message::ExecuteTemplate)
+                        .padding([4, 8])
+                        .style(crate::styles::nav_button)
+                },
             ]
             .spacing(3)
             .align_y(iced::Alignment::Center),
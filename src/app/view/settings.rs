@@ -112,8 +112,7 @@ impl Ultimate64Browser {
                     .iter()
                     .map(|d| {
                         let device = d.clone();
-                        let label = format!("{} - {} ({})", d.ip, d.product, d.firmware);
-                        button(text(label).size(fs.small))
+                        button(text(d.label()).size(fs.small))
                             .on_press(Message::SelectDiscoveredDevice(device))
                             .padding([4, 8])
                             .width(Length::Fill)
@@ -160,6 +159,19 @@ impl Ultimate64Browser {
                 .spacing(8)
                 .align_y(iced::Alignment::Center),
                 discovered_list,
+                row![
+                    text("Also search:").size(fs.normal).color(dim),
+                    text_input(
+                        "eg. 10.0.0.0/16, 192.168.2.0/24",
+                        &self.discovery_subnets_input
+                    )
+                    .on_input(Message::DiscoverySubnetsChanged)
+                    .padding(6)
+                    .size(fs.small as f32)
+                    .width(Length::Fixed(300.0)),
+                ]
+                .spacing(8)
+                .align_y(iced::Alignment::Center),
                 row![
                     text("Password:").size(fs.normal).color(dim),
                     text_input("optional", &self.password_input)
@@ -158,6 +158,15 @@ impl Ultimate64Browser {
                     tooltip::Position::Top,
                 )
                 .style(container::bordered_box),
+                text("|").size(fs.normal),
+                tooltip(
+                    button(text("SNAPSHOT").size(fs.small))
+                        .on_press_maybe(connected.then_some(Message::SaveSnapshot))
+                        .padding([4, 8]),
+                    "Save RAM, CPU, VIC/SID/CIA and colour RAM to a snapshot file",
+                    tooltip::Position::Top,
+                )
+                .style(container::bordered_box),
             ]
             .spacing(6)
             .align_y(iced::Alignment::Center),
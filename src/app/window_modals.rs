@@ -38,10 +38,19 @@ impl Ultimate64Browser {
             self.pending_eject_confirm = false;
             return Task::none();
         }
+        if self.firmware_update.is_some() {
+            return self.handle_firmware_update_close();
+        }
+        if self.network_recovery.is_some() {
+            return self.handle_network_recovery_close();
+        }
         if self.pending_drop.is_some() {
             self.pending_drop = None;
             return Task::none();
         }
+        if self.pending_drop_crt.is_some() {
+            return self.handle_drop_cancel_crt();
+        }
         // In Game Mode: Esc first drops out of fullscreen (restoring the app
         // chrome), then a second Esc leaves the launcher.
         if self.remote_browser.game.active {
@@ -0,0 +1,52 @@
+//! WORKSPACE tab routing. The workspace owns its members' connections; the app
+//! resolves the profile names it picks and saves the member list after every
+//! change.
+
+use iced::Task;
+
+use crate::tab::TabController;
+use crate::workspace::{WorkspaceDevice, WorkspaceMessage};
+use crate::{Message, Ultimate64Browser};
+
+impl Ultimate64Browser {
+    pub(crate) fn handle_workspace(&mut self, msg: WorkspaceMessage) -> Task<Message> {
+        let task = match msg {
+            WorkspaceMessage::AddProfile(name) => {
+                let Some(profile) = self
+                    .profile_manager
+                    .profiles
+                    .iter()
+                    .find(|p| p.name == name)
+                else {
+                    return Task::none();
+                };
+                let connection = &profile.settings.connection;
+                let device = WorkspaceDevice {
+                    name: name.clone(),
+                    host: connection.host.clone(),
+                    password: connection.password.clone(),
+                };
+                if device.host.is_empty() {
+                    return Task::none();
+                }
+                self.workspace.add(device)
+            }
+            WorkspaceMessage::ApplyProfile(name) => {
+                match self.device_profile_manager.apply_source(&name) {
+                    Some((path, baseline)) => self.workspace.apply_profile(&name, path, baseline),
+                    None => Task::none(),
+                }
+            }
+            other => self.workspace.update(other, self.tab_context()),
+        };
+
+        let devices = self.workspace.devices();
+        if devices != self.profile_manager.workspace {
+            self.profile_manager.workspace = devices;
+            if let Err(e) = self.profile_manager.save() {
+                log::error!("Failed to save profiles: {}", e);
+            }
+        }
+        task.map(Message::Workspace)
+    }
+}
@@ -4,10 +4,13 @@
 //! and are reused by the Assembly64 browser when a release ships its files
 //! inside a ZIP. Path components inside the archive are stripped so every
 //! file lands directly in the target directory; hidden and macOS metadata
-//! entries are skipped.
+//! entries are skipped. Lynx archives and Zipcode sets found inside a ZIP are
+//! unpacked too (see `cbm_archive`), and a bare `.lnx` can be extracted the
+//! same way.
 
-use anyhow::{Context, Result};
+use anyhow::{anyhow, Context, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::io::{Cursor, Read};
 use std::path::{Path, PathBuf};
 use zip::ZipArchive;
@@ -34,7 +37,8 @@ pub struct ExtractedFile {
 /// Extract a ZIP from in-memory bytes into `target_dir`. Returns the file
 /// listing. The directory is created if missing; existing files are
 /// overwritten. Path components are flattened (every file lands directly
-/// inside `target_dir`).
+/// inside `target_dir`); names that then collide get `_1`, `_2`, … before
+/// the extension.
 pub fn extract_zip_to_dir(
     zip_data: &[u8],
     source_filename: &str,
@@ -69,32 +73,40 @@ pub fn extract_zip_to_dir(
             continue;
         }
 
-        let out_path = target_dir.join(&clean_filename);
-
         let mut contents = Vec::new();
         file.read_to_end(&mut contents)
             .context("Failed to read ZIP entry contents")?;
 
-        let size = contents.len() as u64;
-
-        std::fs::write(&out_path, &contents)
-            .with_context(|| format!("Failed to write {:?}", out_path))?;
+        write_extracted(target_dir, &mut files, clean_filename, &contents)?;
+    }
 
-        let ext = ext_of(&clean_filename);
+    unpack_cbm_archives(target_dir, &mut files);
 
-        files.push(ExtractedFile {
-            index: files.len() + 1,
-            filename: clean_filename,
-            path: out_path,
-            ext,
-            size,
-        });
+    for (i, f) in files.iter_mut().enumerate() {
+        f.index = i + 1;
     }
 
+    Ok(ExtractedZip {
+        source_filename: source_filename.to_string(),
+        extract_dir: target_dir.to_path_buf(),
+        files,
+    })
+}
+
+/// Extract a Lynx archive's PRG/SEQ/USR files into `target_dir`, named
+/// `<NAME>.<type>`.
+pub fn extract_lynx_to_dir(
+    lynx_data: &[u8],
+    source_filename: &str,
+    target_dir: &Path,
+) -> Result<ExtractedZip> {
+    std::fs::create_dir_all(target_dir).context("Failed to create target directory")?;
+    let mut files = Vec::new();
+    write_lynx_files(lynx_data, target_dir, &mut files)?;
+    unpack_cbm_archives(target_dir, &mut files);
     for (i, f) in files.iter_mut().enumerate() {
         f.index = i + 1;
     }
-
     Ok(ExtractedZip {
         source_filename: source_filename.to_string(),
         extract_dir: target_dir.to_path_buf(),
@@ -102,6 +114,147 @@ pub fn extract_zip_to_dir(
     })
 }
 
+/// Extract a ZIP or Lynx archive, picked by the extension of
+/// `source_filename`.
+pub fn extract_archive_to_dir(
+    data: &[u8],
+    source_filename: &str,
+    target_dir: &Path,
+) -> Result<ExtractedZip> {
+    match ext_of(source_filename).as_str() {
+        "lnx" => extract_lynx_to_dir(data, source_filename, target_dir),
+        _ => extract_zip_to_dir(data, source_filename, target_dir),
+    }
+}
+
+/// Host file name for a CBM file name: path and reserved characters become
+/// `_`.
+fn host_filename(name: &str, ext: &str) -> String {
+    let stem: String = name
+        .trim()
+        .chars()
+        .map(|c| if "/\\:*?\"<>|".contains(c) { '_' } else { c })
+        .collect();
+    let stem = if stem.is_empty() {
+        "FILE".to_string()
+    } else {
+        stem
+    };
+    format!("{}.{}", stem, ext)
+}
+
+/// `filename`, or `NAME_1.ext`, `NAME_2.ext`, … when an earlier file of this
+/// extraction already has that name. Compared without case, as the host
+/// file system may be.
+fn unique_filename(files: &[ExtractedFile], filename: String) -> String {
+    let taken = |name: &str| files.iter().any(|f| f.filename.eq_ignore_ascii_case(name));
+    if !taken(&filename) {
+        return filename;
+    }
+    let (stem, ext) = match filename.rfind('.') {
+        Some(pos) if pos > 0 => filename.split_at(pos),
+        _ => (filename.as_str(), ""),
+    };
+    (1..)
+        .map(|n| format!("{}_{}{}", stem, n, ext))
+        .find(|candidate| !taken(candidate))
+        .unwrap_or_default()
+}
+
+/// Write one extracted file under a name no earlier file of this extraction
+/// uses, and add it to `files`.
+fn write_extracted(
+    target_dir: &Path,
+    files: &mut Vec<ExtractedFile>,
+    filename: String,
+    contents: &[u8],
+) -> Result<()> {
+    let filename = unique_filename(files, filename);
+    let path = target_dir.join(&filename);
+    std::fs::write(&path, contents).with_context(|| format!("Failed to write {:?}", path))?;
+    files.push(ExtractedFile {
+        index: files.len() + 1,
+        ext: ext_of(&filename),
+        filename,
+        path,
+        size: contents.len() as u64,
+    });
+    Ok(())
+}
+
+fn write_lynx_files(
+    lynx_data: &[u8],
+    target_dir: &Path,
+    files: &mut Vec<ExtractedFile>,
+) -> Result<()> {
+    for file in crate::cbm_archive::unpack_lynx(lynx_data).map_err(|e| anyhow!(e))? {
+        let ext = file.file_type.to_string().to_lowercase();
+        write_extracted(
+            target_dir,
+            files,
+            host_filename(&file.name, &ext),
+            &file.data,
+        )?;
+    }
+    Ok(())
+}
+
+/// Unpack the C64 archives among `files`: each Lynx archive into its files
+/// and each complete Zipcode set (`1!NAME` … `4!NAME`) into `NAME.d64`. The
+/// results are appended; an archive that fails to unpack is logged and left
+/// as it is. Lynx goes first, so a Zipcode set shipped inside one is found.
+fn unpack_cbm_archives(target_dir: &Path, files: &mut Vec<ExtractedFile>) {
+    let archives: Vec<(String, PathBuf)> = files
+        .iter()
+        .filter(|f| f.ext == "lnx")
+        .map(|f| (f.filename.clone(), f.path.clone()))
+        .collect();
+    for (filename, path) in archives {
+        let result = std::fs::read(&path)
+            .map_err(anyhow::Error::from)
+            .and_then(|data| write_lynx_files(&data, target_dir, files));
+        if let Err(e) = result {
+            log::warn!("Could not unpack {}: {}", filename, e);
+        }
+    }
+
+    let mut zipcode_sets: HashMap<String, [Option<PathBuf>; 4]> = HashMap::new();
+    for file in files.iter() {
+        if let Some((part, name)) = crate::cbm_archive::zipcode_part(&file.filename) {
+            let stem = Path::new(name)
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or(name)
+                .to_string();
+            zipcode_sets.entry(stem).or_default()[part as usize - 1] = Some(file.path.clone());
+        }
+    }
+
+    let mut sets: Vec<_> = zipcode_sets.into_iter().collect();
+    sets.sort_by(|a, b| a.0.cmp(&b.0));
+    for (name, parts) in sets {
+        let Some(paths) = parts.iter().cloned().collect::<Option<Vec<_>>>() else {
+            log::warn!("Zipcode set \"{}\" is missing parts", name);
+            continue;
+        };
+        let result = paths
+            .iter()
+            .map(std::fs::read)
+            .collect::<std::io::Result<Vec<_>>>()
+            .map_err(anyhow::Error::from)
+            .and_then(|data| {
+                let refs: Vec<&[u8]> = data.iter().map(Vec::as_slice).collect();
+                crate::cbm_archive::zipcode_to_d64(&refs).map_err(|e| anyhow!(e))
+            })
+            .and_then(|image| {
+                write_extracted(target_dir, files, host_filename(&name, "d64"), &image)
+            });
+        if let Err(e) = result {
+            log::warn!("Could not rebuild Zipcode set \"{}\": {}", name, e);
+        }
+    }
+}
+
 /// Filter to extracted files that the Ultimate64 can run/mount directly.
 pub fn runnable_extracted_files(files: &[ExtractedFile]) -> Vec<&ExtractedFile> {
     files
@@ -128,3 +281,69 @@ pub fn ext_of(filename: &str) -> String {
 pub fn is_zip_file(ext: &str) -> bool {
     crate::file_types::is_zip_file(ext)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn zip_with_lynx_yields_runnable_prg() {
+        let mut lynx = b"\x01\x08\x00\x00\x00\r 1  *LYNX XV\r 1 \rDEMO\r 1 \rP\r 5 \r".to_vec();
+        lynx.resize(254, 0);
+        lynx.extend([0x01, 0x08, 0x60, 0x00]);
+
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        zip.start_file("release/demo.lnx", zip::write::SimpleFileOptions::default())
+            .unwrap();
+        zip.write_all(&lynx).unwrap();
+        let zip_data = zip.finish().unwrap().into_inner();
+
+        let dir = std::env::temp_dir().join(format!("u64_lynx_{}", std::process::id()));
+        let extracted = extract_zip_to_dir(&zip_data, "demo.zip", &dir).unwrap();
+        let runnable: Vec<&str> = runnable_extracted_files(&extracted.files)
+            .iter()
+            .map(|f| f.filename.as_str())
+            .collect();
+        assert_eq!(runnable, ["DEMO.prg"]);
+        assert_eq!(
+            std::fs::read(dir.join("DEMO.prg")).unwrap(),
+            [1, 8, 0x60, 0]
+        );
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn colliding_names_get_a_suffix() {
+        let mut lynx = b"\x01\x08\x00\x00\x00\r 1  *LYNX XV\r 1 \rDEMO\r 1 \rP\r 5 \r".to_vec();
+        lynx.resize(254, 0);
+        lynx.extend([0x01, 0x08, 0x60, 0x00]);
+
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, data) in [
+            ("disk1/DEMO.prg", &[1u8, 8, 1][..]),
+            ("disk2/demo.prg", &[1, 8, 2][..]),
+            ("demo.lnx", &lynx[..]),
+        ] {
+            zip.start_file(name, zip::write::SimpleFileOptions::default())
+                .unwrap();
+            zip.write_all(data).unwrap();
+        }
+        let zip_data = zip.finish().unwrap().into_inner();
+
+        let dir = std::env::temp_dir().join(format!("u64_collide_{}", std::process::id()));
+        let extracted = extract_zip_to_dir(&zip_data, "demo.zip", &dir).unwrap();
+        let names: Vec<&str> = extracted
+            .files
+            .iter()
+            .map(|f| f.filename.as_str())
+            .collect();
+        assert_eq!(names, ["DEMO.prg", "demo_1.prg", "demo.lnx", "DEMO_2.prg"]);
+        assert_eq!(std::fs::read(dir.join("DEMO.prg")).unwrap(), [1, 8, 1]);
+        assert_eq!(
+            std::fs::read(dir.join("DEMO_2.prg")).unwrap(),
+            [1, 8, 0x60, 0]
+        );
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
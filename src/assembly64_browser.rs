@@ -26,7 +26,9 @@ use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::Mutex;
 
-use crate::archive::{extract_zip_to_dir, runnable_extracted_files, ExtractedFile, ExtractedZip};
+use crate::archive::{
+    extract_archive_to_dir, runnable_extracted_files, ExtractedFile, ExtractedZip,
+};
 use crate::assembly64::{
     rating_stars, AsmEntry, AsmFile, Assembly64Client, AssemblyError, CategoryRegistry, Choice,
     CompoType, Presets, RatingFilter, RecencyFilter, SearchForm, SortOrder, DEFAULT_PAGE_SIZE,
@@ -158,12 +160,12 @@ impl FileFilter {
         match self {
             FileFilter::All => true,
             FileFilter::Runnable => {
-                crate::file_types::is_runnable(ext) || crate::file_types::is_zip_file(ext)
+                crate::file_types::is_runnable(ext) || crate::file_types::is_archive_file(ext)
             }
             FileFilter::Disk => crate::file_types::is_disk_image(ext),
             FileFilter::Program => matches!(ext, "prg" | "crt"),
             FileFilter::Music => ext == "sid",
-            FileFilter::Archive => crate::file_types::is_zip_file(ext),
+            FileFilter::Archive => crate::file_types::is_archive_file(ext),
         }
     }
 }
@@ -1351,8 +1353,8 @@ impl Assembly64Browser {
                 else {
                     return Task::none();
                 };
-                if !crate::file_types::is_zip_file(&file.ext()) {
-                    self.status_message = Some("Not a ZIP file".into());
+                if !crate::file_types::is_archive_file(&file.ext()) {
+                    self.status_message = Some("Not a ZIP or Lynx archive".into());
                     return Task::none();
                 }
                 self.is_loading = true;
@@ -1388,7 +1390,7 @@ impl Assembly64Browser {
                         tokio::time::timeout(
                             std::time::Duration::from_secs(60),
                             tokio::task::spawn_blocking(move || {
-                                extract_zip_to_dir(&bytes, &safe_name, &target)
+                                extract_archive_to_dir(&bytes, &safe_name, &target)
                                     .map_err(|e| e.to_string())
                             }),
                         )
@@ -2709,7 +2711,7 @@ impl Assembly64Browser {
         let is_selected = self.selected_file_id == Some(file.file_id);
         let is_runnable = crate::file_types::is_runnable(&ext);
         let is_disk_image = crate::file_types::is_disk_image(&ext);
-        let is_zip = crate::file_types::is_zip_file(&ext);
+        let is_zip = crate::file_types::is_archive_file(&ext);
         let ext_color = crate::file_types::ext_color(&ext);
 
         let filename_display = truncate(&file.path, 35);
@@ -2774,7 +2776,7 @@ impl Assembly64Browser {
                     button(text("📦").size(fs.small))
                         .on_press(Assembly64BrowserMessage::ExtractZip(file.file_id))
                         .padding([4, 8]),
-                    "Extract ZIP and browse contents",
+                    "Extract archive (ZIP or Lynx; Zipcode sets become D64s) and browse contents",
                     tooltip::Position::Left,
                 )
                 .style(container::bordered_box),
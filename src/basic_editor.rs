@@ -1,7 +1,8 @@
 //! Iced UI for the BASIC editor tab.
 //!
 //! Layout:
-//! - Toolbar: New / Open .bas / Save .prg / Validate / Send & Run + status pill
+//! - Toolbar: New / Open .bas / List .prg / List C64 / Save .prg / Validate /
+//!   Send & Run + status pill
 //! - Multi-line `text_editor` with hand-rolled BASIC v2 highlighter
 //! - Status bar with the last validation outcome
 //!
@@ -19,8 +20,10 @@ use iced::widget::{
 use iced::{Color, Element, Font, Length, Task};
 use std::ops::Range;
 use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
 
 use crate::basic_tokenizer::{self, ProgramError};
+use crate::remote_device::RemoteDevice;
 
 /// Hard cap on how long a Send & Run can wait for the device. The combined
 /// reqwest connect (5s) + transfer budget needs to feel "interactive" — if
@@ -44,6 +47,11 @@ pub enum BasicEditorMessage {
     NewProgram,
     OpenFile,
     OpenCompleted(Result<(PathBuf, String), String>),
+    /// Pick a tokenized PRG (or a single-PRG disk image) and list it.
+    ListPrg,
+    /// List the BASIC program currently in C64 RAM.
+    ListFromDevice,
+    ListFromDeviceCompleted(Result<String, String>),
     SavePrg,
     SavePrgCompleted(Result<PathBuf, String>),
 }
@@ -84,6 +92,7 @@ impl BasicEditor {
     pub fn update_impl(
         &mut self,
         message: BasicEditorMessage,
+        connection: Option<Arc<Mutex<dyn RemoteDevice>>>,
         host: Option<String>,
         password: Option<String>,
     ) -> Task<BasicEditorMessage> {
@@ -216,6 +225,51 @@ impl BasicEditor {
                 }
                 Task::none()
             }
+            M::ListPrg => Task::perform(
+                async move {
+                    let handle = rfd::AsyncFileDialog::new()
+                        .add_filter("BASIC program", &["prg", "d64", "d71", "d81"])
+                        .add_filter("All files", &["*"])
+                        .pick_file()
+                        .await
+                        .ok_or_else(|| "Cancelled".to_string())?;
+                    let path = handle.path().to_path_buf();
+                    let bytes = handle.read().await;
+                    let text = list_prg_or_disk(&bytes)?;
+                    Ok((path, text))
+                },
+                M::OpenCompleted,
+            ),
+            M::ListFromDevice => {
+                let Some(conn) = connection else {
+                    self.status_message = Some("Not connected to Ultimate64".into());
+                    return Task::none();
+                };
+                self.status_message = Some("Reading BASIC program from C64 RAM…".into());
+                Task::perform(
+                    async move {
+                        let prg = read_basic_from_memory_async(conn).await?;
+                        basic_tokenizer::detokenize_program(&prg)
+                    },
+                    M::ListFromDeviceCompleted,
+                )
+            }
+            M::ListFromDeviceCompleted(result) => {
+                match result {
+                    Ok(text) => {
+                        let lines = text.lines().count();
+                        self.content = Content::with_text(&text);
+                        self.current_file = None;
+                        self.last_validation = None;
+                        self.status_message =
+                            Some(format!("Listed {} line(s) from C64 RAM", lines));
+                    }
+                    Err(e) => {
+                        self.status_message = Some(format!("List failed: {}", e));
+                    }
+                }
+                Task::none()
+            }
             M::SavePrg => {
                 let src = self.source();
                 let bytes = match basic_tokenizer::tokenize_program(&src) {
@@ -283,6 +337,18 @@ impl BasicEditor {
                 BasicEditorMessage::OpenFile,
                 fs.normal
             ),
+            tool_button(
+                "List .prg",
+                "Open a tokenized PRG (or a single-PRG disk image) as source",
+                BasicEditorMessage::ListPrg,
+                fs.normal
+            ),
+            tool_button(
+                "List C64",
+                "List the BASIC program currently in C64 memory",
+                BasicEditorMessage::ListFromDevice,
+                fs.normal
+            ),
             tool_button(
                 "Save .prg",
                 "Tokenize and save as PRG",
@@ -365,6 +431,45 @@ impl BasicEditor {
     }
 }
 
+// -----------------------------------------------------------------------------
+// Listing sources
+// -----------------------------------------------------------------------------
+
+/// List a PRG file, or the only PRG on a D64/D71/D81 (same rule as the run
+/// fast-path: multi-file disks are ambiguous, so they're refused).
+fn list_prg_or_disk(bytes: &[u8]) -> Result<String, String> {
+    if crate::disk_image::detect_kind(bytes.len()).is_some() {
+        let (_, prg) = crate::disk_image::extract_single_prg(bytes)
+            .ok_or_else(|| "Disk image does not hold exactly one PRG".to_string())?;
+        return basic_tokenizer::detokenize_program(&prg);
+    }
+    basic_tokenizer::detokenize_program(bytes)
+}
+
+/// Read the program between TXTTAB ($2B/$2C) and VARTAB ($2D/$2E) out of C64
+/// RAM and return it as a PRG (load address = TXTTAB).
+async fn read_basic_from_memory_async(
+    conn: Arc<Mutex<dyn RemoteDevice>>,
+) -> Result<Vec<u8>, String> {
+    let ptrs = crate::api::read_memory_async(conn.clone(), 0x002B, 4).await?;
+    if ptrs.len() < 4 {
+        return Err("Short read of BASIC pointers".into());
+    }
+    let txttab = u16::from_le_bytes([ptrs[0], ptrs[1]]);
+    let vartab = u16::from_le_bytes([ptrs[2], ptrs[3]]);
+    // An empty program still has the 2-byte end marker; BASIC RAM ends at $A000.
+    if vartab < txttab.saturating_add(2) || vartab > 0xA000 {
+        return Err(format!(
+            "No BASIC program in memory (TXTTAB ${:04X}, VARTAB ${:04X})",
+            txttab, vartab
+        ));
+    }
+    let body = crate::api::read_memory_async(conn, txttab, (vartab - txttab) as u32).await?;
+    let mut prg = txttab.to_le_bytes().to_vec();
+    prg.extend_from_slice(&body);
+    Ok(prg)
+}
+
 // -----------------------------------------------------------------------------
 // Toolbar widgets
 // -----------------------------------------------------------------------------
@@ -781,6 +886,16 @@ mod tests {
         assert!(s.contains("unterminated"));
     }
 
+    #[test]
+    fn list_prg_accepts_single_prg_disk() {
+        let prg = basic_tokenizer::tokenize_program("10 PRINT \"HI\"\n").unwrap();
+        let mut img = crate::disk_image::build_blank_d64("LIST", "01 2A");
+        crate::disk_image::insert_file(&mut img, "HI", crate::disk_image::FileType::Prg, &prg)
+            .unwrap();
+        assert_eq!(list_prg_or_disk(&img).unwrap(), "10 PRINT \"HI\"\n");
+        assert_eq!(list_prg_or_disk(&prg).unwrap(), "10 PRINT \"HI\"\n");
+    }
+
     #[test]
     fn editor_starts_with_runnable_program() {
         let editor = BasicEditor::new();
@@ -796,6 +911,6 @@ impl crate::tab::TabController for BasicEditor {
         message: BasicEditorMessage,
         ctx: crate::tab::TabContext,
     ) -> iced::Task<BasicEditorMessage> {
-        self.update_impl(message, ctx.host, ctx.password)
+        self.update_impl(message, ctx.connection, ctx.host, ctx.password)
     }
 }
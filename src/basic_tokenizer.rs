@@ -1,9 +1,11 @@
-//! Commodore 64 BASIC v2 source → PRG tokenizer.
+//! Commodore 64 BASIC v2 source ↔ PRG tokenizer.
 //!
 //! Pure logic, no UI. Walks plain UTF-8 source line by line, matches keywords
 //! against the BASIC v2 token table, translates string contents to PETSCII
 //! (with petcat-style `{CLR}` / `{$93}` control codes), and emits the standard
 //! PRG layout — `$01 $08` load address, linked lines, `$00 $00` terminator.
+//! [`detokenize_program`] goes the other way, listing a PRG back to source
+//! that re-tokenizes to the same bytes.
 //!
 //! Reference: <https://www.c64-wiki.com/wiki/BASIC_token>.
 
@@ -225,6 +227,20 @@ const RAW_CONTROL: &[(&str, u8)] = &[
     ("PI", 0xFF),
 ];
 
+/// Byte → `{NAME}` for listing. The first name in [`RAW_CONTROL`] wins, so
+/// `$93` lists as `{CLR}` rather than `{CLEAR}`.
+fn control_names() -> &'static HashMap<u8, &'static str> {
+    use std::sync::OnceLock;
+    static TABLE: OnceLock<HashMap<u8, &'static str>> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        let mut m = HashMap::new();
+        for (name, byte) in RAW_CONTROL {
+            m.entry(*byte).or_insert(*name);
+        }
+        m
+    })
+}
+
 fn control_table() -> &'static HashMap<String, u8> {
     use std::sync::OnceLock;
     static TABLE: OnceLock<HashMap<String, u8>> = OnceLock::new();
@@ -429,6 +445,8 @@ pub fn tokenize_line(
     let mut in_string = false;
     // After REM ($8F) we copy raw PETSCII to end-of-line.
     let mut after_rem = false;
+    // After DATA ($83) the C64 leaves keywords uncrunched up to the next `:`.
+    let mut in_data = false;
     let kw = keywords_sorted();
     let mut i = 0usize;
     let body_bytes = body.as_bytes();
@@ -437,6 +455,19 @@ pub fn tokenize_line(
         let c = body_bytes[i];
         let col = body_offset + i + 1;
 
+        // Outside strings a `{...}` that names a control code stands for that
+        // byte (the lister uses it for anything not typeable); anything else
+        // stays literal text.
+        if !in_string && c == b'{' {
+            if let Some(off) = body_bytes[i + 1..].iter().position(|&b| b == b'}') {
+                if let Some(byte) = parse_control_code(&body[i + 1..i + 1 + off]) {
+                    payload.push(byte);
+                    i += off + 2;
+                    continue;
+                }
+            }
+        }
+
         if after_rem {
             payload.push(ascii_to_petscii(c));
             i += 1;
@@ -498,6 +529,14 @@ pub fn tokenize_line(
             i += 1;
             continue;
         }
+        if in_data {
+            if c == b':' {
+                in_data = false;
+            }
+            payload.push(ascii_to_petscii(c));
+            i += 1;
+            continue;
+        }
 
         // Try the longest keyword that matches at this position. Comparison
         // is case-insensitive — the user types `print` or `PRINT` and we
@@ -520,6 +559,9 @@ pub fn tokenize_line(
             if token == 0x8F {
                 after_rem = true;
             }
+            if token == 0x83 {
+                in_data = true;
+            }
             continue;
         }
 
@@ -544,6 +586,105 @@ pub fn tokenize_line(
     }
 }
 
+// -----------------------------------------------------------------------------
+// Detokenization (listing)
+// -----------------------------------------------------------------------------
+
+/// List a tokenized BASIC v2 PRG back to petcat-style source text.
+///
+/// `prg` starts with the two-byte load address. Lines are walked in file
+/// order (link pointers are not trusted, just like petcat) until the `$00 $00`
+/// end marker or the end of the data. Anything that can't be typed literally
+/// is written as a `{NAME}` / `{$NN}` control code, so feeding the result to
+/// [`tokenize_program`] reproduces the original bytes for any program that
+/// was entered through the C64 screen editor.
+pub fn detokenize_program(prg: &[u8]) -> Result<String, String> {
+    if prg.len() < 2 {
+        return Err("PRG is too short to hold a load address".into());
+    }
+    let mut out = String::new();
+    let mut pos = 2usize;
+    while pos + 2 <= prg.len() {
+        if prg[pos] == 0 && prg[pos + 1] == 0 {
+            break;
+        }
+        if pos + 4 > prg.len() {
+            return Err(format!("Truncated line header at offset {}", pos));
+        }
+        let line_no = u16::from_le_bytes([prg[pos + 2], prg[pos + 3]]);
+        let body = &prg[pos + 4..];
+        let len = body
+            .iter()
+            .position(|&b| b == 0)
+            .ok_or_else(|| format!("BASIC line {} has no end-of-line marker", line_no))?;
+        out.push_str(&detokenize_line(line_no, &body[..len]));
+        out.push('\n');
+        pos += 4 + len + 1;
+    }
+    Ok(out)
+}
+
+/// One listed line: `"<line number> <body>"`. Mirrors the tokenizer's
+/// string / REM / DATA state machine so every byte lands back where it was.
+fn detokenize_line(line_no: u16, payload: &[u8]) -> String {
+    let mut out = format!("{} ", line_no);
+    let mut in_string = false;
+    let mut after_rem = false;
+    let mut in_data = false;
+
+    for (i, &b) in payload.iter().enumerate() {
+        if in_string {
+            if b == b'"' {
+                in_string = false;
+                out.push('"');
+            } else {
+                push_petscii(&mut out, b);
+            }
+            continue;
+        }
+        // The tokenizer skips spaces after the line number, so a leading
+        // space byte has to be spelled out.
+        if i == 0 && b == b' ' {
+            out.push_str("{$20}");
+            continue;
+        }
+        if b == b'"' {
+            in_string = true;
+            out.push('"');
+            continue;
+        }
+        if !after_rem && !in_data {
+            if let Some(&(name, token)) = RAW_KEYWORDS.iter().find(|&&(_, t)| t == b) {
+                out.push_str(name);
+                after_rem = token == 0x8F;
+                in_data = token == 0x83;
+                continue;
+            }
+        }
+        if in_data && b == b':' {
+            in_data = false;
+        }
+        push_petscii(&mut out, b);
+    }
+    out
+}
+
+/// Append one PETSCII byte as source text: printable upper-case ASCII as
+/// itself, control codes by name, everything else as `{$NN}`.
+fn push_petscii(out: &mut String, b: u8) {
+    match b {
+        0x20..=0x5F => out.push(b as char),
+        _ => match control_names().get(&b) {
+            Some(name) => {
+                out.push('{');
+                out.push_str(name);
+                out.push('}');
+            }
+            None => out.push_str(&format!("{{${:02X}}}", b)),
+        },
+    }
+}
+
 // -----------------------------------------------------------------------------
 // Char conversions
 // -----------------------------------------------------------------------------
@@ -768,6 +909,50 @@ mod tests {
         assert_eq!(err.len(), 2, "both lines should be reported: {:?}", err);
     }
 
+    #[test]
+    fn detokenize_lists_keywords_and_control_codes() {
+        let bytes = tok("10 PRINT \"{CLR}HELLO{RVS ON}\"\n20 GOTO 10\n");
+        let listing = detokenize_program(&bytes).unwrap();
+        assert_eq!(listing, "10 PRINT \"{CLR}HELLO{RVS ON}\"\n20 GOTO 10\n");
+    }
+
+    #[test]
+    fn detokenize_round_trips_through_tokenizer() {
+        let src = "10 REM \u{7b}$C1}GRAPHICS\n\
+                   20 DATA TO,GOSUB:PRINT A+1\n\
+                   30 IF X<>Y THEN POKE 53280,{$20}:PRINT\"{$60}{F1}\";\n\
+                   40 FOR I=1 TO 10 STEP 2:NEXT\n";
+        let bytes = tok(src);
+        let listing = detokenize_program(&bytes).unwrap();
+        assert_eq!(tok(&listing), bytes, "listing was:\n{}", listing);
+    }
+
+    #[test]
+    fn data_statement_keeps_keywords_uncrunched() {
+        let bytes = tok("10 DATA TO:TO\n");
+        // DATA, plain "TO", ':', then a crunched TO token after the colon.
+        assert_eq!(&bytes[6..], &[0x83, 0x20, b'T', b'O', b':', 0xA4, 0, 0, 0]);
+    }
+
+    #[test]
+    fn detokenize_handles_hand_made_prg() {
+        // 10 PRINT"A" followed by bytes that can't be typed.
+        let prg = [
+            0x01, 0x08, 0x0D, 0x08, 0x0A, 0x00, 0x99, 0x22, 0x41, 0x22, 0xFF, 0x7B, 0xCC, 0x00,
+            0x00, 0x00,
+        ];
+        let listing = detokenize_program(&prg).unwrap();
+        assert_eq!(listing, "10 PRINT\"A\"{PI}{$7B}{$CC}\n");
+        assert_eq!(tok(&listing), prg);
+    }
+
+    #[test]
+    fn detokenize_rejects_broken_input() {
+        assert!(detokenize_program(&[0x01]).is_err());
+        assert!(detokenize_program(&[0x01, 0x08, 0x09, 0x08, 0x0A, 0x00, 0x99]).is_err());
+        assert_eq!(detokenize_program(&[0x01, 0x08]).unwrap(), "");
+    }
+
     #[test]
     fn control_code_names_are_case_and_space_insensitive() {
         let a = tok("10 PRINT \"{rvs on}\"\n");
@@ -0,0 +1,321 @@
+//! Lynx and Zipcode: the C64-native archive formats scene releases ship in.
+//!
+//! A Lynx archive (`.lnx`) is a BASIC stub followed by a text directory and
+//! the files' disk blocks without their link bytes. A Zipcode set is four PRG
+//! files, `1!NAME` … `4!NAME`, that together hold every sector of a 35-track
+//! disk, each sector stored raw, as a fill byte, or run-length encoded.
+
+use crate::disk_image::{self, FileType, ImageKind};
+use crate::petscii;
+
+// ─── Lynx ─────────────────────────────────────────────────────────────────────
+
+/// Data bytes per disk block; Lynx stores blocks without the link bytes
+const LYNX_BLOCK: usize = 254;
+
+/// One file out of a Lynx archive
+#[derive(Debug, Clone)]
+pub struct LynxFile {
+    pub name: String,
+    pub file_type: FileType,
+    pub data: Vec<u8>,
+}
+
+/// First whitespace-separated token of a directory field as a number
+fn lynx_number(field: Option<&[u8]>) -> Result<usize, String> {
+    let field = field.ok_or("Lynx directory ends early")?;
+    String::from_utf8_lossy(field)
+        .split_whitespace()
+        .next()
+        .and_then(|token| token.parse().ok())
+        .ok_or_else(|| {
+            format!(
+                "Bad number in Lynx directory: \"{}\"",
+                String::from_utf8_lossy(field).trim()
+            )
+        })
+}
+
+/// Split a Lynx archive into its files. REL files are skipped: their side
+/// sectors have no use outside a disk.
+///
+/// The directory follows the BASIC stub as CR-terminated fields: the
+/// signature line (starting with the directory size in blocks), the file
+/// count, then per file its name, size in blocks, type letter (plus the
+/// record length for REL) and the bytes used in its last block plus one.
+/// File data starts after the directory blocks, each file on a block
+/// boundary.
+pub fn unpack_lynx(data: &[u8]) -> Result<Vec<LynxFile>, String> {
+    let head = &data[..data.len().min(1024)];
+    let signature = head
+        .windows(4)
+        .position(|w| w == b"LYNX")
+        .ok_or("Not a Lynx archive")?;
+    let line_start = head[..signature]
+        .iter()
+        .rposition(|&b| b == 0x0D)
+        .map_or(0, |p| p + 1);
+    let mut fields = data[line_start..].split(|&b| b == 0x0D);
+    let dir_blocks = lynx_number(fields.next())?;
+    let count = lynx_number(fields.next())?;
+
+    let mut offset = dir_blocks
+        .checked_mul(LYNX_BLOCK)
+        .ok_or("Lynx directory size is out of range")?;
+    let mut files = Vec::new();
+    for _ in 0..count {
+        let raw_name = fields.next().ok_or("Lynx directory ends early")?;
+        let name = petscii::to_string(raw_name).trim().to_string();
+        let blocks = lynx_number(fields.next())?;
+        let type_letter = fields
+            .next()
+            .and_then(|f| f.iter().find(|b| !b.is_ascii_whitespace()))
+            .copied()
+            .ok_or("Lynx directory ends early")?;
+        let file_type = match type_letter {
+            b'P' => FileType::Prg,
+            b'S' => FileType::Seq,
+            b'U' => FileType::Usr,
+            b'R' => {
+                lynx_number(fields.next())?; // record length
+                FileType::Rel
+            }
+            other => {
+                return Err(format!(
+                    "\"{}\": unknown Lynx file type '{}'",
+                    name, other as char
+                ))
+            }
+        };
+        let last_used = lynx_number(fields.next())?;
+
+        let start = offset;
+        offset = blocks
+            .checked_mul(LYNX_BLOCK)
+            .and_then(|len| offset.checked_add(len))
+            .ok_or_else(|| format!("\"{}\": block count {} is out of range", name, blocks))?;
+        if file_type == FileType::Rel {
+            log::info!("Lynx: skipping REL file \"{}\"", name);
+            continue;
+        }
+        let size =
+            (blocks.saturating_sub(1) * LYNX_BLOCK).saturating_add(last_used.saturating_sub(1));
+        let body = data
+            .get(start..start.saturating_add(size))
+            .ok_or_else(|| format!("\"{}\" runs past the end of the archive", name))?;
+        files.push(LynxFile {
+            name,
+            file_type,
+            data: body.to_vec(),
+        });
+    }
+    Ok(files)
+}
+
+// ─── Zipcode ──────────────────────────────────────────────────────────────────
+
+/// Size of the 35-track D64 a Zipcode set rebuilds
+const D64_SIZE: usize = 174_848;
+
+/// Part number and set name of a Zipcode file: `"3!GAME"` → `(3, "GAME")`
+pub fn zipcode_part(filename: &str) -> Option<(u8, &str)> {
+    match filename.as_bytes() {
+        [digit @ b'1'..=b'4', b'!', _, ..] => Some((digit - b'0', &filename[2..])),
+        _ => None,
+    }
+}
+
+fn take<'a>(part: &'a [u8], pos: &mut usize, len: usize) -> Result<&'a [u8], String> {
+    let bytes = part
+        .get(*pos..*pos + len)
+        .ok_or("Zipcode part ends inside a sector")?;
+    *pos += len;
+    Ok(bytes)
+}
+
+/// Rebuild a D64 from the four parts of a Zipcode set, given in order.
+///
+/// Each part is a PRG: its load address (part 1 also carries the disk ID)
+/// and then one record per sector. A record starts with the track, whose
+/// top two bits pick the encoding, and the sector:
+/// - `00`: 256 raw bytes
+/// - `01`: one byte that fills the whole sector
+/// - `10`: a length, an escape byte and `length` encoded bytes, in which
+///   escape, count, value stands for `count` copies of `value`
+pub fn zipcode_to_d64(parts: &[&[u8]]) -> Result<Vec<u8>, String> {
+    if parts.len() != 4 {
+        return Err(format!("A Zipcode set has 4 parts, got {}", parts.len()));
+    }
+    let mut image = vec![0u8; D64_SIZE];
+    let mut present = vec![false; D64_SIZE / 256];
+
+    for (index, part) in parts.iter().enumerate() {
+        let mut pos = if index == 0 { 4 } else { 2 };
+        while pos + 2 <= part.len() {
+            let (mode, track, sector) = (part[pos] >> 6, part[pos] & 0x3F, part[pos + 1]);
+            pos += 2;
+            let offset = disk_image::ts_offset(track, sector, ImageKind::D64)
+                .filter(|&o| o < D64_SIZE)
+                .ok_or_else(|| {
+                    format!(
+                        "Zipcode part {} names T{} S{}, which is not on a 35-track disk",
+                        index + 1,
+                        track,
+                        sector
+                    )
+                })?;
+            let block = &mut image[offset..offset + 256];
+            match mode {
+                0 => block.copy_from_slice(take(part, &mut pos, 256)?),
+                1 => block.fill(take(part, &mut pos, 1)?[0]),
+                2 => {
+                    let header = take(part, &mut pos, 2)?;
+                    let (len, escape) = (header[0] as usize, header[1]);
+                    let encoded = take(part, &mut pos, len)?;
+                    let mut out = Vec::with_capacity(256);
+                    let mut i = 0;
+                    while i < encoded.len() {
+                        if encoded[i] == escape && i + 2 < encoded.len() {
+                            out.extend(std::iter::repeat_n(
+                                encoded[i + 2],
+                                encoded[i + 1] as usize,
+                            ));
+                            i += 3;
+                        } else {
+                            out.push(encoded[i]);
+                            i += 1;
+                        }
+                    }
+                    if out.len() != 256 {
+                        return Err(format!(
+                            "T{} S{} unpacks to {} bytes instead of 256",
+                            track,
+                            sector,
+                            out.len()
+                        ));
+                    }
+                    block.copy_from_slice(&out);
+                }
+                _ => {
+                    return Err(format!(
+                        "Zipcode part {}: unknown record type at T{} S{}",
+                        index + 1,
+                        track,
+                        sector
+                    ))
+                }
+            }
+            present[offset / 256] = true;
+        }
+    }
+
+    let missing = present.iter().filter(|&&p| !p).count();
+    if missing > 0 {
+        return Err(format!(
+            "Zipcode set is incomplete: {} sectors missing",
+            missing
+        ));
+    }
+    Ok(image)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unpack_lynx() {
+        let mut archive = vec![0x01, 0x08, 0x0B, 0x08, 0x0A, 0x00, 0x9E, 0x00, 0x00, 0x00];
+        archive.extend_from_slice(b"\r 1  *LYNX XV  BY WILL CORLEY\r 3 \r");
+        archive.extend_from_slice(b"HELLO\xA0\xA0\r 2 \rP\r 11 \r");
+        archive.extend_from_slice(b"TABLE\r 1 \rR\r 10 \r 21 \r");
+        archive.extend_from_slice(b"NOTES\r 1 \rS\r 5 \r");
+        archive.resize(LYNX_BLOCK, 0);
+        let hello: Vec<u8> = (0..LYNX_BLOCK + 10).map(|i| i as u8).collect();
+        archive.extend(&hello);
+        archive.resize(3 * LYNX_BLOCK, 0);
+        archive.extend([0xAA; LYNX_BLOCK]); // REL blocks
+        archive.extend(b"TEXT");
+
+        let files = unpack_lynx(&archive).unwrap();
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].name, "HELLO");
+        assert_eq!(files[0].file_type, FileType::Prg);
+        assert_eq!(files[0].data, hello);
+        assert_eq!(files[1].file_type, FileType::Seq);
+        assert_eq!(files[1].data, b"TEXT");
+
+        assert!(unpack_lynx(b"not an archive").is_err());
+
+        // Block counts that would overflow the offset are an error, not a panic
+        let huge = format!(
+            "\r 1  *LYNX XV\r 2 \rBIG\r {} \rP\r 5 \rAFTER\r 1 \rP\r 5 \r",
+            usize::MAX / 2
+        );
+        assert!(unpack_lynx(huge.as_bytes()).is_err());
+    }
+
+    /// Zipcode-encode every sector of a 35-track image into four parts
+    fn zipcode_encode(image: &[u8]) -> Vec<Vec<u8>> {
+        let mut parts = vec![vec![0xFE, 0x03, b'0', b'1']];
+        for _ in 0..3 {
+            parts.push(vec![0x00, 0x04]);
+        }
+        for track in 1..=35u8 {
+            let part = &mut parts[match track {
+                1..=8 => 0,
+                9..=16 => 1,
+                17..=25 => 2,
+                _ => 3,
+            }];
+            for sector in 0..disk_image::spt_1541(track).unwrap() {
+                let offset = disk_image::ts_offset(track, sector, ImageKind::D64).unwrap();
+                let block = &image[offset..offset + 256];
+                if block.iter().all(|&b| b == block[0]) {
+                    part.extend([0x40 | track, sector, block[0]]);
+                    continue;
+                }
+                let escape = (0..=255u8).find(|e| !block.contains(e)).unwrap();
+                let mut encoded = Vec::new();
+                let mut i = 0;
+                while i < 256 {
+                    let run = block[i..].iter().take_while(|&&b| b == block[i]).count();
+                    if run >= 4 {
+                        encoded.extend([escape, run.min(255) as u8, block[i]]);
+                        i += run.min(255);
+                    } else {
+                        encoded.push(block[i]);
+                        i += 1;
+                    }
+                }
+                if encoded.len() < 256 {
+                    part.extend([0x80 | track, sector, encoded.len() as u8, escape]);
+                    part.extend(encoded);
+                } else {
+                    part.extend([track, sector]);
+                    part.extend(block);
+                }
+            }
+        }
+        parts
+    }
+
+    #[test]
+    fn test_zipcode_roundtrip() {
+        let mut image = disk_image::build_blank_d64("ZIPPED", "01 2A");
+        let payload: Vec<u8> = (0..3000u32).map(|i| (i * 7 % 251) as u8).collect();
+        disk_image::insert_file(&mut image, "GAME", FileType::Prg, &payload).unwrap();
+
+        let parts = zipcode_encode(&image);
+        let refs: Vec<&[u8]> = parts.iter().map(Vec::as_slice).collect();
+        assert_eq!(zipcode_to_d64(&refs).unwrap(), image);
+
+        let mut short = refs.clone();
+        short[3] = &parts[3][..parts[3].len() / 2];
+        assert!(zipcode_to_d64(&short).is_err());
+
+        assert_eq!(zipcode_part("2!GAME"), Some((2, "GAME")));
+        assert_eq!(zipcode_part("5!GAME"), None);
+        assert_eq!(zipcode_part("1!"), None);
+    }
+}
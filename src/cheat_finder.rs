@@ -0,0 +1,215 @@
+//! Cheat finder: narrow down the address of a game variable (lives, energy,
+//! timer…) by comparing full 64 KB snapshots taken while the game runs.
+//!
+//! A search starts with every address as a candidate. Each later snapshot is
+//! compared against the previous one and only the addresses that pass the
+//! chosen filter survive. Confirmed addresses become [`Freeze`] entries that
+//! the memory editor keeps writing back.
+
+/// Size of a full C64 RAM snapshot
+pub const SNAPSHOT_LEN: usize = 0x10000;
+
+/// How a byte must have changed between two snapshots to stay a candidate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CheatFilter {
+    #[default]
+    Changed,
+    Unchanged,
+    Increased,
+    Decreased,
+    /// Dropped by exactly N (wrapping), e.g. a life lost
+    DecreasedBy,
+    /// Rose by exactly N (wrapping)
+    IncreasedBy,
+    /// Now holds N
+    Equals,
+}
+
+impl CheatFilter {
+    pub const ALL: [CheatFilter; 7] = [
+        CheatFilter::Changed,
+        CheatFilter::Unchanged,
+        CheatFilter::Increased,
+        CheatFilter::Decreased,
+        CheatFilter::DecreasedBy,
+        CheatFilter::IncreasedBy,
+        CheatFilter::Equals,
+    ];
+
+    /// Whether the filter uses the N value
+    pub fn takes_value(self) -> bool {
+        matches!(
+            self,
+            CheatFilter::DecreasedBy | CheatFilter::IncreasedBy | CheatFilter::Equals
+        )
+    }
+
+    pub fn matches(self, n: u8, previous: u8, current: u8) -> bool {
+        match self {
+            CheatFilter::Changed => current != previous,
+            CheatFilter::Unchanged => current == previous,
+            CheatFilter::Increased => current > previous,
+            CheatFilter::Decreased => current < previous,
+            CheatFilter::DecreasedBy => previous.wrapping_sub(current) == n,
+            CheatFilter::IncreasedBy => current.wrapping_sub(previous) == n,
+            CheatFilter::Equals => current == n,
+        }
+    }
+}
+
+impl std::fmt::Display for CheatFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CheatFilter::Changed => write!(f, "Changed"),
+            CheatFilter::Unchanged => write!(f, "Unchanged"),
+            CheatFilter::Increased => write!(f, "Increased"),
+            CheatFilter::Decreased => write!(f, "Decreased"),
+            CheatFilter::DecreasedBy => write!(f, "Decreased by N"),
+            CheatFilter::IncreasedBy => write!(f, "Increased by N"),
+            CheatFilter::Equals => write!(f, "Equals N"),
+        }
+    }
+}
+
+/// One candidate with its value in the last two snapshots
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Candidate {
+    pub address: u16,
+    pub previous: u8,
+    pub current: u8,
+}
+
+/// An in-progress search
+#[derive(Debug, Clone)]
+pub struct CheatSearch {
+    previous: Vec<u8>,
+    current: Vec<u8>,
+    candidates: Vec<u16>,
+    rounds: usize,
+}
+
+impl CheatSearch {
+    /// Start from a first snapshot; every address is a candidate.
+    pub fn new(snapshot: Vec<u8>) -> Result<Self, String> {
+        check_len(&snapshot)?;
+        Ok(Self {
+            previous: snapshot.clone(),
+            current: snapshot,
+            candidates: (0..=u16::MAX).collect(),
+            rounds: 0,
+        })
+    }
+
+    /// Compare a new snapshot against the last one and drop every candidate
+    /// that fails `filter`.
+    pub fn refine(&mut self, filter: CheatFilter, n: u8, snapshot: Vec<u8>) -> Result<(), String> {
+        check_len(&snapshot)?;
+        self.previous = std::mem::replace(&mut self.current, snapshot);
+        let (previous, current) = (&self.previous, &self.current);
+        self.candidates
+            .retain(|&a| filter.matches(n, previous[a as usize], current[a as usize]));
+        self.rounds += 1;
+        Ok(())
+    }
+
+    pub fn candidate_count(&self) -> usize {
+        self.candidates.len()
+    }
+
+    /// Snapshots compared so far
+    pub fn rounds(&self) -> usize {
+        self.rounds
+    }
+
+    /// The first `limit` candidates
+    pub fn candidates(&self, limit: usize) -> impl Iterator<Item = Candidate> + '_ {
+        self.candidates.iter().take(limit).map(|&a| Candidate {
+            address: a,
+            previous: self.previous[a as usize],
+            current: self.current[a as usize],
+        })
+    }
+}
+
+fn check_len(snapshot: &[u8]) -> Result<(), String> {
+    if snapshot.len() == SNAPSHOT_LEN {
+        Ok(())
+    } else {
+        Err(format!(
+            "Snapshot is {} bytes, expected {}",
+            snapshot.len(),
+            SNAPSHOT_LEN
+        ))
+    }
+}
+
+/// An address pinned to a value, re-written on every watch tick
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Freeze {
+    pub address: u16,
+    pub value: u8,
+    pub enabled: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(pokes: &[(u16, u8)]) -> Vec<u8> {
+        let mut data = vec![0u8; SNAPSHOT_LEN];
+        for &(a, v) in pokes {
+            data[a as usize] = v;
+        }
+        data
+    }
+
+    #[test]
+    fn lives_counter_is_found_in_three_rounds() {
+        // $0810 holds lives (3 → 2 → 2 → 1), $0900 is a noisy timer.
+        let mut search = CheatSearch::new(snapshot(&[(0x0810, 3), (0x0900, 10)])).unwrap();
+        assert_eq!(search.candidate_count(), SNAPSHOT_LEN);
+
+        search
+            .refine(
+                CheatFilter::DecreasedBy,
+                1,
+                snapshot(&[(0x0810, 2), (0x0900, 9)]),
+            )
+            .unwrap();
+        assert_eq!(search.candidate_count(), 2);
+
+        search
+            .refine(
+                CheatFilter::Unchanged,
+                0,
+                snapshot(&[(0x0810, 2), (0x0900, 8)]),
+            )
+            .unwrap();
+        search
+            .refine(
+                CheatFilter::Equals,
+                1,
+                snapshot(&[(0x0810, 1), (0x0900, 7)]),
+            )
+            .unwrap();
+
+        let found: Vec<Candidate> = search.candidates(10).collect();
+        assert_eq!(
+            found,
+            vec![Candidate {
+                address: 0x0810,
+                previous: 2,
+                current: 1
+            }]
+        );
+        assert_eq!(search.rounds(), 3);
+    }
+
+    #[test]
+    fn filters_wrap_and_reject_short_snapshots() {
+        assert!(CheatFilter::DecreasedBy.matches(1, 0x00, 0xFF));
+        assert!(CheatFilter::IncreasedBy.matches(2, 0xFF, 0x01));
+        assert!(!CheatFilter::Decreased.matches(0, 0x00, 0xFF));
+        assert!(CheatSearch::new(vec![0; 100]).is_err());
+    }
+}
@@ -0,0 +1,759 @@
+//! Headless command-line mode.
+//!
+//! `ultimate64-manager <command> [options]` runs one device operation and
+//! exits instead of opening the GUI, so build scripts and CI can drive a lab
+//! machine. Every command goes through the same `api` / `ftp_ops` /
+//! `profile_api` / `screenshot_api` functions the tabs use, with the
+//! connection taken from the saved `profiles::ProfileManager` profile
+//! (`--profile`), overridable with `--host` / `--password`.
+//!
+//! Exit codes: 0 success, 1 operation failed, 2 usage error, 3+ a classified
+//! [`DeviceError`] (see [`DeviceError::exit_code`]).
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use crate::device_error::DeviceError;
+use crate::remote_device::RemoteDevice;
+
+const USAGE: &str = "\
+Usage: ultimate64-manager <command> [options]
+
+Commands:
+  run <file>                 Run a PRG/CRT/SID/MOD or disk image (local file,
+                             or a path on the device filesystem)
+  mount <image>              Mount a disk image (local or on the device)
+  upload <file> [dir]        Copy a local file to the device via FTP (default /Temp)
+  peek <addr> [len]          Hex-dump C64 memory (--out FILE writes raw bytes)
+  poke <addr> <byte>...      Write bytes to C64 memory
+  reset                      Reset the C64
+  apply-profile <name|path>  Apply a device profile from the profile repository
+  screenshot [out.png]       Capture the screen via the REST API
+  test <suite.json>          Run a test suite and report JUnit XML (stdout,
+                             or --out FILE)
+  simulate                   Serve a simulated device (REST, FTP, port 64)
+                             on this machine until stopped
+  monitor                    Serve the VICE binary monitor protocol for the
+                             device until stopped
+
+Options:
+  --profile NAME             Saved connection profile (default: the active one)
+  --host HOST                Device host/IP (overrides the profile)
+  --password PW              Network password (overrides the profile)
+  --drive a|b                Drive for run/mount (default a)
+  --mode MODE                Mount mode: readwrite, readonly, unlinked (default readwrite)
+  --flash-defaults           apply-profile: load flash defaults before applying
+  --out FILE                 peek: write raw bytes to FILE instead of a hex dump;
+                             test: write the JUnit XML to FILE
+  --sd DIR                   simulate: folder backing the virtual SD card
+  --bind ADDR                simulate/monitor: address to listen on (default 127.0.0.1)
+  --port-offset N            simulate: add N to ports 80/21/64 (no root needed)
+  --port N                   monitor: port to listen on (default 6502)
+  -h, --help                 Show this help
+
+Numbers accept $C000, 0xC000, C000h or decimal.";
+
+const COMMANDS: &[&str] = &[
+    "run",
+    "mount",
+    "upload",
+    "peek",
+    "poke",
+    "reset",
+    "apply-profile",
+    "screenshot",
+    "test",
+    "simulate",
+    "monitor",
+];
+
+// ─── Errors ──────────────────────────────────────────────────────────────────
+
+#[derive(Debug)]
+pub enum CliError {
+    /// Bad arguments — prints usage.
+    Usage(String),
+    /// Classified device failure.
+    Device(DeviceError),
+    /// Anything else the operation reported.
+    Failed(String),
+}
+
+impl CliError {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            CliError::Usage(_) => 2,
+            CliError::Device(e) => e.exit_code(),
+            CliError::Failed(_) => 1,
+        }
+    }
+}
+
+impl std::fmt::Display for CliError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CliError::Usage(msg) => write!(f, "{}", msg),
+            CliError::Device(e) => write!(f, "{}", e),
+            CliError::Failed(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl From<DeviceError> for CliError {
+    fn from(e: DeviceError) -> Self {
+        CliError::Device(e)
+    }
+}
+
+impl From<String> for CliError {
+    fn from(e: String) -> Self {
+        CliError::Failed(e)
+    }
+}
+
+// ─── Argument parsing ────────────────────────────────────────────────────────
+
+#[derive(Debug, Default, PartialEq)]
+struct Invocation {
+    command: String,
+    args: Vec<String>,
+    profile: Option<String>,
+    host: Option<String>,
+    password: Option<String>,
+    drive: Option<String>,
+    mode: Option<String>,
+    out: Option<PathBuf>,
+    flash_defaults: bool,
+    sd: Option<PathBuf>,
+    bind: Option<String>,
+    port_offset: Option<String>,
+    port: Option<String>,
+}
+
+/// Whether `argv` asks for the CLI at all. Anything else (no arguments, or
+/// platform noise like macOS `-psn_…`) starts the GUI.
+fn wants_cli(argv: &[String]) -> bool {
+    argv.first().is_some_and(|a| {
+        COMMANDS.contains(&a.as_str()) || a == "--help" || a == "-h" || a == "help"
+    })
+}
+
+fn parse_args(argv: &[String]) -> Result<Invocation, CliError> {
+    let mut inv = Invocation::default();
+    let mut it = argv.iter();
+    let value = |it: &mut std::slice::Iter<String>, flag: &str| {
+        it.next()
+            .cloned()
+            .ok_or_else(|| CliError::Usage(format!("{} needs a value", flag)))
+    };
+    while let Some(arg) = it.next() {
+        match arg.as_str() {
+            "--profile" => inv.profile = Some(value(&mut it, arg)?),
+            "--host" => inv.host = Some(value(&mut it, arg)?),
+            "--password" => inv.password = Some(value(&mut it, arg)?),
+            "--drive" => inv.drive = Some(value(&mut it, arg)?.to_lowercase()),
+            "--mode" => inv.mode = Some(value(&mut it, arg)?.to_lowercase()),
+            "--out" => inv.out = Some(PathBuf::from(value(&mut it, arg)?)),
+            "--flash-defaults" => inv.flash_defaults = true,
+            "--sd" => inv.sd = Some(PathBuf::from(value(&mut it, arg)?)),
+            "--bind" => inv.bind = Some(value(&mut it, arg)?),
+            "--port-offset" => inv.port_offset = Some(value(&mut it, arg)?),
+            "--port" => inv.port = Some(value(&mut it, arg)?),
+            "-h" | "--help" | "help" if inv.command.is_empty() => inv.command = "help".into(),
+            flag if flag.starts_with("--") => {
+                return Err(CliError::Usage(format!("Unknown option {}", flag)))
+            }
+            _ if inv.command.is_empty() => inv.command = arg.clone(),
+            _ => inv.args.push(arg.clone()),
+        }
+    }
+    if inv.command != "help" && !COMMANDS.contains(&inv.command.as_str()) {
+        return Err(CliError::Usage(format!(
+            "Unknown command '{}'",
+            inv.command
+        )));
+    }
+    if let Some(d) = &inv.drive {
+        if d != "a" && d != "b" {
+            return Err(CliError::Usage("--drive must be a or b".into()));
+        }
+    }
+    if let Some(m) = &inv.mode {
+        if !["readwrite", "readonly", "unlinked"].contains(&m.as_str()) {
+            return Err(CliError::Usage(
+                "--mode must be readwrite, readonly or unlinked".into(),
+            ));
+        }
+    }
+    Ok(inv)
+}
+
+fn parse_u16(s: &str, what: &str) -> Result<u16, CliError> {
+    crate::memory_editor::parse_length_input(s)
+        .and_then(|v| u16::try_from(v).ok())
+        .ok_or_else(|| CliError::Usage(format!("Invalid {} '{}'", what, s)))
+}
+
+fn parse_byte(s: &str) -> Result<u8, CliError> {
+    crate::memory_editor::parse_length_input(s)
+        .and_then(|v| u8::try_from(v).ok())
+        .ok_or_else(|| CliError::Usage(format!("Invalid byte '{}'", s)))
+}
+
+fn arg<'a>(inv: &'a Invocation, i: usize, what: &str) -> Result<&'a str, CliError> {
+    inv.args
+        .get(i)
+        .map(String::as_str)
+        .ok_or_else(|| CliError::Usage(format!("{} needs {}", inv.command, what)))
+}
+
+// ─── Connection ──────────────────────────────────────────────────────────────
+
+/// Resolved connection: bare host for the `api` runners / FTP, the
+/// `http://` URL for the pool-free helpers, and the REST client for memory.
+struct Target {
+    host: String,
+    host_url: String,
+    password: Option<String>,
+    connection: Arc<Mutex<dyn RemoteDevice>>,
+}
+
+fn resolve_target(inv: &Invocation) -> Result<Target, CliError> {
+    let profiles = crate::profiles::ProfileManager::load();
+    let settings = match &inv.profile {
+        Some(name) => profiles
+            .profiles
+            .iter()
+            .find(|p| p.name.eq_ignore_ascii_case(name))
+            .map(|p| &p.settings)
+            .ok_or_else(|| {
+                CliError::Usage(format!(
+                    "No saved profile '{}' (have: {})",
+                    name,
+                    profiles.profile_names().join(", ")
+                ))
+            })?,
+        None => profiles.active_settings(),
+    };
+
+    let host = inv
+        .host
+        .clone()
+        .unwrap_or_else(|| settings.connection.host.clone())
+        .trim()
+        .trim_start_matches("http://")
+        .trim_end_matches('/')
+        .to_string();
+    if host.is_empty() {
+        return Err(DeviceError::NotConnected.into());
+    }
+    let password = inv
+        .password
+        .clone()
+        .or_else(|| settings.connection.password.clone())
+        .filter(|p| !p.is_empty());
+
+    let parsed = if let Ok(ip) = host.parse::<std::net::Ipv4Addr>() {
+        url::Host::Ipv4(ip)
+    } else if let Ok(ip) = host.parse::<std::net::Ipv6Addr>() {
+        url::Host::Ipv6(ip)
+    } else {
+        url::Host::parse(&host).map_err(|e| CliError::Usage(format!("Invalid host: {}", e)))?
+    };
+    let rest = ultimate64::Rest::new(&parsed, password.clone())
+        .map_err(|e| DeviceError::Build(e.to_string()))?;
+
+    Ok(Target {
+        host_url: format!("http://{}", host),
+        host,
+        password,
+        connection: Arc::new(Mutex::new(rest)),
+    })
+}
+
+/// Cheap authenticated request up front so an offline device or a wrong
+/// password surfaces as a classified [`DeviceError`] (and exit code) rather
+/// than whatever message the operation itself produces.
+async fn probe(target: &Target) -> Result<(), DeviceError> {
+    let client = crate::net_utils::build_device_client(5).map_err(DeviceError::Build)?;
+    let req = crate::net_utils::with_password(
+        client.get(format!("{}/v1/version", target.host_url)),
+        target.password.as_deref(),
+    );
+    crate::net_utils::device_send(req).await.map(|_| ())
+}
+
+// ─── Commands ────────────────────────────────────────────────────────────────
+
+fn extension(path: &str) -> String {
+    Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .unwrap_or_default()
+}
+
+fn is_disk_image(ext: &str) -> bool {
+    matches!(ext, "d64" | "d71" | "d81" | "g64" | "g71")
+}
+
+async fn cmd_run(inv: &Invocation, t: &Target) -> Result<String, CliError> {
+    let file = arg(inv, 0, "a file")?;
+    let ext = extension(file);
+    let drive = inv.drive.as_deref().unwrap_or("a");
+    let local = Path::new(file);
+
+    if local.is_file() {
+        if is_disk_image(&ext) {
+            return Ok(crate::api::run_local_disk_async(
+                &t.host_url,
+                local,
+                drive,
+                t.password.as_deref(),
+                Some(t.connection.clone()),
+            )
+            .await?);
+        }
+        let runner = match ext.as_str() {
+            "prg" => "run_prg",
+            "crt" => "run_crt",
+            "sid" => "sidplay",
+            "mod" => "modplay",
+            _ => {
+                return Err(CliError::Usage(format!(
+                    "Don't know how to run .{} files",
+                    ext
+                )))
+            }
+        };
+        let data = std::fs::read(local).map_err(|e| format!("Read {}: {}", file, e))?;
+        crate::api::upload_runner_async(&t.host_url, runner, data, t.password.as_deref()).await?;
+        return Ok(format!("Running: {}", file));
+    }
+
+    // Not a local file: treat it as a path on the device.
+    let pw = t.password.clone();
+    let msg = match ext.as_str() {
+        "prg" => crate::api::run_prg(&t.host, file, pw).await?,
+        "crt" => crate::api::run_crt(&t.host, file, pw).await?,
+        "sid" => crate::api::sidplay(&t.host, file, pw).await?,
+        "mod" => crate::api::modplay(&t.host, file, pw).await?,
+        e if is_disk_image(e) => {
+            crate::api::run_disk(&t.host, file, drive, pw, Some(t.connection.clone())).await?
+        }
+        _ => {
+            return Err(CliError::Usage(format!(
+                "Don't know how to run .{} files",
+                ext
+            )))
+        }
+    };
+    Ok(msg)
+}
+
+async fn cmd_mount(inv: &Invocation, t: &Target) -> Result<String, CliError> {
+    let image = arg(inv, 0, "a disk image")?;
+    let drive = inv.drive.as_deref().unwrap_or("a");
+    let mode = inv.mode.as_deref().unwrap_or("readwrite");
+    if !is_disk_image(&extension(image)) {
+        return Err(CliError::Usage(format!("{} is not a disk image", image)));
+    }
+    let local = Path::new(image);
+    if local.is_file() {
+        crate::api::upload_mount_disk_async(&t.host_url, local, drive, mode, t.password.as_deref())
+            .await?;
+        Ok(format!(
+            "Mounted: {} on drive {}",
+            image,
+            drive.to_uppercase()
+        ))
+    } else {
+        Ok(crate::api::mount_disk(&t.host, image, drive, mode, t.password.clone()).await?)
+    }
+}
+
+async fn cmd_upload(inv: &Invocation, t: &Target) -> Result<String, CliError> {
+    let file = PathBuf::from(arg(inv, 0, "a local file")?);
+    if !file.is_file() {
+        return Err(CliError::Usage(format!("{} is not a file", file.display())));
+    }
+    let dir = inv
+        .args
+        .get(1)
+        .cloned()
+        .unwrap_or_else(|| "/Temp".to_string());
+    let progress = Arc::new(Mutex::new(None));
+    let name = crate::ftp_ops::upload_file_ftp_to_dir(
+        t.host.clone(),
+        file,
+        dir.clone(),
+        t.password.clone(),
+        progress,
+    )
+    .await?;
+    Ok(format!("Uploaded: {}/{}", dir.trim_end_matches('/'), name))
+}
+
+/// Classic 16-bytes-per-row dump with a printable-ASCII column.
+fn hex_dump(address: u16, data: &[u8]) -> String {
+    let mut out = String::new();
+    for (i, row) in data.chunks(16).enumerate() {
+        let addr = address.wrapping_add((i * 16) as u16);
+        let hex: Vec<String> = row.iter().map(|b| format!("{:02X}", b)).collect();
+        let ascii: String = row
+            .iter()
+            .map(|&b| {
+                if (0x20..0x7F).contains(&b) {
+                    b as char
+                } else {
+                    '.'
+                }
+            })
+            .collect();
+        out.push_str(&format!(
+            "${:04X}: {:<47}  {}\n",
+            addr,
+            hex.join(" "),
+            ascii
+        ));
+    }
+    out
+}
+
+async fn cmd_peek(inv: &Invocation, t: &Target) -> Result<String, CliError> {
+    let address = parse_u16(arg(inv, 0, "an address")?, "address")?;
+    let length = match inv.args.get(1) {
+        Some(s) => crate::memory_editor::parse_length_input(s)
+            .filter(|&n| (1..=0x10000).contains(&n))
+            .ok_or_else(|| CliError::Usage(format!("Invalid length '{}'", s)))?,
+        None => 1,
+    };
+    let data = crate::api::read_memory_async(t.connection.clone(), address, length).await?;
+    match &inv.out {
+        Some(path) => {
+            std::fs::write(path, &data).map_err(|e| format!("Write {}: {}", path.display(), e))?;
+            Ok(format!("Wrote {} bytes to {}", data.len(), path.display()))
+        }
+        None => Ok(hex_dump(address, &data).trim_end().to_string()),
+    }
+}
+
+async fn cmd_poke(inv: &Invocation, t: &Target) -> Result<String, CliError> {
+    let address = parse_u16(arg(inv, 0, "an address")?, "address")?;
+    arg(inv, 1, "at least one byte")?;
+    let bytes = inv.args[1..]
+        .iter()
+        .map(|s| parse_byte(s))
+        .collect::<Result<Vec<u8>, _>>()?;
+    let n = bytes.len();
+    crate::api::write_memory_async(t.connection.clone(), address, bytes).await?;
+    Ok(format!("Wrote {} byte(s) at ${:04X}", n, address))
+}
+
+async fn cmd_apply_profile(inv: &Invocation, t: &Target) -> Result<String, CliError> {
+    use crate::profile_repo::ProfileRepo;
+
+    let wanted = arg(inv, 0, "a profile name, id or path")?;
+    let root = ProfileRepo::default_path()
+        .ok_or_else(|| "Could not locate the profile repository".to_string())?;
+    let repo = ProfileRepo::new(root);
+
+    let path = if Path::new(wanted).is_file() {
+        PathBuf::from(wanted)
+    } else {
+        repo.list_profiles()?
+            .into_iter()
+            .find(|p| p.id.eq_ignore_ascii_case(wanted) || p.name.eq_ignore_ascii_case(wanted))
+            .map(|p| p.path)
+            .ok_or_else(|| format!("No profile '{}' in {}", wanted, repo.root().display()))?
+    };
+    let profile = crate::profile_repo::load_profile_async(path).await?;
+
+    // Same diff the Profiles tab sends; without a stored baseline the whole
+    // profile config is applied.
+    let baseline = match repo.load_baseline("default") {
+        Ok(b) => b.config,
+        Err(_) => {
+            eprintln!("No stored baseline — applying the full profile config");
+            Default::default()
+        }
+    };
+    let diff = crate::device_profile::diff_configs(&baseline, &profile.config);
+    let pre_clean = if inv.flash_defaults { 2 } else { 0 };
+    Ok(crate::profile_api::apply_profile(
+        t.host_url.clone(),
+        &profile,
+        diff,
+        t.password.clone(),
+        Some(t.connection.clone()),
+        pre_clean,
+    )
+    .await?)
+}
+
+async fn cmd_screenshot(inv: &Invocation, t: &Target) -> Result<String, CliError> {
+    let host = t.host.clone();
+    let password = t.password.clone();
+    let saved = tokio::task::spawn_blocking(move || {
+        crate::screenshot_api::capture_screenshot_via_api(&host, password)
+    })
+    .await
+    .map_err(|e| format!("Task error: {}", e))??;
+
+    match inv.args.first() {
+        Some(dest) => {
+            std::fs::copy(&saved, dest).map_err(|e| format!("Copy to {}: {}", dest, e))?;
+            let _ = std::fs::remove_file(&saved);
+            Ok(format!("Screenshot saved: {}", dest))
+        }
+        None => Ok(format!("Screenshot saved: {}", saved)),
+    }
+}
+
+/// `test`: run a suite. Progress goes to stderr; the JUnit XML goes to
+/// `--out` or, without it, stdout. Any failing case fails the command.
+async fn cmd_test(inv: &Invocation, t: &Target) -> Result<String, CliError> {
+    use crate::test_harness::{self, Rig, Suite};
+
+    let path = PathBuf::from(arg(inv, 0, "a suite file")?);
+    let suite = Suite::load(&path)?;
+    let base_dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+    let connection = t.connection.clone();
+    let host = t.host.clone();
+    let password = t.password.clone();
+    let result = tokio::task::spawn_blocking(move || {
+        let capture = move || crate::screenshot_api::capture_frame_via_api(&host, password.clone());
+        let conn = connection.lock().unwrap();
+        let rig = Rig {
+            conn: &*conn,
+            capture: &capture,
+            base_dir,
+            poll: test_harness::POLL,
+        };
+        test_harness::run_suite(&suite, &rig, |case| eprintln!("{}", case.summary()))
+    })
+    .await
+    .map_err(|e| format!("Task error: {}", e))?;
+
+    let xml = result.to_junit();
+    let failed = result.failures() + result.errors();
+    let summary = format!(
+        "{} passed, {} failed, {} errors",
+        result.cases.len() - failed,
+        result.failures(),
+        result.errors()
+    );
+    let report = match &inv.out {
+        Some(out) => {
+            std::fs::write(out, &xml).map_err(|e| format!("Write {}: {}", out.display(), e))?;
+            format!("{} — results in {}", summary, out.display())
+        }
+        None if failed > 0 => {
+            print!("{}", xml);
+            summary
+        }
+        None => return Ok(xml.trim_end().to_string()),
+    };
+    if failed > 0 {
+        Err(CliError::Failed(report))
+    } else {
+        Ok(report)
+    }
+}
+
+/// `simulate`: blocks serving the simulated device until the process is
+/// killed; only returns on a setup error.
+fn cmd_simulate(inv: &Invocation) -> Result<String, CliError> {
+    let sd_root = match &inv.sd {
+        Some(dir) => dir.clone(),
+        None => crate::simulator::default_sd_root()?,
+    };
+    let mut config = crate::simulator::SimConfig::new(sd_root);
+    if let Some(bind) = &inv.bind {
+        config.bind = bind
+            .parse()
+            .map_err(|_| CliError::Usage(format!("Invalid --bind address '{}'", bind)))?;
+    }
+    if let Some(offset) = &inv.port_offset {
+        let offset = offset
+            .parse()
+            .map_err(|_| CliError::Usage(format!("Invalid --port-offset '{}'", offset)))?;
+        config = config.with_port_offset(offset).map_err(CliError::Usage)?;
+    }
+    config.password = inv.password.clone();
+    crate::simulator::run_forever(config)?;
+    Ok(String::new())
+}
+
+/// `monitor`: blocks serving VICE binary-monitor clients until the process
+/// is killed; only returns on a setup error.
+async fn cmd_monitor(inv: &Invocation, t: &Target) -> Result<String, CliError> {
+    let ip: std::net::IpAddr = match &inv.bind {
+        Some(bind) => bind
+            .parse()
+            .map_err(|_| CliError::Usage(format!("Invalid --bind address '{}'", bind)))?,
+        None => std::net::Ipv4Addr::LOCALHOST.into(),
+    };
+    let port = match &inv.port {
+        Some(p) => p
+            .parse()
+            .map_err(|_| CliError::Usage(format!("Invalid --port '{}'", p)))?,
+        None => crate::vice_monitor::DEFAULT_PORT,
+    };
+    let device = crate::vice_monitor::Device {
+        connection: t.connection.clone(),
+        port64: crate::port64::Port64Client::new(t.host.clone(), t.password.clone()),
+        runtime: tokio::runtime::Handle::current(),
+    };
+    tokio::task::spawn_blocking(move || {
+        crate::vice_monitor::run_forever((ip, port).into(), device)
+    })
+    .await
+    .map_err(|e| format!("Task error: {}", e))??;
+    Ok(String::new())
+}
+
+async fn execute(inv: &Invocation, target: Target) -> Result<String, CliError> {
+    probe(&target).await?;
+    match inv.command.as_str() {
+        "run" => cmd_run(inv, &target).await,
+        "mount" => cmd_mount(inv, &target).await,
+        "upload" => cmd_upload(inv, &target).await,
+        "peek" => cmd_peek(inv, &target).await,
+        "poke" => cmd_poke(inv, &target).await,
+        "reset" => {
+            crate::api::reset_machine_async(&target.host_url, target.password.as_deref()).await?;
+            Ok("Machine reset".to_string())
+        }
+        "apply-profile" => cmd_apply_profile(inv, &target).await,
+        "screenshot" => cmd_screenshot(inv, &target).await,
+        "test" => cmd_test(inv, &target).await,
+        "monitor" => cmd_monitor(inv, &target).await,
+        other => Err(CliError::Usage(format!("Unknown command '{}'", other))),
+    }
+}
+
+/// Entry point from `main`. Returns `None` when the arguments don't ask for
+/// the CLI (start the GUI), otherwise the process exit code.
+pub fn run_from_args() -> Option<i32> {
+    let argv: Vec<String> = std::env::args().skip(1).collect();
+    if !wants_cli(&argv) {
+        return None;
+    }
+
+    // The release build is a GUI-subsystem exe on Windows; borrow the
+    // calling console so output shows up in cmd/PowerShell.
+    #[cfg(target_os = "windows")]
+    // SAFETY: plain Win32 call with a constant argument, made before any output.
+    unsafe {
+        winapi::um::wincon::AttachConsole(winapi::um::wincon::ATTACH_PARENT_PROCESS);
+    }
+
+    // Warnings only: stdout is for results, stderr for problems.
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("warn")).init();
+
+    let inv = match parse_args(&argv) {
+        Ok(inv) if inv.command == "help" => {
+            println!("{}", USAGE);
+            return Some(0);
+        }
+        Ok(inv) => inv,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            return Some(e.exit_code());
+        }
+    };
+
+    if inv.command == "simulate" {
+        return Some(match cmd_simulate(&inv) {
+            Ok(_) => 0,
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                e.exit_code()
+            }
+        });
+    }
+
+    let runtime = match tokio::runtime::Runtime::new() {
+        Ok(rt) => rt,
+        Err(e) => {
+            eprintln!("Failed to start runtime: {}", e);
+            return Some(1);
+        }
+    };
+    // Outside the runtime: the REST client builds a blocking reqwest client.
+    let result = resolve_target(&inv).and_then(|target| runtime.block_on(execute(&inv, target)));
+    match result {
+        Ok(msg) => {
+            println!("{}", msg);
+            Some(0)
+        }
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            Some(e.exit_code())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn argv(s: &str) -> Vec<String> {
+        s.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn gui_launch_is_not_a_cli_call() {
+        assert!(!wants_cli(&[]));
+        assert!(!wants_cli(&argv("-psn_0_12345")));
+        assert!(wants_cli(&argv("reset")));
+        assert!(wants_cli(&argv("--help")));
+    }
+
+    #[test]
+    fn options_can_appear_anywhere() {
+        let inv = parse_args(&argv(
+            "mount --drive B game.d64 --mode readonly --host 10.0.0.2 --profile Lab",
+        ))
+        .unwrap();
+        assert_eq!(inv.command, "mount");
+        assert_eq!(inv.args, vec!["game.d64"]);
+        assert_eq!(inv.drive.as_deref(), Some("b"));
+        assert_eq!(inv.mode.as_deref(), Some("readonly"));
+        assert_eq!(inv.host.as_deref(), Some("10.0.0.2"));
+        assert_eq!(inv.profile.as_deref(), Some("Lab"));
+    }
+
+    #[test]
+    fn bad_usage_exits_with_2() {
+        for bad in [
+            "frobnicate",
+            "reset --bogus",
+            "mount x.d64 --drive c",
+            "peek --host",
+        ] {
+            let err = parse_args(&argv(bad)).unwrap_err();
+            assert_eq!(err.exit_code(), 2, "{}", bad);
+        }
+    }
+
+    #[test]
+    fn device_errors_keep_their_exit_codes() {
+        assert_eq!(CliError::from(DeviceError::Unauthorized).exit_code(), 4);
+        assert_eq!(CliError::from(DeviceError::Timeout).exit_code(), 6);
+        assert_eq!(CliError::from("boom".to_string()).exit_code(), 1);
+    }
+
+    #[test]
+    fn numbers_and_dump_format() {
+        assert_eq!(parse_u16("$D020", "address").unwrap(), 0xD020);
+        assert_eq!(parse_byte("0x0e").unwrap(), 0x0E);
+        assert!(parse_byte("$100").is_err());
+        let dump = hex_dump(0x0400, b"HELLO\x00");
+        assert_eq!(
+            dump,
+            "$0400: 48 45 4C 4C 4F 00                                HELLO.\n"
+        );
+    }
+}
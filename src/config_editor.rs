@@ -1,5 +1,6 @@
 use crate::config_api;
 use crate::config_presets::{self, ConfigPreset};
+use crate::network_watchdog;
 use crate::remote_device::RemoteDevice;
 use iced::{
     widget::{
@@ -73,6 +74,8 @@ pub enum ConfigEditorMessage {
     // Batch operations
     SaveAllChanges,
     SaveComplete(Result<String, String>),
+    /// Network settings saved and watched; the app acts on the outcome
+    NetworkSaveComplete(Result<(String, network_watchdog::Outcome), String>),
 
     // Flash operations
     SaveToFlash,
@@ -330,9 +333,25 @@ impl ConfigEditor {
                     }
 
                     self.is_loading = true;
-                    self.status_message = Some("Saving changes...".to_string());
-
                     let changes = self.pending_changes.clone();
+                    if !network_watchdog::network_changes(&changes).is_empty() {
+                        // Record the old values first so the change can be
+                        // undone if the device drops off the network.
+                        self.status_message = Some(
+                            "Saving network settings, then checking the device still answers..."
+                                .to_string(),
+                        );
+                        return Task::perform(
+                            async move {
+                                let (message, outcome) =
+                                    network_watchdog::save_guarded(host, changes, password).await?;
+                                Ok((message, outcome.ok_or("No network settings saved")?))
+                            },
+                            ConfigEditorMessage::NetworkSaveComplete,
+                        );
+                    }
+
+                    self.status_message = Some("Saving changes...".to_string());
                     Task::perform(
                         save_batch_changes(host, changes, password),
                         ConfigEditorMessage::SaveComplete,
@@ -363,6 +382,32 @@ impl ConfigEditor {
                 Task::none()
             }
 
+            ConfigEditorMessage::NetworkSaveComplete(result) => {
+                let outcome = result.as_ref().ok().map(|(_, outcome)| outcome.clone());
+                let task = self.update_impl(
+                    ConfigEditorMessage::SaveComplete(result.map(|(message, _)| message)),
+                    None,
+                    host_url,
+                    password,
+                );
+                // The settings are saved either way; a lost device is still
+                // reported as an error.
+                match outcome {
+                    Some(outcome @ network_watchdog::Outcome::Lost(_)) => {
+                        self.error_message = Some(format!("Saved, but the {}", outcome.summary()));
+                    }
+                    Some(outcome) => {
+                        self.status_message = Some(format!(
+                            "{} — {}",
+                            self.status_message.take().unwrap_or_default(),
+                            outcome.summary()
+                        ));
+                    }
+                    None => {}
+                }
+                task
+            }
+
             ConfigEditorMessage::SaveToFlash => {
                 if let Some(host) = host_url {
                     self.is_loading = true;
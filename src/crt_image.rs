@@ -0,0 +1,495 @@
+//! CRT cartridge images (the VICE `.crt` format)
+//!
+//! A 64-byte header — signature, header length, format version, hardware
+//! type, the EXROM/GAME line states and the cartridge name — is followed by
+//! CHIP packets, each holding one ROM, RAM or flash chip image with its bank
+//! number and load address. All numbers are big-endian.
+//!
+//! Provides functionality to:
+//! - Read the header and the list of CHIP packets, noting truncated packets
+//!   and hardware types the Ultimate does not emulate
+//! - Extract single chips or the whole ROM as raw BIN images
+
+use std::fs;
+use std::path::Path;
+
+const SIGNATURE: &[u8; 16] = b"C64 CARTRIDGE   ";
+const HEADER_LEN: usize = 0x40;
+const CHIP_SIGNATURE: &[u8; 4] = b"CHIP";
+const CHIP_HEADER_LEN: usize = 16;
+
+/// Hardware types the Ultimate's cartridge emulation accepts: the cases of
+/// the hardware-type switch in `C64_CRT::check_cartridge`
+/// (`software/io/c64/c64_crt.cc` in the 1541ultimate firmware sources).
+/// Extend it when a firmware release adds a type.
+const ULTIMATE_TYPES: &[u16] = &[
+    0, 1, 2, 3, 4, 5, 7, 8, 9, 10, 11, 13, 15, 17, 18, 19, 20, 21, 32, 35, 36, 60,
+];
+
+/// Name of a CRT hardware type, as numbered by VICE
+pub fn hardware_name(hardware_type: u16) -> Option<&'static str> {
+    let name = match hardware_type {
+        0 => "Normal cartridge",
+        1 => "Action Replay",
+        2 => "KCS Power Cartridge",
+        3 => "Final Cartridge III",
+        4 => "Simons' BASIC",
+        5 => "Ocean",
+        6 => "Expert Cartridge",
+        7 => "Fun Play, Power Play",
+        8 => "Super Games",
+        9 => "Atomic Power",
+        10 => "Epyx Fastload",
+        11 => "Westermann Learning",
+        12 => "Rex Utility",
+        13 => "Final Cartridge I",
+        14 => "Magic Formel",
+        15 => "C64 Game System, System 3",
+        16 => "Warp Speed",
+        17 => "Dinamic",
+        18 => "Zaxxon, Super Zaxxon",
+        19 => "Magic Desk, Domark, HES Australia",
+        20 => "Super Snapshot V5",
+        21 => "Comal-80",
+        22 => "Structured BASIC",
+        23 => "Ross",
+        24 => "Dela EP64",
+        25 => "Dela EP7x8",
+        26 => "Dela EP256",
+        27 => "Rex EP256",
+        28 => "Mikro Assembler",
+        29 => "Final Cartridge Plus",
+        30 => "Action Replay 4",
+        31 => "Stardos",
+        32 => "EasyFlash",
+        33 => "EasyFlash Xbank",
+        34 => "Capture",
+        35 => "Action Replay 3",
+        36 => "Retro Replay",
+        37 => "MMC64",
+        38 => "MMC Replay",
+        39 => "IDE64",
+        40 => "Super Snapshot V4",
+        41 => "IEEE-488",
+        42 => "Game Killer",
+        43 => "Prophet64",
+        44 => "EXOS",
+        45 => "Freeze Frame",
+        46 => "Freeze Machine",
+        47 => "Snapshot64",
+        48 => "Super Explode V5.0",
+        49 => "Magic Voice",
+        50 => "Action Replay 2",
+        51 => "MACH 5",
+        52 => "Diashow-Maker",
+        53 => "Pagefox",
+        54 => "Kingsoft",
+        55 => "Silverrock 128K",
+        56 => "Formel 64",
+        57 => "RGCD",
+        58 => "RR-Net MK3",
+        59 => "EasyCalc",
+        60 => "GMod2",
+        61 => "MAX Basic",
+        62 => "GMod3",
+        63 => "ZIPP-CODE 48",
+        64 => "Blackbox V8",
+        65 => "Blackbox V3",
+        66 => "Blackbox V4",
+        67 => "REX RAM-Floppy",
+        68 => "BIS-Plus",
+        69 => "SD-BOX",
+        70 => "MultiMAX",
+        71 => "Blackbox V9",
+        72 => "Lt. Kernal Host Adaptor",
+        73 => "RAMLink",
+        74 => "H.E.R.O.",
+        75 => "IEEE Flash! 64",
+        76 => "Turtle Graphics II",
+        77 => "Freeze Frame MK2",
+        78 => "Partner 64",
+        79 => "Hyper-BASIC MK2",
+        80 => "Universal Cartridge 1",
+        81 => "Universal Cartridge 1.5",
+        82 => "Universal Cartridge 2",
+        83 => "BMP Data Turbo 2000",
+        84 => "Profi-DOS",
+        85 => "Magic Desk 16",
+        _ => return None,
+    };
+    Some(name)
+}
+
+/// What a CHIP packet holds
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ChipKind {
+    Rom,
+    Ram,
+    Flash,
+    Eeprom,
+    Unknown(u16),
+}
+
+impl ChipKind {
+    fn from_u16(value: u16) -> Self {
+        match value {
+            0 => ChipKind::Rom,
+            1 => ChipKind::Ram,
+            2 => ChipKind::Flash,
+            3 => ChipKind::Eeprom,
+            other => ChipKind::Unknown(other),
+        }
+    }
+}
+
+impl std::fmt::Display for ChipKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChipKind::Rom => write!(f, "ROM"),
+            ChipKind::Ram => write!(f, "RAM"),
+            ChipKind::Flash => write!(f, "FLASH"),
+            ChipKind::Eeprom => write!(f, "EEPROM"),
+            ChipKind::Unknown(t) => write!(f, "?{:02X}", t),
+        }
+    }
+}
+
+/// One CHIP packet
+#[derive(Debug, Clone)]
+pub struct ChipPacket {
+    pub kind: ChipKind,
+    pub bank: u16,
+    pub load_address: u16,
+    /// Image size as stated in the packet header
+    pub size: u16,
+    /// Byte offset of the chip image in the file
+    offset: usize,
+    /// Bytes of the image actually present; less than `size` when truncated
+    present: usize,
+}
+
+impl ChipPacket {
+    pub fn is_truncated(&self) -> bool {
+        self.present < self.size as usize
+    }
+
+    /// Format as a C64-style listing line
+    pub fn format_line(&self) -> String {
+        format!(
+            "BANK {:>3} ${:04X}-${:04X} {:>2}K {}{}",
+            self.bank,
+            self.load_address,
+            (self.load_address as usize + self.size as usize).saturating_sub(1),
+            (self.size as usize).div_ceil(1024),
+            self.kind,
+            if self.is_truncated() { " SHORT" } else { "" }
+        )
+    }
+}
+
+/// Information about a CRT file
+#[derive(Debug, Clone)]
+pub struct CrtInfo {
+    pub name: String,
+    pub hardware_type: u16,
+    /// Format version, major and minor
+    pub version: (u8, u8),
+    /// EXROM line state at power-on as stored in the header (0 = active)
+    pub exrom: u8,
+    /// GAME line state at power-on as stored in the header (0 = active)
+    pub game: u8,
+    pub chips: Vec<ChipPacket>,
+    /// Problems found while reading; the file can still be sent
+    pub warnings: Vec<String>,
+}
+
+impl CrtInfo {
+    pub fn hardware_name(&self) -> &'static str {
+        hardware_name(self.hardware_type).unwrap_or("Unknown hardware")
+    }
+
+    /// Memory configuration the EXROM/GAME lines select at power-on
+    pub fn memory_mode(&self) -> &'static str {
+        match (self.exrom != 0, self.game != 0) {
+            (false, false) => "16K",
+            (false, true) => "8K",
+            (true, false) => "Ultimax",
+            (true, true) => "ROM off",
+        }
+    }
+
+    /// Number of distinct banks the chips are spread over
+    pub fn bank_count(&self) -> usize {
+        let mut banks: Vec<u16> = self.chips.iter().map(|c| c.bank).collect();
+        banks.sort_unstable();
+        banks.dedup();
+        banks.len()
+    }
+
+    /// Total chip image size in bytes, as stated in the packet headers
+    pub fn rom_size(&self) -> usize {
+        self.chips.iter().map(|c| c.size as usize).sum()
+    }
+
+    /// Format the header line like a C64 directory listing
+    pub fn format_header(&self) -> String {
+        format!("0 \"{}\" TYPE {}", self.name, self.hardware_type)
+    }
+
+    /// Footer: bank count, ROM size and the power-on line states
+    pub fn format_footer(&self) -> String {
+        format!(
+            "{} BANKS, {}K, EXROM {} GAME {} ({}).",
+            self.bank_count(),
+            self.rom_size().div_ceil(1024),
+            self.exrom,
+            self.game,
+            self.memory_mode().to_uppercase()
+        )
+    }
+}
+
+fn be16(data: &[u8], offset: usize) -> u16 {
+    u16::from_be_bytes([data[offset], data[offset + 1]])
+}
+
+fn be32(data: &[u8], offset: usize) -> usize {
+    u32::from_be_bytes([
+        data[offset],
+        data[offset + 1],
+        data[offset + 2],
+        data[offset + 3],
+    ]) as usize
+}
+
+/// Read CRT information from a file path
+pub fn read_crt_info(path: &Path) -> Result<CrtInfo, String> {
+    let data = fs::read(path).map_err(|e| format!("Failed to read file: {}", e))?;
+    read_crt_info_from_bytes(&data)
+}
+
+/// Read the header and CHIP packets. Only a missing or short header is an
+/// error; a truncated packet ends the list with a warning.
+pub fn read_crt_info_from_bytes(data: &[u8]) -> Result<CrtInfo, String> {
+    if data.len() < HEADER_LEN || &data[..16] != SIGNATURE {
+        return Err("Not a CRT file (no C64 CARTRIDGE header)".to_string());
+    }
+    let name: String = data[0x20..0x40]
+        .iter()
+        .take_while(|&&b| b != 0)
+        .map(|&b| b as char)
+        .collect();
+    let mut info = CrtInfo {
+        name: name.trim_end().to_string(),
+        hardware_type: be16(data, 0x16),
+        version: (data[0x14], data[0x15]),
+        exrom: data[0x18],
+        game: data[0x19],
+        chips: Vec::new(),
+        warnings: Vec::new(),
+    };
+
+    // Some tools write a header length of $20; the header is $40 regardless.
+    let mut pos = be32(data, 0x10).max(HEADER_LEN);
+    while pos < data.len() {
+        let index = info.chips.len();
+        if data.len() - pos < CHIP_HEADER_LEN || &data[pos..pos + 4] != CHIP_SIGNATURE {
+            info.warnings.push(format!(
+                "{} stray bytes at ${:X} after CHIP packet {}",
+                data.len() - pos,
+                pos,
+                index
+            ));
+            break;
+        }
+        let packet_len = be32(data, pos + 4);
+        let size = be16(data, pos + 14);
+        let offset = pos + CHIP_HEADER_LEN;
+        let present = (size as usize).min(data.len() - offset);
+        info.chips.push(ChipPacket {
+            kind: ChipKind::from_u16(be16(data, pos + 8)),
+            bank: be16(data, pos + 10),
+            load_address: be16(data, pos + 12),
+            size,
+            offset,
+            present,
+        });
+        if present < size as usize {
+            info.warnings.push(format!(
+                "File is truncated: CHIP packet {} (bank {}) has {} of {} bytes",
+                index, info.chips[index].bank, present, size
+            ));
+            break;
+        }
+        pos += packet_len.max(CHIP_HEADER_LEN + size as usize);
+    }
+
+    if info.chips.is_empty() {
+        info.warnings.push("No CHIP packets".to_string());
+    }
+    match hardware_name(info.hardware_type) {
+        None => info
+            .warnings
+            .push(format!("Unknown hardware type {}", info.hardware_type)),
+        Some(name) if !ULTIMATE_TYPES.contains(&info.hardware_type) => info.warnings.push(format!(
+            "Hardware type {} ({}) is not emulated by the Ultimate",
+            info.hardware_type, name
+        )),
+        Some(_) => {}
+    }
+    Ok(info)
+}
+
+/// Image of chip `index`, as far as it is present in `data`
+pub fn extract_chip(data: &[u8], index: usize) -> Result<(ChipPacket, Vec<u8>), String> {
+    let info = read_crt_info_from_bytes(data)?;
+    let chip = info
+        .chips
+        .get(index)
+        .cloned()
+        .ok_or_else(|| format!("No CHIP packet #{}", index))?;
+    let image = data[chip.offset..chip.offset + chip.present].to_vec();
+    Ok((chip, image))
+}
+
+/// Largest BIN [`to_bin`] will build (a 1 MB EasyFlash is the common maximum)
+const MAX_BIN_LEN: usize = 16 << 20;
+
+/// Offset of a chip inside its bank: ROMH ($A000, or $E000 in Ultimax mode)
+/// follows the 8 KB of ROML.
+fn bank_offset(load_address: u16) -> usize {
+    if load_address >= 0xA000 {
+        0x2000
+    } else {
+        0
+    }
+}
+
+/// The whole ROM as one raw BIN: each chip at `bank * bank size`, plus
+/// $2000 for ROMH, with missing banks and chips filled with $FF — the
+/// layout `cartconv` produces. The bank size is 8 KB, or 16 KB when any bank
+/// uses ROMH or a 16 KB chip.
+pub fn to_bin(data: &[u8]) -> Result<Vec<u8>, String> {
+    let info = read_crt_info_from_bytes(data)?;
+    if let Some(chip) = info.chips.iter().find(|c| c.is_truncated()) {
+        return Err(format!(
+            "CHIP packet for bank {} is truncated; extract single chips instead",
+            chip.bank
+        ));
+    }
+    let bank_size = info
+        .chips
+        .iter()
+        .map(|c| bank_offset(c.load_address) + c.size as usize)
+        .max()
+        .ok_or("The cartridge has no CHIP packets")?
+        .max(0x2000);
+    let banks = info
+        .chips
+        .iter()
+        .map(|c| c.bank as usize)
+        .max()
+        .unwrap_or(0)
+        + 1;
+    let len = banks * bank_size;
+    if len > MAX_BIN_LEN {
+        return Err(format!(
+            "{} banks of {} KB is too large for a BIN image",
+            banks,
+            bank_size / 1024
+        ));
+    }
+    let mut bin = vec![0xFF; len];
+    for chip in &info.chips {
+        let at = chip.bank as usize * bank_size + bank_offset(chip.load_address);
+        bin[at..at + chip.present].copy_from_slice(&data[chip.offset..chip.offset + chip.present]);
+    }
+    Ok(bin)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_crt(hardware_type: u16, exrom: u8, game: u8, chips: &[(u16, u16, &[u8])]) -> Vec<u8> {
+        let mut crt = SIGNATURE.to_vec();
+        crt.extend(0x40u32.to_be_bytes());
+        crt.extend([1, 0]);
+        crt.extend(hardware_type.to_be_bytes());
+        crt.extend([exrom, game]);
+        crt.resize(0x20, 0);
+        crt.extend(b"TEST CART");
+        crt.resize(HEADER_LEN, 0);
+        for (bank, load_address, image) in chips {
+            crt.extend(CHIP_SIGNATURE);
+            crt.extend(((CHIP_HEADER_LEN + image.len()) as u32).to_be_bytes());
+            crt.extend(0u16.to_be_bytes());
+            crt.extend(bank.to_be_bytes());
+            crt.extend(load_address.to_be_bytes());
+            crt.extend((image.len() as u16).to_be_bytes());
+            crt.extend(*image);
+        }
+        crt
+    }
+
+    #[test]
+    fn reads_easyflash_banks() {
+        let crt = build_crt(
+            32,
+            1,
+            0,
+            &[
+                (1, 0x8000, &[0x11; 0x2000]),
+                (0, 0xA000, &[0x22; 0x2000]),
+                (0, 0x8000, &[0x33; 0x2000]),
+            ],
+        );
+        let info = read_crt_info_from_bytes(&crt).unwrap();
+        assert_eq!(info.name, "TEST CART");
+        assert_eq!(info.hardware_name(), "EasyFlash");
+        assert_eq!(info.memory_mode(), "Ultimax");
+        assert_eq!(info.chips.len(), 3);
+        assert_eq!(info.bank_count(), 2);
+        assert_eq!(info.chips[1].load_address, 0xA000);
+        assert!(info.warnings.is_empty());
+
+        let bin = to_bin(&crt).unwrap();
+        assert_eq!(bin.len(), 0x8000);
+        assert_eq!((bin[0], bin[0x2000], bin[0x4000]), (0x33, 0x22, 0x11));
+        // Bank 1 has no ROMH chip
+        assert!(bin[0x6000..].iter().all(|&b| b == 0xFF));
+        let (chip, image) = extract_chip(&crt, 0).unwrap();
+        assert_eq!((chip.bank, image.len()), (1, 0x2000));
+
+        assert!(read_crt_info_from_bytes(b"C64 CARTRIDGE").is_err());
+    }
+
+    #[test]
+    fn bin_pads_missing_banks() {
+        let crt = build_crt(
+            5,
+            0,
+            1,
+            &[(2, 0x8000, &[0x22; 0x2000]), (0, 0x8000, &[0x00; 0x2000])],
+        );
+        let bin = to_bin(&crt).unwrap();
+        assert_eq!(bin.len(), 0x6000);
+        assert!(bin[..0x2000].iter().all(|&b| b == 0x00));
+        assert!(bin[0x2000..0x4000].iter().all(|&b| b == 0xFF));
+        assert!(bin[0x4000..].iter().all(|&b| b == 0x22));
+    }
+
+    #[test]
+    fn warns_about_truncation_and_unsupported_types() {
+        let mut crt = build_crt(6, 0, 1, &[(0, 0x8000, &[0xEA; 0x2000])]);
+        crt.truncate(crt.len() - 0x100);
+        let info = read_crt_info_from_bytes(&crt).unwrap();
+        assert_eq!(info.memory_mode(), "8K");
+        assert!(info.chips[0].is_truncated());
+        assert_eq!(info.warnings.len(), 2);
+        assert!(info.warnings[0].contains("truncated"));
+        assert!(info.warnings[1].contains("Expert Cartridge"));
+        assert!(to_bin(&crt).is_err());
+        assert_eq!(extract_chip(&crt, 0).unwrap().1.len(), 0x1F00);
+    }
+}
@@ -74,6 +74,23 @@ impl DeviceError {
     }
 }
 
+impl DeviceError {
+    /// Process exit code for the headless CLI. 1 and 2 are left for generic
+    /// failures and usage errors, so every variant gets its own code a script
+    /// can branch on.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            DeviceError::NotConnected => 3,
+            DeviceError::Unauthorized => 4,
+            DeviceError::NotFound => 5,
+            DeviceError::Timeout => 6,
+            DeviceError::Http(_) => 7,
+            DeviceError::Network(_) => 8,
+            DeviceError::Build(_) => 9,
+        }
+    }
+}
+
 impl fmt::Display for DeviceError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -111,6 +128,24 @@ mod tests {
         assert_eq!(DeviceError::from_status(500), DeviceError::Http(500));
     }
 
+    #[test]
+    fn exit_codes_are_distinct_and_nonzero() {
+        let all = [
+            DeviceError::NotConnected,
+            DeviceError::Unauthorized,
+            DeviceError::NotFound,
+            DeviceError::Timeout,
+            DeviceError::Http(500),
+            DeviceError::Network("x".into()),
+            DeviceError::Build("x".into()),
+        ];
+        let mut codes: Vec<i32> = all.iter().map(|e| e.exit_code()).collect();
+        assert!(codes.iter().all(|&c| c > 2));
+        codes.sort_unstable();
+        codes.dedup();
+        assert_eq!(codes.len(), all.len());
+    }
+
     #[test]
     fn transient_detection() {
         assert!(DeviceError::Timeout.is_transient());
@@ -148,6 +148,9 @@ pub struct LaunchSettings {
     /// Reset machine after applying config + mounts
     #[serde(default)]
     pub reset_after_apply: bool,
+    /// Launch script run last (see [`crate::launch_script`])
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub script: String,
 }
 
 impl Default for LaunchSettings {
@@ -155,6 +158,7 @@ impl Default for LaunchSettings {
         Self {
             restore_baseline_first: false,
             reset_after_apply: false,
+            script: String::new(),
         }
     }
 }
@@ -4,8 +4,10 @@
 
 use std::path::{Path, PathBuf};
 
-use crate::disk_image::DiskInfo;
+use crate::crt_image::{ChipKind, CrtInfo};
+use crate::disk_image::{DiskInfo, FileType};
 use crate::petscii;
+use crate::tape_image::TapeInfo;
 
 /// Supported preview content types
 #[derive(Debug, Clone)]
@@ -143,23 +145,6 @@ fn petscii_bitmap(petscii: u8) -> [u8; 8] {
 /// The image uses authentic C64 colours: blue background, light blue border,
 /// white text — matching the real C64 directory listing appearance.
 pub fn render_disk_listing_image(disk_info: &DiskInfo) -> Vec<u8> {
-    // C64 screen: 40 columns, each char 8×8 px
-    // Use 4-char border on left/right and 2-char border top/bottom for a spacious look
-    const COLS: usize = 40;
-    const CHAR_W: usize = 8;
-    const CHAR_H: usize = 8;
-    const BORDER_X: usize = 4; // border width in chars
-    const BORDER_Y: usize = 2; // border height in chars
-    const SCALE: usize = 2; // pixel scale factor — 2× makes chars crisply readable
-
-    // C64 authentic colours (RGB)
-    const BG: [u8; 3] = [0x35, 0x28, 0x79]; // C64 blue background
-    const BORDER_COL: [u8; 3] = [0x70, 0x5B, 0xD5]; // C64 light blue border
-    const CYAN: [u8; 3] = [0x5F, 0xD5, 0xCB]; // header / footer
-    const LIGHT_GREEN: [u8; 3] = [0x76, 0xD5, 0x5F]; // PRG files
-    const LIGHT_BLUE: [u8; 3] = [0x70, 0xA4, 0xD5]; // SEQ files
-    const LIGHT_RED: [u8; 3] = [0xD5, 0x6F, 0x5F]; // other file types
-
     // Build lines: (raw bytes, colour)
     // All bytes are PETSCII — uppercase letters are same as ASCII
     let mut lines: Vec<(Vec<u8>, [u8; 3])> = Vec::new();
@@ -196,11 +181,7 @@ pub fn render_disk_listing_image(disk_info: &DiskInfo) -> Vec<u8> {
 
     // One line per directory entry
     for entry in &disk_info.entries {
-        let colour = match entry.file_type {
-            crate::disk_image::FileType::Prg => LIGHT_GREEN,
-            crate::disk_image::FileType::Seq => LIGHT_BLUE,
-            _ => LIGHT_RED,
-        };
+        let colour = file_type_colour(entry.file_type);
         let lock_char = if entry.locked { b'<' } else { b' ' };
         let closed_char = if !entry.closed { b'*' } else { b' ' };
         let file_type_str = format!("{}", entry.file_type);
@@ -243,6 +224,69 @@ pub fn render_disk_listing_image(disk_info: &DiskInfo) -> Vec<u8> {
         lines.push((footer_str.into_bytes(), CYAN));
     }
 
+    render_listing_lines(&lines)
+}
+
+/// Render a C64-style directory listing for a T64 or TAP container.
+pub fn render_tape_listing_image(tape_info: &TapeInfo) -> Vec<u8> {
+    let mut lines: Vec<(Vec<u8>, [u8; 3])> = vec![(tape_info.format_header().into_bytes(), CYAN)];
+    for entry in &tape_info.entries {
+        lines.push((
+            entry.format_line().into_bytes(),
+            file_type_colour(entry.file_type),
+        ));
+    }
+    lines.push((tape_info.format_footer().into_bytes(), CYAN));
+    render_listing_lines(&lines)
+}
+
+/// Render a C64-style listing of a CRT file's CHIP packets.
+pub fn render_crt_listing_image(crt_info: &CrtInfo) -> Vec<u8> {
+    let mut lines: Vec<(Vec<u8>, [u8; 3])> = vec![
+        (crt_info.format_header().into_bytes(), CYAN),
+        (crt_info.hardware_name().to_uppercase().into_bytes(), CYAN),
+    ];
+    for chip in &crt_info.chips {
+        let colour = match chip.kind {
+            _ if chip.is_truncated() => LIGHT_RED,
+            ChipKind::Rom => LIGHT_GREEN,
+            ChipKind::Ram | ChipKind::Flash | ChipKind::Eeprom => LIGHT_BLUE,
+            ChipKind::Unknown(_) => LIGHT_RED,
+        };
+        lines.push((chip.format_line().into_bytes(), colour));
+    }
+    lines.push((crt_info.format_footer().into_bytes(), CYAN));
+    render_listing_lines(&lines)
+}
+
+// C64 authentic colours (RGB)
+const BG: [u8; 3] = [0x35, 0x28, 0x79]; // C64 blue background
+const BORDER_COL: [u8; 3] = [0x70, 0x5B, 0xD5]; // C64 light blue border
+const CYAN: [u8; 3] = [0x5F, 0xD5, 0xCB]; // header / footer
+const LIGHT_GREEN: [u8; 3] = [0x76, 0xD5, 0x5F]; // PRG files
+const LIGHT_BLUE: [u8; 3] = [0x70, 0xA4, 0xD5]; // SEQ files
+const LIGHT_RED: [u8; 3] = [0xD5, 0x6F, 0x5F]; // other file types
+
+fn file_type_colour(file_type: FileType) -> [u8; 3] {
+    match file_type {
+        FileType::Prg => LIGHT_GREEN,
+        FileType::Seq => LIGHT_BLUE,
+        _ => LIGHT_RED,
+    }
+}
+
+/// Draw listing lines (PETSCII bytes + colour) onto a C64 screen and encode
+/// the result as PNG.
+fn render_listing_lines(lines: &[(Vec<u8>, [u8; 3])]) -> Vec<u8> {
+    // C64 screen: 40 columns, each char 8×8 px
+    // Use 4-char border on left/right and 2-char border top/bottom for a spacious look
+    const COLS: usize = 40;
+    const CHAR_W: usize = 8;
+    const CHAR_H: usize = 8;
+    const BORDER_X: usize = 4; // border width in chars
+    const BORDER_Y: usize = 2; // border height in chars
+    const SCALE: usize = 2; // pixel scale factor — 2× makes chars crisply readable
+
     // Image dimensions (before scaling)
     let total_rows = lines.len() + BORDER_Y * 2;
     let img_w_chars = COLS + BORDER_X * 2;
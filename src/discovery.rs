@@ -1,16 +1,56 @@
+//! Finding Ultimate devices on the local network.
+//!
+//! The primary method is the firmware's **Ultimate Ident** service: a UDP
+//! datagram to port 64 gets a reply from every device with the service
+//! enabled, so one broadcast per subnet finds them all regardless of subnet
+//! size. The subnet of every local IPv4 interface, plus any the user adds by
+//! hand, is queried on its directed broadcast address from a socket bound to
+//! the interface that routes to it.
+//!
+//! A subnet where nothing answers (service disabled, or a network that drops
+//! broadcasts) falls back to the old TCP port-80 scan if it has at most
+//! [`MAX_SCAN_HOSTS`] addresses, followed by a `/v1/info` probe.
+
 use crate::net_utils::get_local_ip;
 use serde::{Deserialize, Serialize};
-use std::net::SocketAddr;
+use std::net::{Ipv4Addr, SocketAddr};
 use std::time::Duration;
-use tokio::net::TcpStream;
+use tokio::net::{TcpStream, UdpSocket};
 use tokio::time::timeout;
 
+/// UDP port of the Ultimate Ident service
+const IDENT_PORT: u16 = 64;
+
+/// Ident query. The service answers any datagram; "json" asks for the JSON
+/// form on firmware that has one.
+const IDENT_QUERY: &[u8] = b"json";
+
+/// How long to collect Ident replies
+const IDENT_WAIT: Duration = Duration::from_millis(1500);
+
+/// Largest subnet the port-scan fallback will walk (a /22)
+const MAX_SCAN_HOSTS: u32 = 1022;
+
 /// Discovered Ultimate64/Ultimate-II+ device
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DiscoveredDevice {
     pub ip: String,
     pub product: String,
     pub firmware: String,
+    #[serde(default)]
+    pub hostname: String,
+}
+
+impl DiscoveredDevice {
+    /// One-line description for lists
+    pub fn label(&self) -> String {
+        let name = if self.hostname.is_empty() {
+            self.ip.clone()
+        } else {
+            format!("{} ({})", self.hostname, self.ip)
+        };
+        format!("{} - {} ({})", name, self.product, self.firmware)
+    }
 }
 
 /// Response from Ultimate64 /v1/info endpoint
@@ -19,33 +59,306 @@ struct InfoResponse {
     product: Option<String>,
     #[serde(rename = "firmwareVersion")]
     firmware_version: Option<String>,
+    hostname: Option<String>,
+}
+
+// ─── Subnets ─────────────────────────────────────────────────────────────────
+
+/// An IPv4 network, e.g. `192.168.1.0/24`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Subnet {
+    network: Ipv4Addr,
+    prefix: u8,
+}
+
+impl Subnet {
+    /// Parse `a.b.c.d/n`; a bare address means its /24.
+    pub fn parse(s: &str) -> Result<Subnet, String> {
+        let s = s.trim();
+        let (addr, prefix) = match s.split_once('/') {
+            Some((a, p)) => (
+                a,
+                p.parse::<u8>()
+                    .ok()
+                    .filter(|p| (8..=30).contains(p))
+                    .ok_or_else(|| format!("Invalid prefix length in '{}' (8-30)", s))?,
+            ),
+            None => (s, 24),
+        };
+        let addr: Ipv4Addr = addr
+            .parse()
+            .map_err(|_| format!("Invalid IPv4 address in '{}'", s))?;
+        Ok(Subnet::containing(addr, prefix))
+    }
+
+    /// The subnet of a local interface. Loopback, link-local and
+    /// point-to-point (/31, /32) interfaces have no neighbours to find.
+    fn of_interface(ip: Ipv4Addr, netmask: Ipv4Addr) -> Option<Subnet> {
+        let prefix = u32::from(netmask).leading_ones() as u8;
+        if ip.is_loopback() || ip.is_link_local() || !(8..=30).contains(&prefix) {
+            return None;
+        }
+        Some(Subnet::containing(ip, prefix))
+    }
+
+    fn containing(addr: Ipv4Addr, prefix: u8) -> Subnet {
+        Subnet {
+            network: Ipv4Addr::from(u32::from(addr) & mask(prefix)),
+            prefix,
+        }
+    }
+
+    pub fn broadcast(&self) -> Ipv4Addr {
+        Ipv4Addr::from(u32::from(self.network) | !mask(self.prefix))
+    }
+
+    fn host_count(&self) -> u32 {
+        (1u32 << (32 - self.prefix)) - 2
+    }
+
+    /// Every host address (network and broadcast excluded)
+    fn hosts(&self) -> impl Iterator<Item = Ipv4Addr> {
+        let first = u32::from(self.network) + 1;
+        (first..first + self.host_count()).map(Ipv4Addr::from)
+    }
+}
+
+impl std::fmt::Display for Subnet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.network, self.prefix)
+    }
 }
 
-/// Scan local network for Ultimate64 devices
-/// Uses parallel scanning - completes in 1-2 seconds
-pub async fn discover_devices() -> Vec<DiscoveredDevice> {
-    let local_ip = match get_local_ip() {
-        Some(ip) => ip,
+fn mask(prefix: u8) -> u32 {
+    u32::MAX << (32 - prefix)
+}
+
+/// Parse a comma/space-separated subnet list, skipping (and logging) bad entries.
+pub fn parse_subnets(list: &[String]) -> Vec<Subnet> {
+    list.iter()
+        .filter(|s| !s.trim().is_empty())
+        .filter_map(|s| match Subnet::parse(s) {
+            Ok(subnet) => Some(subnet),
+            Err(e) => {
+                log::warn!("Discovery: {}", e);
+                None
+            }
+        })
+        .collect()
+}
+
+/// Subnets of the local IPv4 interfaces. When they can't be listed, the /24
+/// around the primary local address.
+fn local_subnets() -> Vec<Subnet> {
+    let interfaces = if_addrs::get_if_addrs().unwrap_or_else(|e| {
+        log::warn!("Discovery: could not list network interfaces: {}", e);
+        Vec::new()
+    });
+    let subnets: Vec<Subnet> = interfaces
+        .iter()
+        .filter_map(|interface| match &interface.addr {
+            if_addrs::IfAddr::V4(v4) => Subnet::of_interface(v4.ip, v4.netmask),
+            _ => None,
+        })
+        .collect();
+    if !subnets.is_empty() {
+        return subnets;
+    }
+    match get_local_ip().and_then(|ip| ip.parse::<Ipv4Addr>().ok()) {
+        Some(ip) => vec![Subnet::containing(ip, 24)],
         None => {
             log::warn!("Could not determine local IP for network scan");
-            return Vec::new();
+            Vec::new()
         }
-    };
+    }
+}
+
+/// The local subnets plus `extra`, without repeats.
+fn subnets_to_search(extra: &[String]) -> Vec<Subnet> {
+    let mut subnets = Vec::new();
+    for subnet in local_subnets().into_iter().chain(parse_subnets(extra)) {
+        if !subnets.contains(&subnet) {
+            subnets.push(subnet);
+        }
+    }
+    subnets
+}
+
+// ─── Discovery ───────────────────────────────────────────────────────────────
 
-    let parts: Vec<&str> = local_ip.split('.').collect();
-    if parts.len() != 4 {
-        log::warn!("Invalid local IP format: {}", local_ip);
+/// Find Ultimate devices on every local subnet and on each of
+/// `extra_subnets`: Ident broadcast first, TCP scan of each subnet where
+/// nothing answered.
+pub async fn discover_devices(extra_subnets: Vec<String>) -> Vec<DiscoveredDevice> {
+    let subnets = subnets_to_search(&extra_subnets);
+    if subnets.is_empty() {
         return Vec::new();
     }
 
-    let subnet = format!("{}.{}.{}.", parts[0], parts[1], parts[2]);
-    log::info!("Scanning subnet {}0/24 for Ultimate devices...", subnet);
+    let (mut devices, answered) = ident_broadcast(&subnets).await;
+    for subnet in subnets.iter().filter(|s| !answered.contains(s)) {
+        log::info!(
+            "No Ident replies on {}; falling back to a port scan",
+            subnet
+        );
+        for device in scan_subnet(subnet).await {
+            if !devices.iter().any(|d| d.ip == device.ip) {
+                devices.push(device);
+            }
+        }
+    }
+
+    log::info!("Found {} Ultimate device(s)", devices.len());
+    devices
+}
+
+/// Local address the OS would use to reach `subnet` — the interface to
+/// broadcast from.
+fn local_address_for(subnet: &Subnet) -> Option<Ipv4Addr> {
+    let probe = std::net::UdpSocket::bind("0.0.0.0:0").ok()?;
+    let target = Ipv4Addr::from(u32::from(subnet.network) + 1);
+    probe.connect((target, IDENT_PORT)).ok()?;
+    match probe.local_addr().ok()?.ip() {
+        std::net::IpAddr::V4(ip) if !ip.is_unspecified() => Some(ip),
+        _ => None,
+    }
+}
+
+/// Broadcast an Ident query on every subnet and collect the replies, along
+/// with the subnets that got at least one.
+async fn ident_broadcast(subnets: &[Subnet]) -> (Vec<DiscoveredDevice>, Vec<Subnet>) {
+    let mut listeners = Vec::new();
+    for subnet in subnets {
+        let Some(local) = local_address_for(subnet) else {
+            log::warn!("Discovery: no route to {}", subnet);
+            continue;
+        };
+        let socket = match UdpSocket::bind((local, 0)).await {
+            Ok(s) => s,
+            Err(e) => {
+                log::warn!("Discovery: bind {} failed: {}", local, e);
+                continue;
+            }
+        };
+        if let Err(e) = socket.set_broadcast(true) {
+            log::warn!("Discovery: broadcast not allowed on {}: {}", local, e);
+            continue;
+        }
+        let mut targets = vec![subnet.broadcast()];
+        // The limited broadcast reaches devices on the interface's own
+        // segment even when the configured prefix is wrong.
+        targets.push(Ipv4Addr::BROADCAST);
+        for target in targets {
+            if let Err(e) = socket.send_to(IDENT_QUERY, (target, IDENT_PORT)).await {
+                log::debug!("Discovery: send to {} failed: {}", target, e);
+            }
+        }
+        log::info!("Ident query sent on {} from {}", subnet, local);
+        listeners.push((*subnet, tokio::spawn(collect_replies(socket))));
+    }
+
+    let mut devices: Vec<DiscoveredDevice> = Vec::new();
+    let mut answered = Vec::new();
+    for (subnet, listener) in listeners {
+        for (ip, reply) in listener.await.unwrap_or_default() {
+            let Some(device) = parse_ident(&ip, &reply) else {
+                continue;
+            };
+            if !answered.contains(&subnet) {
+                answered.push(subnet);
+            }
+            if !devices.iter().any(|d| d.ip == ip) {
+                devices.push(enrich(device).await);
+            }
+        }
+    }
+    (devices, answered)
+}
+
+/// Read datagrams until [`IDENT_WAIT`] has passed.
+async fn collect_replies(socket: UdpSocket) -> Vec<(String, Vec<u8>)> {
+    let deadline = tokio::time::Instant::now() + IDENT_WAIT;
+    let mut replies = Vec::new();
+    let mut buf = [0u8; 1500];
+    while let Ok(Ok((n, from))) =
+        tokio::time::timeout_at(deadline, socket.recv_from(&mut buf)).await
+    {
+        replies.push((from.ip().to_string(), buf[..n].to_vec()));
+    }
+    replies
+}
+
+/// Turn an Ident reply into a device. JSON replies carry `product`,
+/// `firmware_version` and `hostname`; older firmware answers with plain
+/// text, whose first line is taken as the name.
+fn parse_ident(ip: &str, reply: &[u8]) -> Option<DiscoveredDevice> {
+    let text = String::from_utf8_lossy(reply);
+    let text = text.trim_matches(|c: char| c.is_whitespace() || c == '\0');
+    if text.is_empty() {
+        return None;
+    }
+    if let Ok(serde_json::Value::Object(map)) = serde_json::from_str(text) {
+        let field = |keys: &[&str]| {
+            keys.iter()
+                .find_map(|k| map.get(*k).and_then(|v| v.as_str()))
+                .unwrap_or_default()
+                .to_string()
+        };
+        return Some(DiscoveredDevice {
+            ip: ip.to_string(),
+            product: field(&["product", "product_name"]),
+            firmware: field(&["firmware_version", "firmwareVersion", "firmware"]),
+            hostname: field(&["hostname", "host_name", "name"]),
+        });
+    }
+    let first = text.lines().next().unwrap_or_default().trim();
+    Some(DiscoveredDevice {
+        ip: ip.to_string(),
+        product: String::new(),
+        firmware: String::new(),
+        hostname: first.to_string(),
+    })
+}
+
+/// Fill in whatever the Ident reply left out from `/v1/info`.
+async fn enrich(mut device: DiscoveredDevice) -> DiscoveredDevice {
+    if !device.product.is_empty() && !device.firmware.is_empty() {
+        return device;
+    }
+    if let Some(info) = check_ultimate_api(&device.ip, 500).await {
+        if device.product.is_empty() {
+            device.product = info.product;
+        }
+        if device.firmware.is_empty() {
+            device.firmware = info.firmware;
+        }
+        if device.hostname.is_empty() {
+            device.hostname = info.hostname;
+        }
+    }
+    if device.product.is_empty() {
+        device.product = "Ultimate Device".to_string();
+    }
+    if device.firmware.is_empty() {
+        device.firmware = "Unknown".to_string();
+    }
+    device
+}
+
+/// TCP-scan port 80 across `subnet`, then verify candidates via `/v1/info`.
+/// Uses parallel scanning - a /24 completes in 1-2 seconds
+async fn scan_subnet(subnet: &Subnet) -> Vec<DiscoveredDevice> {
+    if subnet.host_count() > MAX_SCAN_HOSTS {
+        log::info!("Skipping port scan of {}: too large", subnet);
+        return Vec::new();
+    }
+    log::info!("Scanning subnet {} for Ultimate devices...", subnet);
 
     // Phase 1: Fast parallel TCP port scan (50ms timeout)
-    let mut port_scan_handles = Vec::with_capacity(254);
+    let mut port_scan_handles = Vec::with_capacity(subnet.host_count() as usize);
 
-    for i in 1..=254u8 {
-        let ip = format!("{}{}", subnet, i);
+    for host in subnet.hosts() {
+        let ip = host.to_string();
         port_scan_handles.push(tokio::spawn(async move {
             if check_port_open(&ip, 80, 50).await {
                 Some(ip)
@@ -81,8 +394,6 @@ pub async fn discover_devices() -> Vec<DiscoveredDevice> {
             devices.push(device);
         }
     }
-
-    log::info!("Found {} Ultimate device(s)", devices.len());
     devices
 }
 
@@ -133,6 +444,7 @@ async fn check_ultimate_api(ip: &str, timeout_ms: u64) -> Option<DiscoveredDevic
                 firmware: info
                     .firmware_version
                     .unwrap_or_else(|| "Unknown".to_string()),
+                hostname: info.hostname.unwrap_or_default(),
             });
         }
     }
@@ -143,6 +455,7 @@ async fn check_ultimate_api(ip: &str, timeout_ms: u64) -> Option<DiscoveredDevic
             ip: ip.to_string(),
             product: "Ultimate Device".to_string(),
             firmware: "Unknown".to_string(),
+            hostname: String::new(),
         });
     }
 
@@ -159,4 +472,65 @@ mod tests {
         assert!(ip.is_some());
         println!("Local IP: {:?}", ip);
     }
+
+    #[test]
+    fn subnets_parse_with_broadcast_and_hosts() {
+        let s = Subnet::parse("192.168.7.200/22").unwrap();
+        assert_eq!(s.to_string(), "192.168.4.0/22");
+        assert_eq!(s.broadcast(), Ipv4Addr::new(192, 168, 7, 255));
+        assert_eq!(s.host_count(), 1022);
+        assert_eq!(s.hosts().next(), Some(Ipv4Addr::new(192, 168, 4, 1)));
+        assert_eq!(s.hosts().last(), Some(Ipv4Addr::new(192, 168, 7, 254)));
+
+        assert_eq!(
+            Subnet::parse("10.0.0.9").unwrap().to_string(),
+            "10.0.0.0/24"
+        );
+        assert!(Subnet::parse("10.0.0.0/4").is_err());
+        assert!(Subnet::parse("10.0.0/24").is_err());
+        let list = vec![
+            "10.1.0.0/16".to_string(),
+            "bogus".to_string(),
+            " ".to_string(),
+        ];
+        assert_eq!(parse_subnets(&list).len(), 1);
+    }
+
+    #[test]
+    fn interface_subnets_skip_loopback_and_point_to_point() {
+        let ip = |s: &str| s.parse::<Ipv4Addr>().unwrap();
+        assert_eq!(
+            Subnet::of_interface(ip("192.168.7.20"), ip("255.255.252.0")),
+            Some(Subnet::parse("192.168.4.0/22").unwrap())
+        );
+        assert_eq!(
+            Subnet::of_interface(ip("10.8.1.3"), ip("255.255.255.0")),
+            Some(Subnet::parse("10.8.1.0/24").unwrap())
+        );
+        assert_eq!(Subnet::of_interface(ip("127.0.0.1"), ip("255.0.0.0")), None);
+        assert_eq!(
+            Subnet::of_interface(ip("169.254.3.4"), ip("255.255.0.0")),
+            None
+        );
+        assert_eq!(
+            Subnet::of_interface(ip("10.64.0.2"), ip("255.255.255.255")),
+            None
+        );
+    }
+
+    #[test]
+    fn ident_replies_parse_json_and_text() {
+        let json =
+            br#"{"product":"Ultimate 64 Elite","firmware_version":"3.12","hostname":"U64-Den"}"#;
+        let d = parse_ident("10.0.0.5", json).unwrap();
+        assert_eq!(d.product, "Ultimate 64 Elite");
+        assert_eq!(d.firmware, "3.12");
+        assert_eq!(d.hostname, "U64-Den");
+        assert_eq!(d.label(), "U64-Den (10.0.0.5) - Ultimate 64 Elite (3.12)");
+
+        let d = parse_ident("10.0.0.6", b"Ultimate-II+\r\n\0").unwrap();
+        assert_eq!(d.hostname, "Ultimate-II+");
+        assert!(d.product.is_empty());
+        assert!(parse_ident("10.0.0.7", b"\0\0").is_none());
+    }
 }
@@ -2,13 +2,18 @@
 //!
 //! Provides functionality to:
 //! - Detect and create disk images (D64, D71, D81)
+//! - List G64/G71 images through the GCR decoder in `gcr_image`
 //! - Read directory listings and disk metadata
+//! - Validate and repair images the way the drive's VALIDATE command does
+//! - Browse 1581 partitions and CMD native (DNP) sub-directories
 //! - Extract disk name and ID from BAM/header sectors
 //! - Convert PETSCII to displayable characters
 
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::Path;
 
+use crate::gcr_image::{self, BadSector, GcrReport, SectorError};
 use crate::petscii;
 
 /// Disk image type
@@ -17,6 +22,8 @@ pub enum ImageKind {
     D64,
     D71,
     D81,
+    /// CMD native partition: up to 255 tracks of 256 sectors, browse only
+    Dnp,
 }
 
 impl std::fmt::Display for ImageKind {
@@ -25,6 +32,7 @@ impl std::fmt::Display for ImageKind {
             ImageKind::D64 => write!(f, "D64"),
             ImageKind::D71 => write!(f, "D71"),
             ImageKind::D81 => write!(f, "D81"),
+            ImageKind::Dnp => write!(f, "DNP"),
         }
     }
 }
@@ -37,6 +45,10 @@ pub enum FileType {
     Prg,
     Usr,
     Rel,
+    /// 1581 partition
+    Cbm,
+    /// CMD native sub-directory
+    Dir,
     Unknown(u8),
 }
 
@@ -48,22 +60,40 @@ impl std::fmt::Display for FileType {
             FileType::Prg => write!(f, "PRG"),
             FileType::Usr => write!(f, "USR"),
             FileType::Rel => write!(f, "REL"),
+            FileType::Cbm => write!(f, "CBM"),
+            FileType::Dir => write!(f, "DIR"),
             FileType::Unknown(t) => write!(f, "?{:02X}", t),
         }
     }
 }
 
 impl FileType {
-    fn from_byte(b: u8) -> Self {
+    pub(crate) fn from_byte(b: u8) -> Self {
         match b & 0x07 {
             0 => FileType::Del,
             1 => FileType::Seq,
             2 => FileType::Prg,
             3 => FileType::Usr,
             4 => FileType::Rel,
+            5 => FileType::Cbm,
+            6 => FileType::Dir,
             x => FileType::Unknown(x),
         }
     }
+
+    /// Low three bits of the directory type byte (without closed/locked flags).
+    fn type_bits(self) -> u8 {
+        match self {
+            FileType::Del => 0,
+            FileType::Seq => 1,
+            FileType::Prg => 2,
+            FileType::Usr => 3,
+            FileType::Rel => 4,
+            FileType::Cbm => 5,
+            FileType::Dir => 6,
+            FileType::Unknown(x) => x & 0x07,
+        }
+    }
 }
 
 /// A directory entry from a disk image
@@ -84,6 +114,11 @@ pub struct DirEntry {
 }
 
 impl DirEntry {
+    /// A 1581 partition or CMD sub-directory, listed with [`read_disk_info_in`]
+    pub fn is_directory(&self) -> bool {
+        matches!(self.file_type, FileType::Cbm | FileType::Dir)
+    }
+
     /// Format as a C64-style directory line
     pub fn format_line(&self) -> String {
         let lock_char = if self.locked { '<' } else { ' ' };
@@ -104,6 +139,15 @@ pub struct DiskInfo {
     pub dos_type: String,
     pub entries: Vec<DirEntry>,
     pub blocks_free: u16,
+    /// Set when the listing was decoded from a G64/G71: the GCR format and
+    /// the sectors that could not be read
+    pub gcr: Option<GcrReport>,
+    /// Tracks in the image (35, 40 or 42 for a D64)
+    pub tracks: u8,
+    /// Sectors flagged in the error-info bytes appended to the image
+    pub error_info: Vec<BadSector>,
+    /// Partitions / sub-directories above this listing; empty at the root
+    pub location: Vec<String>,
 }
 
 impl DiskInfo {
@@ -112,6 +156,18 @@ impl DiskInfo {
         format!("0 \"{}\" {} {}", self.name, self.disk_id, self.dos_type)
     }
 
+    /// Image type as shown to the user: the GCR container when decoded from
+    /// one, e.g. "G64 (D64)", and the track count of an extended D64
+    pub fn kind_label(&self) -> String {
+        match &self.gcr {
+            Some(report) => format!("{} ({})", report.format, self.kind),
+            None if self.kind == ImageKind::D64 && self.tracks != 35 => {
+                format!("D64 ({} tracks)", self.tracks)
+            }
+            None => self.kind.to_string(),
+        }
+    }
+
     /// Format the footer line with blocks free
     pub fn format_footer(&self) -> String {
         format!("{} BLOCKS FREE.", self.blocks_free)
@@ -129,31 +185,108 @@ impl DiskInfo {
     }
 }
 
+/// Layout of a sector image, worked out from its size
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Geometry {
+    pub kind: ImageKind,
+    /// 35, 40 or 42 for a D64; 70 for a D71; 80 for a D81
+    pub tracks: u8,
+    /// One error-info byte per sector follows the sector data
+    pub error_info: bool,
+}
+
+impl Geometry {
+    pub fn sectors(&self) -> usize {
+        (1..=self.tracks)
+            .map(|t| track_sectors(self.kind, t) as usize)
+            .sum()
+    }
+}
+
+/// Sectors on any track of the image, including the extended D64 tracks
+fn track_sectors(kind: ImageKind, track: u8) -> u8 {
+    match kind {
+        ImageKind::D81 => 40,
+        ImageKind::D71 if track > 35 => spt_1541(track - 35).unwrap_or(0),
+        _ => spt_1541(track).unwrap_or(0),
+    }
+}
+
+/// Image layout from its size: every supported track count, each with or
+/// without error-info bytes (e.g. 174_848 / 175_531 for a 35-track D64).
+pub fn geometry(len: usize) -> Option<Geometry> {
+    [
+        (ImageKind::D64, 35),
+        (ImageKind::D64, 40),
+        (ImageKind::D64, 42),
+        (ImageKind::D71, 70),
+        (ImageKind::D81, 80),
+    ]
+    .into_iter()
+    .find_map(|(kind, tracks)| {
+        let mut geometry = Geometry {
+            kind,
+            tracks,
+            error_info: false,
+        };
+        let sectors = geometry.sectors();
+        if len == sectors * 257 {
+            geometry.error_info = true;
+        } else if len != sectors * 256 {
+            return None;
+        }
+        Some(geometry)
+    })
+}
+
 /// Detect disk image type from file size
 pub fn detect_kind(len: usize) -> Option<ImageKind> {
-    // Most common sizes (with and without error info blocks):
-    // D64: 174_848 (35 tracks), 175_531 (35 + error info), 196_608 (40 tracks)
-    // D71: 349_696 (70 tracks), 351_062 (70 + error info)
-    match len {
-        174_848 | 175_531 | 196_608 | 197_376 => Some(ImageKind::D64),
-        349_696 | 351_062 => Some(ImageKind::D71),
-        819_200 => Some(ImageKind::D81),
-        _ => None,
-    }
+    geometry(len).map(|g| g.kind)
 }
 
 /// Sectors per track for 1541 layout (also used by 1571 per side)
-fn spt_1541(track: u8) -> Option<u8> {
+pub(crate) fn spt_1541(track: u8) -> Option<u8> {
     match track {
         1..=17 => Some(21),
         18..=24 => Some(19),
         25..=30 => Some(18),
         31..=35 => Some(17),
-        36..=40 => Some(17), // Extended tracks (some D64 variants)
+        36..=42 => Some(17), // Extended tracks (40/42-track D64s)
         _ => None,
     }
 }
 
+/// Bytes of sector data, leaving out any appended error-info bytes
+fn sector_data_len(data: &[u8]) -> usize {
+    geometry(data.len()).map_or(data.len(), |g| g.sectors() * 256)
+}
+
+/// Sectors flagged in the image's error-info bytes, in track/sector order.
+/// Empty for an image without them.
+pub fn error_info_sectors(data: &[u8]) -> Vec<BadSector> {
+    let Some(geometry) = geometry(data.len()).filter(|g| g.error_info) else {
+        return Vec::new();
+    };
+    let errors = &data[geometry.sectors() * 256..];
+    let mut bad = Vec::new();
+    for track in 1..=geometry.tracks {
+        for sector in 0..track_sectors(geometry.kind, track) {
+            let index = ts_offset(track, sector, geometry.kind).unwrap_or(0) / 256;
+            if let Some(error) = errors
+                .get(index)
+                .and_then(|&b| SectorError::from_error_byte(b))
+            {
+                bad.push(BadSector {
+                    track,
+                    sector,
+                    error,
+                });
+            }
+        }
+    }
+    bad
+}
+
 /// Compute byte offset for a given (track, sector).
 /// Tracks are 1-based. Sector is 0-based.
 pub fn ts_offset(track: u8, sector: u8, kind: ImageKind) -> Option<usize> {
@@ -169,9 +302,14 @@ pub fn ts_offset(track: u8, sector: u8, kind: ImageKind) -> Option<usize> {
         return Some(((track as usize - 1) * 40 + sector as usize) * 256);
     }
 
+    // DNP: 256 sectors on every track
+    if kind == ImageKind::Dnp {
+        return Some(((track as usize - 1) * 256 + sector as usize) * 256);
+    }
+
     // In D71, tracks 1..=35 are side 0, 36..=70 are side 1 (1541 layout repeated).
     let (side_track, side) = match (kind, track) {
-        (ImageKind::D64, 1..=40) => (track, 0usize),
+        (ImageKind::D64, 1..=42) => (track, 0usize),
         (ImageKind::D71, 1..=35) => (track, 0usize),
         (ImageKind::D71, 36..=70) => (track - 35, 1usize),
         _ => return None,
@@ -205,7 +343,7 @@ pub fn ts_offset(track: u8, sector: u8, kind: ImageKind) -> Option<usize> {
 /// Read a sector from the disk image
 fn read_sector(data: &[u8], track: u8, sector: u8, kind: ImageKind) -> Option<&[u8]> {
     let offset = ts_offset(track, sector, kind)?;
-    if offset + 256 <= data.len() {
+    if offset + 256 <= sector_data_len(data) {
         Some(&data[offset..offset + 256])
     } else {
         None
@@ -216,6 +354,7 @@ fn read_sector(data: &[u8], track: u8, sector: u8, kind: ImageKind) -> Option<&[
 fn count_free_blocks(data: &[u8], kind: ImageKind) -> u16 {
     match kind {
         ImageKind::D81 => count_free_blocks_d81(data),
+        ImageKind::Dnp => count_free_blocks_dnp(data),
         _ => count_free_blocks_d64_d71(data, kind),
     }
 }
@@ -239,18 +378,15 @@ fn count_free_blocks_d64_d71(data: &[u8], kind: ImageKind) -> u16 {
         }
     }
 
-    // D71 has a second BAM at track 53, sector 0 for the second side (tracks 36-70)
+    // D71 keeps the free counts for side 1 (tracks 36-70) in the same BAM
+    // sector at $DD..$FF; the bitmaps live in track 53, sector 0. Track 53
+    // holds that second BAM and is never available for files.
     if kind == ImageKind::D71 {
-        if let Some(bam2) = read_sector(data, 53, 0, kind) {
-            for track in 1u8..=35 {
-                if track == 18 {
-                    continue;
-                }
-                let offset = (track as usize - 1) * 3;
-                if offset < bam2.len() {
-                    free += bam2[offset] as u16;
-                }
+        for track in 36u8..=70 {
+            if track == 53 {
+                continue;
             }
+            free += bam[0xDD + (track as usize - 36)] as u16;
         }
     }
 
@@ -266,8 +402,12 @@ fn count_free_blocks_d81(data: &[u8]) -> u16 {
             Some(s) => s,
             None => continue,
         };
-        // Entries start at offset 16; 6 bytes each; 40 tracks per BAM block
+        // Entries start at offset 16; 6 bytes each; 40 tracks per BAM block.
+        // Track 40 holds the directory and is left out of BLOCKS FREE.
         for i in 0..40usize {
+            if bam_sector == 1 && i == 39 {
+                continue;
+            }
             let off = 16 + i * 6;
             if off < bam.len() {
                 free += bam[off] as u16;
@@ -277,6 +417,15 @@ fn count_free_blocks_d81(data: &[u8]) -> u16 {
     free
 }
 
+/// DNP: one bit per sector (set = free), 32 bytes per track, from 1/2. The
+/// first 32 bytes stand in for track 0 and hold the partition info instead.
+fn count_free_blocks_dnp(data: &[u8]) -> u16 {
+    let tracks = dnp_tracks(data.len()).unwrap_or(0) as usize;
+    let bam = ts_offset(1, 2, ImageKind::Dnp).unwrap_or(0);
+    data.get(bam + 32..bam + (tracks + 1) * 32)
+        .map_or(0, |bits| bits.iter().map(|b| b.count_ones() as u16).sum())
+}
+
 /// Read disk information from a file path
 pub fn read_disk_info(path: &Path) -> Result<DiskInfo, String> {
     let data = fs::read(path).map_err(|e| format!("Failed to read file: {}", e))?;
@@ -286,8 +435,19 @@ pub fn read_disk_info(path: &Path) -> Result<DiskInfo, String> {
 
 /// Read disk information from raw bytes
 pub fn read_disk_info_from_bytes(data: &[u8]) -> Result<DiskInfo, String> {
-    let kind = detect_kind(data.len())
+    if gcr_image::is_gcr_image(data) {
+        let (image, report) = gcr_image::decode(data)?;
+        let mut info = read_disk_info_from_bytes(&image)?;
+        info.gcr = Some(report);
+        return Ok(info);
+    }
+    if is_dnp(data) {
+        return read_dnp_info(data);
+    }
+
+    let geometry = geometry(data.len())
         .ok_or_else(|| format!("Unknown disk image format (size: {} bytes)", data.len()))?;
+    let kind = geometry.kind;
 
     // D81 header is at track 40, sector 0; D64/D71 header is at track 18, sector 0
     let (header_track, name_off, id_off, dos_off) = match kind {
@@ -312,25 +472,28 @@ pub fn read_disk_info_from_bytes(data: &[u8]) -> Result<DiskInfo, String> {
         dos_type,
         entries,
         blocks_free,
+        gcr: None,
+        tracks: geometry.tracks,
+        error_info: error_info_sectors(data),
+        location: Vec::new(),
     })
 }
 
 /// Read all directory entries from the disk
 fn read_directory(data: &[u8], kind: ImageKind) -> Result<Vec<DirEntry>, String> {
-    let mut entries = Vec::new();
+    read_directory_from(data, kind, directory_start(kind))
+}
 
-    // D81 directory starts at track 40, sector 3; D64/D71 at track 18, sector 1
-    let (mut track, mut sector) = match kind {
-        ImageKind::D81 => (40u8, 3u8),
-        _ => (18u8, 1u8),
-    };
-    let mut iterations = 0;
+/// Directory entries of the chain starting at `start`
+fn read_directory_from(
+    data: &[u8],
+    kind: ImageKind,
+    start: (u8, u8),
+) -> Result<Vec<DirEntry>, String> {
+    let mut entries = Vec::new();
 
     // Follow the directory chain
-    while track != 0 && iterations < 20 {
-        // Safety limit
-        iterations += 1;
-
+    for (track, sector) in directory_chain_from(data, kind, start) {
         let dir_sector = match read_sector(data, track, sector, kind) {
             Some(s) => s,
             None => break,
@@ -375,13 +538,49 @@ fn read_directory(data: &[u8], kind: ImageKind) -> Result<Vec<DirEntry>, String>
                 first_sector,
             });
         }
+    }
+
+    Ok(entries)
+}
+
+/// First directory sector: track 40, sector 3 on a D81; track 18, sector 1
+/// on a D64/D71.
+fn directory_start(kind: ImageKind) -> (u8, u8) {
+    match kind {
+        ImageKind::D81 => (40, 3),
+        ImageKind::Dnp => (1, 34),
+        _ => (18, 1),
+    }
+}
+
+/// Track/sector list of the directory chain, in order. Stops at the end
+/// marker, at an unreadable link, or once the chain is longer than the
+/// directory track could hold (a loop on a corrupt disk).
+fn directory_chain(data: &[u8], kind: ImageKind) -> Vec<(u8, u8)> {
+    directory_chain_from(data, kind, directory_start(kind))
+}
 
-        // Follow chain to next directory sector
+/// [`directory_chain`] starting at `start`, for partitions and sub-directories
+fn directory_chain_from(data: &[u8], kind: ImageKind, start: (u8, u8)) -> Vec<(u8, u8)> {
+    let (mut track, mut sector) = start;
+    let limit = match kind {
+        ImageKind::D81 => 40,
+        ImageKind::Dnp => 1024,
+        _ => 20,
+    };
+    let mut chain = Vec::new();
+    while track != 0 && chain.len() < limit {
+        let Some(dir_sector) = read_sector(data, track, sector, kind) else {
+            break;
+        };
+        if chain.contains(&(track, sector)) {
+            break;
+        }
+        chain.push((track, sector));
         track = dir_sector[0];
         sector = dir_sector[1];
     }
-
-    Ok(entries)
+    chain
 }
 
 /// Quick check if a file appears to be a supported disk image
@@ -408,6 +607,138 @@ pub fn get_disk_summary(path: &Path) -> Result<String, String> {
     ))
 }
 
+// ─── Partitions and sub-directories ───────────────────────────────────────────
+//
+// A 1581 partition (CBM entry) that starts on sector 0 and covers at least
+// three whole tracks can hold its own directory: header at (T,0), BAM at (T,1)
+// and (T,2), directory from (T,3), all laid out like track 40. In a DNP image
+// each DIR entry points at a header block laid out like the root's at 1/1.
+// Links in both stay absolute, so the sector reader works unchanged.
+
+const DNP_TRACK_BYTES: usize = 256 * 256;
+
+/// Tracks in a DNP-sized image: whole 256-sector tracks, 1 to 255
+pub fn dnp_tracks(len: usize) -> Option<u8> {
+    let tracks = len / DNP_TRACK_BYTES;
+    (len.is_multiple_of(DNP_TRACK_BYTES) && (1..=255).contains(&tracks)).then_some(tracks as u8)
+}
+
+/// A DNP image: the right size and a native-format header at 1/1 (linked to
+/// the root directory at 1/34). A 3-track DNP is the size of a 40-track D64,
+/// so at that size a 1541 BAM at 18/0 (linked to 18/1, DOS type 'A') wins.
+fn is_dnp(data: &[u8]) -> bool {
+    if dnp_tracks(data.len()).is_none() || data[256..259] != [0x01, 0x22, b'H'] {
+        return false;
+    }
+    let d64_bam = ts_offset(18, 0, ImageKind::D64)
+        .filter(|_| data.len() == 3 * DNP_TRACK_BYTES)
+        .map(|bam| &data[bam..bam + 3]);
+    d64_bam != Some(&[18, 1, b'A'][..])
+}
+
+/// Listing of the directory whose header block (1581 layout: link to the
+/// first directory block, name at 4, ID at 22, DOS type at 25) is at `header`
+fn directory_info(
+    data: &[u8],
+    kind: ImageKind,
+    header: (u8, u8),
+    blocks_free: u16,
+    tracks: u8,
+) -> Result<DiskInfo, String> {
+    let block = read_sector(data, header.0, header.1, kind).ok_or_else(|| {
+        format!(
+            "Failed to read directory header at T{} S{}",
+            header.0, header.1
+        )
+    })?;
+    Ok(DiskInfo {
+        kind,
+        name: petscii::to_string(&block[4..20]),
+        disk_id: petscii::to_string(&block[22..24]),
+        dos_type: petscii::to_string(&block[25..27]),
+        entries: read_directory_from(data, kind, (block[0], block[1]))?,
+        blocks_free,
+        gcr: None,
+        tracks,
+        error_info: Vec::new(),
+        location: Vec::new(),
+    })
+}
+
+fn read_dnp_info(data: &[u8]) -> Result<DiskInfo, String> {
+    directory_info(
+        data,
+        ImageKind::Dnp,
+        (1, 1),
+        count_free_blocks(data, ImageKind::Dnp),
+        dnp_tracks(data.len()).unwrap_or(0),
+    )
+}
+
+/// Listing of the 1581 partition behind `entry`, when it was formatted as a
+/// sub-directory. BLOCKS FREE counts the partition's tracks after its first.
+fn read_1581_partition(data: &[u8], entry: &DirEntry) -> Result<DiskInfo, String> {
+    let plain = || {
+        format!(
+            "\"{}\" is a plain partition, not a sub-directory",
+            entry.name.trim()
+        )
+    };
+    let first = entry.first_track as u16;
+    let last = first + (entry.size_blocks / 40).max(1) - 1;
+    if entry.first_sector != 0
+        || !entry.size_blocks.is_multiple_of(40)
+        || entry.size_blocks < 120
+        || first == 0
+        || last > 80
+        || (first..=last).contains(&40)
+    {
+        return Err(plain());
+    }
+    let first = first as u8;
+    let header = read_sector(data, first, 0, ImageKind::D81).ok_or_else(plain)?;
+    if header[2] != 0x44 {
+        return Err(plain());
+    }
+    let mut free = 0u16;
+    for track in first + 1..=last as u8 {
+        let bam_sector = if track <= 40 { 1 } else { 2 };
+        if let Some(bam) = read_sector(data, first, bam_sector, ImageKind::D81) {
+            free += bam[16 + (track as usize - 1) % 40 * 6] as u16;
+        }
+    }
+    directory_info(data, ImageKind::D81, (first, 0), free, 80)
+}
+
+/// Listing of a sub-directory, reached by following `path` (listing indices
+/// from the root down) through 1581 partitions on a D81 or DIR entries on a
+/// DNP. An empty path is the root listing.
+pub fn read_disk_info_in(data: &[u8], path: &[usize]) -> Result<DiskInfo, String> {
+    let mut info = read_disk_info_from_bytes(data)?;
+    for &index in path {
+        let entry = info
+            .entries
+            .get(index)
+            .ok_or_else(|| format!("No directory entry #{}", index))?;
+        let mut sub = match (info.kind, entry.file_type) {
+            (ImageKind::D81, FileType::Cbm) => read_1581_partition(data, entry)?,
+            (ImageKind::Dnp, FileType::Dir) => directory_info(
+                data,
+                ImageKind::Dnp,
+                (entry.first_track, entry.first_sector),
+                info.blocks_free,
+                info.tracks,
+            )?,
+            _ => return Err(format!("\"{}\" is not a directory", entry.name.trim())),
+        };
+        sub.location = std::mem::take(&mut info.location);
+        sub.location.push(entry.name.trim().to_string());
+        sub.error_info = std::mem::take(&mut info.error_info);
+        info = sub;
+    }
+    Ok(info)
+}
+
 // ─── File extraction ──────────────────────────────────────────────────────────
 
 /// Follow a CBM file's sector chain and return its raw bytes (including the
@@ -471,6 +802,895 @@ pub fn extract_single_prg(data: &[u8]) -> Option<(String, Vec<u8>)> {
     Some((only.name.trim().to_string(), bytes))
 }
 
+// ─── Writable filesystem ──────────────────────────────────────────────────────
+//
+// CBM DOS editing on top of `ts_offset`/`read_sector`: BAM allocation with the
+// drive's own interleave, directory-sector growth on the directory track, and
+// scratch/rename/lock/re-order of existing entries. Every operation either
+// succeeds completely or leaves the image untouched.
+//
+// Entries are addressed by their index in `DiskInfo::entries`, i.e. the order
+// `read_directory` returns them in.
+
+/// Directory track and sector interleaves used by the drive that owns `kind`.
+struct DosLayout {
+    dir_track: u8,
+    /// Sector step between consecutive blocks of a file.
+    data_interleave: u8,
+    /// Sector step between consecutive directory blocks.
+    dir_interleave: u8,
+}
+
+fn dos_layout(kind: ImageKind) -> DosLayout {
+    match kind {
+        ImageKind::D64 => DosLayout {
+            dir_track: 18,
+            data_interleave: 10,
+            dir_interleave: 3,
+        },
+        ImageKind::D71 => DosLayout {
+            dir_track: 18,
+            data_interleave: 6,
+            dir_interleave: 3,
+        },
+        ImageKind::D81 | ImageKind::Dnp => DosLayout {
+            dir_track: if kind == ImageKind::Dnp { 1 } else { 40 },
+            data_interleave: 1,
+            dir_interleave: 1,
+        },
+    }
+}
+
+/// Sectors on `track` for tracks the BAM covers. 40-track D64 extensions are
+/// not allocatable: their BAM layout differs between DOS variants.
+fn sectors_per_track(kind: ImageKind, track: u8) -> Option<u8> {
+    match (kind, track) {
+        (ImageKind::D64, 1..=35) => spt_1541(track),
+        (ImageKind::D71, 1..=35) => spt_1541(track),
+        (ImageKind::D71, 36..=70) => spt_1541(track - 35),
+        (ImageKind::D81, 1..=80) => Some(40),
+        _ => None,
+    }
+}
+
+/// Byte offsets of a track's free-count byte and of its sector bitmap.
+fn bam_entry(kind: ImageKind, track: u8) -> Option<(usize, usize)> {
+    sectors_per_track(kind, track)?;
+    match kind {
+        ImageKind::D81 => {
+            let bam_sector = if track <= 40 { 1 } else { 2 };
+            let count = ts_offset(40, bam_sector, kind)? + 16 + ((track as usize - 1) % 40) * 6;
+            Some((count, count + 1))
+        }
+        _ if track <= 35 => {
+            let count = ts_offset(18, 0, kind)? + 4 + (track as usize - 1) * 4;
+            Some((count, count + 1))
+        }
+        _ => {
+            let rel = track as usize - 36;
+            let count = ts_offset(18, 0, kind)? + 0xDD + rel;
+            let bitmap = ts_offset(53, 0, kind)? + rel * 3;
+            Some((count, bitmap))
+        }
+    }
+}
+
+fn is_block_free(data: &[u8], kind: ImageKind, track: u8, sector: u8) -> bool {
+    match bam_entry(kind, track) {
+        Some((_, bitmap)) if sector < sectors_per_track(kind, track).unwrap_or(0) => {
+            data[bitmap + sector as usize / 8] & (1 << (sector % 8)) != 0
+        }
+        _ => false,
+    }
+}
+
+/// Mark a block used or free, keeping the track's free count in step.
+fn set_block_free(data: &mut [u8], kind: ImageKind, track: u8, sector: u8, free: bool) {
+    let Some((count, bitmap)) = bam_entry(kind, track) else {
+        return;
+    };
+    if sector >= sectors_per_track(kind, track).unwrap_or(0)
+        || is_block_free(data, kind, track, sector) == free
+    {
+        return;
+    }
+    let mask = 1 << (sector % 8);
+    if free {
+        data[bitmap + sector as usize / 8] |= mask;
+        data[count] = data[count].saturating_add(1);
+    } else {
+        data[bitmap + sector as usize / 8] &= !mask;
+        data[count] = data[count].saturating_sub(1);
+    }
+}
+
+fn track_has_free(data: &[u8], kind: ImageKind, track: u8) -> bool {
+    bam_entry(kind, track).is_some_and(|(count, _)| data[count] > 0)
+}
+
+/// Tracks ordered by distance from the directory track, the way the DOS
+/// picks a track for the first block of a new file. On a D71 side 1 follows
+/// side 0, ordered around its BAM track 53.
+fn track_search_order(kind: ImageKind) -> Vec<u8> {
+    let around = |centre: u8, lo: u8, hi: u8| {
+        let mut order = Vec::new();
+        for dist in 1..=(hi - lo) {
+            if centre >= lo + dist {
+                order.push(centre - dist);
+            }
+            if centre + dist <= hi {
+                order.push(centre + dist);
+            }
+        }
+        order
+    };
+    match kind {
+        ImageKind::D64 => around(18, 1, 35),
+        ImageKind::D71 => {
+            let mut order = around(18, 1, 35);
+            order.extend(around(53, 36, 70));
+            order
+        }
+        ImageKind::D81 => around(40, 1, 80),
+        // Never written: `image_kind` turns DNP images away
+        ImageKind::Dnp => Vec::new(),
+    }
+}
+
+/// First free sector on `track`, scanning upwards from `start` and wrapping.
+fn free_sector_from(data: &[u8], kind: ImageKind, track: u8, start: u8) -> Option<u8> {
+    let spt = sectors_per_track(kind, track)?;
+    (0..spt)
+        .map(|i| (start % spt + i) % spt)
+        .find(|&s| is_block_free(data, kind, track, s))
+}
+
+/// Pick the next data block after `prev` (or the first block of a file when
+/// `prev` is `None`) and mark it used.
+///
+/// Follows the drive's interleave on the current track; once that track is
+/// full it moves one track further away from the directory, falling back to
+/// the nearest-to-directory search when it runs off the edge of the disk.
+fn allocate_data_block(
+    data: &mut [u8],
+    kind: ImageKind,
+    prev: Option<(u8, u8)>,
+) -> Option<(u8, u8)> {
+    let layout = dos_layout(kind);
+    let mut candidates: Vec<(u8, u8)> = Vec::new();
+    if let Some((track, sector)) = prev {
+        candidates.push((track, sector.wrapping_add(layout.data_interleave)));
+        let (centre, lo, hi) = match (kind, track) {
+            (ImageKind::D71, 36..=70) => (53, 36, 70),
+            (ImageKind::D71, _) | (ImageKind::D64, _) => (layout.dir_track, 1, 35),
+            (ImageKind::D81, _) => (layout.dir_track, 1, 80),
+            (ImageKind::Dnp, _) => return None,
+        };
+        if track < centre {
+            candidates.extend((lo..track).rev().map(|t| (t, 0)));
+        } else {
+            candidates.extend((track + 1..=hi).map(|t| (t, 0)));
+        }
+    }
+    candidates.extend(track_search_order(kind).into_iter().map(|t| (t, 0)));
+
+    for (track, start) in candidates {
+        if track == layout.dir_track || !track_has_free(data, kind, track) {
+            continue;
+        }
+        if let Some(sector) = free_sector_from(data, kind, track, start) {
+            set_block_free(data, kind, track, sector, false);
+            return Some((track, sector));
+        }
+    }
+    None
+}
+
+/// Free blocks usable for file data (everything except the directory track).
+fn free_data_blocks(data: &[u8], kind: ImageKind) -> usize {
+    let dir_track = dos_layout(kind).dir_track;
+    track_search_order(kind)
+        .into_iter()
+        .filter(|&t| t != dir_track)
+        .filter_map(|t| bam_entry(kind, t))
+        .map(|(count, _)| data[count] as usize)
+        .sum()
+}
+
+fn sector_mut(data: &mut [u8], track: u8, sector: u8, kind: ImageKind) -> Option<&mut [u8]> {
+    let offset = ts_offset(track, sector, kind)?;
+    if offset + 256 > sector_data_len(data) {
+        return None;
+    }
+    data.get_mut(offset..offset + 256)
+}
+
+/// Byte offsets of every used directory slot, in `read_directory` order.
+fn used_entry_offsets(data: &[u8], kind: ImageKind) -> Vec<usize> {
+    directory_chain(data, kind)
+        .into_iter()
+        .filter_map(|(t, s)| ts_offset(t, s, kind))
+        .flat_map(|base| (0..8).map(move |i| base + i * 32))
+        .filter(|&off| data[off + 2] != 0)
+        .collect()
+}
+
+fn entry_offset(data: &[u8], kind: ImageKind, index: usize) -> Result<usize, String> {
+    used_entry_offsets(data, kind)
+        .get(index)
+        .copied()
+        .ok_or_else(|| format!("No directory entry #{}", index))
+}
+
+fn image_kind(data: &[u8]) -> Result<ImageKind, String> {
+    if is_dnp(data) {
+        return Err("DNP images can be browsed but not changed".to_string());
+    }
+    detect_kind(data.len())
+        .ok_or_else(|| format!("Unknown disk image format (size: {} bytes)", data.len()))
+}
+
+/// 16-byte PETSCII filename, $A0-padded. Characters outside printable ASCII
+/// become `?`; longer names are rejected rather than silently cut.
+fn petscii_filename(name: &str) -> Result<[u8; 16], String> {
+    if name.is_empty() {
+        return Err("File name is empty".to_string());
+    }
+    if name.chars().count() > 16 {
+        return Err(format!(
+            "File name \"{}\" is longer than 16 characters",
+            name
+        ));
+    }
+    let clean: String = name
+        .chars()
+        .map(|c| {
+            if c == ' ' || c.is_ascii_graphic() {
+                c
+            } else {
+                '?'
+            }
+        })
+        .collect();
+    let mut out = [0xA0u8; 16];
+    write_petscii_name(&mut out, &clean);
+    Ok(out)
+}
+
+/// Track/sector list of a file's data chain, or an error on a broken chain.
+fn file_blocks(
+    data: &[u8],
+    kind: ImageKind,
+    start_t: u8,
+    start_s: u8,
+) -> Result<Vec<(u8, u8)>, String> {
+    let mut blocks = Vec::new();
+    let (mut track, mut sector) = (start_t, start_s);
+    while track != 0 {
+        if blocks.len() >= 4000 || blocks.contains(&(track, sector)) {
+            return Err("File chain loops".to_string());
+        }
+        let sec = read_sector(data, track, sector, kind)
+            .ok_or_else(|| format!("File chain points at invalid block {}/{}", track, sector))?;
+        blocks.push((track, sector));
+        track = sec[0];
+        sector = sec[1];
+    }
+    Ok(blocks)
+}
+
+/// The `count` consecutive blocks of a 1581 partition from `start`, running on
+/// into the next track. Whether they exist is left to the caller.
+fn partition_blocks(kind: ImageKind, start: (u8, u8), count: u16) -> Vec<(u8, u8)> {
+    std::iter::successors(Some(start), |&(track, sector)| {
+        if sector + 1 < track_sectors(kind, track) {
+            Some((track, sector + 1))
+        } else {
+            track.checked_add(1).map(|track| (track, 0))
+        }
+    })
+    .take(count as usize)
+    .collect()
+}
+
+/// Append a directory block on the directory track and link it to the end of
+/// the chain. Returns the byte offset of its first entry slot.
+fn grow_directory(data: &mut [u8], kind: ImageKind) -> Result<usize, String> {
+    let layout = dos_layout(kind);
+    let &(last_t, last_s) = directory_chain(data, kind)
+        .last()
+        .ok_or_else(|| "Directory is unreadable".to_string())?;
+    let sector = free_sector_from(
+        data,
+        kind,
+        layout.dir_track,
+        last_s.wrapping_add(layout.dir_interleave),
+    )
+    .ok_or_else(|| "Directory full".to_string())?;
+    set_block_free(data, kind, layout.dir_track, sector, false);
+
+    let new_block = sector_mut(data, layout.dir_track, sector, kind)
+        .ok_or_else(|| "Directory full".to_string())?;
+    new_block.fill(0);
+    new_block[1] = 0xFF;
+    if let Some(last) = sector_mut(data, last_t, last_s, kind) {
+        last[0] = layout.dir_track;
+        last[1] = sector;
+    }
+    ts_offset(layout.dir_track, sector, kind).ok_or_else(|| "Directory full".to_string())
+}
+
+/// Write `payload` into the image as a new closed file of `file_type`.
+///
+/// `payload` is the raw file contents; for a PRG that includes its two-byte
+/// load address. The entry takes the first free directory slot, growing the
+/// directory if every slot is in use.
+pub fn insert_file(
+    data: &mut [u8],
+    name: &str,
+    file_type: FileType,
+    payload: &[u8],
+) -> Result<(), String> {
+    let kind = image_kind(data)?;
+    if !matches!(file_type, FileType::Seq | FileType::Prg | FileType::Usr) {
+        return Err(format!("Cannot write {} files", file_type));
+    }
+    let raw_name = petscii_filename(name)?;
+    if used_entry_offsets(data, kind)
+        .iter()
+        .any(|&off| data[off + 5..off + 21] == raw_name)
+    {
+        return Err(format!("File \"{}\" already exists", name));
+    }
+
+    let blocks_needed = payload.len().div_ceil(254).max(1);
+    if blocks_needed > free_data_blocks(data, kind) {
+        return Err(format!(
+            "Disk full: {} blocks needed, {} free",
+            blocks_needed,
+            free_data_blocks(data, kind)
+        ));
+    }
+
+    let mut work = data.to_vec();
+
+    // Directory slot first, so a full directory fails before any data moves.
+    let free_slot = directory_chain(&work, kind)
+        .into_iter()
+        .filter_map(|(t, s)| ts_offset(t, s, kind))
+        .flat_map(|base| (0..8).map(move |i| base + i * 32))
+        .find(|&off| work[off + 2] == 0);
+    let slot = match free_slot {
+        Some(off) => off,
+        None => grow_directory(&mut work, kind)?,
+    };
+
+    let mut blocks = Vec::with_capacity(blocks_needed);
+    for _ in 0..blocks_needed {
+        let block = allocate_data_block(&mut work, kind, blocks.last().copied())
+            .ok_or_else(|| "Disk full".to_string())?;
+        blocks.push(block);
+    }
+
+    for (i, &(track, sector)) in blocks.iter().enumerate() {
+        let chunk = payload.chunks(254).nth(i).unwrap_or(&[]);
+        let block = sector_mut(&mut work, track, sector, kind)
+            .ok_or_else(|| format!("Invalid block {}/{}", track, sector))?;
+        block.fill(0);
+        match blocks.get(i + 1) {
+            Some(&(next_t, next_s)) => {
+                block[0] = next_t;
+                block[1] = next_s;
+            }
+            None => {
+                // Last block: link byte 1 is the index of the final used byte.
+                block[0] = 0;
+                block[1] = chunk.len() as u8 + 1;
+            }
+        }
+        block[2..2 + chunk.len()].copy_from_slice(chunk);
+    }
+
+    // Bytes 0-1 of a slot are the sector link (first slot only) — leave them.
+    let entry = &mut work[slot + 2..slot + 32];
+    entry.fill(0);
+    entry[0] = 0x80 | file_type.type_bits();
+    entry[1] = blocks[0].0;
+    entry[2] = blocks[0].1;
+    entry[3..19].copy_from_slice(&raw_name);
+    entry[28] = (blocks_needed & 0xFF) as u8;
+    entry[29] = (blocks_needed >> 8) as u8;
+
+    data.copy_from_slice(&work);
+    Ok(())
+}
+
+/// Read a file's contents out of the image: `(name, type, bytes)`.
+/// G64/G71 images are decoded first; unreadable sectors read as zeros.
+pub fn extract_file(data: &[u8], index: usize) -> Result<(String, FileType, Vec<u8>), String> {
+    if gcr_image::is_gcr_image(data) {
+        let (image, _) = gcr_image::decode(data)?;
+        return extract_file(&image, index);
+    }
+    let kind = image_kind(data)?;
+    let entry = read_directory(data, kind)?
+        .into_iter()
+        .nth(index)
+        .ok_or_else(|| format!("No directory entry #{}", index))?;
+    if entry.first_track == 0 {
+        return Err(format!("\"{}\" has no data", entry.name));
+    }
+    let bytes = follow_file_chain(data, kind, entry.first_track, entry.first_sector)
+        .ok_or_else(|| format!("\"{}\" has a broken sector chain", entry.name))?;
+    Ok((entry.name, entry.file_type, bytes))
+}
+
+/// Scratch a file: free its blocks in the BAM and clear the directory slot.
+/// A REL file's side-sector chain goes too, and a 1581 partition frees its
+/// whole block range. Locked files are refused, as on the drive.
+pub fn scratch_file(data: &mut [u8], index: usize) -> Result<(), String> {
+    let kind = image_kind(data)?;
+    let slot = entry_offset(data, kind, index)?;
+    if data[slot + 2] & 0x40 != 0 {
+        return Err("File is locked".to_string());
+    }
+    let start = (data[slot + 3], data[slot + 4]);
+    let blocks = if start.0 == 0 {
+        Vec::new()
+    } else if FileType::from_byte(data[slot + 2]) == FileType::Cbm {
+        let listed = u16::from_le_bytes([data[slot + 30], data[slot + 31]]);
+        let blocks = partition_blocks(kind, start, listed);
+        if let Some(&(track, sector)) = blocks
+            .iter()
+            .find(|&&(t, s)| read_sector(data, t, s, kind).is_none())
+        {
+            return Err(format!(
+                "Partition runs into invalid block {}/{}",
+                track, sector
+            ));
+        }
+        blocks
+    } else {
+        let mut blocks = file_blocks(data, kind, start.0, start.1)?;
+        if FileType::from_byte(data[slot + 2]) == FileType::Rel && data[slot + 21] != 0 {
+            blocks.extend(file_blocks(data, kind, data[slot + 21], data[slot + 22])?);
+        }
+        blocks
+    };
+    for (track, sector) in blocks {
+        set_block_free(data, kind, track, sector, true);
+    }
+    data[slot + 2] = 0;
+    Ok(())
+}
+
+/// Give an entry a new filename.
+pub fn rename_file(data: &mut [u8], index: usize, new_name: &str) -> Result<(), String> {
+    let kind = image_kind(data)?;
+    let slot = entry_offset(data, kind, index)?;
+    let raw_name = petscii_filename(new_name)?;
+    if used_entry_offsets(data, kind)
+        .iter()
+        .any(|&off| off != slot && data[off + 5..off + 21] == raw_name)
+    {
+        return Err(format!("File \"{}\" already exists", new_name));
+    }
+    data[slot + 5..slot + 21].copy_from_slice(&raw_name);
+    Ok(())
+}
+
+/// Set or clear the write-protect (`<`) flag on an entry.
+pub fn set_locked(data: &mut [u8], index: usize, locked: bool) -> Result<(), String> {
+    let kind = image_kind(data)?;
+    let slot = entry_offset(data, kind, index)?;
+    if locked {
+        data[slot + 2] |= 0x40;
+    } else {
+        data[slot + 2] &= !0x40;
+    }
+    Ok(())
+}
+
+/// Move the entry at `from` so it appears at position `to` in the listing.
+/// Only the 30 entry bytes move; directory sector links stay in place.
+pub fn move_entry(data: &mut [u8], from: usize, to: usize) -> Result<(), String> {
+    let kind = image_kind(data)?;
+    let slots = used_entry_offsets(data, kind);
+    if from >= slots.len() || to >= slots.len() {
+        return Err(format!("No directory entry #{}", from.max(to)));
+    }
+    let mut contents: Vec<Vec<u8>> = slots
+        .iter()
+        .map(|&off| data[off + 2..off + 32].to_vec())
+        .collect();
+    let moved = contents.remove(from);
+    contents.insert(to, moved);
+    for (&off, bytes) in slots.iter().zip(contents) {
+        data[off + 2..off + 32].copy_from_slice(&bytes);
+    }
+    Ok(())
+}
+
+// ─── Validate ─────────────────────────────────────────────────────────────────
+//
+// The drive's VALIDATE: follow every file's sector chain, rebuild the BAM from
+// the blocks actually in use, and drop entries for files that were never
+// closed. Chain damage (loops, cross-links, links off the disk) is reported
+// but left alone, as the drive would stop on it. Tracks past 35 on a 40/42-track
+// D64 have no standard BAM, so their blocks are followed but not checked.
+// A 1581 partition owns its whole block range, which is claimed as a unit.
+
+/// Something wrong with an image, as found by [`validate`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum Issue {
+    /// A file's chain points at a block that isn't on the disk
+    BrokenChain { file: String, track: u8, sector: u8 },
+    /// A file's chain runs back into itself
+    ChainLoop { file: String },
+    /// A block belongs to two files (or to a file and the directory)
+    CrossLinked {
+        file: String,
+        other: String,
+        track: u8,
+        sector: u8,
+    },
+    /// A file that was never closed (`*` in the listing)
+    Unclosed { file: String },
+    /// The directory's block count differs from the chain length
+    BlockCount {
+        file: String,
+        listed: u16,
+        actual: u16,
+    },
+    /// A file's chain passes through a sector flagged in the error info
+    ReadError {
+        file: String,
+        track: u8,
+        sector: u8,
+        error: SectorError,
+    },
+    /// The BAM marks a block free that is in use
+    UsedMarkedFree { track: u8, sector: u8 },
+    /// The BAM marks a block used that nothing uses
+    FreeMarkedUsed { track: u8, sector: u8 },
+    /// A track's free count disagrees with its bitmap
+    FreeCount { track: u8, listed: u8, actual: u8 },
+}
+
+impl Issue {
+    /// Whether [`repair`] fixes this
+    pub fn repairable(&self) -> bool {
+        matches!(
+            self,
+            Issue::Unclosed { .. }
+                | Issue::UsedMarkedFree { .. }
+                | Issue::FreeMarkedUsed { .. }
+                | Issue::FreeCount { .. }
+        )
+    }
+}
+
+impl std::fmt::Display for Issue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Issue::BrokenChain {
+                file,
+                track,
+                sector,
+            } => write!(
+                f,
+                "\"{}\": chain points off the disk at T{} S{}",
+                file, track, sector
+            ),
+            Issue::ChainLoop { file } => write!(f, "\"{}\": chain loops", file),
+            Issue::CrossLinked {
+                file,
+                other,
+                track,
+                sector,
+            } => write!(
+                f,
+                "\"{}\": T{} S{} also used by {}",
+                file, track, sector, other
+            ),
+            Issue::Unclosed { file } => write!(f, "\"{}\": never closed", file),
+            Issue::BlockCount {
+                file,
+                listed,
+                actual,
+            } => write!(
+                f,
+                "\"{}\": listed as {} blocks, chain has {}",
+                file, listed, actual
+            ),
+            Issue::ReadError {
+                file,
+                track,
+                sector,
+                error,
+            } => write!(
+                f,
+                "\"{}\": T{} S{} has error {}",
+                file, track, sector, error
+            ),
+            Issue::UsedMarkedFree { track, sector } => {
+                write!(f, "T{} S{} in use but marked free", track, sector)
+            }
+            Issue::FreeMarkedUsed { track, sector } => {
+                write!(f, "T{} S{} unused but marked allocated", track, sector)
+            }
+            Issue::FreeCount {
+                track,
+                listed,
+                actual,
+            } => write!(
+                f,
+                "Track {}: BAM says {} free, bitmap has {}",
+                track, listed, actual
+            ),
+        }
+    }
+}
+
+/// What [`validate`] found
+#[derive(Debug, Clone, PartialEq)]
+pub struct Validation {
+    pub issues: Vec<Issue>,
+    /// Closed files whose chains were followed
+    pub files: usize,
+    /// Blocks in use by files, the directory and the BAM
+    pub blocks_used: usize,
+}
+
+impl Validation {
+    pub fn summary(&self) -> String {
+        let repairable = self.issues.iter().filter(|i| i.repairable()).count();
+        match (self.issues.len(), repairable) {
+            (0, _) => format!(
+                "No problems: {} files, {} blocks in use",
+                self.files, self.blocks_used
+            ),
+            (n, r) if n == r => format!("{} problem(s), all fixed by Repair", n),
+            (n, r) => format!(
+                "{} problem(s): {} fixed by Repair, {} need attention",
+                n,
+                r,
+                n - r
+            ),
+        }
+    }
+}
+
+/// Blocks the DOS itself owns: header and BAM blocks, the whole BAM track on
+/// side 1 of a D71, and the directory chain
+fn system_blocks(data: &[u8], kind: ImageKind) -> Vec<(u8, u8)> {
+    let mut blocks = match kind {
+        ImageKind::D64 => vec![(18, 0)],
+        ImageKind::D71 => std::iter::once((18, 0))
+            .chain((0..track_sectors(kind, 53)).map(|s| (53, s)))
+            .collect(),
+        ImageKind::D81 => vec![(40, 0), (40, 1), (40, 2)],
+        // Never validated: `image_kind` turns DNP images away
+        ImageKind::Dnp => Vec::new(),
+    };
+    blocks.extend(directory_chain(data, kind));
+    blocks
+}
+
+/// Tracks with a BAM entry
+fn bam_tracks(kind: ImageKind) -> impl Iterator<Item = u8> {
+    (1..=80).filter(move |&track| sectors_per_track(kind, track).is_some())
+}
+
+/// Every file's chain followed: who owns each block, chain problems, and
+/// the directory slots of files that were never closed
+struct Allocation {
+    owners: HashMap<(u8, u8), String>,
+    issues: Vec<Issue>,
+    files: usize,
+    unclosed: Vec<usize>,
+}
+
+/// Record `file` as the owner of a block, or report the cross-link
+fn claim_block(
+    owners: &mut HashMap<(u8, u8), String>,
+    issues: &mut Vec<Issue>,
+    file: &str,
+    track: u8,
+    sector: u8,
+) {
+    match owners.get(&(track, sector)) {
+        Some(other) => issues.push(Issue::CrossLinked {
+            file: file.to_string(),
+            other: other.clone(),
+            track,
+            sector,
+        }),
+        None => {
+            owners.insert((track, sector), format!("\"{}\"", file));
+        }
+    }
+}
+
+fn allocate_files(data: &[u8], kind: ImageKind, bad_sectors: &[BadSector]) -> Allocation {
+    let read_errors: HashMap<(u8, u8), SectorError> = bad_sectors
+        .iter()
+        .map(|b| ((b.track, b.sector), b.error))
+        .collect();
+    let mut owners: HashMap<(u8, u8), String> = system_blocks(data, kind)
+        .into_iter()
+        .map(|block| (block, "the directory".to_string()))
+        .collect();
+    let mut issues = Vec::new();
+    let mut files = 0;
+    let mut unclosed = Vec::new();
+
+    for slot in used_entry_offsets(data, kind) {
+        let type_byte = data[slot + 2];
+        let file = petscii::to_string(&data[slot + 5..slot + 21])
+            .trim()
+            .to_string();
+        if type_byte & 0x80 == 0 {
+            issues.push(Issue::Unclosed { file });
+            unclosed.push(slot);
+            continue;
+        }
+        files += 1;
+        let listed = u16::from_le_bytes([data[slot + 30], data[slot + 31]]);
+
+        // A 1581 partition is a run of consecutive blocks; what lies inside
+        // is its own business, so nothing in it is followed.
+        if FileType::from_byte(type_byte) == FileType::Cbm {
+            let start = (data[slot + 3], data[slot + 4]);
+            for (track, sector) in partition_blocks(kind, start, listed) {
+                if read_sector(data, track, sector, kind).is_none() {
+                    issues.push(Issue::BrokenChain {
+                        file: file.clone(),
+                        track,
+                        sector,
+                    });
+                    break;
+                }
+                claim_block(&mut owners, &mut issues, &file, track, sector);
+            }
+            continue;
+        }
+
+        // A REL file's side sectors form a second chain.
+        let mut chains = vec![(data[slot + 3], data[slot + 4])];
+        if FileType::from_byte(type_byte) == FileType::Rel {
+            chains.push((data[slot + 21], data[slot + 22]));
+        }
+        let mut blocks = 0u16;
+        let mut intact = true;
+        let mut seen = HashSet::new();
+        for (mut track, mut sector) in chains {
+            while track != 0 {
+                if !seen.insert((track, sector)) {
+                    issues.push(Issue::ChainLoop { file: file.clone() });
+                    intact = false;
+                    break;
+                }
+                let Some(block) = read_sector(data, track, sector, kind) else {
+                    issues.push(Issue::BrokenChain {
+                        file: file.clone(),
+                        track,
+                        sector,
+                    });
+                    intact = false;
+                    break;
+                };
+                claim_block(&mut owners, &mut issues, &file, track, sector);
+                if let Some(&error) = read_errors.get(&(track, sector)) {
+                    issues.push(Issue::ReadError {
+                        file: file.clone(),
+                        track,
+                        sector,
+                        error,
+                    });
+                }
+                blocks += 1;
+                (track, sector) = (block[0], block[1]);
+            }
+        }
+        if intact && listed != blocks {
+            issues.push(Issue::BlockCount {
+                file,
+                listed,
+                actual: blocks,
+            });
+        }
+    }
+
+    Allocation {
+        owners,
+        issues,
+        files,
+        unclosed,
+    }
+}
+
+/// Check an image without changing it: every file's chain, then the BAM
+/// against the blocks in use. G64/G71 images are decoded first, and sectors
+/// the decoder couldn't read count as read errors.
+pub fn validate(data: &[u8]) -> Result<Validation, String> {
+    if gcr_image::is_gcr_image(data) {
+        let (decoded, report) = gcr_image::decode(data)?;
+        return check(&decoded, &report.bad_sectors);
+    }
+    check(data, &error_info_sectors(data))
+}
+
+fn check(data: &[u8], bad_sectors: &[BadSector]) -> Result<Validation, String> {
+    let kind = image_kind(data)?;
+    let Allocation {
+        owners,
+        mut issues,
+        files,
+        ..
+    } = allocate_files(data, kind, bad_sectors);
+
+    for track in bam_tracks(kind) {
+        let Some((count, _)) = bam_entry(kind, track) else {
+            continue;
+        };
+        let mut free = 0u8;
+        for sector in 0..track_sectors(kind, track) {
+            let marked_free = is_block_free(data, kind, track, sector);
+            let used = owners.contains_key(&(track, sector));
+            if marked_free {
+                free += 1;
+            }
+            if used && marked_free {
+                issues.push(Issue::UsedMarkedFree { track, sector });
+            } else if !used && !marked_free {
+                issues.push(Issue::FreeMarkedUsed { track, sector });
+            }
+        }
+        if data[count] != free {
+            issues.push(Issue::FreeCount {
+                track,
+                listed: data[count],
+                actual: free,
+            });
+        }
+    }
+
+    Ok(Validation {
+        issues,
+        files,
+        blocks_used: owners.len(),
+    })
+}
+
+/// VALIDATE the image in place: scratch files that were never closed and
+/// rewrite the BAM from the blocks in use. Returns what was found before the
+/// repair; issues that aren't [`Issue::repairable`] are still there.
+pub fn repair(data: &mut [u8]) -> Result<Validation, String> {
+    let kind = image_kind(data)?;
+    let found = validate(data)?;
+    let allocation = allocate_files(data, kind, &[]);
+    for slot in allocation.unclosed {
+        data[slot + 2] = 0;
+    }
+    let bitmap_len = if kind == ImageKind::D81 { 5 } else { 3 };
+    for track in bam_tracks(kind) {
+        let Some((count, bitmap)) = bam_entry(kind, track) else {
+            continue;
+        };
+        data[bitmap..bitmap + bitmap_len].fill(0);
+        let mut free = 0u8;
+        for sector in 0..track_sectors(kind, track) {
+            if !allocation.owners.contains_key(&(track, sector)) {
+                data[bitmap + sector as usize / 8] |= 1 << (sector % 8);
+                free += 1;
+            }
+        }
+        data[count] = free;
+    }
+    Ok(found)
+}
+
 // ─── Disk image creation ──────────────────────────────────────────────────────
 
 /// Write a PETSCII disk name into a 16-byte slice, padding with 0xA0 (shifted space).
@@ -515,14 +1735,18 @@ pub fn build_blank_d64(name: &str, disk_id: &str) -> Vec<u8> {
         (31, 35, 17, 0x01FFFF),
     ];
     for track in 1u8..=35 {
-        if track == 18 {
-            continue;
-        } // directory track — leave zeroed
         let (spt, mask) = zone
             .iter()
             .find(|&&(lo, hi, _, _)| track >= lo && track <= hi)
             .map(|&(_, _, s, m)| (s, m))
             .unwrap_or((17, 0x01FFFF));
+        // Directory track: sectors 0 (BAM) and 1 (first directory block) are
+        // in use; the rest stay free so the directory can grow into them.
+        let (spt, mask) = if track == 18 {
+            (spt - 2, mask & !0b11)
+        } else {
+            (spt, mask)
+        };
         let off = bam + 4 + (track as usize - 1) * 4;
         img[off] = spt;
         img[off + 1] = (mask & 0xFF) as u8;
@@ -571,7 +1795,8 @@ pub fn build_blank_d64(name: &str, disk_id: &str) -> Vec<u8> {
 /// Create a blank, formatted D71 image (349,696 bytes).
 ///
 /// The D71 is two back-to-back 1541 sides. Side 0 uses the same layout as D64;
-/// side 1 has a secondary BAM at track 53, sector 0.
+/// the BAM sector at track 18 also carries the side-1 free counts at $DD..$FF,
+/// and the side-1 sector bitmaps live at track 53, sector 0.
 pub fn build_blank_d71(name: &str, disk_id: &str) -> Vec<u8> {
     let d64 = build_blank_d64(name, disk_id);
     let mut img = vec![0u8; 349_696];
@@ -579,16 +1804,16 @@ pub fn build_blank_d71(name: &str, disk_id: &str) -> Vec<u8> {
     // Side 0: copy D64 layout
     img[..174_848].copy_from_slice(&d64[..174_848]);
 
-    // Side 1 BAM at track 53, sector 0
+    // Mark the disk as double-sided in the main BAM.
+    let bam = ts_offset(18, 0, ImageKind::D71).unwrap_or(0x16500);
+    img[bam + 3] = 0x80;
+
+    // Side 1 bitmaps at track 53, sector 0
     // Track 53 on D71 = track 18 of side 1 (relative track 53-35=18)
     if let Some(bam2) = ts_offset(53, 0, ImageKind::D71) {
         if bam2 + 256 <= img.len() {
-            img[bam2] = 0; // no chain
-            img[bam2 + 1] = 0xFF;
-            img[bam2 + 2] = 0x44; // 'D' — 1571 DOS version
-
-            // Side-1 BAM entries: 3 bytes each (free count + 2-byte bitmap)
-            // Tracks 36-70 → relative tracks 1-35 on side 1
+            // Side-1 bitmaps: 3 bytes per track, free counts go into the
+            // main BAM. Tracks 36-70 → relative tracks 1-35 on side 1
             let zone: &[(u8, u8, u8, u32)] = &[
                 (1, 17, 21, 0x1FFFFF),
                 (18, 24, 19, 0x07FFFF),
@@ -598,18 +1823,17 @@ pub fn build_blank_d71(name: &str, disk_id: &str) -> Vec<u8> {
             for rel in 1u8..=35 {
                 if rel == 18 {
                     continue;
-                } // directory track on side 1
+                } // BAM track on side 1 — stays fully allocated
                 let (spt, mask) = zone
                     .iter()
                     .find(|&&(lo, hi, _, _)| rel >= lo && rel <= hi)
                     .map(|&(_, _, s, m)| (s, m))
                     .unwrap_or((17, 0x01FFFF));
+                img[bam + 0xDD + (rel as usize - 1)] = spt;
                 let off = bam2 + (rel as usize - 1) * 3;
-                if off + 3 <= img.len() {
-                    img[off] = spt;
-                    img[off + 1] = (mask & 0xFF) as u8;
-                    img[off + 2] = ((mask >> 8) & 0xFF) as u8;
-                }
+                img[off] = (mask & 0xFF) as u8;
+                img[off + 1] = ((mask >> 8) & 0xFF) as u8;
+                img[off + 2] = ((mask >> 16) & 0xFF) as u8;
             }
         }
     }
@@ -675,8 +1899,15 @@ pub fn build_blank_d81(name: &str, disk_id: &str) -> Vec<u8> {
                 break;
             }
             if track == 40 {
-                // Directory track — mark as occupied (count=0)
-                img[entry] = 0;
+                // Directory track — header, both BAM blocks and the first
+                // directory block (sectors 0-3) are in use, the rest is free
+                // for directory growth.
+                img[entry] = 36;
+                img[entry + 1] = 0xF0;
+                img[entry + 2] = 0xFF;
+                img[entry + 3] = 0xFF;
+                img[entry + 4] = 0xFF;
+                img[entry + 5] = 0xFF;
             } else {
                 img[entry] = 40; // 40 sectors free
                                  // All 40 bits set across 5 bytes
@@ -753,8 +1984,9 @@ mod tests {
         assert_eq!(img[bam + 2], 0x41); // DOS version 'A'
                                         // First data track (track 1) should have 21 free sectors
         assert_eq!(img[bam + 4], 21);
-        // Directory track (18) should have zero free sectors
-        assert_eq!(img[bam + 4 + 17 * 4], 0);
+        // Directory track (18): only the BAM and first directory block are used
+        assert_eq!(img[bam + 4 + 17 * 4], 17);
+        assert_eq!(read_disk_info_from_bytes(&img).unwrap().blocks_free, 664);
     }
 
     #[test]
@@ -819,6 +2051,370 @@ mod tests {
         assert_eq!(bytes, payload);
     }
 
+    #[test]
+    fn test_blank_blocks_free() {
+        let d71 = build_blank_d71("TWO", "01 2A");
+        assert_eq!(read_disk_info_from_bytes(&d71).unwrap().blocks_free, 1328);
+        let d81 = build_blank_d81("EIGHTY", "01 3D");
+        assert_eq!(read_disk_info_from_bytes(&d81).unwrap().blocks_free, 3160);
+    }
+
+    #[test]
+    fn test_insert_and_extract_roundtrip() {
+        for mut img in [
+            build_blank_d64("RW", "01 2A"),
+            build_blank_d71("RW", "01 2A"),
+            build_blank_d81("RW", "01 3D"),
+        ] {
+            let before = read_disk_info_from_bytes(&img).unwrap().blocks_free;
+            let payload: Vec<u8> = (0..1000u32).map(|i| (i * 7) as u8).collect();
+            insert_file(&mut img, "demo", FileType::Prg, &payload).unwrap();
+
+            let info = read_disk_info_from_bytes(&img).unwrap();
+            assert_eq!(info.entries.len(), 1);
+            assert_eq!(info.entries[0].name, "DEMO");
+            assert_eq!(info.entries[0].size_blocks, 4);
+            assert!(info.entries[0].closed);
+            assert_eq!(info.blocks_free, before - 4);
+
+            let (name, file_type, bytes) = extract_file(&img, 0).unwrap();
+            assert_eq!(name, "DEMO");
+            assert_eq!(file_type, FileType::Prg);
+            assert_eq!(bytes, payload);
+        }
+    }
+
+    #[test]
+    fn test_insert_uses_1541_interleave() {
+        let mut img = build_blank_d64("IL", "01 2A");
+        insert_file(&mut img, "A", FileType::Prg, &[0u8; 300]).unwrap();
+        let info = read_disk_info_from_bytes(&img).unwrap();
+        // First block on the track next to the directory, second one 10 sectors on.
+        assert_eq!(
+            (info.entries[0].first_track, info.entries[0].first_sector),
+            (17, 0)
+        );
+        let first = read_sector(&img, 17, 0, ImageKind::D64).unwrap();
+        assert_eq!((first[0], first[1]), (17, 10));
+    }
+
+    #[test]
+    fn test_scratch_frees_blocks() {
+        let mut img = build_blank_d64("DEL", "01 2A");
+        insert_file(&mut img, "ONE", FileType::Seq, &[1u8; 600]).unwrap();
+        insert_file(&mut img, "TWO", FileType::Prg, &[2u8; 10]).unwrap();
+        scratch_file(&mut img, 0).unwrap();
+        let info = read_disk_info_from_bytes(&img).unwrap();
+        assert_eq!(info.entries.len(), 1);
+        assert_eq!(info.entries[0].name, "TWO");
+        assert_eq!(info.blocks_free, 663);
+    }
+
+    #[test]
+    fn test_locked_file_cannot_be_scratched() {
+        let mut img = build_blank_d64("LOCK", "01 2A");
+        insert_file(&mut img, "KEEP", FileType::Prg, &[0, 8, 1]).unwrap();
+        set_locked(&mut img, 0, true).unwrap();
+        assert!(read_disk_info_from_bytes(&img).unwrap().entries[0].locked);
+        assert!(scratch_file(&mut img, 0).is_err());
+        set_locked(&mut img, 0, false).unwrap();
+        assert!(scratch_file(&mut img, 0).is_ok());
+    }
+
+    #[test]
+    fn test_directory_grows_past_first_sector() {
+        let mut img = build_blank_d64("MANY", "01 2A");
+        for i in 0..9 {
+            insert_file(&mut img, &format!("FILE{}", i), FileType::Prg, &[0, 8, i]).unwrap();
+        }
+        let info = read_disk_info_from_bytes(&img).unwrap();
+        assert_eq!(info.entries.len(), 9);
+        assert_eq!(info.entries[8].name, "FILE8");
+        // Second directory block at 18/4 (interleave 3), marked used in the BAM.
+        assert_eq!(
+            directory_chain(&img, ImageKind::D64),
+            vec![(18, 1), (18, 4)]
+        );
+        assert!(!is_block_free(&img, ImageKind::D64, 18, 4));
+        assert_eq!(info.blocks_free, 664 - 9);
+    }
+
+    #[test]
+    fn test_insert_rejects_duplicates_and_full_disk() {
+        let mut img = build_blank_d64("FULL", "01 2A");
+        insert_file(&mut img, "SAME", FileType::Prg, &[0, 8]).unwrap();
+        assert!(insert_file(&mut img, "same", FileType::Prg, &[0, 8]).is_err());
+
+        let snapshot = img.clone();
+        let too_big = vec![0u8; 665 * 254];
+        assert!(insert_file(&mut img, "BIG", FileType::Prg, &too_big).is_err());
+        assert_eq!(img, snapshot);
+    }
+
+    #[test]
+    fn test_rename_and_move_entry() {
+        let mut img = build_blank_d64("ORDER", "01 2A");
+        for name in ["A", "B", "C"] {
+            insert_file(&mut img, name, FileType::Prg, &[0, 8]).unwrap();
+        }
+        move_entry(&mut img, 2, 0).unwrap();
+        rename_file(&mut img, 1, "AA").unwrap();
+        assert!(rename_file(&mut img, 0, "B").is_err());
+        let names: Vec<String> = read_disk_info_from_bytes(&img)
+            .unwrap()
+            .entries
+            .into_iter()
+            .map(|e| e.name)
+            .collect();
+        assert_eq!(names, ["C", "AA", "B"]);
+    }
+
+    #[test]
+    fn test_validate_and_repair_bam() {
+        let mut img = build_blank_d64("CHECK", "01 2A");
+        insert_file(&mut img, "LONG", FileType::Prg, &[0u8; 600]).unwrap();
+        insert_file(&mut img, "SHORT", FileType::Prg, &[0, 8]).unwrap();
+        assert!(validate(&img).unwrap().issues.is_empty());
+        for blank in [
+            build_blank_d71("SIDES", "01 2A"),
+            build_blank_d81("LARGE", "01 3D"),
+        ] {
+            assert_eq!(validate(&blank).unwrap().issues, []);
+        }
+
+        let slots = used_entry_offsets(&img, ImageKind::D64);
+        // Free LONG's first block in the BAM and leave SHORT unclosed.
+        let (track, sector) = (img[slots[0] + 3], img[slots[0] + 4]);
+        let (count, bitmap) = bam_entry(ImageKind::D64, track).unwrap();
+        img[bitmap + sector as usize / 8] |= 1 << (sector % 8);
+        img[count] += 1;
+        img[slots[1] + 2] &= 0x7f;
+
+        let found = validate(&img).unwrap();
+        assert!(found
+            .issues
+            .contains(&Issue::UsedMarkedFree { track, sector }));
+        assert!(found.issues.contains(&Issue::Unclosed {
+            file: "SHORT".into()
+        }));
+        assert!(found.issues.iter().all(Issue::repairable));
+
+        repair(&mut img).unwrap();
+        assert!(validate(&img).unwrap().issues.is_empty());
+        let info = read_disk_info_from_bytes(&img).unwrap();
+        assert_eq!(info.entries.len(), 1);
+        assert_eq!(info.blocks_free, 664 - 3);
+    }
+
+    #[test]
+    fn test_validate_reports_cross_links_and_error_bytes() {
+        let mut img = build_blank_d64("LINKS", "01 2A");
+        insert_file(&mut img, "ONE", FileType::Prg, &[0, 8]).unwrap();
+        insert_file(&mut img, "TWO", FileType::Prg, &[0, 8]).unwrap();
+        let slots = used_entry_offsets(&img, ImageKind::D64);
+        let (track, sector) = (img[slots[0] + 3], img[slots[0] + 4]);
+        img[slots[1] + 3] = track;
+        img[slots[1] + 4] = sector;
+
+        // Append error info marking ONE's block as a checksum error.
+        let mut with_errors = img.clone();
+        let mut errors = vec![0x01u8; 683];
+        errors[ts_offset(track, sector, ImageKind::D64).unwrap() / 256] = 0x05;
+        with_errors.extend(errors);
+        assert_eq!(geometry(with_errors.len()).unwrap().tracks, 35);
+
+        let found = validate(&with_errors).unwrap();
+        assert!(found.issues.iter().any(|i| matches!(
+            i,
+            Issue::CrossLinked { file, .. } if file == "TWO"
+        )));
+        assert!(found.issues.contains(&Issue::ReadError {
+            file: "ONE".into(),
+            track,
+            sector,
+            error: SectorError::DataChecksum,
+        }));
+        assert!(found.issues.iter().any(|i| !i.repairable()));
+        let info = read_disk_info_from_bytes(&with_errors).unwrap();
+        assert_eq!(info.error_info.len(), 1);
+
+        let forty = geometry(196_608).unwrap();
+        assert_eq!((forty.kind, forty.tracks), (ImageKind::D64, 40));
+        assert_eq!(geometry(205_312 / 256 * 257).map(|g| g.tracks), Some(42));
+    }
+
+    /// Directory entry `slot` of the block at `block`: type, start, name, size
+    fn put_entry(img: &mut [u8], block: usize, slot: usize, entry: (u8, u8, u8, &str, u16)) {
+        let (type_byte, track, sector, name, blocks) = entry;
+        let at = block + slot * 32;
+        img[at + 2] = type_byte;
+        img[at + 3] = track;
+        img[at + 4] = sector;
+        write_petscii_name(&mut img[at + 5..at + 21], name);
+        img[at + 30..at + 32].copy_from_slice(&blocks.to_le_bytes());
+    }
+
+    #[test]
+    fn test_1581_partition_listing() {
+        let mut img = build_blank_d81("ROOT", "01 3D");
+        let dir = ts_offset(40, 3, ImageKind::D81).unwrap();
+        put_entry(&mut img, dir, 0, (0x85, 41, 0, "SUB", 120));
+        put_entry(&mut img, dir, 1, (0x85, 50, 5, "RAW", 10));
+
+        // Partition on tracks 41-43: header, BAM and one directory block
+        let header = ts_offset(41, 0, ImageKind::D81).unwrap();
+        img[header..header + 3].copy_from_slice(&[41, 3, 0x44]);
+        write_petscii_name(&mut img[header + 4..header + 20], "SUB");
+        let bam = ts_offset(41, 2, ImageKind::D81).unwrap();
+        img[bam + 16 + 6] = 39; // track 42
+        img[bam + 16 + 12] = 40; // track 43
+        let sub_dir = ts_offset(41, 3, ImageKind::D81).unwrap();
+        img[sub_dir + 1] = 0xFF;
+        put_entry(&mut img, sub_dir, 0, (0x82, 42, 0, "INNER", 1));
+
+        let root = read_disk_info_from_bytes(&img).unwrap();
+        assert_eq!(root.entries[0].file_type, FileType::Cbm);
+        assert!(root.entries[0].is_directory());
+
+        let sub = read_disk_info_in(&img, &[0]).unwrap();
+        assert_eq!(sub.name, "SUB");
+        assert_eq!(sub.location, ["SUB"]);
+        assert_eq!(sub.blocks_free, 79);
+        assert_eq!(sub.entries.len(), 1);
+        assert_eq!(sub.entries[0].name, "INNER");
+
+        assert!(read_disk_info_in(&img, &[1]).is_err());
+        assert!(read_disk_info_in(&img, &[0, 0]).is_err());
+    }
+
+    #[test]
+    fn test_validate_keeps_1581_partition_blocks() {
+        let mut img = build_blank_d81("ROOT", "01 3D");
+        insert_file(&mut img, "ROOTFILE", FileType::Prg, &[0u8; 300]).unwrap();
+        let dir = ts_offset(40, 3, ImageKind::D81).unwrap();
+        put_entry(&mut img, dir, 1, (0x85, 41, 0, "PART", 120));
+        for track in 41..=43 {
+            let (count, bitmap) = bam_entry(ImageKind::D81, track).unwrap();
+            img[count] = 0;
+            img[bitmap..bitmap + 5].fill(0);
+        }
+        // Partition contents that don't look like a chain
+        let header = ts_offset(41, 0, ImageKind::D81).unwrap();
+        img[header..header + 2].copy_from_slice(&[0xEE, 0xEE]);
+
+        let found = validate(&img).unwrap();
+        assert_eq!(found.issues, []);
+        assert_eq!(found.files, 2);
+
+        let before = img.clone();
+        repair(&mut img).unwrap();
+        assert_eq!(img, before);
+        assert_eq!(
+            read_disk_info_from_bytes(&img).unwrap().blocks_free,
+            3160 - 2 - 120
+        );
+    }
+
+    #[test]
+    fn test_scratch_frees_rel_side_sectors() {
+        let mut img = build_blank_d64("REL", "01");
+        let free = read_disk_info_from_bytes(&img).unwrap().blocks_free;
+        insert_file(&mut img, "DATA", FileType::Prg, &[0u8; 300]).unwrap();
+        // Turn it into a REL file with a one-block side-sector chain at 35/0
+        let slot = entry_offset(&img, ImageKind::D64, 0).unwrap();
+        img[slot + 2] = 0x84;
+        img[slot + 21..slot + 23].copy_from_slice(&[35, 0]);
+        let side = ts_offset(35, 0, ImageKind::D64).unwrap();
+        img[side..side + 2].copy_from_slice(&[0, 0xFF]);
+        set_block_free(&mut img, ImageKind::D64, 35, 0, false);
+        assert_eq!(
+            read_disk_info_from_bytes(&img).unwrap().blocks_free,
+            free - 3
+        );
+
+        scratch_file(&mut img, 0).unwrap();
+        assert_eq!(read_disk_info_from_bytes(&img).unwrap().blocks_free, free);
+    }
+
+    #[test]
+    fn test_scratch_frees_whole_1581_partition() {
+        let mut img = build_blank_d81("ROOT", "01 3D");
+        let dir = ts_offset(40, 3, ImageKind::D81).unwrap();
+        put_entry(&mut img, dir, 0, (0x85, 41, 0, "PART", 120));
+        for track in 41..=43 {
+            let (count, bitmap) = bam_entry(ImageKind::D81, track).unwrap();
+            img[count] = 0;
+            img[bitmap..bitmap + 5].fill(0);
+        }
+        // A raw area whose first bytes point somewhere unrelated
+        let header = ts_offset(41, 0, ImageKind::D81).unwrap();
+        img[header..header + 2].copy_from_slice(&[10, 0]);
+        assert_eq!(
+            read_disk_info_from_bytes(&img).unwrap().blocks_free,
+            3160 - 120
+        );
+
+        scratch_file(&mut img, 0).unwrap();
+        assert_eq!(read_disk_info_from_bytes(&img).unwrap().blocks_free, 3160);
+        assert_eq!(validate(&img).unwrap().issues, []);
+
+        // One running off the end of the disk is refused
+        let mut img = build_blank_d81("ROOT", "01 3D");
+        put_entry(&mut img, dir, 0, (0x85, 80, 0, "LONG", 80));
+        assert!(scratch_file(&mut img, 0).is_err());
+    }
+
+    #[test]
+    fn test_dnp_subdirectory_listing() {
+        let mut img = vec![0u8; 2 * DNP_TRACK_BYTES];
+        let header = ts_offset(1, 1, ImageKind::Dnp).unwrap();
+        img[header..header + 3].copy_from_slice(&[1, 34, b'H']);
+        write_petscii_name(&mut img[header + 4..header + 20], "NATIVE");
+        let bam = ts_offset(1, 2, ImageKind::Dnp).unwrap();
+        img[bam + 64..bam + 96].fill(0xFF); // track 2 all free
+        let root_dir = ts_offset(1, 34, ImageKind::Dnp).unwrap();
+        img[root_dir + 1] = 0xFF;
+        put_entry(&mut img, root_dir, 0, (0x86, 1, 40, "GAMES", 2));
+
+        let games = ts_offset(1, 40, ImageKind::Dnp).unwrap();
+        img[games..games + 3].copy_from_slice(&[1, 41, b'H']);
+        write_petscii_name(&mut img[games + 4..games + 20], "GAMES");
+        let games_dir = ts_offset(1, 41, ImageKind::Dnp).unwrap();
+        img[games_dir + 1] = 0xFF;
+        put_entry(&mut img, games_dir, 0, (0x82, 2, 0, "PACMAN", 33));
+
+        let root = read_disk_info_from_bytes(&img).unwrap();
+        assert_eq!((root.kind, root.tracks), (ImageKind::Dnp, 2));
+        assert_eq!(root.name, "NATIVE");
+        assert_eq!(root.blocks_free, 256);
+        assert_eq!(root.entries[0].file_type, FileType::Dir);
+
+        let sub = read_disk_info_in(&img, &[0]).unwrap();
+        assert_eq!(sub.location, ["GAMES"]);
+        assert_eq!(sub.entries[0].name, "PACMAN");
+        assert!(insert_file(&mut img, "NEW", FileType::Prg, &[0, 8]).is_err());
+    }
+
+    #[test]
+    fn test_40_track_d64_is_not_taken_for_dnp() {
+        let mut img = build_blank_d64("FORTY", "40");
+        img.resize(3 * DNP_TRACK_BYTES, 0);
+        // File data at 1/1 that happens to look like a DNP root header
+        img[256..259].copy_from_slice(&[0x01, 0x22, b'H']);
+
+        let info = read_disk_info_from_bytes(&img).unwrap();
+        assert_eq!((info.kind, info.tracks), (ImageKind::D64, 40));
+        assert_eq!(info.name, "FORTY");
+        insert_file(&mut img, "NEW", FileType::Prg, &[0, 8]).unwrap();
+
+        // Without a 1541 BAM the same bytes are a 3-track DNP
+        let mut dnp = vec![0u8; 3 * DNP_TRACK_BYTES];
+        dnp[256..259].copy_from_slice(&[0x01, 0x22, b'H']);
+        assert!(is_dnp(&dnp));
+        dnp[257] = 0x23;
+        assert!(!is_dnp(&dnp));
+    }
+
     #[test]
     fn test_file_type() {
         assert_eq!(FileType::from_byte(0x00), FileType::Del);
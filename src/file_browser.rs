@@ -22,11 +22,15 @@ const FILE_LIST_SCROLLABLE_ID: &str = "file_browser_list";
 /// keybind can call `text_input::focus(...)` on it from main.rs.
 pub const PATH_INPUT_ID: &str = "local_path_input";
 
-use crate::archive::{extract_zip_to_dir, MAX_ZIP_EXTRACT_BYTES};
+use crate::archive::{extract_archive_to_dir, MAX_ZIP_EXTRACT_BYTES};
+use crate::crt_image::{self, CrtInfo};
 use crate::dir_preview::{self, ContentPreview};
-use crate::disk_image::{self, DiskInfo, FileType};
+use crate::disk_image::{self, DiskInfo, FileType, ImageKind, Validation};
+use crate::launch_script::{self, LaunchRequest};
+use crate::machine_snapshot;
 use crate::net_utils::REST_TIMEOUT_SECS;
 use crate::pdf_preview;
+use crate::tape_image::{self, TapeInfo};
 /// Longer timeout for run_disk which includes boot delays
 const RUN_DISK_TIMEOUT_SECS: u64 = 15;
 
@@ -43,6 +47,12 @@ pub enum FileBrowserMessage {
     RunDisk(PathBuf, String), // Mount, reset, load and run
     RunDiskCompleted(Result<(), String>),
     LoadAndRun(PathBuf),
+    /// Run a `.u64launch` file (as-is, nothing attached)
+    RunScriptFile(PathBuf),
+    /// Handled by the app, which owns the script runner
+    RunLaunchScript(LaunchRequest),
+    /// Start the guided firmware update — handled by the app
+    UpdateFirmware(PathBuf),
     LoadCompleted(Result<(), String>),
     RefreshFiles,
     NavigateUp,
@@ -60,7 +70,59 @@ pub enum FileBrowserMessage {
     // Disk info popup
     ShowDiskInfo(PathBuf),
     DiskInfoLoaded(Result<DiskInfo, String>),
+    /// List a 1581 partition or DNP sub-directory of the open image, given
+    /// as listing indices from the root (empty for the root itself).
+    OpenDiskDirectory(Vec<usize>),
+    DiskDirectoryLoaded(Vec<usize>, Result<DiskInfo, String>),
     CloseDiskInfo,
+    /// Pick a local file and write it into the image shown in the disk info popup.
+    InsertIntoDiskImage,
+    InsertFilePicked(Option<PathBuf>),
+    /// Edit one directory entry (by listing index) of the open disk image.
+    EditDiskEntry(usize, DiskEntryEdit),
+    /// Swap an entry's name for an input to rename it.
+    DiskRenameStart(usize),
+    DiskRenameInputChanged(String),
+    DiskRenameCancel,
+    DiskImageEdited(Result<String, String>),
+    /// Check the open image's file chains and BAM (the drive's VALIDATE).
+    ValidateDiskImage,
+    DiskValidated(Result<Validation, String>),
+    /// Rebuild the BAM and drop unclosed files, then re-open the image.
+    RepairDiskImage,
+    // Tape info popup (T64 entries / TAP ROM-loader headers)
+    ShowTapeInfo(PathBuf),
+    TapeInfoLoaded(Result<TapeInfo, String>),
+    CloseTapeInfo,
+    /// Run one T64 entry (by listing index) via `run_prg`.
+    RunTapeEntry(usize),
+    /// Write one T64 entry next to the archive as a .prg.
+    ExtractTapeEntry(usize),
+    TapeEntryExtracted(Result<String, String>),
+    /// Wrap a raw PRG/SEQ/USR file in a PC64 container, or write the file
+    /// inside a container back out raw.
+    ConvertPc64(PathBuf),
+    Pc64Converted(Result<PathBuf, String>),
+    // CRT info popup (hardware type, CHIP packets)
+    ShowCrtInfo(PathBuf),
+    CrtInfoLoaded(Result<CrtInfo, String>),
+    CloseCrtInfo,
+    /// Write one CHIP packet (by listing index) next to the cartridge as a .bin.
+    ExtractCrtChip(usize),
+    /// Write the whole cartridge ROM next to it as one .bin.
+    ExtractCrtBin,
+    CrtExtracted(Result<String, String>),
+    /// A CRT was checked before sending; warnings need confirming.
+    CrtChecked(PathBuf, Result<Vec<String>, String>),
+    ConfirmRunCrt,
+    CancelRunCrt,
+    // Machine snapshot popup (thumbnail + CPU state)
+    ShowSnapshotInfo(PathBuf),
+    /// Description lines and thumbnail PNG
+    SnapshotInfoLoaded(Result<(Vec<String>, Vec<u8>), String>),
+    CloseSnapshotInfo,
+    RestoreSnapshot,
+    SnapshotRestored(Result<String, String>),
     // Content preview popup (text/image files)
     ShowContentPreview(PathBuf),
     ContentPreviewLoaded(Result<ContentPreview, String>),
@@ -144,6 +206,18 @@ pub enum FileBrowserMessage {
     CreateDiskConfirm,
 }
 
+/// Per-entry action offered in the disk info popup's file list.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiskEntryEdit {
+    /// Save the file next to the image as `NAME.prg` / `.seq` / `.usr`.
+    Extract,
+    Scratch,
+    Rename(String),
+    ToggleLock,
+    MoveUp,
+    MoveDown,
+}
+
 /// The action to execute once we know the drive is enabled
 #[derive(Debug, Clone)]
 pub enum PendingDriveAction {
@@ -158,6 +232,21 @@ pub struct FileEntry {
     pub is_dir: bool,
     pub extension: Option<String>,
     pub size: Option<u64>,
+    /// C64 filename stored inside a PC64 container (`.P00`, `.S00`, …)
+    pub c64_name: Option<String>,
+}
+
+impl FileEntry {
+    /// Whether the host name or the embedded C64 name contains `filter`
+    /// (already lowercased)
+    fn matches_filter(&self, filter: &str) -> bool {
+        filter.is_empty()
+            || self.name.to_lowercase().contains(filter)
+            || self
+                .c64_name
+                .as_ref()
+                .is_some_and(|n| n.to_lowercase().contains(filter))
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -237,9 +326,26 @@ pub struct FileBrowser {
     // Disk info popup state
     disk_info_popup: Option<DiskInfo>,
     disk_info_path: Option<PathBuf>,
+    // Partition / sub-directory shown, as listing indices from the root
+    disk_info_dir: Vec<usize>,
     disk_info_loading: bool,
+    // Result of the last Validate on the open image
+    disk_validation: Option<Validation>,
+    // Entry being renamed in the popup and the name typed so far
+    disk_rename: Option<(usize, String)>,
     // Rendered C64-style PETSCII listing image (PNG bytes)
     disk_listing_image: Option<Vec<u8>>,
+    // Tape info popup state (shares disk_listing_image)
+    tape_info_popup: Option<TapeInfo>,
+    tape_info_path: Option<PathBuf>,
+    // CRT info popup state (shares disk_listing_image)
+    crt_info_popup: Option<CrtInfo>,
+    crt_info_path: Option<PathBuf>,
+    // CRT waiting for the user to confirm its warnings before it is sent
+    crt_run_pending: Option<(PathBuf, Vec<String>)>,
+    // Snapshot popup state (thumbnail in disk_listing_image)
+    snapshot_info: Option<Vec<String>>,
+    snapshot_path: Option<PathBuf>,
     // Content preview popup state (text/image files)
     content_preview: Option<ContentPreview>,
     content_preview_path: Option<PathBuf>,
@@ -309,7 +415,17 @@ impl FileBrowser {
             is_loading: false,
             disk_info_popup: None,
             disk_info_path: None,
+            disk_info_dir: Vec::new(),
             disk_info_loading: false,
+            disk_validation: None,
+            disk_rename: None,
+            tape_info_popup: None,
+            tape_info_path: None,
+            crt_info_popup: None,
+            crt_info_path: None,
+            crt_run_pending: None,
+            snapshot_info: None,
+            snapshot_path: None,
             disk_listing_image: None,
             content_preview: None,
             content_preview_path: None,
@@ -372,7 +488,7 @@ impl FileBrowser {
         let filtered: Vec<&FileEntry> = self
             .files
             .iter()
-            .filter(|f| filter.is_empty() || f.name.to_lowercase().contains(&filter))
+            .filter(|f| f.matches_filter(&filter))
             .collect();
         if filtered.is_empty() {
             return;
@@ -411,7 +527,7 @@ impl FileBrowser {
         let idx = self
             .files
             .iter()
-            .filter(|f| filter.is_empty() || f.name.to_lowercase().contains(&filter))
+            .filter(|f| f.matches_filter(&filter))
             .position(|f| &f.path == selected);
         let Some(idx) = idx else {
             return Task::none();
@@ -512,6 +628,9 @@ impl FileBrowser {
                 Task::none()
             }
             FileBrowserMessage::RunDisk(path, drive) => {
+                if let Some(script) = launch_script::sidecar(&path) {
+                    return self.launch_script(&script, Some(&path));
+                }
                 if connection.is_some() {
                     // Record as "last run" before the drive-check round-trip
                     // — the user clicked Run, so this is what they meant
@@ -542,19 +661,30 @@ impl FileBrowser {
                 Task::none()
             }
             FileBrowserMessage::LoadAndRun(path) => {
+                if let Some(script) = launch_script::sidecar(&path) {
+                    return self.launch_script(&script, Some(&path));
+                }
                 if let Some(conn) = connection {
-                    // Record as "last run" — extension drives which variant
-                    // we'll re-fire when the user later hits "↪ Run last".
-                    self.last_run = match path
+                    let ext = path
                         .extension()
                         .and_then(|s| s.to_str())
-                        .map(|s| s.to_lowercase())
-                        .as_deref()
-                    {
-                        Some("prg") => Some(LastRun::Prg(path.clone())),
+                        .map(|s| s.to_lowercase());
+                    // Record as "last run" — extension drives which variant
+                    // we'll re-fire when the user later hits "↪ Run last".
+                    self.last_run = match ext.as_deref() {
+                        Some("prg") | Some("t64") => Some(LastRun::Prg(path.clone())),
+                        Some(e) if crate::pc64::container_type(e) == Some(FileType::Prg) => {
+                            Some(LastRun::Prg(path.clone()))
+                        }
                         Some("crt") => Some(LastRun::Crt(path.clone())),
                         _ => self.last_run.take(), // unknown ext → keep previous
                     };
+                    // Look a cartridge over before it goes to the device
+                    if ext.as_deref() == Some("crt") {
+                        return Task::perform(check_crt_async(path.clone()), move |result| {
+                            FileBrowserMessage::CrtChecked(path.clone(), result)
+                        });
+                    }
                     self.status_message = Some(format!(
                         "Loading {}...",
                         path.file_name().unwrap_or_default().to_string_lossy()
@@ -765,9 +895,12 @@ impl FileBrowser {
             // Disk info popup messages
             FileBrowserMessage::ShowDiskInfo(path) => {
                 self.disk_info_loading = true;
+                self.disk_validation = None;
+                self.disk_rename = None;
                 self.disk_info_path = Some(path.clone());
+                self.disk_info_dir.clear();
                 Task::perform(
-                    async move { load_disk_info_async(path).await },
+                    async move { load_disk_info_async(path, Vec::new()).await },
                     FileBrowserMessage::DiskInfoLoaded,
                 )
             }
@@ -787,12 +920,349 @@ impl FileBrowser {
                 }
                 Task::none()
             }
+            FileBrowserMessage::OpenDiskDirectory(dir) => {
+                let Some(image) = self.disk_info_path.clone() else {
+                    return Task::none();
+                };
+                Task::perform(load_disk_info_async(image, dir.clone()), move |result| {
+                    FileBrowserMessage::DiskDirectoryLoaded(dir.clone(), result)
+                })
+            }
+            FileBrowserMessage::DiskDirectoryLoaded(dir, result) => {
+                match result {
+                    Ok(info) => {
+                        self.disk_listing_image =
+                            Some(dir_preview::render_disk_listing_image(&info));
+                        self.disk_info_popup = Some(info);
+                        self.disk_info_dir = dir;
+                    }
+                    Err(e) => {
+                        self.status_message = Some(format!("Cannot open directory: {}", e));
+                    }
+                }
+                Task::none()
+            }
             FileBrowserMessage::CloseDiskInfo => {
                 self.disk_info_popup = None;
+                self.disk_info_dir.clear();
+                self.disk_validation = None;
+                self.disk_rename = None;
                 self.disk_info_path = None;
                 self.disk_listing_image = None;
                 Task::none()
             }
+            FileBrowserMessage::InsertIntoDiskImage => Task::perform(
+                async {
+                    rfd::AsyncFileDialog::new()
+                        .set_title("Insert file into disk image")
+                        .pick_file()
+                        .await
+                        .map(|handle| handle.path().to_path_buf())
+                },
+                FileBrowserMessage::InsertFilePicked,
+            ),
+            FileBrowserMessage::InsertFilePicked(file) => {
+                let (Some(file), Some(image)) = (file, self.disk_info_path.clone()) else {
+                    return Task::none();
+                };
+                self.status_message = Some(format!(
+                    "Writing {} into image...",
+                    file.file_name().unwrap_or_default().to_string_lossy()
+                ));
+                Task::perform(
+                    insert_into_image_async(image, file),
+                    FileBrowserMessage::DiskImageEdited,
+                )
+            }
+            FileBrowserMessage::EditDiskEntry(index, edit) => {
+                self.disk_rename = None;
+                let Some(image) = self.disk_info_path.clone() else {
+                    return Task::none();
+                };
+                Task::perform(
+                    edit_image_entry_async(image, index, edit),
+                    FileBrowserMessage::DiskImageEdited,
+                )
+            }
+            FileBrowserMessage::DiskRenameStart(index) => {
+                let name = self
+                    .disk_info_popup
+                    .as_ref()
+                    .and_then(|info| info.entries.get(index))
+                    .map(|entry| entry.name.clone())
+                    .unwrap_or_default();
+                self.disk_rename = Some((index, name));
+                Task::none()
+            }
+            FileBrowserMessage::DiskRenameInputChanged(value) => {
+                if let Some((_, name)) = &mut self.disk_rename {
+                    *name = value;
+                }
+                Task::none()
+            }
+            FileBrowserMessage::DiskRenameCancel => {
+                self.disk_rename = None;
+                Task::none()
+            }
+            FileBrowserMessage::DiskImageEdited(result) => match result {
+                Ok(msg) => {
+                    self.status_message = Some(msg);
+                    self.load_directory(&self.current_directory.clone());
+                    match self.disk_info_path.clone() {
+                        Some(image) => Task::done(FileBrowserMessage::ShowDiskInfo(image)),
+                        None => Task::none(),
+                    }
+                }
+                Err(e) => {
+                    self.status_message = Some(format!("Disk image edit failed: {}", e));
+                    Task::none()
+                }
+            },
+            FileBrowserMessage::ValidateDiskImage => {
+                let Some(image) = self.disk_info_path.clone() else {
+                    return Task::none();
+                };
+                Task::perform(
+                    validate_image_async(image),
+                    FileBrowserMessage::DiskValidated,
+                )
+            }
+            FileBrowserMessage::DiskValidated(result) => {
+                match result {
+                    Ok(validation) => {
+                        self.status_message = Some(validation.summary());
+                        self.disk_validation = Some(validation);
+                    }
+                    Err(e) => {
+                        self.status_message = Some(format!("Validate failed: {}", e));
+                    }
+                }
+                Task::none()
+            }
+            FileBrowserMessage::RepairDiskImage => {
+                let Some(image) = self.disk_info_path.clone() else {
+                    return Task::none();
+                };
+                Task::perform(
+                    repair_image_async(image),
+                    FileBrowserMessage::DiskImageEdited,
+                )
+            }
+            // Tape info popup messages
+            FileBrowserMessage::ShowTapeInfo(path) => {
+                self.disk_info_loading = true;
+                self.tape_info_path = Some(path.clone());
+                Task::perform(
+                    load_tape_info_async(path),
+                    FileBrowserMessage::TapeInfoLoaded,
+                )
+            }
+            FileBrowserMessage::TapeInfoLoaded(result) => {
+                self.disk_info_loading = false;
+                match result {
+                    Ok(info) => {
+                        self.disk_listing_image =
+                            Some(dir_preview::render_tape_listing_image(&info));
+                        self.tape_info_popup = Some(info);
+                    }
+                    Err(e) => {
+                        self.status_message = Some(format!("Failed to read tape: {}", e));
+                        self.tape_info_path = None;
+                    }
+                }
+                Task::none()
+            }
+            FileBrowserMessage::CloseTapeInfo => {
+                self.tape_info_popup = None;
+                self.tape_info_path = None;
+                self.disk_listing_image = None;
+                Task::none()
+            }
+            FileBrowserMessage::RunTapeEntry(index) => {
+                let Some(image) = self.tape_info_path.clone() else {
+                    return Task::none();
+                };
+                let Some(conn) = connection else {
+                    self.status_message = Some("Not connected to Ultimate64".to_string());
+                    return Task::none();
+                };
+                self.status_message = Some(format!("Loading T64 entry #{}...", index));
+                Task::perform(
+                    run_tape_entry_async(conn, image, index),
+                    FileBrowserMessage::LoadCompleted,
+                )
+            }
+            FileBrowserMessage::ExtractTapeEntry(index) => {
+                let Some(image) = self.tape_info_path.clone() else {
+                    return Task::none();
+                };
+                Task::perform(
+                    extract_tape_entry_async(image, index),
+                    FileBrowserMessage::TapeEntryExtracted,
+                )
+            }
+            FileBrowserMessage::TapeEntryExtracted(result) => {
+                match result {
+                    Ok(msg) => {
+                        self.status_message = Some(msg);
+                        self.load_directory(&self.current_directory.clone());
+                    }
+                    Err(e) => {
+                        self.status_message = Some(format!("Extract failed: {}", e));
+                    }
+                }
+                Task::none()
+            }
+            FileBrowserMessage::ConvertPc64(path) => {
+                Task::perform(convert_pc64_async(path), FileBrowserMessage::Pc64Converted)
+            }
+            FileBrowserMessage::Pc64Converted(result) => {
+                match result {
+                    Ok(target) => {
+                        self.status_message = Some(format!("Wrote {}", target.display()));
+                        self.load_directory(&self.current_directory.clone());
+                        self.selected_file = Some(target);
+                    }
+                    Err(e) => {
+                        self.status_message = Some(format!("PC64 conversion failed: {}", e));
+                    }
+                }
+                Task::none()
+            }
+            // CRT info popup messages
+            FileBrowserMessage::ShowCrtInfo(path) => {
+                self.disk_info_loading = true;
+                self.crt_info_path = Some(path.clone());
+                Task::perform(load_crt_info_async(path), FileBrowserMessage::CrtInfoLoaded)
+            }
+            FileBrowserMessage::CrtInfoLoaded(result) => {
+                self.disk_info_loading = false;
+                match result {
+                    Ok(info) => {
+                        self.disk_listing_image =
+                            Some(dir_preview::render_crt_listing_image(&info));
+                        self.crt_info_popup = Some(info);
+                    }
+                    Err(e) => {
+                        self.status_message = Some(format!("Failed to read cartridge: {}", e));
+                        self.crt_info_path = None;
+                    }
+                }
+                Task::none()
+            }
+            FileBrowserMessage::CloseCrtInfo => {
+                self.crt_info_popup = None;
+                self.crt_info_path = None;
+                self.disk_listing_image = None;
+                Task::none()
+            }
+            FileBrowserMessage::ExtractCrtChip(index) => {
+                let Some(crt) = self.crt_info_path.clone() else {
+                    return Task::none();
+                };
+                Task::perform(
+                    extract_crt_async(crt, Some(index)),
+                    FileBrowserMessage::CrtExtracted,
+                )
+            }
+            FileBrowserMessage::ExtractCrtBin => {
+                let Some(crt) = self.crt_info_path.clone() else {
+                    return Task::none();
+                };
+                Task::perform(
+                    extract_crt_async(crt, None),
+                    FileBrowserMessage::CrtExtracted,
+                )
+            }
+            FileBrowserMessage::CrtExtracted(result) => {
+                match result {
+                    Ok(msg) => {
+                        self.status_message = Some(msg);
+                        self.load_directory(&self.current_directory.clone());
+                    }
+                    Err(e) => {
+                        self.status_message = Some(format!("Extract failed: {}", e));
+                    }
+                }
+                Task::none()
+            }
+            FileBrowserMessage::CrtChecked(path, result) => match result {
+                Ok(warnings) if warnings.is_empty() => self.send_crt(connection, path),
+                Ok(warnings) => {
+                    self.crt_run_pending = Some((path, warnings));
+                    Task::none()
+                }
+                Err(e) => {
+                    self.status_message = Some(format!(
+                        "Not sending {}: {}",
+                        path.file_name().unwrap_or_default().to_string_lossy(),
+                        e
+                    ));
+                    Task::none()
+                }
+            },
+            FileBrowserMessage::ConfirmRunCrt => match self.crt_run_pending.take() {
+                Some((path, _)) => self.send_crt(connection, path),
+                None => Task::none(),
+            },
+            FileBrowserMessage::CancelRunCrt => {
+                self.crt_run_pending = None;
+                Task::none()
+            }
+            // Snapshot popup messages
+            FileBrowserMessage::ShowSnapshotInfo(path) => {
+                self.disk_info_loading = true;
+                self.snapshot_path = Some(path.clone());
+                Task::perform(
+                    load_snapshot_info_async(path),
+                    FileBrowserMessage::SnapshotInfoLoaded,
+                )
+            }
+            FileBrowserMessage::SnapshotInfoLoaded(result) => {
+                self.disk_info_loading = false;
+                match result {
+                    Ok((lines, thumbnail)) => {
+                        self.disk_listing_image = Some(thumbnail);
+                        self.snapshot_info = Some(lines);
+                    }
+                    Err(e) => {
+                        self.status_message = Some(format!("Failed to read snapshot: {}", e));
+                        self.snapshot_path = None;
+                    }
+                }
+                Task::none()
+            }
+            FileBrowserMessage::CloseSnapshotInfo => {
+                self.snapshot_info = None;
+                self.snapshot_path = None;
+                self.disk_listing_image = None;
+                Task::none()
+            }
+            FileBrowserMessage::RunScriptFile(path) => self.launch_script(&path, None),
+            FileBrowserMessage::RunLaunchScript(_) | FileBrowserMessage::UpdateFirmware(_) => {
+                Task::none()
+            }
+            FileBrowserMessage::RestoreSnapshot => {
+                let Some(path) = self.snapshot_path.clone() else {
+                    return Task::none();
+                };
+                let Some(conn) = connection else {
+                    self.status_message = Some("Not connected to Ultimate64".to_string());
+                    return Task::none();
+                };
+                self.status_message = Some("Restoring snapshot...".to_string());
+                Task::perform(
+                    machine_snapshot::restore_snapshot(conn, path),
+                    FileBrowserMessage::SnapshotRestored,
+                )
+            }
+            FileBrowserMessage::SnapshotRestored(result) => {
+                self.status_message = Some(match result {
+                    Ok(msg) => msg,
+                    Err(e) => format!("Restore failed: {}", e),
+                });
+                Task::none()
+            }
             // Content preview popup messages (text/image files)
             FileBrowserMessage::ShowContentPreview(path) => {
                 self.content_preview_loading = true;
@@ -1307,8 +1777,16 @@ impl FileBrowser {
                     .and_then(|s| s.to_str())
                     .map(|s| s.to_lowercase());
                 let action = match ext.as_deref() {
-                    Some("prg") | Some("crt") => Some(FileBrowserMessage::LoadAndRun(path.clone())),
+                    Some("prg") | Some("crt") | Some("t64") => {
+                        Some(FileBrowserMessage::LoadAndRun(path.clone()))
+                    }
+                    Some(e) if crate::pc64::container_type(e) == Some(FileType::Prg) => {
+                        Some(FileBrowserMessage::LoadAndRun(path.clone()))
+                    }
                     Some("sid") => Some(FileBrowserMessage::PlaySid(path.clone())),
+                    Some(launch_script::EXTENSION) => {
+                        Some(FileBrowserMessage::RunScriptFile(path.clone()))
+                    }
                     Some(e) if crate::file_types::is_disk_image(e) => {
                         Some(FileBrowserMessage::RunDisk(
                             path.clone(),
@@ -1799,10 +2277,89 @@ impl FileBrowser {
             .into();
         }
 
+        // A cartridge with warnings waits here until the user decides
+        if let Some((path, warnings)) = &self.crt_run_pending {
+            let mut lines = column![text(format!(
+                "{} may not run:",
+                path.file_name().unwrap_or_default().to_string_lossy()
+            ))
+            .size(fs.normal)]
+            .spacing(4);
+            for warning in warnings {
+                lines = lines.push(text(format!("• {}", warning)).size(fs.small));
+            }
+            let dialog = container(
+                column![
+                    lines,
+                    row![
+                        button(text("Send anyway").size(fs.small))
+                            .on_press(FileBrowserMessage::ConfirmRunCrt)
+                            .padding([5, 15])
+                            .style(button::secondary),
+                        button(text("Cancel").size(fs.small))
+                            .on_press(FileBrowserMessage::CancelRunCrt)
+                            .padding([5, 15])
+                            .style(button::secondary),
+                    ]
+                    .spacing(10),
+                ]
+                .spacing(12)
+                .padding(20),
+            )
+            .style(crate::styles::subtle_tooltip)
+            .width(Length::Fill);
+
+            return column![
+                self.build_nav_row(font_size),
+                self.build_quick_nav_row(font_size),
+                dialog,
+                self.build_status_bar(font_size),
+            ]
+            .spacing(2)
+            .padding(5)
+            .into();
+        }
+
         // If disk info popup is open, show it instead of the file list
         if let Some(disk_info) = &self.disk_info_popup {
             let popup = self.view_disk_info_popup(disk_info, font_size);
 
+            column![
+                self.build_nav_row(font_size),
+                self.build_quick_nav_row(font_size),
+                popup,
+                self.build_status_bar(font_size),
+            ]
+            .spacing(2)
+            .padding(5)
+            .into()
+        } else if let Some(tape_info) = &self.tape_info_popup {
+            let popup = self.view_tape_info_popup(tape_info, font_size);
+
+            column![
+                self.build_nav_row(font_size),
+                self.build_quick_nav_row(font_size),
+                popup,
+                self.build_status_bar(font_size),
+            ]
+            .spacing(2)
+            .padding(5)
+            .into()
+        } else if let Some(crt_info) = &self.crt_info_popup {
+            let popup = self.view_crt_info_popup(crt_info, font_size);
+
+            column![
+                self.build_nav_row(font_size),
+                self.build_quick_nav_row(font_size),
+                popup,
+                self.build_status_bar(font_size),
+            ]
+            .spacing(2)
+            .padding(5)
+            .into()
+        } else if let Some(lines) = &self.snapshot_info {
+            let popup = self.view_snapshot_popup(lines, font_size);
+
             column![
                 self.build_nav_row(font_size),
                 self.build_quick_nav_row(font_size),
@@ -2126,13 +2683,11 @@ impl FileBrowser {
             .into()
         } else {
             // Filter files based on filter text
+            let filter = self.filter.to_lowercase();
             let filtered_files: Vec<&FileEntry> = self
                 .files
                 .iter()
-                .filter(|f| {
-                    self.filter.is_empty()
-                        || f.name.to_lowercase().contains(&self.filter.to_lowercase())
-                })
+                .filter(|f| f.matches_filter(&filter))
                 .collect();
 
             // File list with row dividers
@@ -2183,14 +2738,61 @@ impl FileBrowser {
         font_size: u32,
     ) -> Element<'_, FileBrowserMessage> {
         let fs = crate::styles::FontSizes::from_base(font_size);
+        // G64/G71 listings are decoded copies; edits can't be written back.
+        // Edits and extraction address root entries, so sub-directories and
+        // DNP images are browse-only.
+        let at_root = disk_info.location.is_empty();
+        let editable = disk_info.gcr.is_none() && at_root && disk_info.kind != ImageKind::Dnp;
+        let extractable = at_root && disk_info.kind != ImageKind::Dnp;
 
         // Header with disk name and close button
-        let header = row![
-            text(format!("{} - ", disk_info.kind)).size(fs.small),
+        let up = (!at_root).then(|| {
+            let mut parent = self.disk_info_dir.clone();
+            parent.pop();
+            tooltip(
+                button(text("Up").size(fs.small))
+                    .on_press(FileBrowserMessage::OpenDiskDirectory(parent))
+                    .padding([4, 10])
+                    .style(crate::styles::nav_button),
+                "Back to the enclosing directory",
+                tooltip::Position::Bottom,
+            )
+            .style(crate::styles::subtle_tooltip)
+        });
+        let location: String = disk_info
+            .location
+            .iter()
+            .map(|name| format!(" / {}", name))
+            .collect();
+        let header = row![
+            up,
+            text(format!("{} - ", disk_info.kind_label())).size(fs.small),
             text(format!("\"{}\"", disk_info.name)).size(fs.normal),
+            text(location).size(fs.small),
             Space::new().width(Length::Fill),
             text(format!("{} {}", disk_info.disk_id, disk_info.dos_type)).size(fs.small),
             Space::new().width(10),
+            tooltip(
+                button(text("Insert file").size(fs.small))
+                    .on_press_maybe(editable.then_some(FileBrowserMessage::InsertIntoDiskImage))
+                    .padding([4, 10])
+                    .style(crate::styles::action_button),
+                "Write a local file into this image (PRG, SEQ or USR)",
+                tooltip::Position::Left,
+            )
+            .style(crate::styles::subtle_tooltip),
+            tooltip(
+                button(text("Validate").size(fs.small))
+                    .on_press_maybe(
+                        (disk_info.kind != ImageKind::Dnp)
+                            .then_some(FileBrowserMessage::ValidateDiskImage),
+                    )
+                    .padding([4, 10])
+                    .style(crate::styles::action_button),
+                "Check file chains and the BAM, like the drive's VALIDATE",
+                tooltip::Position::Left,
+            )
+            .style(crate::styles::subtle_tooltip),
             tooltip(
                 button(text("Close").size(fs.small))
                     .on_press(FileBrowserMessage::CloseDiskInfo)
@@ -2218,7 +2820,7 @@ impl FileBrowser {
                     )
                     .padding(4),
                 )
-                .height(Length::Fill)
+                .height(Length::FillPortion(2))
                 .into()
             } else {
                 // Fallback: plain text listing (used while image is loading)
@@ -2261,21 +2863,320 @@ impl FileBrowser {
                 .into()
             };
 
+        // Per-file actions: extract to the host, rename, scratch, lock and
+        // re-order
+        let entry_button = |label: &'static str, msg: Option<FileBrowserMessage>| {
+            let msg = msg.filter(|m| match m {
+                FileBrowserMessage::OpenDiskDirectory(_) => true,
+                FileBrowserMessage::EditDiskEntry(_, DiskEntryEdit::Extract) => extractable,
+                _ => editable,
+            });
+            button(text(label).size(fs.tiny))
+                .on_press_maybe(msg)
+                .padding([1, 6])
+                .style(crate::styles::nav_button)
+        };
+        let last = disk_info.entries.len().saturating_sub(1);
+        let mut entry_rows: Vec<Element<'_, FileBrowserMessage>> = Vec::new();
+        for (i, entry) in disk_info.entries.iter().enumerate() {
+            let renaming = self
+                .disk_rename
+                .as_ref()
+                .filter(|(index, _)| *index == i && editable)
+                .map(|(_, name)| name);
+            let (name, rename): (Element<'_, FileBrowserMessage>, _) = match renaming {
+                Some(new_name) => (
+                    iced::widget::text_input("new name...", new_name)
+                        .on_input(FileBrowserMessage::DiskRenameInputChanged)
+                        .on_submit(FileBrowserMessage::EditDiskEntry(
+                            i,
+                            DiskEntryEdit::Rename(new_name.clone()),
+                        ))
+                        .size(fs.tiny)
+                        .width(Length::Fill)
+                        .into(),
+                    entry_button("Cancel", Some(FileBrowserMessage::DiskRenameCancel)),
+                ),
+                None => (
+                    text(format!("\"{}\"", entry.name))
+                        .size(fs.tiny)
+                        .width(Length::Fill)
+                        .into(),
+                    entry_button("Rename", Some(FileBrowserMessage::DiskRenameStart(i))),
+                ),
+            };
+            entry_rows.push(
+                row![
+                    name,
+                    text(entry.file_type.to_string()).size(fs.tiny),
+                    if entry.is_directory() {
+                        let mut dir = self.disk_info_dir.clone();
+                        dir.push(i);
+                        entry_button("Open", Some(FileBrowserMessage::OpenDiskDirectory(dir)))
+                    } else {
+                        entry_button(
+                            "Extract",
+                            (entry.first_track != 0).then_some(FileBrowserMessage::EditDiskEntry(
+                                i,
+                                DiskEntryEdit::Extract,
+                            )),
+                        )
+                    },
+                    rename,
+                    entry_button(
+                        if entry.locked { "Unlock" } else { "Lock" },
+                        Some(FileBrowserMessage::EditDiskEntry(
+                            i,
+                            DiskEntryEdit::ToggleLock
+                        )),
+                    ),
+                    entry_button(
+                        "Scratch",
+                        (!entry.locked).then_some(FileBrowserMessage::EditDiskEntry(
+                            i,
+                            DiskEntryEdit::Scratch
+                        )),
+                    ),
+                    entry_button(
+                        "↑",
+                        (i > 0)
+                            .then_some(FileBrowserMessage::EditDiskEntry(i, DiskEntryEdit::MoveUp)),
+                    ),
+                    entry_button(
+                        "↓",
+                        (i < last).then_some(FileBrowserMessage::EditDiskEntry(
+                            i,
+                            DiskEntryEdit::MoveDown
+                        )),
+                    ),
+                ]
+                .spacing(4)
+                .align_y(iced::Alignment::Center)
+                .into(),
+            );
+        }
+        let entry_list = scrollable(
+            Column::with_children(entry_rows)
+                .spacing(2)
+                .padding(iced::Padding::ZERO.right(12)),
+        )
+        .height(Length::FillPortion(1));
+
+        // GCR images: readable/unreadable summary, per-sector errors on hover
+        let gcr_summary: Element<'_, FileBrowserMessage> = match &disk_info.gcr {
+            Some(report) if report.bad_sectors.is_empty() && report.density.is_empty() => {
+                text(report.summary()).size(fs.tiny).into()
+            }
+            Some(report) => tooltip(
+                text(report.summary())
+                    .size(fs.tiny)
+                    .color(iced::Color::from_rgb(0.9, 0.6, 0.3)),
+                text(report.details()).size(fs.tiny),
+                tooltip::Position::Top,
+            )
+            .style(crate::styles::subtle_tooltip)
+            .into(),
+            None => Space::new().width(0).into(),
+        };
+
+        // Appended error bytes: count of flagged sectors, the list on hover
+        let error_summary: Element<'_, FileBrowserMessage> = if disk_info.error_info.is_empty() {
+            Space::new().width(0).into()
+        } else {
+            let details = disk_info
+                .error_info
+                .iter()
+                .map(|b| format!("T{} S{}: {}", b.track, b.sector, b.error))
+                .collect::<Vec<_>>()
+                .join("\n");
+            tooltip(
+                text(format!("{} sector error(s)", disk_info.error_info.len()))
+                    .size(fs.tiny)
+                    .color(iced::Color::from_rgb(0.9, 0.6, 0.3)),
+                text(details).size(fs.tiny),
+                tooltip::Position::Top,
+            )
+            .style(crate::styles::subtle_tooltip)
+            .into()
+        };
+
         // Footer with blocks free
         let footer = row![
             text(format!("{} BLOCKS FREE", disk_info.blocks_free)).size(fs.small),
             Space::new().width(Length::Fill),
+            gcr_summary,
+            error_summary,
             text(format!("{} files", disk_info.entries.len())).size(fs.tiny),
         ]
         .spacing(10);
 
         // Popup container with border styling
+        let mut body = column![header, rule::horizontal(1), listing, rule::horizontal(1)];
+        if let Some(validation) = &self.disk_validation {
+            body = body
+                .push(self.view_disk_validation(validation, editable, font_size))
+                .push(rule::horizontal(1));
+        }
+        container(
+            body.push(entry_list)
+                .push(rule::horizontal(1))
+                .push(footer)
+                .spacing(5)
+                .padding(10),
+        )
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .style(crate::styles::subtle_tooltip)
+        .into()
+    }
+
+    /// Validate results: summary, Repair, and the issues found
+    fn view_disk_validation<'a>(
+        &self,
+        validation: &'a Validation,
+        editable: bool,
+        font_size: u32,
+    ) -> Element<'a, FileBrowserMessage> {
+        let fs = crate::styles::FontSizes::from_base(font_size);
+        let repairable = validation.issues.iter().any(|i| i.repairable());
+        let summary_color = if validation.issues.is_empty() {
+            iced::Color::from_rgb(0.5, 0.8, 0.5)
+        } else {
+            iced::Color::from_rgb(0.9, 0.6, 0.3)
+        };
+        let header = row![
+            text(validation.summary())
+                .size(fs.small)
+                .color(summary_color)
+                .width(Length::Fill),
+            tooltip(
+                button(text("Repair").size(fs.small))
+                    .on_press_maybe(
+                        (editable && repairable).then_some(FileBrowserMessage::RepairDiskImage)
+                    )
+                    .padding([4, 10])
+                    .style(crate::styles::action_button),
+                "Rebuild the BAM and scratch unclosed files, writing the image in place",
+                tooltip::Position::Left,
+            )
+            .style(crate::styles::subtle_tooltip),
+        ]
+        .spacing(5)
+        .align_y(iced::Alignment::Center);
+
+        let issues = validation.issues.iter().map(|issue| {
+            let color = if issue.repairable() {
+                iced::Color::from_rgb(0.7, 0.7, 0.7)
+            } else {
+                iced::Color::from_rgb(0.9, 0.5, 0.5)
+            };
+            text(issue.to_string()).size(fs.tiny).color(color).into()
+        });
+        column![
+            header,
+            container(scrollable(
+                Column::with_children(issues)
+                    .spacing(2)
+                    .padding(iced::Padding::ZERO.right(12)),
+            ))
+            .max_height(120),
+        ]
+        .spacing(4)
+        .into()
+    }
+
+    fn view_tape_info_popup(
+        &self,
+        tape_info: &TapeInfo,
+        font_size: u32,
+    ) -> Element<'_, FileBrowserMessage> {
+        let fs = crate::styles::FontSizes::from_base(font_size);
+
+        let header = row![
+            text(format!("{} - ", tape_info.kind)).size(fs.small),
+            text(format!("\"{}\"", tape_info.name)).size(fs.normal),
+            Space::new().width(Length::Fill),
+            tooltip(
+                button(text("Close").size(fs.small))
+                    .on_press(FileBrowserMessage::CloseTapeInfo)
+                    .padding([4, 10])
+                    .style(button::secondary),
+                "Close tape listing",
+                tooltip::Position::Left,
+            )
+            .style(crate::styles::subtle_tooltip),
+        ]
+        .spacing(5)
+        .align_y(iced::Alignment::Center);
+
+        let listing: Element<'_, FileBrowserMessage> = match &self.disk_listing_image {
+            Some(png_bytes) => {
+                let handle = iced::widget::image::Handle::from_bytes(png_bytes.clone());
+                scrollable(
+                    container(
+                        iced::widget::image(handle)
+                            .width(Length::Fill)
+                            .height(Length::Shrink),
+                    )
+                    .padding(4),
+                )
+                .height(Length::FillPortion(2))
+                .into()
+            }
+            None => Space::new().height(Length::FillPortion(2)).into(),
+        };
+
+        // Per-file actions: only T64 entries carry their data; TAP headers
+        // are listed for reference
+        let is_t64 = tape_info.kind == tape_image::TapeKind::T64;
+        let entry_button = |label: &'static str, msg: FileBrowserMessage| {
+            button(text(label).size(fs.tiny))
+                .on_press_maybe(is_t64.then_some(msg))
+                .padding([1, 6])
+                .style(crate::styles::nav_button)
+        };
+        let mut entry_rows: Vec<Element<'_, FileBrowserMessage>> = Vec::new();
+        for (i, entry) in tape_info.entries.iter().enumerate() {
+            entry_rows.push(
+                row![
+                    text(format!("\"{}\"", entry.name))
+                        .size(fs.tiny)
+                        .width(Length::Fill),
+                    text(format!(
+                        "{} ${:04X}-${:04X}",
+                        entry.file_type, entry.start_address, entry.end_address
+                    ))
+                    .size(fs.tiny),
+                    entry_button("Run", FileBrowserMessage::RunTapeEntry(i)),
+                    entry_button("Extract", FileBrowserMessage::ExtractTapeEntry(i)),
+                ]
+                .spacing(4)
+                .align_y(iced::Alignment::Center)
+                .into(),
+            );
+        }
+        let entry_list = scrollable(
+            Column::with_children(entry_rows)
+                .spacing(2)
+                .padding(iced::Padding::ZERO.right(12)),
+        )
+        .height(Length::FillPortion(1));
+
+        let footer = row![
+            text(tape_info.format_footer()).size(fs.small),
+            Space::new().width(Length::Fill),
+            text(format!("{} files", tape_info.entries.len())).size(fs.tiny),
+        ]
+        .spacing(10);
+
         container(
             column![
                 header,
                 rule::horizontal(1),
                 listing,
                 rule::horizontal(1),
+                entry_list,
+                rule::horizontal(1),
                 footer,
             ]
             .spacing(5)
@@ -2287,6 +3188,217 @@ impl FileBrowser {
         .into()
     }
 
+    fn view_crt_info_popup(
+        &self,
+        crt_info: &CrtInfo,
+        font_size: u32,
+    ) -> Element<'_, FileBrowserMessage> {
+        let fs = crate::styles::FontSizes::from_base(font_size);
+
+        let run_message = self
+            .crt_info_path
+            .clone()
+            .map(FileBrowserMessage::LoadAndRun);
+        let header = row![
+            text("CRT - ").size(fs.small),
+            text(format!("\"{}\"", crt_info.name)).size(fs.normal),
+            Space::new().width(Length::Fill),
+            tooltip(
+                button(text("Run").size(fs.small))
+                    .on_press_maybe(run_message)
+                    .padding([4, 10])
+                    .style(button::secondary),
+                "Load and run on Ultimate64",
+                tooltip::Position::Left,
+            )
+            .style(crate::styles::subtle_tooltip),
+            tooltip(
+                button(text("BIN").size(fs.small))
+                    .on_press(FileBrowserMessage::ExtractCrtBin)
+                    .padding([4, 10])
+                    .style(button::secondary),
+                "Write the whole ROM next to the cartridge as one .bin",
+                tooltip::Position::Left,
+            )
+            .style(crate::styles::subtle_tooltip),
+            tooltip(
+                button(text("Close").size(fs.small))
+                    .on_press(FileBrowserMessage::CloseCrtInfo)
+                    .padding([4, 10])
+                    .style(button::secondary),
+                "Close cartridge info",
+                tooltip::Position::Left,
+            )
+            .style(crate::styles::subtle_tooltip),
+        ]
+        .spacing(5)
+        .align_y(iced::Alignment::Center);
+
+        let listing: Element<'_, FileBrowserMessage> = match &self.disk_listing_image {
+            Some(png_bytes) => {
+                let handle = iced::widget::image::Handle::from_bytes(png_bytes.clone());
+                scrollable(
+                    container(
+                        iced::widget::image(handle)
+                            .width(Length::Fill)
+                            .height(Length::Shrink),
+                    )
+                    .padding(4),
+                )
+                .height(Length::FillPortion(2))
+                .into()
+            }
+            None => Space::new().height(Length::FillPortion(2)).into(),
+        };
+
+        let mut chip_rows: Vec<Element<'_, FileBrowserMessage>> = Vec::new();
+        for (i, chip) in crt_info.chips.iter().enumerate() {
+            chip_rows.push(
+                row![
+                    text(chip.format_line()).size(fs.tiny).width(Length::Fill),
+                    button(text("Extract").size(fs.tiny))
+                        .on_press(FileBrowserMessage::ExtractCrtChip(i))
+                        .padding([1, 6])
+                        .style(crate::styles::nav_button),
+                ]
+                .spacing(4)
+                .align_y(iced::Alignment::Center)
+                .into(),
+            );
+        }
+        for warning in &crt_info.warnings {
+            chip_rows.push(
+                text(format!("⚠ {}", warning))
+                    .size(fs.tiny)
+                    .color(iced::Color::from_rgb(0.9, 0.6, 0.3))
+                    .into(),
+            );
+        }
+        let chip_list = scrollable(
+            Column::with_children(chip_rows)
+                .spacing(2)
+                .padding(iced::Padding::ZERO.right(12)),
+        )
+        .height(Length::FillPortion(1));
+
+        let footer = row![
+            text(format!(
+                "{} (type {}), format v{}.{}",
+                crt_info.hardware_name(),
+                crt_info.hardware_type,
+                crt_info.version.0,
+                crt_info.version.1
+            ))
+            .size(fs.small),
+            Space::new().width(Length::Fill),
+            text(format!(
+                "EXROM {} GAME {} - {}",
+                crt_info.exrom,
+                crt_info.game,
+                crt_info.memory_mode()
+            ))
+            .size(fs.tiny),
+        ]
+        .spacing(10);
+
+        container(
+            column![
+                header,
+                rule::horizontal(1),
+                listing,
+                rule::horizontal(1),
+                chip_list,
+                rule::horizontal(1),
+                footer,
+            ]
+            .spacing(5)
+            .padding(10),
+        )
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .style(crate::styles::subtle_tooltip)
+        .into()
+    }
+
+    fn view_snapshot_popup<'a>(
+        &'a self,
+        lines: &'a [String],
+        font_size: u32,
+    ) -> Element<'a, FileBrowserMessage> {
+        let fs = crate::styles::FontSizes::from_base(font_size);
+        let name = self
+            .snapshot_path
+            .as_ref()
+            .and_then(|p| p.file_name())
+            .and_then(|n| n.to_str())
+            .unwrap_or("");
+
+        let header = row![
+            text("SNAPSHOT - ").size(fs.small),
+            text(crate::string_utils::truncate_string(name, 40)).size(fs.normal),
+            Space::new().width(Length::Fill),
+            tooltip(
+                button(text("Restore").size(fs.small))
+                    .on_press(FileBrowserMessage::RestoreSnapshot)
+                    .padding([4, 10])
+                    .style(crate::styles::action_button),
+                "Write this snapshot to the C64 and continue from it",
+                tooltip::Position::Left,
+            )
+            .style(crate::styles::subtle_tooltip),
+            tooltip(
+                button(text("Close").size(fs.small))
+                    .on_press(FileBrowserMessage::CloseSnapshotInfo)
+                    .padding([4, 10])
+                    .style(button::secondary),
+                "Close snapshot preview",
+                tooltip::Position::Left,
+            )
+            .style(crate::styles::subtle_tooltip),
+        ]
+        .spacing(5)
+        .align_y(iced::Alignment::Center);
+
+        let thumbnail: Element<'_, FileBrowserMessage> = match &self.disk_listing_image {
+            Some(png_bytes) => {
+                let handle = iced::widget::image::Handle::from_bytes(png_bytes.clone());
+                container(
+                    iced::widget::image(handle)
+                        .width(Length::Fill)
+                        .height(Length::Fill),
+                )
+                .center_x(Length::Fill)
+                .center_y(Length::Fill)
+                .into()
+            }
+            None => Space::new().height(Length::Fill).into(),
+        };
+
+        let details = Column::with_children(
+            lines
+                .iter()
+                .map(|l| text(l.as_str()).size(fs.small).into())
+                .collect::<Vec<Element<'_, FileBrowserMessage>>>(),
+        )
+        .spacing(2);
+
+        container(
+            column![
+                header,
+                rule::horizontal(1),
+                thumbnail,
+                rule::horizontal(1),
+                details,
+            ]
+            .spacing(5)
+            .padding(10),
+        )
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .style(crate::styles::subtle_tooltip)
+        .into()
+    }
+
     fn view_content_preview_popup<'a>(
         &'a self,
         content: &'a ContentPreview,
@@ -2442,12 +3554,15 @@ impl FileBrowser {
             match entry.extension.as_deref() {
                 Some("d64") | Some("d71") | Some("d81") | Some("g64") | Some("g71")
                 | Some("g81") => "DSK",
-                Some("prg") | Some("p00") | Some("seq") | Some("usr") | Some("rel") => "PRG",
+                Some("prg") | Some("seq") | Some("usr") | Some("rel") => "PRG",
+                Some(e) if crate::pc64::container_type(e).is_some() => "P00",
                 Some("crt") => "CRT",
                 Some("sid") => "SID",
                 Some("mod") | Some("xm") | Some("s3m") => "MOD",
                 Some("tap") | Some("t64") => "TAP",
                 Some("reu") => "REU",
+                Some(machine_snapshot::EXTENSION) => "SNP",
+                Some(launch_script::EXTENSION) => "SCR",
                 Some("rom") | Some("bin") => "ROM",
                 Some("cfg") => "CFG",
                 Some("u2l") | Some("u2p") | Some("u2r") | Some("u64") | Some("ue2") => "UPD",
@@ -2455,6 +3570,7 @@ impl FileBrowser {
                 Some("png") | Some("jpg") | Some("jpeg") | Some("gif") | Some("bmp") => "IMG",
                 Some("pdf") => "PDF",
                 Some("zip") => "ZIP",
+                Some("lnx") => "LNX",
                 _ => "",
             }
         };
@@ -2465,7 +3581,12 @@ impl FileBrowser {
         // boundary" when the cut lands mid-codepoint. truncate_string is
         // already in string_utils for exactly this.
         let max_name_len = 45;
-        let display_name = crate::string_utils::truncate_string(&entry.name, max_name_len);
+        // PC64 containers show the C64 name they carry, not the 8.3 host name
+        let shown_name = match &entry.c64_name {
+            Some(c64_name) => format!("\"{}\"", c64_name),
+            None => entry.name.clone(),
+        };
+        let display_name = crate::string_utils::truncate_string(&shown_name, max_name_len);
 
         // Check if this is a disk image that can show info
         let is_disk_image = entry
@@ -2563,16 +3684,170 @@ impl FileBrowser {
 
                     buttons.into()
                 }
-                Some("prg") | Some("crt") => tooltip(
+                Some("t64") => row![
+                    tooltip(
+                        button(text("?").size(fs.small))
+                            .on_press(FileBrowserMessage::ShowTapeInfo(entry.path.clone()))
+                            .padding([2, 5])
+                            .style(crate::styles::action_button),
+                        "Show tape directory listing",
+                        tooltip::Position::Top,
+                    )
+                    .style(crate::styles::subtle_tooltip),
+                    tooltip(
+                        button(text("Run").size(fs.small))
+                            .on_press(FileBrowserMessage::LoadAndRun(entry.path.clone()))
+                            .padding([2, 10])
+                            .style(crate::styles::action_button),
+                        "Run the first PRG in the archive",
+                        tooltip::Position::Top,
+                    )
+                    .style(crate::styles::subtle_tooltip),
+                ]
+                .spacing(2)
+                .into(),
+                Some("dnp") => tooltip(
+                    button(text("?").size(fs.small))
+                        .on_press(FileBrowserMessage::ShowDiskInfo(entry.path.clone()))
+                        .padding([2, 5])
+                        .style(crate::styles::action_button),
+                    "Show the CMD native partition's directory listing",
+                    tooltip::Position::Top,
+                )
+                .style(crate::styles::subtle_tooltip)
+                .into(),
+                Some("tap") => tooltip(
+                    button(text("?").size(fs.small))
+                        .on_press(FileBrowserMessage::ShowTapeInfo(entry.path.clone()))
+                        .padding([2, 5])
+                        .style(crate::styles::action_button),
+                    "List the files found by the CBM ROM loader",
+                    tooltip::Position::Top,
+                )
+                .style(crate::styles::subtle_tooltip)
+                .into(),
+                Some(launch_script::EXTENSION) => tooltip(
                     button(text("Run").size(fs.small))
-                        .on_press(FileBrowserMessage::LoadAndRun(entry.path.clone()))
+                        .on_press(FileBrowserMessage::RunScriptFile(entry.path.clone()))
                         .padding([2, 10])
                         .style(crate::styles::action_button),
-                    "Load and run on Ultimate64",
+                    "Run this launch script",
+                    tooltip::Position::Top,
+                )
+                .style(crate::styles::subtle_tooltip)
+                .into(),
+                Some(e) if crate::file_types::is_update_file(e) => tooltip(
+                    button(text("Update").size(fs.small))
+                        .on_press(FileBrowserMessage::UpdateFirmware(entry.path.clone()))
+                        .padding([2, 5])
+                        .style(crate::styles::action_button),
+                    "Check this firmware against the device, back up its configuration and upload it",
+                    tooltip::Position::Top,
+                )
+                .style(crate::styles::subtle_tooltip)
+                .into(),
+                Some(machine_snapshot::EXTENSION) => tooltip(
+                    button(text("?").size(fs.small))
+                        .on_press(FileBrowserMessage::ShowSnapshotInfo(entry.path.clone()))
+                        .padding([2, 5])
+                        .style(crate::styles::action_button),
+                    "Show the snapshot's screen and CPU state",
                     tooltip::Position::Top,
                 )
                 .style(crate::styles::subtle_tooltip)
                 .into(),
+                Some("crt") => row![
+                    tooltip(
+                        button(text("?").size(fs.small))
+                            .on_press(FileBrowserMessage::ShowCrtInfo(entry.path.clone()))
+                            .padding([2, 5])
+                            .style(crate::styles::action_button),
+                        "Show hardware type and CHIP packets",
+                        tooltip::Position::Top,
+                    )
+                    .style(crate::styles::subtle_tooltip),
+                    tooltip(
+                        button(text("Run").size(fs.small))
+                            .on_press(FileBrowserMessage::LoadAndRun(entry.path.clone()))
+                            .padding([2, 10])
+                            .style(crate::styles::action_button),
+                        "Load and run on Ultimate64",
+                        tooltip::Position::Top,
+                    )
+                    .style(crate::styles::subtle_tooltip),
+                ]
+                .spacing(2)
+                .into(),
+                Some(ext @ ("prg" | "seq" | "usr")) => {
+                    let container = format!("{}00", ext[..1].to_uppercase());
+                    let mut buttons = row![].spacing(2);
+                    if ext == "prg" {
+                        buttons = buttons.push(
+                            tooltip(
+                                button(text("Run").size(fs.small))
+                                    .on_press(FileBrowserMessage::LoadAndRun(entry.path.clone()))
+                                    .padding([2, 10])
+                                    .style(crate::styles::action_button),
+                                "Load and run on Ultimate64",
+                                tooltip::Position::Top,
+                            )
+                            .style(crate::styles::subtle_tooltip),
+                        );
+                    }
+                    buttons
+                        .push(
+                            tooltip(
+                                button(text(container.clone()).size(fs.small))
+                                    .on_press(FileBrowserMessage::ConvertPc64(entry.path.clone()))
+                                    .padding([2, 5])
+                                    .style(crate::styles::action_button),
+                                text(format!(
+                                    "Wrap in a PC64 .{} container named after this file",
+                                    container
+                                ))
+                                .size(fs.normal),
+                                tooltip::Position::Top,
+                            )
+                            .style(crate::styles::subtle_tooltip),
+                        )
+                        .into()
+                }
+                Some(ext) if crate::pc64::container_type(ext).is_some() => {
+                    let file_type = crate::pc64::container_type(ext).unwrap_or(FileType::Prg);
+                    let mut buttons = row![].spacing(2);
+                    if file_type == FileType::Prg {
+                        buttons = buttons.push(
+                            tooltip(
+                                button(text("Run").size(fs.small))
+                                    .on_press(FileBrowserMessage::LoadAndRun(entry.path.clone()))
+                                    .padding([2, 10])
+                                    .style(crate::styles::action_button),
+                                "Strip the PC64 header, load and run on Ultimate64",
+                                tooltip::Position::Top,
+                            )
+                            .style(crate::styles::subtle_tooltip),
+                        );
+                    }
+                    // A raw REL file would lose the record length.
+                    if file_type != FileType::Rel {
+                        buttons = buttons.push(
+                            tooltip(
+                                button(text(file_type.to_string()).size(fs.small))
+                                    .on_press(FileBrowserMessage::ConvertPc64(entry.path.clone()))
+                                    .padding([2, 5])
+                                    .style(crate::styles::action_button),
+                                text(format!(
+                                    "Write the {} file inside next to this one under its C64 name",
+                                    file_type
+                                ))
+                                .size(fs.normal),
+                                tooltip::Position::Top,
+                            )
+                            .style(crate::styles::subtle_tooltip),
+                        );
+                    }
+                    buttons.into()
+                }
                 Some("sid") => tooltip(
                     button(text("Play").size(fs.small))
                         .on_press(FileBrowserMessage::PlaySid(entry.path.clone()))
@@ -2593,8 +3868,8 @@ impl FileBrowser {
                 )
                 .style(crate::styles::subtle_tooltip)
                 .into(),
-                Some("zip") => {
-                    // Extract the ZIP into a sibling subdirectory, then navigate there.
+                Some("zip") | Some("lnx") => {
+                    // Extract the archive into a sibling subdirectory, then navigate there.
                     // Very large ZIPs (TOSEC etc.) are rejected with a clear error message.
                     tooltip(
                         button(text("Extract").size(fs.small))
@@ -2602,7 +3877,7 @@ impl FileBrowser {
                             .padding([2, 8])
                             .style(crate::styles::action_button),
                         text(format!(
-                            "Extract ZIP to subfolder (max {} MB)",
+                            "Extract to subfolder, unpacking Lynx and Zipcode sets (max {} MB)",
                             MAX_ZIP_EXTRACT_BYTES / (1024 * 1024)
                         ))
                         .size(fs.normal),
@@ -2675,17 +3950,18 @@ impl FileBrowser {
             .width(Length::Fill)
             .style(button::text);
 
-        let filename_element: Element<'_, FileBrowserMessage> = if entry.name.len() > max_name_len {
-            tooltip(
-                filename_button,
-                text(entry.name.clone()).size(fs.normal),
-                tooltip::Position::Top,
-            )
-            .style(crate::styles::subtle_tooltip)
-            .into()
-        } else {
-            filename_button.into()
-        };
+        let filename_element: Element<'_, FileBrowserMessage> =
+            if entry.c64_name.is_some() || entry.name.len() > max_name_len {
+                tooltip(
+                    filename_button,
+                    text(entry.name.clone()).size(fs.normal),
+                    tooltip::Position::Top,
+                )
+                .style(crate::styles::subtle_tooltip)
+                .into()
+            } else {
+                filename_button.into()
+            };
 
         // Size column
         let size_text = if entry.is_dir {
@@ -2890,6 +4166,40 @@ impl FileBrowser {
         });
     }
 
+    /// Read a launch script and hand it to the app to run.
+    fn launch_script(&mut self, script: &Path, file: Option<&Path>) -> Task<FileBrowserMessage> {
+        match launch_script::read_local(script, file) {
+            Ok(request) => {
+                self.status_message = Some(format!("Running {}...", request.name));
+                Task::done(FileBrowserMessage::RunLaunchScript(request))
+            }
+            Err(e) => {
+                self.status_message = Some(format!("Launch script: {}", e));
+                Task::none()
+            }
+        }
+    }
+
+    /// Send a cartridge that has passed (or been waved through) its check.
+    fn send_crt(
+        &mut self,
+        connection: Option<Arc<Mutex<dyn RemoteDevice>>>,
+        path: PathBuf,
+    ) -> Task<FileBrowserMessage> {
+        let Some(conn) = connection else {
+            self.status_message = Some("Not connected to Ultimate64".to_string());
+            return Task::none();
+        };
+        self.status_message = Some(format!(
+            "Loading {}...",
+            path.file_name().unwrap_or_default().to_string_lossy()
+        ));
+        Task::perform(
+            load_and_run_async(conn, path),
+            FileBrowserMessage::LoadCompleted,
+        )
+    }
+
     fn load_directory(&mut self, path: &Path) {
         self.files.clear();
         self.filter.clear();
@@ -2922,12 +4232,19 @@ impl FileBrowser {
                             None
                         };
 
+                        let c64_name = if is_dir {
+                            None
+                        } else {
+                            crate::pc64::read_name(&path)
+                        };
+
                         Some(FileEntry {
                             path,
                             name,
                             is_dir,
                             extension,
                             size,
+                            c64_name,
                         })
                     })
                 })
@@ -2941,13 +4258,252 @@ impl FileBrowser {
     }
 }
 
-async fn load_disk_info_async(path: PathBuf) -> Result<DiskInfo, String> {
+async fn load_disk_info_async(path: PathBuf, dir: Vec<usize>) -> Result<DiskInfo, String> {
     // Run disk reading in blocking task to avoid blocking async runtime
-    tokio::task::spawn_blocking(move || disk_image::read_disk_info(&path))
+    tokio::task::spawn_blocking(move || {
+        let data = std::fs::read(&path).map_err(|e| format!("Failed to read file: {}", e))?;
+        disk_image::read_disk_info_in(&data, &dir)
+    })
+    .await
+    .map_err(|e| format!("Task error: {}", e))?
+}
+
+async fn load_snapshot_info_async(path: PathBuf) -> Result<(Vec<String>, Vec<u8>), String> {
+    tokio::task::spawn_blocking(move || {
+        let snapshot = machine_snapshot::Snapshot::load(&path)?;
+        Ok((snapshot.describe(), snapshot.thumbnail_png()?))
+    })
+    .await
+    .map_err(|e| format!("Task error: {}", e))?
+}
+
+async fn load_tape_info_async(path: PathBuf) -> Result<TapeInfo, String> {
+    tokio::task::spawn_blocking(move || tape_image::read_tape_info(&path))
         .await
         .map_err(|e| format!("Task error: {}", e))?
 }
 
+async fn load_crt_info_async(path: PathBuf) -> Result<CrtInfo, String> {
+    tokio::task::spawn_blocking(move || crt_image::read_crt_info(&path))
+        .await
+        .map_err(|e| format!("Task error: {}", e))?
+}
+
+/// Warnings about a CRT about to be sent; an error if it isn't one at all.
+pub(crate) async fn check_crt_async(path: PathBuf) -> Result<Vec<String>, String> {
+    load_crt_info_async(path).await.map(|info| info.warnings)
+}
+
+/// Write CHIP packet `index`, or the whole ROM when `None`, next to the
+/// cartridge as a .bin, refusing to overwrite an existing host file.
+async fn extract_crt_async(crt: PathBuf, index: Option<usize>) -> Result<String, String> {
+    tokio::task::spawn_blocking(move || {
+        let data = std::fs::read(&crt).map_err(|e| format!("Failed to read file: {}", e))?;
+        let stem = crt
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("cartridge");
+        let (name, bin) = match index {
+            Some(index) => {
+                let (chip, image) = crt_image::extract_chip(&data, index)?;
+                (
+                    format!(
+                        "{}_bank{:02}_{:04x}.bin",
+                        stem, chip.bank, chip.load_address
+                    ),
+                    image,
+                )
+            }
+            None => (format!("{}.bin", stem), crt_image::to_bin(&data)?),
+        };
+        let target = crt.parent().unwrap_or(Path::new(".")).join(name);
+        if target.exists() {
+            return Err(format!("{} already exists", target.display()));
+        }
+        std::fs::write(&target, &bin)
+            .map_err(|e| format!("Failed to write {}: {}", target.display(), e))?;
+        Ok(format!(
+            "Extracted {} bytes to {}",
+            bin.len(),
+            target.display()
+        ))
+    })
+    .await
+    .map_err(|e| format!("Task error: {}", e))?
+}
+
+/// Write T64 entry `index` next to the archive as `<NAME>.prg`, refusing to
+/// overwrite an existing host file.
+async fn extract_tape_entry_async(image: PathBuf, index: usize) -> Result<String, String> {
+    tokio::task::spawn_blocking(move || {
+        let data = std::fs::read(&image).map_err(|e| format!("Failed to read image: {}", e))?;
+        let (name, prg) = tape_image::extract_t64_prg(&data, index)?;
+        let stem: String = name
+            .chars()
+            .map(|c| if "/\\:*?\"<>|".contains(c) { '_' } else { c })
+            .collect();
+        let dir = image.parent().unwrap_or(Path::new("."));
+        let target = dir.join(format!("{}.prg", stem.trim()));
+        if target.exists() {
+            return Err(format!("{} already exists", target.display()));
+        }
+        std::fs::write(&target, &prg)
+            .map_err(|e| format!("Failed to write {}: {}", target.display(), e))?;
+        Ok(format!("Extracted to {}", target.display()))
+    })
+    .await
+    .map_err(|e| format!("Task error: {}", e))?
+}
+
+/// Write a local file into a D64/D71/D81 image. The CBM name is the host
+/// file stem (upper-cased, cut to 16 chars); `.seq`/`.usr` keep their type,
+/// everything else goes in as PRG. A PC64 container brings its own name and
+/// type.
+async fn insert_into_image_async(image: PathBuf, file: PathBuf) -> Result<String, String> {
+    tokio::task::spawn_blocking(move || {
+        let mut data = std::fs::read(&image).map_err(|e| format!("Failed to read image: {}", e))?;
+        let (name, file_type, payload) = if crate::pc64::is_container_path(&file) {
+            let container = crate::pc64::read(&file)?;
+            (container.name, container.file_type, container.data)
+        } else {
+            let payload =
+                std::fs::read(&file).map_err(|e| format!("Failed to read file: {}", e))?;
+            let ext = file
+                .extension()
+                .and_then(|s| s.to_str())
+                .map(|s| s.to_lowercase());
+            let file_type = match ext.as_deref() {
+                Some("seq") => FileType::Seq,
+                Some("usr") => FileType::Usr,
+                _ => FileType::Prg,
+            };
+            let name: String = file
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or("FILE")
+                .to_uppercase()
+                .chars()
+                .take(16)
+                .collect();
+            (name, file_type, payload)
+        };
+        disk_image::insert_file(&mut data, &name, file_type, &payload)?;
+        std::fs::write(&image, &data).map_err(|e| format!("Failed to write image: {}", e))?;
+        Ok(format!("Inserted \"{}\" ({})", name, file_type))
+    })
+    .await
+    .map_err(|e| format!("Task error: {}", e))?
+}
+
+/// Unwrap a PC64 container, or wrap a raw file in one
+async fn convert_pc64_async(path: PathBuf) -> Result<PathBuf, String> {
+    tokio::task::spawn_blocking(move || {
+        if crate::pc64::is_container_path(&path) {
+            crate::pc64::unwrap_to_raw(&path)
+        } else {
+            crate::pc64::wrap_raw(&path)
+        }
+    })
+    .await
+    .map_err(|e| format!("Task error: {}", e))?
+}
+
+async fn validate_image_async(image: PathBuf) -> Result<Validation, String> {
+    tokio::task::spawn_blocking(move || {
+        let data = std::fs::read(&image).map_err(|e| format!("Failed to read image: {}", e))?;
+        disk_image::validate(&data)
+    })
+    .await
+    .map_err(|e| format!("Task error: {}", e))?
+}
+
+/// VALIDATE the image on disk in place; the message says what is left that
+/// a repair can't fix.
+async fn repair_image_async(image: PathBuf) -> Result<String, String> {
+    tokio::task::spawn_blocking(move || {
+        let mut data = std::fs::read(&image).map_err(|e| format!("Failed to read image: {}", e))?;
+        let found = disk_image::repair(&mut data)?;
+        std::fs::write(&image, &data).map_err(|e| format!("Failed to write image: {}", e))?;
+        let left = found.issues.iter().filter(|i| !i.repairable()).count();
+        let fixed = found.issues.len() - left;
+        Ok(match left {
+            0 => format!("Image repaired: {} problem(s) fixed", fixed),
+            _ => format!(
+                "Image repaired: {} problem(s) fixed, {} left (damaged file chains)",
+                fixed, left
+            ),
+        })
+    })
+    .await
+    .map_err(|e| format!("Task error: {}", e))?
+}
+
+/// Apply a [`DiskEntryEdit`] to the image on disk. Extract writes the file
+/// next to the image and refuses to overwrite an existing host file.
+async fn edit_image_entry_async(
+    image: PathBuf,
+    index: usize,
+    edit: DiskEntryEdit,
+) -> Result<String, String> {
+    tokio::task::spawn_blocking(move || {
+        let mut data = std::fs::read(&image).map_err(|e| format!("Failed to read image: {}", e))?;
+        let msg = match edit {
+            DiskEntryEdit::Extract => {
+                let (name, file_type, bytes) = disk_image::extract_file(&data, index)?;
+                let stem: String = name
+                    .chars()
+                    .map(|c| if "/\\:*?\"<>|".contains(c) { '_' } else { c })
+                    .collect();
+                let dir = image.parent().unwrap_or(Path::new("."));
+                let target = dir.join(format!(
+                    "{}.{}",
+                    stem.trim(),
+                    file_type.to_string().to_lowercase()
+                ));
+                if target.exists() {
+                    return Err(format!("{} already exists", target.display()));
+                }
+                std::fs::write(&target, &bytes)
+                    .map_err(|e| format!("Failed to write {}: {}", target.display(), e))?;
+                return Ok(format!("Extracted to {}", target.display()));
+            }
+            DiskEntryEdit::Scratch => {
+                disk_image::scratch_file(&mut data, index)?;
+                "File scratched"
+            }
+            DiskEntryEdit::Rename(name) => {
+                disk_image::rename_file(&mut data, index, name.trim())?;
+                "File renamed"
+            }
+            DiskEntryEdit::ToggleLock => {
+                let locked = disk_image::read_disk_info_from_bytes(&data)?
+                    .entries
+                    .get(index)
+                    .map(|e| e.locked)
+                    .ok_or_else(|| format!("No directory entry #{}", index))?;
+                disk_image::set_locked(&mut data, index, !locked)?;
+                if locked {
+                    "File unlocked"
+                } else {
+                    "File locked"
+                }
+            }
+            DiskEntryEdit::MoveUp => {
+                disk_image::move_entry(&mut data, index, index.saturating_sub(1))?;
+                "Entry moved up"
+            }
+            DiskEntryEdit::MoveDown => {
+                disk_image::move_entry(&mut data, index, index + 1)?;
+                "Entry moved down"
+            }
+        };
+        std::fs::write(&image, &data).map_err(|e| format!("Failed to write image: {}", e))?;
+        Ok(msg.to_string())
+    })
+    .await
+    .map_err(|e| format!("Task error: {}", e))?
+}
+
 /// Read a ZIP from disk, extract it to `target_dir`, and return the target dir
 /// path on success.  The actual extraction runs on a blocking thread so the
 /// async runtime is not stalled.
@@ -2971,7 +4527,7 @@ async fn extract_zip_file_async(zip_path: PathBuf, target_dir: PathBuf) -> Resul
     tokio::time::timeout(
         std::time::Duration::from_secs(60),
         tokio::task::spawn_blocking(move || {
-            extract_zip_to_dir(&data, &filename, &target_dir_clone)
+            extract_archive_to_dir(&data, &filename, &target_dir_clone)
                 .map(|_| target_dir_clone)
                 .map_err(|e| e.to_string())
         }),
@@ -3093,6 +4649,8 @@ async fn load_and_run_async(
         log::error!("Failed to read file: {}", e);
         e.to_string()
     })?;
+    // A PC64 container carries its PRG behind a 26-byte header
+    let data = crate::pc64::runnable_payload(&path, data)?;
 
     let ext = path
         .extension()
@@ -3114,6 +4672,15 @@ async fn load_and_run_async(
                     log::info!("Running as PRG");
                     conn.run_prg(&data).map_err(|e| e.to_string())
                 }
+                Some(e) if crate::pc64::container_type(e).is_some() => {
+                    log::info!("Running PC64 container as PRG");
+                    conn.run_prg(&data).map_err(|e| e.to_string())
+                }
+                Some("t64") => {
+                    let (name, prg) = tape_image::first_t64_prg(&data)?;
+                    log::info!("Running T64 entry \"{}\" as PRG", name);
+                    conn.run_prg(&prg).map_err(|e| e.to_string())
+                }
                 _ => Err("Unsupported file type".to_string()),
             }
         }),
@@ -3127,6 +4694,31 @@ async fn load_and_run_async(
     }
 }
 
+async fn run_tape_entry_async(
+    connection: Arc<Mutex<dyn RemoteDevice>>,
+    image: PathBuf,
+    index: usize,
+) -> Result<(), String> {
+    let data = std::fs::read(&image).map_err(|e| format!("Failed to read image: {}", e))?;
+    let (name, prg) = tape_image::extract_t64_prg(&data, index)?;
+    log::info!("Running T64 entry \"{}\"", name);
+
+    let result = tokio::time::timeout(
+        tokio::time::Duration::from_secs(REST_TIMEOUT_SECS),
+        tokio::task::spawn_blocking(move || {
+            let conn = connection.lock().unwrap();
+            conn.run_prg(&prg).map_err(|e| e.to_string())
+        }),
+    )
+    .await;
+
+    match result {
+        Ok(Ok(inner)) => inner,
+        Ok(Err(e)) => Err(format!("Task error: {}", e)),
+        Err(_) => Err("Load timed out - device may be offline".to_string()),
+    }
+}
+
 /// Outcome of the one-shot CSDB → Assembly64 folder migration.
 #[derive(Debug, Clone, Copy)]
 pub enum MigrationOutcome {
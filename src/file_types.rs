@@ -66,7 +66,7 @@ pub fn is_tape_file(ext: &str) -> bool {
 
 /// Check if an extension is a program file
 pub fn is_program_file(ext: &str) -> bool {
-    matches!(ext, "prg" | "p00" | "seq" | "usr" | "rel")
+    matches!(ext, "prg" | "seq" | "usr" | "rel") || crate::pc64::container_type(ext).is_some()
 }
 
 /// Check if an extension is a music/audio file supported by the device
@@ -112,7 +112,7 @@ pub fn is_device_file(ext: &str) -> bool {
         || is_rom_file(ext)
         || is_config_file(ext)
         || is_update_file(ext)
-        || is_zip_file(ext)
+        || is_archive_file(ext)
         || ext == "crt"
 }
 
@@ -167,6 +167,11 @@ pub fn is_zip_file(ext: &str) -> bool {
     ext == "zip"
 }
 
+/// Check if a file extension is an archive the app unpacks: ZIP or Lynx
+pub fn is_archive_file(ext: &str) -> bool {
+    is_zip_file(ext) || ext == "lnx"
+}
+
 /// Check if a file extension indicates a runnable C64 file
 pub fn is_runnable(ext: &str) -> bool {
     matches!(
@@ -228,13 +233,14 @@ pub fn get_file_icon(name: &str) -> &'static str {
 /// Get the display color for a file extension in the CSDb browser
 pub fn ext_color(ext: &str) -> iced::Color {
     match ext {
-        "prg" | "p00" | "seq" | "usr" | "rel" => iced::Color::from_rgb(0.5, 0.8, 0.5),
+        "prg" | "seq" | "usr" | "rel" => iced::Color::from_rgb(0.5, 0.8, 0.5),
+        e if crate::pc64::container_type(e).is_some() => iced::Color::from_rgb(0.5, 0.8, 0.5),
         "d64" | "d71" | "d81" | "g64" | "g71" | "g81" => iced::Color::from_rgb(0.5, 0.7, 0.9),
         "crt" => iced::Color::from_rgb(0.9, 0.7, 0.5),
         "sid" => iced::Color::from_rgb(0.8, 0.5, 0.8),
         "mod" | "xm" | "s3m" => iced::Color::from_rgb(0.7, 0.5, 0.9),
         "tap" | "t64" => iced::Color::from_rgb(0.8, 0.6, 0.4),
-        "zip" => iced::Color::from_rgb(0.9, 0.9, 0.5),
+        "zip" | "lnx" => iced::Color::from_rgb(0.9, 0.9, 0.5),
         "reu" => iced::Color::from_rgb(0.6, 0.8, 0.8),
         "rom" | "bin" => iced::Color::from_rgb(0.7, 0.7, 0.5),
         "cfg" => iced::Color::from_rgb(0.6, 0.7, 0.8),
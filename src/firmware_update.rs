@@ -0,0 +1,391 @@
+//! Guided firmware update for `.u64` / `.ue2` / `.u2p` / `.u2l` / `.u2r` images.
+//!
+//! The steps are:
+//!
+//! 1. **Inspect.** Read the image and take the target model from its
+//!    extension. Reject files that can't be an update image (empty, zip
+//!    archives, downloaded web pages). Block the update when the model doesn't
+//!    match the connected device's `DeviceInfo::product`.
+//! 2. **Back up.** Save the full device configuration as a preset in the
+//!    presets folder, so the Config tab can load it back afterwards.
+//! 3. **Upload.** Send the image over FTP to [`REMOTE_DIR`].
+//! 4. **Start.** The REST API has no update runner. The app opens the device
+//!    menu, and the user picks the uploaded file and chooses *Run Update*.
+//! 5. **Watch.** The normal status poll keeps running through the reboot.
+//!    The update is done once it reports a different `firmware_version`.
+//!
+//! Update images carry no documented magic number. The header check is
+//! therefore only a sanity check. The version string found in the image is
+//! shown for information and never used to allow or block an update.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Device folder the image is uploaded to (the RAM disk)
+pub const REMOTE_DIR: &str = "/Temp";
+
+/// Smallest file accepted as an update image
+const MIN_IMAGE_SIZE: usize = 64 * 1024;
+
+/// How far into the image to look for an embedded version string
+const VERSION_SCAN_LEN: usize = 2 * 1024 * 1024;
+
+/// How long to wait for the new firmware version to show up once the user
+/// has started the update (flashing plus reboot)
+pub const REBOOT_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+// ─── Models ──────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Model {
+    Ultimate64,
+    Ultimate64Elite2,
+    UltimateIIPlus,
+    UltimateIIPlusL,
+    UltimateIIPlusRom,
+}
+
+impl Model {
+    /// Target model for an update file extension (lowercase).
+    pub fn from_extension(ext: &str) -> Option<Self> {
+        match ext {
+            "u64" => Some(Model::Ultimate64),
+            "ue2" => Some(Model::Ultimate64Elite2),
+            "u2p" => Some(Model::UltimateIIPlus),
+            "u2l" => Some(Model::UltimateIIPlusL),
+            "u2r" => Some(Model::UltimateIIPlusRom),
+            _ => None,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Model::Ultimate64 => "Ultimate 64 / Elite",
+            Model::Ultimate64Elite2 => "Ultimate 64 Elite II",
+            Model::UltimateIIPlus => "Ultimate-II+",
+            Model::UltimateIIPlusL => "Ultimate-II+L",
+            Model::UltimateIIPlusRom => "Ultimate-II+ (ROM variant)",
+        }
+    }
+
+    /// Whether an image for this model may be installed on a device that
+    /// reports `product` (e.g. `"Ultimate 64 Elite"`, `"Ultimate-II+L"`).
+    pub fn matches_product(self, product: &str) -> bool {
+        let p: String = product
+            .to_lowercase()
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric() || *c == '+')
+            .collect();
+        let elite2 = p.starts_with("ultimate64") && p.ends_with("ii");
+        match self {
+            Model::Ultimate64 => p.starts_with("ultimate64") && !elite2,
+            Model::Ultimate64Elite2 => elite2,
+            Model::UltimateIIPlus | Model::UltimateIIPlusRom => p == "ultimateii+",
+            Model::UltimateIIPlusL => p == "ultimateii+l",
+        }
+    }
+}
+
+// ─── Inspection ──────────────────────────────────────────────────────────────
+
+/// What the app knows about an update image before sending it.
+#[derive(Debug, Clone)]
+pub struct FirmwareImage {
+    pub path: PathBuf,
+    pub name: String,
+    pub model: Model,
+    pub size: usize,
+    /// Version string found in the image, if any (e.g. `"V3.12a"`)
+    pub version: Option<String>,
+}
+
+impl FirmwareImage {
+    pub fn read(path: &Path) -> Result<Self, String> {
+        let data = std::fs::read(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        Self::inspect(path, &data)
+    }
+
+    pub fn inspect(path: &Path, data: &[u8]) -> Result<Self, String> {
+        let name = path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        let ext = path
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        let model = Model::from_extension(&ext)
+            .ok_or_else(|| format!("{} is not a firmware update file", name))?;
+
+        if data.starts_with(b"PK\x03\x04") {
+            return Err(format!(
+                "{} is a zip archive — extract the update file first",
+                name
+            ));
+        }
+        let head = &data[..data.len().min(512)];
+        let trimmed = head.trim_ascii_start();
+        if trimmed.starts_with(b"<") || (!head.is_empty() && is_text(head)) {
+            return Err(format!(
+                "{} looks like a text file or web page, not a firmware image",
+                name
+            ));
+        }
+        if data.len() < MIN_IMAGE_SIZE {
+            return Err(format!(
+                "{} is only {} bytes — too small to be a firmware image",
+                name,
+                data.len()
+            ));
+        }
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            name,
+            model,
+            size: data.len(),
+            version: embedded_version(&data[..data.len().min(VERSION_SCAN_LEN)]),
+        })
+    }
+
+    /// Refuse the update when the connected device is a different model.
+    pub fn check_device(&self, product: &str) -> Result<(), String> {
+        if self.model.matches_product(product) {
+            Ok(())
+        } else {
+            Err(format!(
+                "{} is for the {}, but the connected device is a {}",
+                self.name,
+                self.model.label(),
+                product
+            ))
+        }
+    }
+}
+
+fn is_text(bytes: &[u8]) -> bool {
+    bytes
+        .iter()
+        .all(|&b| b.is_ascii_graphic() || b.is_ascii_whitespace())
+}
+
+/// First `V<d>.<dd>[a-z]` in the image, e.g. `V3.12a`.
+fn embedded_version(data: &[u8]) -> Option<String> {
+    data.windows(5).enumerate().find_map(|(i, w)| {
+        let is_version = w[0] == b'V'
+            && w[1].is_ascii_digit()
+            && w[2] == b'.'
+            && w[3].is_ascii_digit()
+            && w[4].is_ascii_digit();
+        if !is_version || (i > 0 && data[i - 1].is_ascii_alphanumeric()) {
+            return None;
+        }
+        let mut end = i + 5;
+        if data.get(end).is_some_and(|b| b.is_ascii_lowercase()) {
+            end += 1;
+        }
+        if data.get(end).is_some_and(|b| b.is_ascii_alphanumeric()) {
+            return None;
+        }
+        Some(String::from_utf8_lossy(&data[i..end]).to_string())
+    })
+}
+
+// ─── Session ─────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Stage {
+    /// Inspected and matched; waiting for the user to go ahead
+    Ready,
+    BackingUp,
+    Uploading,
+    /// Uploaded; the user starts *Run Update* on the device
+    AwaitingStart {
+        remote_path: String,
+    },
+    /// Waiting for the status poll to report a new firmware version
+    Rebooting {
+        since: Instant,
+    },
+    Done {
+        version: String,
+    },
+    Failed(String),
+}
+
+/// One update run, shown in the firmware update dialog.
+#[derive(Debug, Clone)]
+pub struct UpdateSession {
+    pub image: FirmwareImage,
+    pub product: String,
+    /// Firmware the device reported before the update
+    pub from_version: String,
+    pub stage: Stage,
+    /// Where the pre-update configuration backup was written
+    pub backup: Option<PathBuf>,
+}
+
+impl UpdateSession {
+    /// Start a session for `image` on a device reporting `product` and
+    /// `firmware`. A model mismatch leaves the session failed.
+    pub fn new(image: FirmwareImage, product: String, firmware: String) -> Self {
+        let stage = match image.check_device(&product) {
+            Ok(()) => Stage::Ready,
+            Err(e) => Stage::Failed(e),
+        };
+        Self {
+            image,
+            product,
+            from_version: firmware,
+            stage,
+            backup: None,
+        }
+    }
+
+    /// True while the backup or upload is running; the dialog can't be
+    /// closed until it finishes.
+    pub fn is_busy(&self) -> bool {
+        matches!(self.stage, Stage::BackingUp | Stage::Uploading)
+    }
+
+    /// True while waiting for the device to come back after the update
+    pub fn is_rebooting(&self) -> bool {
+        matches!(self.stage, Stage::Rebooting { .. })
+    }
+
+    /// Feed a firmware version from the status poll. Returns true once the
+    /// update is seen to have completed.
+    pub fn observe(&mut self, firmware: &str) -> bool {
+        if self.is_rebooting() && firmware != self.from_version {
+            self.stage = Stage::Done {
+                version: firmware.to_string(),
+            };
+            return true;
+        }
+        false
+    }
+
+    /// Timer tick while rebooting. Gives up after [`REBOOT_TIMEOUT`] whether
+    /// or not the device has answered a poll in the meantime.
+    pub fn tick(&mut self, now: Instant) {
+        if let Stage::Rebooting { since } = self.stage {
+            if now.saturating_duration_since(since) > REBOOT_TIMEOUT {
+                self.stage = Stage::Failed(format!(
+                    "Still on firmware {} after {} minutes — check the device screen",
+                    self.from_version,
+                    REBOOT_TIMEOUT.as_secs() / 60
+                ));
+            }
+        }
+    }
+}
+
+// ─── Device operations ───────────────────────────────────────────────────────
+
+/// Save the full device configuration as a preset named after the firmware
+/// it was taken from. Returns the file written.
+pub async fn backup_config(
+    host_url: String,
+    password: Option<String>,
+    from_version: String,
+) -> Result<PathBuf, String> {
+    let categories =
+        crate::config_api::fetch_categories(host_url.clone(), password.clone()).await?;
+    let mut preset = crate::config_api::fetch_all_config(host_url, categories, password).await?;
+    preset.name = Some(format!("Before firmware update (was {})", from_version));
+    let dir = crate::config_presets::presets_dir()
+        .ok_or_else(|| "Could not determine the presets folder".to_string())?;
+    let path = dir.join(format!(
+        "pre-update-{}-{}.json",
+        from_version.replace(|c: char| !c.is_ascii_alphanumeric() && c != '.', "_"),
+        chrono::Local::now().format("%Y%m%d_%H%M%S")
+    ));
+    crate::config_presets::save_preset_to_file(&preset, &path)?;
+    Ok(path)
+}
+
+/// Upload the image to [`REMOTE_DIR`]. Returns its device path.
+pub async fn upload(
+    host: String,
+    image: PathBuf,
+    password: Option<String>,
+) -> Result<String, String> {
+    let progress = std::sync::Arc::new(std::sync::Mutex::new(None));
+    let name = crate::ftp_ops::upload_file_ftp_to_dir(
+        host,
+        image,
+        REMOTE_DIR.to_string(),
+        password,
+        progress,
+    )
+    .await?;
+    Ok(format!("{}/{}", REMOTE_DIR, name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn image(name: &str) -> FirmwareImage {
+        let mut data = vec![0u8; MIN_IMAGE_SIZE];
+        data[1000..1010].copy_from_slice(b"\0V3.12a U6");
+        FirmwareImage::inspect(Path::new(name), &data).unwrap()
+    }
+
+    #[test]
+    fn inspect_reads_model_and_version_and_rejects_non_images() {
+        let fw = image("update.UE2");
+        assert_eq!(fw.model, Model::Ultimate64Elite2);
+        assert_eq!(fw.version.as_deref(), Some("V3.12a"));
+
+        assert!(FirmwareImage::inspect(Path::new("x.prg"), &[0; MIN_IMAGE_SIZE]).is_err());
+        assert!(FirmwareImage::inspect(Path::new("x.u64"), &[0; 100]).is_err());
+        assert!(FirmwareImage::inspect(Path::new("x.u64"), b"PK\x03\x04rest").is_err());
+        let html = b"<!DOCTYPE html><html>".repeat(5000);
+        assert!(FirmwareImage::inspect(Path::new("x.u64"), &html).is_err());
+    }
+
+    #[test]
+    fn models_match_products_and_sessions_finish_on_new_version() {
+        assert!(Model::Ultimate64.matches_product("Ultimate 64"));
+        assert!(Model::Ultimate64.matches_product("Ultimate 64 Elite"));
+        assert!(!Model::Ultimate64.matches_product("Ultimate 64 Elite-II"));
+        assert!(Model::Ultimate64Elite2.matches_product("Ultimate 64-II"));
+        assert!(Model::UltimateIIPlus.matches_product("Ultimate-II+"));
+        assert!(!Model::UltimateIIPlus.matches_product("Ultimate-II+L"));
+        assert!(Model::UltimateIIPlusL.matches_product("Ultimate-II+L"));
+
+        let blocked = UpdateSession::new(image("update.u2p"), "Ultimate 64".into(), "3.11".into());
+        assert!(matches!(blocked.stage, Stage::Failed(_)));
+
+        let mut session =
+            UpdateSession::new(image("update.u64"), "Ultimate 64".into(), "3.11".into());
+        assert_eq!(session.stage, Stage::Ready);
+        session.stage = Stage::Rebooting {
+            since: Instant::now(),
+        };
+        assert!(!session.observe("3.11"));
+        assert!(session.observe("3.12"));
+        assert_eq!(
+            session.stage,
+            Stage::Done {
+                version: "3.12".into()
+            }
+        );
+    }
+
+    #[test]
+    fn reboot_times_out_without_any_poll() {
+        let mut session =
+            UpdateSession::new(image("update.u64"), "Ultimate 64".into(), "3.11".into());
+        let since = Instant::now();
+        session.stage = Stage::Rebooting { since };
+        session.tick(since + REBOOT_TIMEOUT / 2);
+        assert!(session.is_rebooting());
+        session.tick(since + REBOOT_TIMEOUT + Duration::from_secs(1));
+        assert!(matches!(session.stage, Stage::Failed(_)));
+        // A late answer doesn't revive a failed session
+        assert!(!session.observe("3.12"));
+    }
+}
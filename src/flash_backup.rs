@@ -0,0 +1,313 @@
+//! Whole-flash backups read page by page with `CMD_READFLASH`.
+//!
+//! A backup is two files next to each other. `<name>.bin` holds every page at
+//! `page * page_size`. `<name>.json` is the manifest: the flash geometry, the
+//! device it came from, and an MD5 for each page that has been read (`null`
+//! for pages still to read). An interrupted backup is resumed by reading only
+//! the pages without a checksum. Backups are compared page by page through
+//! their manifests, or against the live device by re-reading each page.
+
+use serde::{Deserialize, Serialize};
+use std::fs::OpenOptions;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+/// Manifest format written by this version
+const MANIFEST_VERSION: u32 = 1;
+
+/// Pages between manifest saves while a backup runs; a crash re-reads at most
+/// this many pages on resume
+pub const SAVE_EVERY: u32 = 64;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FlashManifest {
+    pub version: u32,
+    /// Host the pages were read from
+    pub device: String,
+    pub created: String,
+    pub page_size: u32,
+    pub page_count: u32,
+    /// File name of the page image, next to the manifest
+    pub image: String,
+    /// MD5 of each page; `None` until the page has been read
+    pub pages: Vec<Option<String>>,
+}
+
+/// A backup on disk: the manifest and where its files are.
+#[derive(Debug, Clone)]
+pub struct FlashBackup {
+    pub manifest_path: PathBuf,
+    pub manifest: FlashManifest,
+}
+
+/// Pages that differ between two readings of the flash.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FlashDiff {
+    pub changed: Vec<u32>,
+    /// Pages missing from one side (an unfinished backup)
+    pub unknown: Vec<u32>,
+}
+
+pub fn page_checksum(data: &[u8]) -> String {
+    format!("{:x}", md5::compute(data))
+}
+
+impl FlashBackup {
+    /// Start a new backup: `image` is the `.bin` path picked by the user; the
+    /// manifest goes next to it. The image is pre-sized to the full flash.
+    pub fn create(
+        image: &Path,
+        page_size: u32,
+        page_count: u32,
+        device: String,
+    ) -> Result<Self, String> {
+        let image = image.with_extension("bin");
+        let file =
+            std::fs::File::create(&image).map_err(|e| format!("{}: {}", image.display(), e))?;
+        file.set_len(page_size as u64 * page_count as u64)
+            .map_err(|e| format!("{}: {}", image.display(), e))?;
+        let backup = Self {
+            manifest_path: image.with_extension("json"),
+            manifest: FlashManifest {
+                version: MANIFEST_VERSION,
+                device,
+                created: chrono::Local::now().to_rfc3339(),
+                page_size,
+                page_count,
+                image: image
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .to_string(),
+                pages: vec![None; page_count as usize],
+            },
+        };
+        backup.save()?;
+        Ok(backup)
+    }
+
+    /// Open a backup from its manifest (or its `.bin`, whose manifest is
+    /// found next to it).
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let manifest_path = path.with_extension("json");
+        let json = std::fs::read_to_string(&manifest_path)
+            .map_err(|e| format!("{}: {}", manifest_path.display(), e))?;
+        let manifest: FlashManifest = serde_json::from_str(&json).map_err(|e| {
+            format!(
+                "{} is not a flash backup manifest: {}",
+                manifest_path.display(),
+                e
+            )
+        })?;
+        if manifest.version > MANIFEST_VERSION {
+            return Err(format!(
+                "Flash backup format {} is newer than this app supports",
+                manifest.version
+            ));
+        }
+        if manifest.pages.len() != manifest.page_count as usize {
+            return Err("Flash backup manifest is damaged (page list length)".to_string());
+        }
+        // The image must sit next to the manifest; a path here could point
+        // the backup at any file on disk.
+        let mut components = Path::new(&manifest.image).components();
+        if !matches!(
+            (components.next(), components.next()),
+            (Some(std::path::Component::Normal(_)), None)
+        ) || manifest.image.contains(['/', '\\'])
+        {
+            return Err(format!(
+                "Flash backup manifest is damaged (image name {:?})",
+                manifest.image
+            ));
+        }
+        Ok(Self {
+            manifest_path,
+            manifest,
+        })
+    }
+
+    pub fn image_path(&self) -> PathBuf {
+        self.manifest_path.with_file_name(&self.manifest.image)
+    }
+
+    pub fn name(&self) -> String {
+        self.manifest.image.clone()
+    }
+
+    /// Whether this backup was taken from flash with the given geometry.
+    pub fn matches(&self, page_size: u32, page_count: u32) -> bool {
+        self.manifest.page_size == page_size && self.manifest.page_count == page_count
+    }
+
+    /// Pages still to read, in order.
+    pub fn missing_pages(&self) -> Vec<u32> {
+        (0..self.manifest.page_count)
+            .filter(|&p| self.manifest.pages[p as usize].is_none())
+            .collect()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.manifest.pages.iter().all(Option::is_some)
+    }
+
+    /// Write one page into the image and record its checksum. The manifest is
+    /// only saved every [`SAVE_EVERY`] pages; call [`Self::save`] when done.
+    pub fn store_page(&mut self, page: u32, data: &[u8]) -> Result<(), String> {
+        let page_size = self.manifest.page_size as usize;
+        if page >= self.manifest.page_count {
+            return Err(format!("Page {} is outside the flash", page));
+        }
+        if data.len() != page_size {
+            return Err(format!(
+                "Page {} returned {} bytes, expected {}",
+                page,
+                data.len(),
+                page_size
+            ));
+        }
+        let path = self.image_path();
+        let mut file = OpenOptions::new()
+            .write(true)
+            .open(&path)
+            .map_err(|e| format!("{}: {}", path.display(), e))?;
+        file.seek(SeekFrom::Start(page as u64 * page_size as u64))
+            .and_then(|_| file.write_all(data))
+            .map_err(|e| format!("{}: {}", path.display(), e))?;
+        self.manifest.pages[page as usize] = Some(page_checksum(data));
+        if (page + 1).is_multiple_of(SAVE_EVERY) {
+            self.save()?;
+        }
+        Ok(())
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(&self.manifest)
+            .map_err(|e| format!("Failed to serialize manifest: {}", e))?;
+        std::fs::write(&self.manifest_path, json)
+            .map_err(|e| format!("{}: {}", self.manifest_path.display(), e))
+    }
+
+    /// Re-hash the image against the manifest. Returns pages whose stored
+    /// bytes no longer match their checksum.
+    pub fn check_image(&self) -> Result<Vec<u32>, String> {
+        let path = self.image_path();
+        let mut file =
+            std::fs::File::open(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        let mut page = vec![0u8; self.manifest.page_size as usize];
+        let mut bad = Vec::new();
+        for (i, sum) in self.manifest.pages.iter().enumerate() {
+            file.read_exact(&mut page)
+                .map_err(|e| format!("{}: {}", path.display(), e))?;
+            if sum.as_ref().is_some_and(|s| *s != page_checksum(&page)) {
+                bad.push(i as u32);
+            }
+        }
+        Ok(bad)
+    }
+
+    /// Compare a page read from the device with this backup.
+    pub fn page_differs(&self, page: u32, data: &[u8]) -> Option<bool> {
+        self.manifest
+            .pages
+            .get(page as usize)?
+            .as_ref()
+            .map(|sum| *sum != page_checksum(data))
+    }
+}
+
+/// Compare two backups page by page. Fails when their geometry differs.
+pub fn diff(a: &FlashManifest, b: &FlashManifest) -> Result<FlashDiff, String> {
+    if a.page_size != b.page_size || a.page_count != b.page_count {
+        return Err(format!(
+            "Backups cover different flash layouts ({} × {} vs {} × {} bytes)",
+            a.page_count, a.page_size, b.page_count, b.page_size
+        ));
+    }
+    let mut result = FlashDiff::default();
+    for (i, (x, y)) in a.pages.iter().zip(&b.pages).enumerate() {
+        match (x, y) {
+            (Some(x), Some(y)) if x != y => result.changed.push(i as u32),
+            (Some(_), Some(_)) => {}
+            _ => result.unknown.push(i as u32),
+        }
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("u64_flash_{}_{}", std::process::id(), name));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn backup_resumes_from_manifest_and_checks_image() {
+        let dir = temp_dir("resume");
+        let mut backup = FlashBackup::create(&dir.join("u64.bin"), 4, 3, "Test".into()).unwrap();
+        backup.store_page(1, &[1, 2, 3, 4]).unwrap();
+        backup.save().unwrap();
+        assert!(backup.store_page(2, &[0; 3]).is_err());
+
+        // Reopen as after a dropped connection: only pages 0 and 2 remain.
+        let mut reopened = FlashBackup::open(&dir.join("u64.json")).unwrap();
+        assert_eq!(reopened.missing_pages(), vec![0, 2]);
+        reopened.store_page(0, &[9; 4]).unwrap();
+        reopened.store_page(2, &[7; 4]).unwrap();
+        reopened.save().unwrap();
+        assert!(reopened.is_complete());
+        assert_eq!(
+            std::fs::read(reopened.image_path()).unwrap(),
+            [9, 9, 9, 9, 1, 2, 3, 4, 7, 7, 7, 7]
+        );
+        assert_eq!(reopened.check_image().unwrap(), Vec::<u32>::new());
+        assert_eq!(reopened.page_differs(1, &[1, 2, 3, 4]), Some(false));
+        assert_eq!(reopened.page_differs(1, &[0, 2, 3, 4]), Some(true));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn manifest_image_must_be_a_plain_file_name() {
+        let dir = temp_dir("image_name");
+        let backup = FlashBackup::create(&dir.join("u64.bin"), 4, 3, "Test".into()).unwrap();
+        backup.save().unwrap();
+        let manifest_path = dir.join("u64.json");
+        let json = std::fs::read_to_string(&manifest_path).unwrap();
+        for image in ["../u64.bin", "/etc/passwd", "sub\\u64.bin", "..", ""] {
+            let mut manifest: FlashManifest = serde_json::from_str(&json).unwrap();
+            manifest.image = image.to_string();
+            std::fs::write(&manifest_path, serde_json::to_string(&manifest).unwrap()).unwrap();
+            assert!(FlashBackup::open(&manifest_path).is_err(), "{:?}", image);
+        }
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn diff_reports_changed_and_unknown_pages() {
+        let manifest = |pages: Vec<Option<&str>>| FlashManifest {
+            version: 1,
+            device: String::new(),
+            created: String::new(),
+            page_size: 256,
+            page_count: pages.len() as u32,
+            image: String::new(),
+            pages: pages.into_iter().map(|p| p.map(str::to_string)).collect(),
+        };
+        let a = manifest(vec![Some("a"), Some("b"), Some("c"), None]);
+        let b = manifest(vec![Some("a"), Some("x"), Some("c"), Some("d")]);
+        assert_eq!(
+            diff(&a, &b).unwrap(),
+            FlashDiff {
+                changed: vec![1],
+                unknown: vec![3],
+            }
+        );
+        let mut shorter = b.clone();
+        shorter.page_count = 2;
+        assert!(diff(&a, &shorter).is_err());
+    }
+}
@@ -103,6 +103,8 @@ pub struct GameCtx {
 pub struct Launch {
     pub path: String,
     pub local: bool,
+    /// Launch script to run instead of the default run/mount
+    pub script: Option<String>,
 }
 
 /// Result of [`GameMode::update`]. `task` is the module's own follow-up work;
@@ -145,6 +147,9 @@ pub struct GameEntry {
     /// True when this game lives on the local filesystem (vs the device FTP).
     /// Determines how art is read and how the game is launched.
     pub local: bool,
+    /// Sidecar launch script (see [`crate::launch_script`])
+    #[serde(default)]
+    pub script_path: Option<String>,
 }
 
 /// Decoded box art + screenshot for a game.
@@ -396,6 +401,7 @@ impl GameMode {
                     g.run_path.clone().map(|path| Launch {
                         path,
                         local: g.local,
+                        script: g.script_path.clone(),
                     })
                 });
                 GameUpdate {
@@ -1106,7 +1112,7 @@ async fn read_image(
 //  Cache — persist the resolved game list, keyed by roots + a cheap signature.
 // ─────────────────────────────────────────────────────────────────────────────
 
-const CACHE_VERSION: u32 = 1;
+const CACHE_VERSION: u32 = 2;
 /// If a root has at most this many subdirs, the staleness signature also samples
 /// one level deeper (so games added inside a small set of letter buckets are
 /// detected). Above it, only the root listing is signed (keeps huge
@@ -1352,6 +1358,7 @@ fn emit_flat(local: bool, files: &[RemoteFileEntry], art: &ArtIndex, out: &mut V
             shot_path: shot,
             key: f.path.clone(),
             local,
+            script_path: find_script(files, &base, false),
         });
     }
 }
@@ -1405,6 +1412,7 @@ async fn walk(
                         shot_path: shot,
                         key: f.path.clone(),
                         local,
+                        script_path: find_script(&files, &base, false),
                     });
                 }
             }
@@ -1421,6 +1429,7 @@ async fn walk(
                     shot_path: shot,
                     key: dir.clone(),
                     local,
+                    script_path: find_script(&files, base.as_deref().unwrap_or(""), true),
                 });
             }
             FolderKind::Group => {
@@ -1434,6 +1443,20 @@ async fn walk(
     }
 }
 
+/// Launch script for a game: `<stem>.u64launch` beside it. A game folder
+/// (`any_in_folder`) falls back to whichever script the folder has.
+fn find_script(files: &[RemoteFileEntry], base: &str, any_in_folder: bool) -> Option<String> {
+    let scripts: Vec<&RemoteFileEntry> = files
+        .iter()
+        .filter(|f| !f.is_dir && file_ext(&f.name) == crate::launch_script::EXTENSION)
+        .collect();
+    scripts
+        .iter()
+        .find(|f| stem_lower(&f.name) == base)
+        .or_else(|| scripts.first().filter(|_| any_in_folder))
+        .map(|f| f.path.clone())
+}
+
 /// Art for a file-game: a sibling image with the same stem wins, else central
 /// art by basename.
 fn resolve_file_art(
@@ -1844,6 +1867,7 @@ mod tests {
                 key: "/Usb0/Games/Arkanoid.crt".to_string(),
                 letter: 'A',
                 local: false,
+                script_path: None,
             }],
         };
         let json = serde_json::to_string(&cache).unwrap();
@@ -1856,6 +1880,24 @@ mod tests {
         assert!(!back.games[0].local);
     }
 
+    #[test]
+    fn find_script_matches_stem_or_any_in_folder() {
+        let files = vec![
+            entry("Game.prg", false),
+            entry("Other.U64LAUNCH", false),
+            entry("game.u64launch", false),
+        ];
+        assert_eq!(
+            find_script(&files, "game", false).as_deref(),
+            Some("/games/game.u64launch")
+        );
+        assert_eq!(find_script(&files, "nope", false), None);
+        assert_eq!(
+            find_script(&files, "nope", true).as_deref(),
+            Some("/games/Other.U64LAUNCH")
+        );
+    }
+
     #[test]
     fn headers_before_counts_section_changes() {
         let mk = |title: &str| GameEntry {
@@ -1866,6 +1908,7 @@ mod tests {
             key: title.to_string(),
             letter: leading_letter(title),
             local: false,
+            script_path: None,
         };
         let games = vec![mk("Alpha"), mk("Arc"), mk("Beta"), mk("Cyan")];
         assert_eq!(headers_before(&games, 0), 1); // A
@@ -0,0 +1,599 @@
+//! GCR image decoding for G64/G71 disk images
+//!
+//! Rebuilds 256-byte sectors from the raw 1541/1571 GCR track data so the
+//! D64/D71 directory code can read them. A sector that can't be read is
+//! reported with the DOS error code a real drive would give; copy-protected
+//! disks still list whatever is readable.
+
+use crate::disk_image::{self, ImageKind};
+
+/// GCR container type
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GcrFormat {
+    G64,
+    G71,
+}
+
+impl std::fmt::Display for GcrFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GcrFormat::G64 => write!(f, "G64"),
+            GcrFormat::G71 => write!(f, "G71"),
+        }
+    }
+}
+
+/// Why a sector could not be read, named after the 1541 DOS errors
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SectorError {
+    /// 20: no header block for this sector on the track
+    HeaderNotFound,
+    /// 21: no sync mark anywhere on the track
+    NoSync,
+    /// 22: header found but no data block after it
+    DataNotFound,
+    /// 23: data block checksum mismatch
+    DataChecksum,
+    /// 24: invalid GCR code in the data block
+    GcrDecode,
+    /// 27: header block checksum mismatch
+    HeaderChecksum,
+    /// 25: written data did not verify
+    WriteVerify,
+    /// 28: data block too long, overwriting the next header
+    LongData,
+    /// 29: header carries a different disk ID
+    IdMismatch,
+    /// 74: no disk in the drive
+    NotReady,
+}
+
+impl SectorError {
+    /// DOS error number as reported on the error channel
+    pub fn code(&self) -> u8 {
+        match self {
+            SectorError::HeaderNotFound => 20,
+            SectorError::NoSync => 21,
+            SectorError::DataNotFound => 22,
+            SectorError::DataChecksum => 23,
+            SectorError::GcrDecode => 24,
+            SectorError::HeaderChecksum => 27,
+            SectorError::WriteVerify => 25,
+            SectorError::LongData => 28,
+            SectorError::IdMismatch => 29,
+            SectorError::NotReady => 74,
+        }
+    }
+
+    /// Decode a D64/D71/D81 error-info byte. `None` for a readable sector
+    /// (0 and 1 both mean "OK"; 8, write protect, is not a sector fault).
+    pub fn from_error_byte(byte: u8) -> Option<Self> {
+        Some(match byte {
+            0x02 => SectorError::HeaderNotFound,
+            0x03 => SectorError::NoSync,
+            0x04 => SectorError::DataNotFound,
+            0x05 => SectorError::DataChecksum,
+            0x06 => SectorError::GcrDecode,
+            0x07 => SectorError::WriteVerify,
+            0x09 => SectorError::HeaderChecksum,
+            0x0A => SectorError::LongData,
+            0x0B => SectorError::IdMismatch,
+            0x0F => SectorError::NotReady,
+            _ => return None,
+        })
+    }
+}
+
+impl std::fmt::Display for SectorError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let what = match self {
+            SectorError::HeaderNotFound => "header not found",
+            SectorError::NoSync => "no sync",
+            SectorError::DataNotFound => "data block not found",
+            SectorError::DataChecksum => "data checksum",
+            SectorError::GcrDecode => "GCR decoding",
+            SectorError::HeaderChecksum => "header checksum",
+            SectorError::WriteVerify => return write!(f, "25, WRITE ERROR (verify)"),
+            SectorError::LongData => return write!(f, "28, WRITE ERROR (long data block)"),
+            SectorError::IdMismatch => return write!(f, "29, DISK ID MISMATCH"),
+            SectorError::NotReady => return write!(f, "74, DRIVE NOT READY"),
+        };
+        write!(f, "{}, READ ERROR ({})", self.code(), what)
+    }
+}
+
+/// An unreadable sector
+#[derive(Debug, Clone, PartialEq)]
+pub struct BadSector {
+    pub track: u8,
+    pub sector: u8,
+    pub error: SectorError,
+}
+
+/// A track not written at the standard speed zone for its position
+#[derive(Debug, Clone, PartialEq)]
+pub struct DensityMismatch {
+    pub track: u8,
+    pub expected: u8,
+    /// `None` when the track uses a per-byte speed map
+    pub found: Option<u8>,
+}
+
+/// What decoding a GCR image ran into
+#[derive(Debug, Clone, PartialEq)]
+pub struct GcrReport {
+    pub format: GcrFormat,
+    pub bad_sectors: Vec<BadSector>,
+    pub density: Vec<DensityMismatch>,
+}
+
+impl GcrReport {
+    /// One-line summary for listings
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        match self.bad_sectors.len() {
+            0 => parts.push("all sectors readable".to_string()),
+            1 => parts.push("1 unreadable sector".to_string()),
+            n => parts.push(format!("{} unreadable sectors", n)),
+        }
+        match self.density.len() {
+            0 => {}
+            1 => parts.push("1 track with non-standard density".to_string()),
+            n => parts.push(format!("{} tracks with non-standard density", n)),
+        }
+        parts.join(", ")
+    }
+
+    /// One line per problem, for tooltips
+    pub fn details(&self) -> String {
+        let sectors = self
+            .bad_sectors
+            .iter()
+            .map(|b| format!("T{} S{}: {}", b.track, b.sector, b.error));
+        let density = self.density.iter().map(|d| match d.found {
+            Some(zone) => format!(
+                "T{}: speed zone {} (expected {})",
+                d.track, zone, d.expected
+            ),
+            None => format!("T{}: variable speed zones", d.track),
+        });
+        sectors.chain(density).collect::<Vec<_>>().join("\n")
+    }
+}
+
+/// Check the GCR signature at the start of an image
+pub fn is_gcr_image(data: &[u8]) -> bool {
+    data.starts_with(b"GCR-1541") || data.starts_with(b"GCR-1571")
+}
+
+// ─── Container ────────────────────────────────────────────────────────────────
+
+/// Half-track tables of a G64/G71 container
+struct Container<'a> {
+    data: &'a [u8],
+    half_tracks: usize,
+}
+
+impl<'a> Container<'a> {
+    fn table(&self, table: usize, half_track: usize) -> Option<u32> {
+        if half_track >= self.half_tracks {
+            return None;
+        }
+        let at = 12 + (table * self.half_tracks + half_track) * 4;
+        let b = self.data.get(at..at + 4)?;
+        Some(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    /// Raw GCR bytes of a half-track (index 0 = track 1), `None` when the
+    /// track is absent from the image.
+    fn track(&self, half_track: usize) -> Option<&'a [u8]> {
+        let offset = self.table(0, half_track)? as usize;
+        if offset == 0 {
+            return None;
+        }
+        let len = self.data.get(offset..offset + 2)?;
+        let len = u16::from_le_bytes([len[0], len[1]]) as usize;
+        let bytes = self.data.get(offset + 2..offset + 2 + len)?;
+        (!bytes.is_empty()).then_some(bytes)
+    }
+
+    fn speed(&self, half_track: usize) -> Option<u32> {
+        self.table(1, half_track)
+    }
+}
+
+/// Speed zone the 1541 writes a track at (3 = fastest, outer tracks).
+fn standard_zone(track: u8) -> u8 {
+    match track {
+        1..=17 => 3,
+        18..=24 => 2,
+        25..=30 => 1,
+        _ => 0,
+    }
+}
+
+// ─── GCR ──────────────────────────────────────────────────────────────────────
+
+/// 5-bit GCR code → nybble, 0xFF for invalid codes
+const GCR_DECODE: [u8; 32] = {
+    let mut t = [0xFFu8; 32];
+    let codes = [
+        0x0A, 0x0B, 0x12, 0x13, 0x0E, 0x0F, 0x16, 0x17, 0x09, 0x19, 0x1A, 0x1B, 0x0D, 0x1D, 0x1E,
+        0x15,
+    ];
+    let mut n = 0;
+    while n < 16 {
+        t[codes[n] as usize] = n as u8;
+        n += 1;
+    }
+    t
+};
+
+/// A track as a circular bit stream
+struct Bits<'a> {
+    bytes: &'a [u8],
+}
+
+impl Bits<'_> {
+    fn len(&self) -> usize {
+        self.bytes.len() * 8
+    }
+
+    fn bit(&self, i: usize) -> u8 {
+        let i = i % self.len();
+        self.bytes[i / 8] >> (7 - i % 8) & 1
+    }
+
+    fn quintet(&self, at: usize) -> u8 {
+        (0..5).fold(0, |acc, n| acc << 1 | self.bit(at + n))
+    }
+
+    /// Decode `count` bytes of GCR starting at bit `at`. The flag is false
+    /// if any 5-bit group was not a valid GCR code.
+    fn decode(&self, at: usize, count: usize) -> (Vec<u8>, bool) {
+        let mut ok = true;
+        let bytes = (0..count)
+            .map(|n| {
+                let hi = GCR_DECODE[self.quintet(at + n * 10) as usize];
+                let lo = GCR_DECODE[self.quintet(at + n * 10 + 5) as usize];
+                ok &= hi != 0xFF && lo != 0xFF;
+                (hi & 0x0F) << 4 | (lo & 0x0F)
+            })
+            .collect();
+        (bytes, ok)
+    }
+
+    /// Bit positions where a sync (10+ one bits) ends, sorted. The stream is
+    /// scanned twice so a sync that wraps around the index hole is counted
+    /// with its full length.
+    fn sync_ends(&self) -> Vec<usize> {
+        let total = self.len();
+        let mut ones = 0;
+        let mut ends = Vec::new();
+        for i in 0..total * 2 {
+            if self.bit(i) == 1 {
+                ones += 1;
+                continue;
+            }
+            if ones >= 10 && i >= total {
+                ends.push(i - total);
+            }
+            ones = 0;
+        }
+        ends.sort_unstable();
+        ends
+    }
+}
+
+/// Gap between a header and its data block, in bits, beyond which the data
+/// block is treated as missing (the 1541 writes ~9 gap bytes + sync).
+const MAX_HEADER_GAP_BITS: usize = 60 * 8;
+
+/// A sector found on a track: its data (when a data block was found) and
+/// the first error hit reading it
+struct FoundSector {
+    sector: u8,
+    data: Option<[u8; 256]>,
+    error: Option<SectorError>,
+}
+
+/// Decode every sector found on one track; the flag is false when the track
+/// has no sync mark at all.
+fn decode_track(bits: &Bits, track: u8) -> (bool, Vec<FoundSector>) {
+    let syncs = bits.sync_ends();
+    let total = bits.len();
+    let mut sectors: Vec<FoundSector> = Vec::new();
+
+    for (n, &start) in syncs.iter().enumerate() {
+        let (header, header_ok) = bits.decode(start, 8);
+        if !header_ok || header[0] != 0x08 || header[3] != track {
+            continue;
+        }
+        let sector = header[2];
+        let checksum_ok = header[1] == header[2] ^ header[3] ^ header[4] ^ header[5];
+
+        let data_start = syncs
+            .get(n + 1)
+            .copied()
+            .or_else(|| syncs.first().map(|&s| s + total))
+            .filter(|&s| s > start && s - start <= MAX_HEADER_GAP_BITS + 80);
+        let (data, error) = match data_start {
+            None => (None, Some(SectorError::DataNotFound)),
+            Some(at) => {
+                let (block, gcr_ok) = bits.decode(at, 260);
+                if block[0] != 0x07 {
+                    (None, Some(SectorError::DataNotFound))
+                } else {
+                    let mut payload = [0u8; 256];
+                    payload.copy_from_slice(&block[1..257]);
+                    let sum = payload.iter().fold(0, |acc, b| acc ^ b);
+                    let error = if !gcr_ok {
+                        Some(SectorError::GcrDecode)
+                    } else if sum != block[257] {
+                        Some(SectorError::DataChecksum)
+                    } else {
+                        None
+                    };
+                    (Some(payload), error)
+                }
+            }
+        };
+        let error = if checksum_ok {
+            error
+        } else {
+            Some(SectorError::HeaderChecksum)
+        };
+
+        // Protected disks may repeat a header; keep the best read.
+        let found = FoundSector {
+            sector,
+            data,
+            error,
+        };
+        match sectors.iter_mut().find(|s| s.sector == sector) {
+            Some(existing) if existing.error.is_some() && error.is_none() => *existing = found,
+            Some(_) => {}
+            None => sectors.push(found),
+        }
+    }
+    (!syncs.is_empty(), sectors)
+}
+
+// ─── Image ────────────────────────────────────────────────────────────────────
+
+/// Decode a G64/G71 into an equivalent D64/D71 sector image. Unreadable
+/// sectors are zero-filled (or keep their data when only the checksum or
+/// GCR decoding failed) and listed in the report. A G64 becomes a 40-track
+/// D64 when any of tracks 36–40 hold readable sectors.
+pub fn decode(data: &[u8]) -> Result<(Vec<u8>, GcrReport), String> {
+    let format = if data.starts_with(b"GCR-1541") {
+        GcrFormat::G64
+    } else if data.starts_with(b"GCR-1571") {
+        GcrFormat::G71
+    } else {
+        return Err("Not a G64/G71 image".to_string());
+    };
+    let half_tracks = *data.get(9).ok_or("G64 header truncated")? as usize;
+    if data.len() < 12 + half_tracks * 8 {
+        return Err("G64 track tables truncated".to_string());
+    }
+    let container = Container { data, half_tracks };
+
+    // (track number on disk, half-track index in the image)
+    let tracks: Vec<(u8, usize)> = match format {
+        GcrFormat::G64 => (1..=40).map(|t| (t, (t as usize - 1) * 2)).collect(),
+        GcrFormat::G71 => (1..=35)
+            .map(|t| (t, (t as usize - 1) * 2))
+            .chain((36..=70).map(|t| (t, 84 + (t as usize - 36) * 2)))
+            .collect(),
+    };
+
+    let mut decoded = Vec::new();
+    for &(track, half_track) in &tracks {
+        let side_track = if track > 35 && format == GcrFormat::G71 {
+            track - 35
+        } else {
+            track
+        };
+        let spt = disk_image::spt_1541(side_track).unwrap_or(17);
+        let (has_sync, sectors) = match container.track(half_track) {
+            Some(bytes) => decode_track(&Bits { bytes }, track),
+            None => (false, Vec::new()),
+        };
+        let density = container.track(half_track).and_then(|_| {
+            let expected = standard_zone(side_track);
+            match container.speed(half_track)? {
+                zone if zone == expected as u32 => None,
+                zone => Some(DensityMismatch {
+                    track,
+                    expected,
+                    found: (zone < 4).then_some(zone as u8),
+                }),
+            }
+        });
+        decoded.push((track, spt, has_sync, sectors, density));
+    }
+
+    let extended = format == GcrFormat::G64
+        && decoded
+            .iter()
+            .any(|(t, _, _, sectors, _)| *t > 35 && sectors.iter().any(|s| s.error.is_none()));
+    let (kind, size, last_track) = match format {
+        GcrFormat::G64 if extended => (ImageKind::D64, 196_608, 40),
+        GcrFormat::G64 => (ImageKind::D64, 174_848, 35),
+        GcrFormat::G71 => (ImageKind::D71, 349_696, 70),
+    };
+
+    let mut image = vec![0u8; size];
+    let mut report = GcrReport {
+        format,
+        bad_sectors: Vec::new(),
+        density: Vec::new(),
+    };
+    for (track, spt, has_sync, sectors, density) in decoded {
+        if track > last_track {
+            continue;
+        }
+        report.density.extend(density);
+        for sector in 0..spt {
+            let found = sectors.iter().find(|s| s.sector == sector);
+            if let Some(FoundSector {
+                data: Some(payload),
+                ..
+            }) = found
+            {
+                let at = disk_image::ts_offset(track, sector, kind)
+                    .ok_or("Sector outside the image layout")?;
+                image[at..at + 256].copy_from_slice(payload);
+            }
+            let error = match found {
+                Some(found) => found.error,
+                None if has_sync => Some(SectorError::HeaderNotFound),
+                None => Some(SectorError::NoSync),
+            };
+            if let Some(error) = error {
+                report.bad_sectors.push(BadSector {
+                    track,
+                    sector,
+                    error,
+                });
+            }
+        }
+    }
+    Ok((image, report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::disk_image::{build_blank_d64, insert_file, read_disk_info_from_bytes, FileType};
+
+    const GCR_ENCODE: [u8; 16] = [
+        0x0A, 0x0B, 0x12, 0x13, 0x0E, 0x0F, 0x16, 0x17, 0x09, 0x19, 0x1A, 0x1B, 0x0D, 0x1D, 0x1E,
+        0x15,
+    ];
+
+    fn gcr(bytes: &[u8]) -> Vec<u8> {
+        let mut out = Vec::new();
+        let (mut acc, mut n) = (0u64, 0);
+        for &b in bytes {
+            for q in [GCR_ENCODE[(b >> 4) as usize], GCR_ENCODE[(b & 15) as usize]] {
+                acc = acc << 5 | q as u64;
+                n += 5;
+                while n >= 8 {
+                    n -= 8;
+                    out.push((acc >> n) as u8);
+                }
+            }
+        }
+        out
+    }
+
+    /// Write a D64 out as a G64 the way the 1541 formats a disk.
+    fn to_g64(d64: &[u8], corrupt: impl Fn(u8, u8, &mut Vec<u8>, &mut Vec<u8>)) -> Vec<u8> {
+        let mut out = b"GCR-1541".to_vec();
+        out.extend_from_slice(&[0, 84, 0x00, 0x1E]);
+        let tables = out.len();
+        out.resize(tables + 84 * 8, 0);
+        for track in 1..=35u8 {
+            let mut raw = Vec::new();
+            for sector in 0..disk_image::spt_1541(track).unwrap() {
+                let at = disk_image::ts_offset(track, sector, ImageKind::D64).unwrap();
+                let data = &d64[at..at + 256];
+                let mut header = vec![0x08, sector ^ track ^ 0x41 ^ 0x42, sector, track];
+                header.extend_from_slice(&[0x41, 0x42, 0x0F, 0x0F]);
+                let mut block = vec![0x07];
+                block.extend_from_slice(data);
+                block.push(data.iter().fold(0, |a, b| a ^ b));
+                block.extend_from_slice(&[0, 0]);
+                corrupt(track, sector, &mut header, &mut block);
+                raw.extend_from_slice(&[0xFF; 5]);
+                raw.extend(gcr(&header));
+                raw.extend_from_slice(&[0x55; 9]);
+                raw.extend_from_slice(&[0xFF; 5]);
+                raw.extend(gcr(&block));
+                raw.extend_from_slice(&[0x55; 8]);
+            }
+            let half = (track as usize - 1) * 2;
+            let offset = out.len() as u32;
+            out[tables + half * 4..tables + half * 4 + 4].copy_from_slice(&offset.to_le_bytes());
+            let speed = standard_zone(track) as u32;
+            let s = tables + (84 + half) * 4;
+            out[s..s + 4].copy_from_slice(&speed.to_le_bytes());
+            out.extend_from_slice(&(raw.len() as u16).to_le_bytes());
+            out.extend(raw);
+        }
+        out
+    }
+
+    fn sample_d64() -> Vec<u8> {
+        let mut d64 = build_blank_d64("GCR TEST", "AB 2A");
+        insert_file(&mut d64, "HELLO", FileType::Prg, &[1, 8, 0xEA, 0x60]).unwrap();
+        d64
+    }
+
+    #[test]
+    fn decodes_a_clean_g64_back_to_the_d64() {
+        let d64 = sample_d64();
+        let g64 = to_g64(&d64, |_, _, _, _| {});
+        let (image, report) = decode(&g64).unwrap();
+        assert_eq!(report.bad_sectors, vec![]);
+        assert_eq!(report.summary(), "all sectors readable");
+        assert_eq!(image, d64);
+
+        let info = read_disk_info_from_bytes(&g64).unwrap();
+        assert_eq!(info.name.trim(), "GCR TEST");
+        assert_eq!(info.entries[0].name, "HELLO");
+        assert_eq!(info.gcr.unwrap().format, GcrFormat::G64);
+    }
+
+    #[test]
+    fn reports_errors_and_lists_what_is_readable() {
+        let d64 = sample_d64();
+        let g64 = to_g64(&d64, |track, sector, header, block| match (track, sector) {
+            (1, 0) => header[1] ^= 0xFF,
+            (1, 1) => block[100] ^= 0xFF,
+            (1, 2) => block[0] = 0x08,
+            (2, 5) => header[3] = 9,
+            _ => {}
+        });
+        let (_, report) = decode(&g64).unwrap();
+        let errors: Vec<_> = report
+            .bad_sectors
+            .iter()
+            .map(|b| (b.track, b.sector, b.error.code()))
+            .collect();
+        assert_eq!(errors, [(1, 0, 27), (1, 1, 23), (1, 2, 22), (2, 5, 20)]);
+
+        // The directory track is intact, so the listing still works.
+        let info = read_disk_info_from_bytes(&g64).unwrap();
+        assert_eq!(info.entries.len(), 1);
+        assert_eq!(info.gcr.unwrap().bad_sectors.len(), 4);
+    }
+
+    #[test]
+    fn flags_missing_tracks_and_density() {
+        let mut g64 = to_g64(&sample_d64(), |_, _, _, _| {});
+        // Drop track 35 and move track 1 to a slower zone.
+        let t35 = 12 + 68 * 4;
+        g64[t35..t35 + 4].fill(0);
+        g64[12 + 84 * 4] = 1;
+        let (_, report) = decode(&g64).unwrap();
+        assert_eq!(
+            report
+                .bad_sectors
+                .iter()
+                .filter(|b| b.error == SectorError::NoSync)
+                .count(),
+            17
+        );
+        assert_eq!(
+            report.density,
+            [DensityMismatch {
+                track: 1,
+                expected: 3,
+                found: Some(1)
+            }]
+        );
+    }
+}
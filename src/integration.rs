@@ -19,6 +19,13 @@
 //!
 //! Off-network (host unset) every test no-ops (SKIP, passes).
 //!
+//! No hardware? Point them at the local simulator (binds ports 80/21/64):
+//!
+//! ```text
+//! ultimate64-manager simulate --sd /tmp/u64-sd &
+//! U64_TEST_HOST=127.0.0.1 U64_TEST_DESTRUCTIVE=1 cargo test -- --ignored live_
+//! ```
+//!
 //! ## Robustness
 //! * The Ultimate64's HTTP server chokes on concurrent connections, so every
 //!   test takes a process-wide [`device_lock`] — they run one-at-a-time even
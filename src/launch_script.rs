@@ -0,0 +1,888 @@
+//! Launch scripts — how to start a title, one command per line.
+//!
+//! Disk templates used to be lists of `RESET` / `TYPE ...` strings that were
+//! matched by prefix and silently skipped when misspelled. A launch script is
+//! parsed and validated up front (every bad line is reported, like the BASIC
+//! validator), then executed a statement at a time so the UI can show which
+//! line is running.
+//!
+//! ```text
+//! # Comments start with '#'. Keywords are case-insensitive.
+//! MOUNT B RW            # attached file on drive B, read/write
+//! RESET                 # reset and wait for READY.
+//! LOAD 9                # LOAD"*",9,1 and wait for it to finish
+//! WAITFOR "READY." 60
+//! IF PEEK($02A6) = 0 THEN POKE $D020, 0
+//! TYPE run\n
+//! ```
+//!
+//! | Command | Effect |
+//! |---|---|
+//! | `RESET` | Reset, wait for `READY.` |
+//! | `MOUNT [path] [A\|B] [RO\|RW\|UL]` | Mount an image (default: attached file, A, RO) |
+//! | `UNMOUNT [A\|B]` | Remove the image from a drive |
+//! | `DMALOAD [path]` / `DMARUN [path]` | Load a PRG by DMA, optionally running it |
+//! | `LOAD [device]` | `LOAD"*",<device>,1`, wait for `READY.` |
+//! | `RUN` | Type `RUN` |
+//! | `TYPE text` | Type the rest of the line; `\n` is RETURN |
+//! | `WAIT ms` | Port-64 `CMD_WAIT`, then pause the script |
+//! | `WAITFOR "text" [secs]` | Poll the screen until `text` shows up |
+//! | `POKE addr, v[, v...]` | Write bytes |
+//! | `IF PEEK(addr) op v THEN cmd` | `op` is `= <> < > <= >=` |
+//! | `IF SCREEN "text" THEN cmd` | Run `cmd` if `text` is on screen |
+//!
+//! A script is attached to a file or Game Mode entry by saving it next to it
+//! with the same stem and a `.u64launch` extension; profiles carry theirs
+//! inline. Relative paths resolve against the script's own folder.
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use ultimate64::drives::MountMode;
+
+use crate::remote_device::RemoteDevice;
+
+/// Extension of a sidecar script
+pub const EXTENSION: &str = "u64launch";
+
+/// `WAITFOR` timeout when none is given
+const DEFAULT_WAITFOR_SECS: u64 = 30;
+
+/// Interval between screen polls
+const POLL: Duration = Duration::from_millis(250);
+
+// ─── Syntax ──────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Compare {
+    Eq,
+    Ne,
+    Lt,
+    Gt,
+    Le,
+    Ge,
+}
+
+impl Compare {
+    fn holds(self, a: u8, b: u8) -> bool {
+        match self {
+            Compare::Eq => a == b,
+            Compare::Ne => a != b,
+            Compare::Lt => a < b,
+            Compare::Gt => a > b,
+            Compare::Le => a <= b,
+            Compare::Ge => a >= b,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Condition {
+    Peek {
+        address: u16,
+        compare: Compare,
+        value: u8,
+    },
+    Screen(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Reset,
+    Mount {
+        path: Option<String>,
+        drive: &'static str,
+        mode: MountMode,
+    },
+    Unmount {
+        drive: &'static str,
+    },
+    DmaLoad {
+        path: Option<String>,
+        run: bool,
+    },
+    Load {
+        device: u8,
+    },
+    Run,
+    Type(String),
+    Wait(u16),
+    WaitFor {
+        text: String,
+        timeout_secs: u64,
+    },
+    Poke {
+        address: u16,
+        values: Vec<u8>,
+    },
+    If {
+        condition: Condition,
+        then: Box<Command>,
+    },
+}
+
+/// One parsed line
+#[derive(Debug, Clone, PartialEq)]
+pub struct Statement {
+    /// 1-based line in the source
+    pub line: usize,
+    pub source: String,
+    pub command: Command,
+}
+
+/// A line that failed to parse or run
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScriptError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl std::fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Line {}: {}", self.line, self.message)
+    }
+}
+
+/// Join errors for a status line or error dialog.
+pub fn describe(errors: &[ScriptError]) -> String {
+    errors
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// ─── Parser ──────────────────────────────────────────────────────────────────
+
+/// Parse a whole script, collecting an error for every bad line.
+pub fn parse(source: &str) -> Result<Vec<Statement>, Vec<ScriptError>> {
+    let mut statements = Vec::new();
+    let mut errors = Vec::new();
+    for (i, raw) in source.lines().enumerate() {
+        // Only the left edge is trimmed here: trailing spaces can be TYPE text.
+        let text = strip_comment(raw).trim_start();
+        if text.trim_end().is_empty() {
+            continue;
+        }
+        match parse_command(text, true) {
+            Ok(command) => statements.push(Statement {
+                line: i + 1,
+                source: text.trim_end().to_string(),
+                command,
+            }),
+            Err(message) => errors.push(ScriptError {
+                line: i + 1,
+                message,
+            }),
+        }
+    }
+    if errors.is_empty() {
+        Ok(statements)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Drop a `#` comment, unless it's inside quotes or part of `TYPE` text.
+fn strip_comment(line: &str) -> &str {
+    let (keyword, _) = split_word(line.trim_start());
+    if keyword.eq_ignore_ascii_case("TYPE") {
+        return line;
+    }
+    let mut quoted = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            '#' if !quoted => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+fn split_word(s: &str) -> (&str, &str) {
+    match s.find(char::is_whitespace) {
+        Some(i) => (&s[..i], s[i..].trim_start()),
+        None => (s, ""),
+    }
+}
+
+fn parse_command(text: &str, allow_if: bool) -> Result<Command, String> {
+    let (keyword, raw_rest) = split_word(text);
+    let rest = raw_rest.trim_end();
+    let command = match keyword.to_ascii_uppercase().as_str() {
+        "RESET" => no_args("RESET", rest, Command::Reset)?,
+        "RUN" => no_args("RUN", rest, Command::Run)?,
+        "MOUNT" => parse_mount(rest)?,
+        "UNMOUNT" => Command::Unmount {
+            drive: match rest {
+                "" => "a",
+                d => drive(d).ok_or_else(|| format!("Expected drive A or B, got '{}'", d))?,
+            },
+        },
+        "DMALOAD" | "DMARUN" => Command::DmaLoad {
+            path: optional_path(rest)?,
+            run: keyword.eq_ignore_ascii_case("DMARUN"),
+        },
+        "LOAD" => Command::Load {
+            device: match rest {
+                "" => 8,
+                d => d
+                    .parse()
+                    .ok()
+                    .filter(|d| (8..=11).contains(d))
+                    .ok_or_else(|| format!("Device must be 8-11, got '{}'", d))?,
+            },
+        },
+        "TYPE" => {
+            // The raw line, not `rest`: leading spaces are part of the text.
+            let body = text[keyword.len()..].strip_prefix(' ').unwrap_or("");
+            if body.is_empty() {
+                return Err("TYPE needs some text".to_string());
+            }
+            Command::Type(body.replace("\\n", "\n"))
+        }
+        "WAIT" => Command::Wait(
+            rest.parse()
+                .ok()
+                .filter(|&ms| ms > 0)
+                .ok_or_else(|| format!("WAIT takes 1-65535 milliseconds, got '{}'", rest))?,
+        ),
+        "WAITFOR" => {
+            let (text, rest) = quoted(rest)?;
+            let timeout_secs = match rest {
+                "" => DEFAULT_WAITFOR_SECS,
+                s => s
+                    .parse()
+                    .ok()
+                    .filter(|&s| s > 0)
+                    .ok_or_else(|| format!("Invalid timeout '{}'", s))?,
+            };
+            Command::WaitFor { text, timeout_secs }
+        }
+        "POKE" => parse_poke(rest)?,
+        "IF" if !allow_if => return Err("IF can't be nested".to_string()),
+        "IF" => parse_if(raw_rest)?,
+        _ => return Err(format!("Unknown command '{}'", keyword)),
+    };
+    Ok(command)
+}
+
+fn no_args(keyword: &str, rest: &str, command: Command) -> Result<Command, String> {
+    if rest.is_empty() {
+        Ok(command)
+    } else {
+        Err(format!("{} takes no arguments", keyword))
+    }
+}
+
+fn drive(word: &str) -> Option<&'static str> {
+    match word.to_ascii_uppercase().as_str() {
+        "A" => Some("a"),
+        "B" => Some("b"),
+        _ => None,
+    }
+}
+
+fn mount_mode(word: &str) -> Option<MountMode> {
+    match word.to_ascii_uppercase().as_str() {
+        "RO" => Some(MountMode::ReadOnly),
+        "RW" => Some(MountMode::ReadWrite),
+        "UL" => Some(MountMode::Unlinked),
+        _ => None,
+    }
+}
+
+fn parse_mount(mut rest: &str) -> Result<Command, String> {
+    let mut path = None;
+    let mut drive_letter = None;
+    let mut mode = None;
+    while !rest.is_empty() {
+        if rest.starts_with('"') {
+            let (p, r) = quoted(rest)?;
+            path = Some(p);
+            rest = r;
+            continue;
+        }
+        let (word, r) = split_word(rest);
+        if let Some(d) = drive(word).filter(|_| drive_letter.is_none()) {
+            drive_letter = Some(d);
+        } else if let Some(m) = mount_mode(word).filter(|_| mode.is_none()) {
+            mode = Some(m);
+        } else if path.is_none() {
+            path = Some(word.to_string());
+        } else {
+            return Err(format!("Unexpected '{}' after MOUNT path", word));
+        }
+        rest = r;
+    }
+    Ok(Command::Mount {
+        path,
+        drive: drive_letter.unwrap_or("a"),
+        mode: mode.unwrap_or(MountMode::ReadOnly),
+    })
+}
+
+fn optional_path(rest: &str) -> Result<Option<String>, String> {
+    if rest.is_empty() {
+        Ok(None)
+    } else if rest.starts_with('"') {
+        match quoted(rest)? {
+            (p, "") => Ok(Some(p)),
+            (_, extra) => Err(format!("Unexpected '{}' after path", extra)),
+        }
+    } else {
+        Ok(Some(rest.to_string()))
+    }
+}
+
+/// A `"..."` string at the start of `s`, and what follows it.
+fn quoted(s: &str) -> Result<(String, &str), String> {
+    let body = s
+        .strip_prefix('"')
+        .ok_or_else(|| "Expected a quoted string".to_string())?;
+    let end = body
+        .find('"')
+        .ok_or_else(|| "Missing closing quote".to_string())?;
+    Ok((body[..end].to_string(), body[end + 1..].trim_start()))
+}
+
+fn number(s: &str, max: u32) -> Result<u32, String> {
+    crate::memory_editor::parse_length_input(s)
+        .filter(|&v| v <= max)
+        .ok_or_else(|| format!("Invalid number '{}'", s.trim()))
+}
+
+fn parse_poke(rest: &str) -> Result<Command, String> {
+    let mut parts = rest.split(',');
+    let address = number(parts.next().unwrap_or(""), 0xFFFF)? as u16;
+    let values = parts
+        .map(|v| number(v, 0xFF).map(|v| v as u8))
+        .collect::<Result<Vec<_>, _>>()?;
+    if values.is_empty() {
+        return Err("POKE needs an address and at least one value".to_string());
+    }
+    Ok(Command::Poke { address, values })
+}
+
+fn parse_if(rest: &str) -> Result<Command, String> {
+    // ASCII upper-casing keeps byte offsets, so positions map back to `rest`.
+    let upper = rest.to_ascii_uppercase();
+    let then_at = find_then(&upper).ok_or_else(|| "IF needs a THEN".to_string())?;
+    let condition = parse_condition(rest[..then_at].trim(), &upper[..then_at])?;
+    let then = parse_command(rest[then_at + 6..].trim_start(), false)?;
+    Ok(Command::If {
+        condition,
+        then: Box::new(then),
+    })
+}
+
+/// The first ` THEN ` outside quotes, so `SCREEN "A THEN B"` stays whole.
+fn find_then(upper: &str) -> Option<usize> {
+    let mut quoted = false;
+    for (i, c) in upper.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            ' ' if !quoted && upper[i..].starts_with(" THEN ") => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn parse_condition(text: &str, upper: &str) -> Result<Condition, String> {
+    if upper.starts_with("SCREEN") {
+        let (s, extra) = quoted(text[6..].trim_start())?;
+        if !extra.is_empty() {
+            return Err(format!("Unexpected '{}' after SCREEN text", extra));
+        }
+        return Ok(Condition::Screen(s));
+    }
+    let inner = text
+        .get(..5)
+        .filter(|p| p.eq_ignore_ascii_case("PEEK("))
+        .map(|_| &text[5..])
+        .ok_or_else(|| "Condition must be PEEK(addr) or SCREEN \"text\"".to_string())?;
+    let close = inner
+        .find(')')
+        .ok_or_else(|| "Missing ')' after PEEK address".to_string())?;
+    let address = number(&inner[..close], 0xFFFF)? as u16;
+    let rest = inner[close + 1..].trim_start();
+    let (compare, value) = [
+        ("<=", Compare::Le),
+        (">=", Compare::Ge),
+        ("<>", Compare::Ne),
+        ("=", Compare::Eq),
+        ("<", Compare::Lt),
+        (">", Compare::Gt),
+    ]
+    .iter()
+    .find_map(|(op, c)| rest.strip_prefix(op).map(|v| (*c, v)))
+    .ok_or_else(|| "Expected = <> < > <= or >= after PEEK(...)".to_string())?;
+    Ok(Condition::Peek {
+        address,
+        compare,
+        value: number(value, 0xFF)? as u8,
+    })
+}
+
+// ─── Execution ───────────────────────────────────────────────────────────────
+
+/// A file on the local disk or on the device's filesystem
+#[derive(Debug, Clone, PartialEq)]
+pub enum Location {
+    Local(PathBuf),
+    Device(String),
+}
+
+impl Location {
+    fn join(&self, path: &str) -> Location {
+        match self {
+            Location::Local(script) => {
+                let dir = script.parent().map(PathBuf::from).unwrap_or_default();
+                Location::Local(dir.join(path))
+            }
+            Location::Device(_) if path.starts_with('/') => Location::Device(path.to_string()),
+            Location::Device(script) => {
+                let dir = script.rsplit_once('/').map(|(d, _)| d).unwrap_or("");
+                Location::Device(format!("{}/{}", dir, path))
+            }
+        }
+    }
+}
+
+/// Script text plus what it's attached to — what a pane hands the app to run.
+#[derive(Debug, Clone)]
+pub struct LaunchRequest {
+    pub name: String,
+    pub source: String,
+    /// Where the script was read from; relative paths resolve against it
+    pub script: Option<Location>,
+    /// The file `MOUNT` / `DMALOAD` use when given no path
+    pub file: Option<Location>,
+}
+
+/// Sidecar script for `path`, if one exists: same stem, [`EXTENSION`].
+pub fn sidecar(path: &std::path::Path) -> Option<PathBuf> {
+    let script = path.with_extension(EXTENSION);
+    (script != path && script.is_file()).then_some(script)
+}
+
+/// Read a local script, attached to `file` if given.
+pub fn read_local(
+    script: &std::path::Path,
+    file: Option<&std::path::Path>,
+) -> Result<LaunchRequest, String> {
+    let source =
+        std::fs::read_to_string(script).map_err(|e| format!("{}: {}", script.display(), e))?;
+    Ok(LaunchRequest {
+        name: script
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string(),
+        source,
+        script: Some(Location::Local(script.to_path_buf())),
+        file: file.map(|f| Location::Local(f.to_path_buf())),
+    })
+}
+
+/// Everything a statement needs to reach the device
+pub struct Env {
+    pub connection: Arc<Mutex<dyn RemoteDevice>>,
+    /// Bare host (no scheme)
+    pub host: String,
+    pub password: Option<String>,
+    pub script: Option<Location>,
+    pub file: Option<Location>,
+    /// Set to stop a `WAITFOR` that's still polling
+    pub cancel: Arc<AtomicBool>,
+}
+
+impl Env {
+    fn resolve(&self, path: Option<&str>) -> Result<Location, String> {
+        match (path, &self.script) {
+            (None, _) => self
+                .file
+                .clone()
+                .ok_or_else(|| "No file attached; give a path".to_string()),
+            (Some(p), Some(script)) => Ok(script.join(p)),
+            (Some(p), None) if p.starts_with('/') => Ok(Location::Device(p.to_string())),
+            (Some(p), None) => Err(format!(
+                "'{}' is relative but the script has no folder; use a device path",
+                p
+            )),
+        }
+    }
+
+    async fn blocking<T: Send + 'static>(
+        &self,
+        f: impl FnOnce(&dyn RemoteDevice) -> Result<T, String> + Send + 'static,
+    ) -> Result<T, String> {
+        let conn = self.connection.clone();
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap();
+            f(&*conn)
+        })
+        .await
+        .map_err(|e| format!("Task error: {}", e))?
+    }
+
+    fn port64(&self) -> crate::port64::Port64Client {
+        crate::port64::Port64Client::new(self.host.clone(), self.password.clone())
+    }
+}
+
+/// Screen text contains `needle` (case-insensitive).
+fn screen_contains(conn: &dyn RemoteDevice, needle: &str) -> Result<bool, String> {
+    let base = crate::test_harness::screen_base(conn)?;
+    let codes = conn
+        .read_mem(base, 1000)
+        .map_err(|e| format!("Screen read failed: {}", e))?;
+    let needle = needle.to_ascii_uppercase();
+    Ok(crate::test_harness::screen_lines(&codes)
+        .iter()
+        .any(|l| l.contains(&needle)))
+}
+
+/// Poll the screen for `text` until it shows up, `timeout_secs` pass or
+/// `cancel` is set.
+fn wait_for_screen(
+    conn: &dyn RemoteDevice,
+    text: &str,
+    timeout_secs: u64,
+    cancel: &AtomicBool,
+) -> Result<(), String> {
+    let deadline = Instant::now() + Duration::from_secs(timeout_secs);
+    loop {
+        if cancel.load(Ordering::Relaxed) {
+            return Err("Stopped".to_string());
+        }
+        // A busy bus during a load reads as "not yet".
+        if screen_contains(conn, text).unwrap_or(false) {
+            return Ok(());
+        }
+        if Instant::now() >= deadline {
+            return Err(format!(
+                "Timed out after {}s waiting for \"{}\"",
+                timeout_secs, text
+            ));
+        }
+        std::thread::sleep(POLL);
+    }
+}
+
+fn condition_holds(conn: &dyn RemoteDevice, condition: &Condition) -> Result<bool, String> {
+    match condition {
+        Condition::Peek {
+            address,
+            compare,
+            value,
+        } => {
+            let byte = conn
+                .read_mem(*address, 1)
+                .map_err(|e| format!("Read ${:04X} failed: {}", address, e))?;
+            Ok(compare.holds(byte.first().copied().unwrap_or(0), *value))
+        }
+        Condition::Screen(text) => screen_contains(conn, text),
+    }
+}
+
+fn mode_name(mode: &MountMode) -> &'static str {
+    match mode {
+        MountMode::ReadWrite => "readwrite",
+        MountMode::ReadOnly => "readonly",
+        MountMode::Unlinked => "unlinked",
+    }
+}
+
+/// Run one command against the device.
+pub async fn execute(env: &Env, command: &Command) -> Result<(), String> {
+    if let Command::If { condition, then } = command {
+        let condition = condition.clone();
+        if env
+            .blocking(move |c| condition_holds(c, &condition))
+            .await?
+        {
+            return execute_simple(env, then).await;
+        }
+        return Ok(());
+    }
+    execute_simple(env, command).await
+}
+
+async fn execute_simple(env: &Env, command: &Command) -> Result<(), String> {
+    match command.clone() {
+        Command::Reset => env.blocking(crate::run_ops::reset_to_ready).await,
+        Command::Mount { path, drive, mode } => match env.resolve(path.as_deref())? {
+            Location::Local(p) => {
+                env.blocking(move |c| {
+                    c.mount_disk_image(&p, drive.to_string(), mode, false)
+                        .map_err(|e| format!("Mount failed: {}", e))
+                })
+                .await
+            }
+            Location::Device(p) => {
+                crate::api::mount_disk(&env.host, &p, drive, mode_name(&mode), env.password.clone())
+                    .await
+                    .map(|_| ())
+            }
+        },
+        Command::Unmount { drive } => {
+            let url = format!("http://{}", env.host);
+            crate::api::unmount_disk_async(&url, drive, env.password.as_deref()).await
+        }
+        Command::DmaLoad { path, run } => match env.resolve(path.as_deref())? {
+            Location::Local(p) => {
+                let data = std::fs::read(&p).map_err(|e| format!("{}: {}", p.display(), e))?;
+                let client = env.port64();
+                let result = if run {
+                    client.dma_run(&data).await
+                } else {
+                    client.dma_load(&data).await
+                };
+                result.map_err(|e| format!("DMA failed: {}", e))
+            }
+            Location::Device(p) if run => crate::api::run_prg(&env.host, &p, env.password.clone())
+                .await
+                .map(|_| ()),
+            Location::Device(p) => {
+                let url = format!("http://{}", env.host);
+                crate::api::load_prg_async(&url, &p, env.password.as_deref())
+                    .await
+                    .map(|_| ())
+            }
+        },
+        Command::Load { device } => {
+            env.blocking(move |c| crate::run_ops::load_and_wait(c, &device.to_string()))
+                .await
+        }
+        Command::Run => {
+            env.blocking(|c| {
+                c.type_text("run\n")
+                    .map_err(|e| format!("Type RUN failed: {}", e))
+            })
+            .await
+        }
+        Command::Type(text) => {
+            env.blocking(move |c| {
+                c.type_text(&text)
+                    .map_err(|e| format!("Type failed: {}", e))
+            })
+            .await
+        }
+        Command::Wait(ms) => {
+            // CMD_WAIT holds back any port-64 command queued behind it; the
+            // local sleep is what actually paces the script.
+            if let Err(e) = env.port64().wait(ms).await {
+                log::debug!("CMD_WAIT not sent: {}", e);
+            }
+            tokio::time::sleep(Duration::from_millis(ms as u64)).await;
+            Ok(())
+        }
+        Command::WaitFor { text, timeout_secs } => {
+            let cancel = env.cancel.clone();
+            env.blocking(move |c| wait_for_screen(c, &text, timeout_secs, &cancel))
+                .await
+        }
+        Command::Poke { address, values } => {
+            env.blocking(move |c| {
+                c.write_mem(address, &values)
+                    .map_err(|e| format!("Write ${:04X} failed: {}", address, e))
+            })
+            .await
+        }
+        Command::If { .. } => Err("IF can't be nested".to_string()),
+    }
+}
+
+/// Run a whole script, reporting each statement before it starts.
+pub async fn run(
+    env: &Env,
+    statements: &[Statement],
+    mut progress: impl FnMut(&Statement),
+) -> Result<(), ScriptError> {
+    for statement in statements {
+        progress(statement);
+        execute(env, &statement.command)
+            .await
+            .map_err(|message| ScriptError {
+                line: statement.line,
+                message,
+            })?;
+    }
+    Ok(())
+}
+
+// ─── Stepped runs ────────────────────────────────────────────────────────────
+
+/// A script the UI steps through, one statement per task.
+pub struct Stepper {
+    pub name: String,
+    /// Tags step results so a stopped run's last one can be told apart
+    pub id: u64,
+    env: Arc<Env>,
+    statements: Vec<Statement>,
+    next: usize,
+}
+
+impl Stepper {
+    pub fn new(name: String, env: Env, source: &str) -> Result<Self, Vec<ScriptError>> {
+        static NEXT_ID: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+        let statements = parse(source)?;
+        Ok(Self {
+            name,
+            id: NEXT_ID.fetch_add(1, std::sync::atomic::Ordering::Relaxed),
+            env: Arc::new(env),
+            statements,
+            next: 0,
+        })
+    }
+
+    /// The statement the next [`Stepper::step`] runs
+    pub fn current(&self) -> Option<&Statement> {
+        self.statements.get(self.next)
+    }
+
+    /// Stop the run: a `WAITFOR` in flight gives up on its next poll and
+    /// releases the device.
+    pub fn stop(&self) {
+        self.env.cancel.store(true, Ordering::Relaxed);
+    }
+
+    /// Start the current statement and advance past it. `None` once done.
+    pub fn step(&mut self) -> Option<impl std::future::Future<Output = Result<(), ScriptError>>> {
+        let statement = self.current()?.clone();
+        self.next += 1;
+        let env = self.env.clone();
+        Some(async move {
+            execute(&env, &statement.command)
+                .await
+                .map_err(|message| ScriptError {
+                    line: statement.line,
+                    message,
+                })
+        })
+    }
+}
+
+// ─── Tests ───────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::remote_device::mock::MockDevice;
+
+    #[test]
+    fn parses_commands_and_reports_every_bad_line() {
+        let script = "# boot\n\
+                      MOUNT \"disk 2.d64\" b rw\n\
+                      reset\n\
+                      LOAD 9\n\
+                      TYPE  sys 49152\\n\n\
+                      WAITFOR \"ready.\" 5 # comment\n\
+                      IF PEEK($D020) <> 0 THEN POKE $D020, 0, $0E\n\
+                      IF SCREEN \"PRESS FIRE\" THEN TYPE \\n\n\
+                      IF SCREEN \"A THEN B\" then RUN\n";
+        let statements = parse(script).unwrap();
+        let commands: Vec<_> = statements.iter().map(|s| s.command.clone()).collect();
+        assert_eq!(
+            commands[0],
+            Command::Mount {
+                path: Some("disk 2.d64".into()),
+                drive: "b",
+                mode: MountMode::ReadWrite,
+            }
+        );
+        assert_eq!(commands[1], Command::Reset);
+        assert_eq!(commands[2], Command::Load { device: 9 });
+        assert_eq!(commands[3], Command::Type(" sys 49152\n".into()));
+        assert_eq!(
+            commands[4],
+            Command::WaitFor {
+                text: "ready.".into(),
+                timeout_secs: 5
+            }
+        );
+        assert_eq!(
+            commands[5],
+            Command::If {
+                condition: Condition::Peek {
+                    address: 0xD020,
+                    compare: Compare::Ne,
+                    value: 0
+                },
+                then: Box::new(Command::Poke {
+                    address: 0xD020,
+                    values: vec![0, 0x0E]
+                }),
+            }
+        );
+        assert_eq!(statements[5].line, 7);
+        assert_eq!(
+            commands[6],
+            Command::If {
+                condition: Condition::Screen("PRESS FIRE".into()),
+                then: Box::new(Command::Type("\n".into())),
+            }
+        );
+        assert_eq!(
+            commands[7],
+            Command::If {
+                condition: Condition::Screen("A THEN B".into()),
+                then: Box::new(Command::Run),
+            }
+        );
+
+        let errors = parse("RESET now\nBOOT\nPOKE 1024\nLOAD 3\nIF PEEK(1) = 1 THEN IF PEEK(1) = 1 THEN RUN\nWAITFOR \"x")
+            .unwrap_err();
+        let lines: Vec<usize> = errors.iter().map(|e| e.line).collect();
+        assert_eq!(lines, vec![1, 2, 3, 4, 5, 6]);
+        assert_eq!(errors[1].to_string(), "Line 2: Unknown command 'BOOT'");
+    }
+
+    #[test]
+    fn conditions_read_memory_and_screen() {
+        let dev = MockDevice::new();
+        // MockDevice reads back 0xAA everywhere; as screen codes that's
+        // reverse-video '*', so the screen is all asterisks.
+        let peek = |compare, value| Condition::Peek {
+            address: 0xC000,
+            compare,
+            value,
+        };
+        assert!(condition_holds(&dev, &peek(Compare::Eq, 0xAA)).unwrap());
+        assert!(!condition_holds(&dev, &peek(Compare::Lt, 0xAA)).unwrap());
+        assert!(condition_holds(&dev, &peek(Compare::Ge, 0x10)).unwrap());
+        assert!(condition_holds(&dev, &Condition::Screen("***".into())).unwrap());
+        assert!(!condition_holds(&dev, &Condition::Screen("READY.".into())).unwrap());
+
+        // A stopped run's WAITFOR gives up at once instead of timing out
+        let cancel = AtomicBool::new(false);
+        assert_eq!(wait_for_screen(&dev, "***", 30, &cancel), Ok(()));
+        cancel.store(true, Ordering::Relaxed);
+        let start = Instant::now();
+        assert_eq!(
+            wait_for_screen(&dev, "READY.", 30, &cancel),
+            Err("Stopped".to_string())
+        );
+        assert!(start.elapsed() < Duration::from_secs(1));
+
+        let script = Location::Local(PathBuf::from("/games/a/boot.u64launch"));
+        assert_eq!(
+            script.join("side2.d64"),
+            Location::Local(PathBuf::from("/games/a/side2.d64"))
+        );
+        let script = Location::Device("/Usb0/games/boot.u64launch".into());
+        assert_eq!(
+            script.join("b.d64"),
+            Location::Device("/Usb0/games/b.d64".into())
+        );
+        assert_eq!(
+            script.join("/Temp/x.prg"),
+            Location::Device("/Temp/x.prg".into())
+        );
+    }
+}
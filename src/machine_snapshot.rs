@@ -0,0 +1,678 @@
+//! Machine-state snapshots ("freeze files")
+//!
+//! A snapshot holds the full 64 KB of RAM, the CPU registers, the VIC-II,
+//! SID and CIA register files and the colour RAM in one versioned file.
+//!
+//! Capture parks the CPU in a small 6502 stub through [`crate::nmi_stub`],
+//! the same way the REU reader does. The NMI stacks PC and P; the stub records A/X/Y/S and the processor port,
+//! then parks the CPU in a loop. While it is parked the host reads memory and,
+//! on request, the stub copies RAM hidden under the ROMs and I/O into a
+//! buffer the REST API can read.
+//!
+//! Restore enters the same stub, has it copy the RAM under I/O into place,
+//! writes everything else with DMA, loads the saved registers into the stub
+//! and lets it `RTI` into the saved PC.
+//!
+//! Limits: the C64 must be running with the KERNAL banked in (the NMI goes
+//! through $0318). CIA interrupt masks and TOD clocks can't be read back; on
+//! restore CIA1 Timer A IRQs are re-enabled when that timer is running, as
+//! the KERNAL sets them up, and CIA2 NMIs stay off. Timer latches are
+//! restored from the counter values at capture time.
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::nmi_stub::{
+    self, read, write, Backup, ACK_DONE, ACK_EXITED, ACK_READY, MARKER, REQ_EXIT, REQ_WORK,
+    STUB_ADDR, STUB_AREA_LEN,
+};
+use crate::remote_device::RemoteDevice;
+
+/// File extension for snapshots
+pub const EXTENSION: &str = "u64snap";
+
+const MAGIC: &[u8; 8] = b"U64SNAP\0";
+const VERSION: u16 = 1;
+
+const VIC_LEN: usize = 0x2F;
+const SID_LEN: usize = 0x19;
+const CIA_LEN: usize = 0x10;
+const COLOUR_LEN: usize = 0x400;
+const RAM_LEN: usize = 0x10000;
+const HEADER_LEN: usize = 8 + 2 + 8 + 9;
+const FILE_LEN: usize = HEADER_LEN + RAM_LEN + VIC_LEN + SID_LEN + 2 * CIA_LEN + COLOUR_LEN;
+
+// ─── Stub layout ──────────────────────────────────────────────────────────────
+
+/// Saved A, X, Y, S, $00, $01 — then the copy source page, target page and
+/// page count
+const REGS_ADDR: u16 = 0x03F0;
+/// Copy buffer for RAM under the ROMs and I/O
+const BUFFER: u16 = 0x2000;
+const BUFFER_PAGES: u8 = 0x20;
+
+/// Build the session stub. It:
+/// stores A/X/Y/S and $00/$01 in the register block, maps I/O in with the
+/// ROMs out, acknowledges CIA2, reports ready and loops on the marker.
+/// `REQ_WORK` copies whole pages with all RAM banked in; `REQ_EXIT` loads
+/// S, $00/$01 and A/X/Y from the register block and returns with RTI.
+fn build_stub() -> Vec<u8> {
+    let mut c: Vec<u8> = Vec::with_capacity(0xB0);
+    let [marker, _] = MARKER.to_le_bytes();
+    let reg = |i: u16| (REGS_ADDR + i).to_le_bytes();
+    let [a_lo, a_hi] = reg(0);
+    let [x_lo, x_hi] = reg(1);
+    let [y_lo, y_hi] = reg(2);
+    let [s_lo, s_hi] = reg(3);
+    let [d_lo, d_hi] = reg(4);
+    let [p_lo, p_hi] = reg(5);
+
+    // STA/STX/STY regs, TSX / STX S, save $00/$01
+    c.extend_from_slice(&[0x8D, a_lo, a_hi, 0x8E, x_lo, x_hi, 0x8C, y_lo, y_hi]);
+    c.extend_from_slice(&[0xBA, 0x8E, s_lo, s_hi]);
+    c.extend_from_slice(&[0xA5, 0x00, 0x8D, d_lo, d_hi, 0xA5, 0x01, 0x8D, p_lo, p_hi]);
+    // $00 = $2F, $01 = $35 — I/O in, ROMs out
+    c.extend_from_slice(&[0xA9, 0x2F, 0x85, 0x00, 0xA9, 0x35, 0x85, 0x01]);
+    // LDA $DD0D — acknowledge the CIA2 NMI
+    c.extend_from_slice(&[0xAD, 0x0D, 0xDD]);
+    c.extend_from_slice(&[0xA9, ACK_READY, 0x85, marker]);
+
+    // loop: LDA marker / CMP #REQ_WORK / BEQ copy / CMP #REQ_EXIT / BNE loop
+    let loop_at = c.len();
+    c.extend_from_slice(&[0xA5, marker, 0xC9, REQ_WORK, 0xF0, 0x00]);
+    let beq_copy = c.len() - 1;
+    c.extend_from_slice(&[0xC9, REQ_EXIT, 0xD0, 0x00]);
+    let bne_loop = c.len() - 1;
+    c[bne_loop] = (loop_at as isize - c.len() as isize) as u8;
+
+    // exit: LDX S / TXS, restore $00/$01, report, LDY/LDX/LDA, RTI
+    c.extend_from_slice(&[0xAE, s_lo, s_hi, 0x9A]);
+    c.extend_from_slice(&[0xAD, d_lo, d_hi, 0x85, 0x00, 0xAD, p_lo, p_hi, 0x85, 0x01]);
+    c.extend_from_slice(&[0xA9, ACK_EXITED, 0x85, marker]);
+    c.extend_from_slice(&[0xAC, y_lo, y_hi, 0xAE, x_lo, x_hi, 0xAD, a_lo, a_hi, 0x40]);
+
+    // copy: patch the page operands below from the parameter block
+    c[beq_copy] = (c.len() - beq_copy - 1) as u8;
+    let copy_at = c.len();
+    let src_op = STUB_ADDR + copy_at as u16 + 23;
+    let dst_op = src_op + 3;
+    let [src_lo, src_hi] = src_op.to_le_bytes();
+    let [dst_lo, dst_hi] = dst_op.to_le_bytes();
+    let [s6_lo, s6_hi] = reg(6);
+    let [s7_lo, s7_hi] = reg(7);
+    let [s8_lo, s8_hi] = reg(8);
+    c.extend_from_slice(&[0xAD, s6_lo, s6_hi, 0x8D, src_lo, src_hi]);
+    c.extend_from_slice(&[0xAD, s7_lo, s7_hi, 0x8D, dst_lo, dst_hi]);
+    c.extend_from_slice(&[0xAE, s8_lo, s8_hi]);
+    // $01 = $34 — everything RAM
+    c.extend_from_slice(&[0xA9, 0x34, 0x85, 0x01, 0xA0, 0x00]);
+    // page: LDA $xx00,Y / STA $yy00,Y / INY / BNE page
+    let page_at = c.len();
+    debug_assert_eq!(STUB_ADDR + page_at as u16 + 2, src_op);
+    c.extend_from_slice(&[0xB9, 0x00, 0x00, 0x99, 0x00, 0x00, 0xC8, 0xD0, 0xF7]);
+    // INC src / INC dst / DEX / BNE page
+    c.extend_from_slice(&[0xEE, src_lo, src_hi, 0xEE, dst_lo, dst_hi, 0xCA, 0xD0, 0x00]);
+    let bne_page = c.len() - 1;
+    c[bne_page] = (page_at as isize - c.len() as isize) as u8;
+    c.extend_from_slice(&[0xA9, 0x35, 0x85, 0x01]);
+    c.extend_from_slice(&[0xA9, ACK_DONE, 0x85, marker]);
+    let [llo, lhi] = (STUB_ADDR + loop_at as u16).to_le_bytes();
+    c.extend_from_slice(&[0x4C, llo, lhi]);
+
+    c
+}
+
+// ─── Snapshot ─────────────────────────────────────────────────────────────────
+
+/// CPU state at the moment of capture
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CpuState {
+    pub a: u8,
+    pub x: u8,
+    pub y: u8,
+    /// Stack pointer with the NMI frame (P, PC) still on the stack
+    pub sp: u8,
+    pub p: u8,
+    pub pc: u16,
+    /// Processor port data direction ($00) and data ($01)
+    pub ddr: u8,
+    pub port: u8,
+}
+
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    /// Seconds since the Unix epoch
+    pub created: u64,
+    pub cpu: CpuState,
+    pub ram: Vec<u8>,
+    pub vic: Vec<u8>,
+    pub sid: Vec<u8>,
+    pub cia1: Vec<u8>,
+    pub cia2: Vec<u8>,
+    pub colour: Vec<u8>,
+}
+
+impl Snapshot {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let c = &self.cpu;
+        let mut out = Vec::with_capacity(FILE_LEN);
+        out.extend_from_slice(MAGIC);
+        out.extend_from_slice(&VERSION.to_le_bytes());
+        out.extend_from_slice(&self.created.to_le_bytes());
+        let [pc_lo, pc_hi] = c.pc.to_le_bytes();
+        out.extend_from_slice(&[c.a, c.x, c.y, c.sp, c.p, pc_lo, pc_hi, c.ddr, c.port]);
+        for part in [
+            &self.ram,
+            &self.vic,
+            &self.sid,
+            &self.cia1,
+            &self.cia2,
+            &self.colour,
+        ] {
+            out.extend_from_slice(part);
+        }
+        out
+    }
+
+    pub fn from_bytes(data: &[u8]) -> Result<Self, String> {
+        if data.len() < HEADER_LEN || &data[..8] != MAGIC {
+            return Err("Not a machine snapshot".to_string());
+        }
+        let version = u16::from_le_bytes([data[8], data[9]]);
+        if version != VERSION {
+            return Err(format!("Unsupported snapshot version {}", version));
+        }
+        if data.len() != FILE_LEN {
+            return Err(format!(
+                "Snapshot is {} bytes, expected {}",
+                data.len(),
+                FILE_LEN
+            ));
+        }
+        let created = u64::from_le_bytes(data[10..18].try_into().unwrap());
+        let r = &data[18..27];
+        let cpu = CpuState {
+            a: r[0],
+            x: r[1],
+            y: r[2],
+            sp: r[3],
+            p: r[4],
+            pc: u16::from_le_bytes([r[5], r[6]]),
+            ddr: r[7],
+            port: r[8],
+        };
+        let mut rest = &data[HEADER_LEN..];
+        let mut take = |n: usize| {
+            let (part, tail) = rest.split_at(n);
+            rest = tail;
+            part.to_vec()
+        };
+        Ok(Self {
+            created,
+            cpu,
+            ram: take(RAM_LEN),
+            vic: take(VIC_LEN),
+            sid: take(SID_LEN),
+            cia1: take(CIA_LEN),
+            cia2: take(CIA_LEN),
+            colour: take(COLOUR_LEN),
+        })
+    }
+
+    pub fn load(path: &std::path::Path) -> Result<Self, String> {
+        let data = std::fs::read(path).map_err(|e| format!("Failed to read file: {}", e))?;
+        Self::from_bytes(&data)
+    }
+
+    /// The screen at capture time, rendered like an API screenshot (PNG)
+    pub fn thumbnail_png(&self) -> Result<Vec<u8>, String> {
+        use crate::screenshot_api::{FRAME_HEIGHT, FRAME_WIDTH};
+        let rgb = crate::screenshot_api::render_from_memory(
+            &self.ram,
+            &self.vic,
+            self.cia2[0],
+            &self.colour,
+        );
+        let mut png_bytes = Vec::new();
+        let mut encoder = png::Encoder::new(
+            std::io::Cursor::new(&mut png_bytes),
+            FRAME_WIDTH as u32,
+            FRAME_HEIGHT as u32,
+        );
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder
+            .write_header()
+            .map_err(|e| format!("PNG encode failed: {}", e))?;
+        writer
+            .write_image_data(&rgb)
+            .map_err(|e| format!("PNG encode failed: {}", e))?;
+        drop(writer);
+        Ok(png_bytes)
+    }
+
+    /// One line per fact, for the file browser popup
+    pub fn describe(&self) -> Vec<String> {
+        let c = &self.cpu;
+        let created = chrono::DateTime::from_timestamp(self.created as i64, 0)
+            .map(|t| {
+                t.with_timezone(&chrono::Local)
+                    .format("%Y-%m-%d %H:%M:%S")
+                    .to_string()
+            })
+            .unwrap_or_else(|| "unknown".to_string());
+        vec![
+            format!("Captured {}", created),
+            format!(
+                "PC=${:04X} A=${:02X} X=${:02X} Y=${:02X} SP=${:02X} P=${:02X}",
+                c.pc,
+                c.a,
+                c.x,
+                c.y,
+                c.sp.wrapping_add(3),
+                c.p
+            ),
+            format!(
+                "$00=${:02X} $01=${:02X}  VIC bank ${:04X}",
+                c.ddr,
+                c.port,
+                (3 - (self.cia2[0] & 0x03) as u16) * 0x4000
+            ),
+        ]
+    }
+}
+
+// ─── Device access ────────────────────────────────────────────────────────────
+
+/// Inject the snapshot stub and enter it.
+fn enter(conn: &dyn RemoteDevice, backup: &Backup) -> Result<(), String> {
+    nmi_stub::enter(conn, backup, &build_stub(), "Snapshot")
+}
+
+/// Have the parked stub copy `pages` pages with all RAM banked in.
+fn copy_pages(conn: &dyn RemoteDevice, src: u16, dst: u16, pages: u8) -> Result<(), String> {
+    write(
+        conn,
+        REGS_ADDR + 6,
+        &[(src >> 8) as u8, (dst >> 8) as u8, pages],
+    )?;
+    if nmi_stub::request(conn, Duration::from_secs(2))? {
+        Ok(())
+    } else {
+        Err(format!("Copy from ${:04X} timed out", src))
+    }
+}
+
+/// CIA registers without the interrupt control register, which clears
+/// pending interrupts when read
+fn read_cia(conn: &dyn RemoteDevice, base: u16) -> Result<Vec<u8>, String> {
+    let mut regs = read(conn, base, 0x0D)?;
+    regs.push(0x00);
+    regs.extend(read(conn, base + 0x0E, 2)?);
+    Ok(regs)
+}
+
+/// Capture the running machine. Blocking; the C64 is parked in the stub for
+/// the duration of the reads.
+pub fn capture(conn: &dyn RemoteDevice) -> Result<Snapshot, String> {
+    let cia1 = read_cia(conn, 0xDC00)?;
+    let cia2 = read_cia(conn, 0xDD00)?;
+    let backup = Backup::read(conn)?;
+    enter(conn, &backup)?;
+
+    type Parked = (Vec<u8>, Vec<u8>, Vec<u8>, Vec<u8>, Vec<u8>);
+    let result = (|| -> Result<Parked, String> {
+        let regs = read(conn, REGS_ADDR, 6)?;
+        let mut ram = vec![0u8; RAM_LEN];
+        ram[..0xA000].copy_from_slice(&read(conn, 0x0000, 0xA000)?);
+        ram[0xC000..0xD000].copy_from_slice(&read(conn, 0xC000, 0x1000)?);
+        for (start, pages) in [(0xA000u16, 0x20u8), (0xD000, 0x10), (0xE000, 0x20)] {
+            copy_pages(conn, start, BUFFER, pages)?;
+            let len = pages as usize * 0x100;
+            ram[start as usize..start as usize + len].copy_from_slice(&read(conn, BUFFER, len)?);
+        }
+        write(
+            conn,
+            BUFFER,
+            &ram[BUFFER as usize..][..BUFFER_PAGES as usize * 0x100],
+        )?;
+        let vic = read(conn, 0xD000, VIC_LEN)?;
+        let sid = read(conn, 0xD400, SID_LEN)?;
+        let colour = read(conn, 0xD800, COLOUR_LEN)?;
+        Ok((regs, ram, vic, sid, colour))
+    })();
+    let left = nmi_stub::leave(conn, "Snapshot");
+    backup.restore(conn);
+    let _ = write(conn, 0xDD0E, &[cia2[0x0E] & 0xEF]);
+
+    let (regs, mut ram, vic, sid, colour) = result?;
+    left?;
+
+    // Put back what the stub displaced so the image is the program's own RAM
+    let stub = STUB_ADDR as usize;
+    ram[stub..stub + STUB_AREA_LEN as usize].copy_from_slice(&backup.stub_area);
+    ram[MARKER as usize] = backup.marker;
+    ram[0x0318..0x031A].copy_from_slice(&backup.nmi_vector);
+
+    let sp = regs[3];
+    let stack = |i: u8| ram[0x0100 + sp.wrapping_add(i) as usize];
+    let cpu = CpuState {
+        a: regs[0],
+        x: regs[1],
+        y: regs[2],
+        sp,
+        p: stack(1),
+        pc: u16::from_le_bytes([stack(2), stack(3)]),
+        ddr: regs[4],
+        port: regs[5],
+    };
+    Ok(Snapshot {
+        created: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+        cpu,
+        ram,
+        vic,
+        sid,
+        cia1,
+        cia2,
+        colour,
+    })
+}
+
+/// Write CIA registers: ports, DDRs, timer latches and SDR, then the control
+/// registers with force-load so the counters pick up the latches.
+fn write_cia(conn: &dyn RemoteDevice, base: u16, regs: &[u8]) -> Result<(), String> {
+    write(conn, base + 0x0D, &[0x7F])?;
+    write(conn, base, &regs[..0x08])?;
+    write(conn, base + 0x0C, &regs[0x0C..0x0D])?;
+    write(conn, base + 0x0E, &[regs[0x0E] | 0x10, regs[0x0F] | 0x10])
+}
+
+/// Restore a snapshot onto the running machine. Blocking.
+pub fn restore(conn: &dyn RemoteDevice, snapshot: &Snapshot) -> Result<(), String> {
+    let backup = Backup::read(conn)?;
+    enter(conn, &backup)?;
+    let ram = &snapshot.ram;
+
+    // Past this point the machine state is being replaced, so a failure
+    // leaves the stub parked rather than half-restoring the old program.
+    let d000 = &ram[0xD000..0xE000];
+    write(conn, BUFFER, d000)?;
+    copy_pages(conn, BUFFER, 0xD000, 0x10)?;
+
+    // RAM, minus the handshake byte and the stub until it has returned
+    let stub_end = (STUB_ADDR + STUB_AREA_LEN) as usize;
+    write(conn, 0x0000, &ram[..MARKER as usize])?;
+    write(
+        conn,
+        MARKER + 1,
+        &ram[MARKER as usize + 1..STUB_ADDR as usize],
+    )?;
+    write(conn, stub_end as u16, &ram[stub_end..0xD000])?;
+    write(conn, 0xE000, &ram[0xE000..])?;
+
+    write(conn, 0xD000, &snapshot.vic)?;
+    write(conn, 0xD400, &snapshot.sid)?;
+    write(conn, 0xD800, &snapshot.colour)?;
+    write_cia(conn, 0xDC00, &snapshot.cia1)?;
+    write_cia(conn, 0xDD00, &snapshot.cia2)?;
+    if snapshot.cia1[0x0E] & 0x01 != 0 {
+        write(conn, 0xDC0D, &[0x81])?;
+    }
+
+    let c = &snapshot.cpu;
+    write(conn, REGS_ADDR, &[c.a, c.x, c.y, c.sp, c.ddr, c.port])?;
+    write(conn, MARKER, &[REQ_EXIT])?;
+    if !nmi_stub::wait_for(conn, ACK_EXITED, Duration::from_secs(1))? {
+        return Err("Snapshot stub did not return".to_string());
+    }
+    write(conn, MARKER, &ram[MARKER as usize..][..1])?;
+    write(conn, STUB_ADDR, &ram[STUB_ADDR as usize..stub_end])
+}
+
+// ─── Parked sessions ──────────────────────────────────────────────────────────
+
+/// The CPU held in the stub between host requests, for the binary monitor.
+/// Bytes the stub displaces are served from (and patched into) the backup,
+/// and $00/$01 map to the saved processor port, so a debugger sees the
+/// program's own memory.
+pub(crate) struct Parked {
+    backup: Backup,
+    cia2_cra: u8,
+}
+
+/// Copy the overlap of `region` (based at `base`) into `data` (based at
+/// `address`), or the other way round when `into_region` is set. Returns the
+/// overlapping indices of `data`.
+fn overlay(
+    base: u16,
+    region: &mut [u8],
+    address: u16,
+    data: &mut [u8],
+    into_region: bool,
+) -> std::ops::Range<usize> {
+    let (base, address) = (base as usize, address as usize);
+    let start = base.max(address);
+    let end = (base + region.len()).min(address + data.len()).max(start);
+    for i in start..end {
+        if into_region {
+            region[i - base] = data[i - address];
+        } else {
+            data[i - address] = region[i - base];
+        }
+    }
+    start - address..end - address
+}
+
+impl Parked {
+    /// Stop the CPU in the stub.
+    pub(crate) fn park(conn: &dyn RemoteDevice) -> Result<Self, String> {
+        let cia2_cra = read(conn, 0xDD0E, 1)?[0];
+        let backup = Backup::read(conn)?;
+        enter(conn, &backup)?;
+        Ok(Self { backup, cia2_cra })
+    }
+
+    /// Registers as the program left them; `sp` still counts the NMI frame.
+    pub(crate) fn cpu(&self, conn: &dyn RemoteDevice) -> Result<CpuState, String> {
+        let regs = read(conn, REGS_ADDR, 6)?;
+        let stack = read(conn, 0x0100, 0x100)?;
+        let sp = regs[3];
+        let frame = |i: u8| stack[sp.wrapping_add(i) as usize];
+        Ok(CpuState {
+            a: regs[0],
+            x: regs[1],
+            y: regs[2],
+            sp,
+            p: frame(1),
+            pc: u16::from_le_bytes([frame(2), frame(3)]),
+            ddr: regs[4],
+            port: regs[5],
+        })
+    }
+
+    /// Replace the registers the stub will return with.
+    pub(crate) fn set_cpu(&self, conn: &dyn RemoteDevice, cpu: &CpuState) -> Result<(), String> {
+        let [pcl, pch] = cpu.pc.to_le_bytes();
+        for (i, value) in [(1u8, cpu.p), (2, pcl), (3, pch)] {
+            write(conn, 0x0100 + cpu.sp.wrapping_add(i) as u16, &[value])?;
+        }
+        write(
+            conn,
+            REGS_ADDR,
+            &[cpu.a, cpu.x, cpu.y, cpu.sp, cpu.ddr, cpu.port],
+        )
+    }
+
+    fn displaced(&mut self) -> [(u16, &mut [u8]); 4] {
+        [
+            (MARKER, std::slice::from_mut(&mut self.backup.marker)),
+            (0x0318, &mut self.backup.nmi_vector),
+            (STUB_ADDR, &mut self.backup.stub_area),
+            (0xDD04, &mut self.backup.cia2_timer),
+        ]
+    }
+
+    /// Read memory with the displaced bytes put back.
+    pub(crate) fn read(
+        &mut self,
+        conn: &dyn RemoteDevice,
+        address: u16,
+        length: usize,
+    ) -> Result<Vec<u8>, String> {
+        let mut data = read(conn, address, length)?;
+        if address < 2 {
+            let mut port = read(conn, REGS_ADDR + 4, 2)?;
+            overlay(0, &mut port, address, &mut data, false);
+        }
+        for (base, region) in self.displaced() {
+            overlay(base, region, address, &mut data, false);
+        }
+        Ok(data)
+    }
+
+    /// Write memory; displaced bytes land in the backup and are written back
+    /// when the CPU resumes.
+    pub(crate) fn write(
+        &mut self,
+        conn: &dyn RemoteDevice,
+        address: u16,
+        data: &[u8],
+    ) -> Result<(), String> {
+        let mut data = data.to_vec();
+        let mut held = vec![false; data.len()];
+        if address < 2 {
+            let mut port = read(conn, REGS_ADDR + 4, 2)?;
+            held[overlay(0, &mut port, address, &mut data, true)].fill(true);
+            write(conn, REGS_ADDR + 4, &port)?;
+        }
+        for (base, region) in self.displaced() {
+            held[overlay(base, region, address, &mut data, true)].fill(true);
+        }
+        // Write the runs that aren't held back
+        let mut i = 0;
+        while i < data.len() {
+            if held[i] {
+                i += 1;
+                continue;
+            }
+            let run = held[i..].iter().take_while(|k| !**k).count();
+            write(conn, address + i as u16, &data[i..i + run])?;
+            i += run;
+        }
+        Ok(())
+    }
+
+    /// Let the program continue and put the displaced bytes back.
+    pub(crate) fn resume(self, conn: &dyn RemoteDevice) -> Result<(), String> {
+        let left = nmi_stub::leave(conn, "Snapshot");
+        self.backup.restore(conn);
+        let _ = write(conn, 0xDD0E, &[self.cia2_cra & 0xEF]);
+        left
+    }
+}
+
+// ─── Async wrappers ───────────────────────────────────────────────────────────
+
+async fn blocking<T: Send + 'static>(
+    connection: Arc<Mutex<dyn RemoteDevice>>,
+    f: impl FnOnce(&dyn RemoteDevice) -> Result<T, String> + Send + 'static,
+) -> Result<T, String> {
+    nmi_stub::blocking(connection, 60, "Snapshot", f).await
+}
+
+/// Capture and save to `path`.
+pub async fn save_snapshot(
+    connection: Arc<Mutex<dyn RemoteDevice>>,
+    path: std::path::PathBuf,
+) -> Result<String, String> {
+    let snapshot = blocking(connection, capture).await?;
+    std::fs::write(&path, snapshot.to_bytes()).map_err(|e| format!("Failed to save: {}", e))?;
+    Ok(format!(
+        "Snapshot saved to {} (PC=${:04X})",
+        path.display(),
+        snapshot.cpu.pc
+    ))
+}
+
+/// Load `path` and restore it.
+pub async fn restore_snapshot(
+    connection: Arc<Mutex<dyn RemoteDevice>>,
+    path: std::path::PathBuf,
+) -> Result<String, String> {
+    let snapshot = Snapshot::load(&path)?;
+    let pc = snapshot.cpu.pc;
+    blocking(connection, move |conn| restore(conn, &snapshot)).await?;
+    Ok(format!(
+        "Restored {} (PC=${:04X})",
+        path.file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("snapshot"),
+        pc
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mos6510;
+
+    #[test]
+    fn stub_fits_and_branches_land_on_code() {
+        let stub = build_stub();
+        assert!(stub.len() <= (REGS_ADDR - STUB_ADDR) as usize);
+        let listing = mos6510::disassemble(STUB_ADDR, &stub);
+        assert!(listing.iter().all(|i| !i.illegal));
+        let starts: Vec<u16> = listing.iter().map(|i| i.address).collect();
+        for i in &listing {
+            if i.mode == mos6510::AddrMode::Relative {
+                let target = i.target().unwrap();
+                assert!(
+                    starts.contains(&target),
+                    "{} lands mid-instruction",
+                    i.text()
+                );
+            }
+        }
+        let text: Vec<String> = listing.iter().map(|i| i.text()).collect();
+        assert!(text.contains(&"LDA $0000,Y".to_string()));
+        assert!(text.contains(&"STA $0000,Y".to_string()));
+        assert_eq!(text.iter().filter(|t| *t == "RTI").count(), 1);
+        // The self-modified operands are the ones the INCs touch
+        let lda = listing.iter().find(|i| i.text() == "LDA $0000,Y").unwrap();
+        assert!(text.contains(&format!("INC ${:04X}", lda.address + 2)));
+    }
+
+    #[test]
+    fn file_round_trips() {
+        let snapshot = Snapshot {
+            created: 1_700_000_000,
+            cpu: CpuState {
+                a: 1,
+                x: 2,
+                y: 3,
+                sp: 0xF0,
+                p: 0x24,
+                pc: 0x0810,
+                ddr: 0x2F,
+                port: 0x37,
+            },
+            ram: (0..RAM_LEN).map(|i| i as u8).collect(),
+            vic: vec![0x11; VIC_LEN],
+            sid: vec![0x22; SID_LEN],
+            cia1: vec![0x33; CIA_LEN],
+            cia2: vec![0x03; CIA_LEN],
+            colour: vec![0x0E; COLOUR_LEN],
+        };
+        let bytes = snapshot.to_bytes();
+        assert_eq!(bytes.len(), FILE_LEN);
+        let back = Snapshot::from_bytes(&bytes).unwrap();
+        assert_eq!(back.cpu, snapshot.cpu);
+        assert_eq!(back.ram, snapshot.ram);
+        assert_eq!(back.colour, snapshot.colour);
+        assert!(Snapshot::from_bytes(&bytes[..100]).is_err());
+        assert!(back.thumbnail_png().unwrap().starts_with(b"\x89PNG"));
+    }
+}
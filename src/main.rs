@@ -116,10 +116,14 @@ mod assembly64;
 mod assembly64_browser;
 mod basic_editor;
 mod basic_tokenizer;
+mod cbm_archive;
 mod cfg_format;
+mod cheat_finder;
+mod cli;
 mod config_api;
 mod config_editor;
 mod config_presets;
+mod crt_image;
 mod csdb_screenshots;
 mod debug_stream;
 mod device_error;
@@ -129,16 +133,25 @@ mod discovery;
 mod disk_image;
 mod file_browser;
 mod file_types;
+mod firmware_update;
+mod flash_backup;
 mod folder_favorites;
 mod ftp_ops;
 mod game_mode;
+mod gcr_image;
 #[cfg(test)]
 mod integration;
+mod launch_script;
+mod machine_snapshot;
 mod memory_editor;
 mod mod_info;
+mod mos6510;
 mod music_ops;
 mod music_player;
 mod net_utils;
+mod network_watchdog;
+mod nmi_stub;
+mod pc64;
 mod pdf_preview;
 mod petscii;
 mod port64;
@@ -148,21 +161,28 @@ mod profile_repo;
 mod profiles;
 mod remote_browser;
 mod remote_device;
+mod reu;
 mod run_ops;
 mod screenshot_api;
 mod settings;
 mod sid_info;
 mod sid_monitor;
+mod simulator;
 mod stream_control;
+mod stream_recorder;
 mod streaming;
 mod string_utils;
 mod styles;
 mod tab;
+mod tape_image;
 mod templates;
+mod test_harness;
 mod version_check;
 mod vic_shader;
+mod vice_monitor;
 mod video_scaling;
 mod virtual_keyboard;
+mod workspace;
 
 use assembly64_browser::{Assembly64Browser, Assembly64BrowserMessage};
 use basic_editor::{BasicEditor, BasicEditorMessage};
@@ -180,10 +200,16 @@ use sid_monitor::{SidMonitor, SidMonitorMessage};
 use streaming::{StreamingMessage, VideoStreaming};
 use tab::{TabContext, TabController};
 use templates::{DiskTemplate, TemplateManager};
+use workspace::{Workspace, WorkspaceMessage};
 
 const APP_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 pub fn main() -> iced::Result {
+    // `ultimate64-manager <command> …` runs headless and exits (see cli.rs).
+    if let Some(code) = cli::run_from_args() {
+        std::process::exit(code);
+    }
+
     // Force OpenGL backend on Linux for better compatibility with multi-GPU systems
     // Users can override with WGPU_BACKEND=vulkan if needed
     #[cfg(target_os = "linux")]
@@ -305,6 +331,7 @@ pub enum Message {
 
     // Device profile manager
     DeviceProfileManager(ProfileManagerMessage),
+    Workspace(WorkspaceMessage),
 
     // Connection
     HostInputChanged(String),
@@ -320,6 +347,12 @@ pub enum Message {
     TemplateSelected(DiskTemplate),
     ExecuteTemplate,
 
+    // Launch scripts
+    RunLaunchScript(launch_script::LaunchRequest),
+    /// Run id and the outcome of its statement
+    LaunchScriptStepped(u64, Result<(), launch_script::ScriptError>),
+    StopLaunchScript,
+
     // Function bar actions
     FnView,
     FnCopy,
@@ -375,7 +408,28 @@ pub enum Message {
     ResumeMachine,
     PoweroffMachine,
     MenuButton,
+    SaveSnapshot,
+    SnapshotPathSelected(Option<PathBuf>),
     MachineCommandCompleted(Result<String, String>),
+    // ── Firmware update dialog ───────────────────────────────────────
+    /// Back up the configuration, then upload the image
+    FirmwareUpdateProceed,
+    FirmwareBackupDone(Result<PathBuf, String>),
+    FirmwareUploadDone(Result<String, String>),
+    /// The user has started *Run Update* on the device; watch for the reboot
+    FirmwareUpdateStarted,
+    FirmwareUpdateClose,
+    /// Once a second while waiting for the reboot, so the timeout fires even
+    /// when the device never answers a status poll
+    FirmwareUpdateTick,
+    // ── Network rollback watchdog ────────────────────────────────────
+    /// Probe the lost device again
+    NetworkRecoveryRetry,
+    NetworkRecoveryRetried(network_watchdog::Outcome),
+    /// Close the dialog and search the network from SETTINGS
+    NetworkRecoverySearch,
+    NetworkRecoveryConnect(String),
+    NetworkRecoveryClose,
     // ── DEVICE tab: drive control / keyboard / debug register ────────
     /// Switch a drive's emulated type. (drive "a"|"b", mode "1541"|"1571"|"1581")
     DeviceSetDriveMode(String, String),
@@ -426,6 +480,7 @@ pub enum Message {
     // Settings
     DefaultSongDurationChanged(String),
     FontSizeChanged(String),
+    DiscoverySubnetsChanged(String),
     // Game library roots (Game Mode). Text field + add/remove.
     GameLibraryInputChanged(String),
     GameLibraryAddRoot,
@@ -476,6 +531,12 @@ pub enum Message {
     DropCancel,
     /// Async drop action finished — surface the result message.
     DropCompleted(Result<String, String>),
+    /// A dropped cartridge was checked; warnings wait for confirmation.
+    DropCrtChecked(PathBuf, Result<Vec<String>, String>),
+    /// Send the dropped cartridge despite the warnings.
+    DropConfirmCrt,
+    /// Don't send the dropped cartridge.
+    DropCancelCrt,
     /// User clicked the Cancel button while a drop action was in flight.
     /// Aborts the running Task without waiting for the network timeout.
     DropAbort,
@@ -538,6 +599,13 @@ impl DropAction {
                 path: p,
                 runner: "run_prg",
             }],
+            // PC64 program container; the header is stripped before sending
+            e if pc64::container_type(e) == Some(disk_image::FileType::Prg) => {
+                vec![DropAction::RunOnDevice {
+                    path: p,
+                    runner: "run_prg",
+                }]
+            }
             "crt" => vec![DropAction::RunOnDevice {
                 path: p,
                 runner: "run_crt",
@@ -577,6 +645,7 @@ pub enum Tab {
     Assembly64,
     BasicEditor,
     Device,
+    Workspace,
     Settings,
 }
 
@@ -593,6 +662,7 @@ impl std::fmt::Display for Tab {
             Tab::Assembly64 => write!(f, "Assembly64"),
             Tab::BasicEditor => write!(f, "BASIC"),
             Tab::Device => write!(f, "Device"),
+            Tab::Workspace => write!(f, "Workspace"),
             Tab::Settings => write!(f, "Settings"),
         }
     }
@@ -608,6 +678,10 @@ pub enum Pane {
 pub struct StatusInfo {
     pub connected: bool,
     pub device_info: Option<String>,
+    /// `DeviceInfo::product` / `firmware_version` from the last poll; the
+    /// firmware update checks models against the first and watches the second.
+    pub product: Option<String>,
+    pub firmware_version: Option<String>,
     pub mounted_disks: Vec<(String, String)>,
     /// Live per-drive state (name, power, type) from `drive_list`, so the drive
     /// control strip can reflect what's actually set on the device.
@@ -642,6 +716,8 @@ pub struct Ultimate64Browser {
     settings: AppSettings,
     template_manager: TemplateManager,
     selected_template: Option<DiskTemplate>,
+    /// Launch script being stepped through, if any
+    launch_run: Option<launch_script::Stepper>,
     connection: Option<Arc<Mutex<dyn RemoteDevice>>>,
     host_url: Option<String>, // Store host URL for direct HTTP requests
     status: StatusInfo,
@@ -665,6 +741,8 @@ pub struct Ultimate64Browser {
     host_input: String,
     password_input: String,
     font_size_input: String,
+    /// Settings: extra discovery subnets as typed (comma-separated).
+    discovery_subnets_input: String,
     /// Settings: staged text for adding a new Game Mode library root.
     game_library_input: String,
 
@@ -677,6 +755,9 @@ pub struct Ultimate64Browser {
     /// File the OS dropped on our window — when `Some`, the view renders
     /// a centered modal asking the user what to do with it.
     pending_drop: Option<PathBuf>,
+    /// Dropped cartridge whose check found problems, with the warnings —
+    /// sent only once the user confirms, as in the file browser.
+    pending_drop_crt: Option<(PathBuf, Vec<String>)>,
     /// True while a drop action is sending data to the device — disables
     /// the dialog buttons so a slow upload can't be triggered twice.
     drop_in_flight: bool,
@@ -686,6 +767,10 @@ pub struct Ultimate64Browser {
     /// eject only fires after the user explicitly confirms — accidentally
     /// clearing a mount has no undo.
     pending_eject_confirm: bool,
+    /// Firmware update in progress (or finished/blocked) — shown as a dialog.
+    firmware_update: Option<firmware_update::UpdateSession>,
+    /// Device lost after a network settings change — shown as a dialog.
+    network_recovery: Option<network_watchdog::Recovery>,
     /// When true, the Help overlay (cheatsheet of every keybind) is rendered
     /// on top of whatever tab is active. Toggled by `?`.
     show_help: bool,
@@ -697,6 +782,8 @@ pub struct Ultimate64Browser {
     profile_manager: ProfileManager,
     // Device profile manager (Ultimate64 config profiles)
     device_profile_manager: DeviceProfileManager,
+    /// WORKSPACE tab: devices driven as a group, each with its own connection.
+    workspace: Workspace,
     new_profile_name: String,
     rename_profile_name: String,
     // Device discovery
@@ -739,6 +826,7 @@ impl Ultimate64Browser {
         log::info!("Initializing application...");
 
         let profile_manager = ProfileManager::load();
+        let workspace = Workspace::new(&profile_manager.workspace);
         let settings = profile_manager.active_settings().clone();
         log::info!("Active profile: {}", profile_manager.active_profile);
 
@@ -803,6 +891,7 @@ impl Ultimate64Browser {
             host_input: settings.connection.host.clone(),
             password_input: settings.connection.password.clone().unwrap_or_default(),
             font_size_input: settings.preferences.font_size.to_string(),
+            discovery_subnets_input: settings.preferences.discovery_subnets.join(", "),
             game_library_input: String::new(),
             settings: settings.clone(),
             host_url: None,
@@ -812,11 +901,14 @@ impl Ultimate64Browser {
             debug_stream: debug_stream::DebugStreamCapture::new(),
             template_manager: TemplateManager::new(),
             selected_template: None,
+            launch_run: None,
             connection: None,
             new_version: None,
             status: StatusInfo {
                 connected: false,
                 device_info: None,
+                product: None,
+                firmware_version: None,
                 mounted_disks: Vec::new(),
                 drives: Vec::new(),
             },
@@ -826,14 +918,18 @@ impl Ultimate64Browser {
             assembly64_browser: Assembly64Browser::new(),
             basic_editor: BasicEditor::new(),
             pending_drop: None,
+            pending_drop_crt: None,
             drop_in_flight: false,
             drop_handle: None,
             pending_eject_confirm: false,
+            firmware_update: None,
+            network_recovery: None,
             show_help: false,
             main_window_id: Some(main_window_id),
             streaming_window_id: None,
             profile_manager,
             device_profile_manager: DeviceProfileManager::new(),
+            workspace,
             new_profile_name: String::new(),
             rename_profile_name: String::new(),
             is_discovering: false,
@@ -969,6 +1065,12 @@ impl Ultimate64Browser {
                 .update(msg, ctx.clone())
                 .map(Message::Monitor),
             Message::LeftBrowser(msg) => {
+                if let FileBrowserMessage::RunLaunchScript(request) = msg {
+                    return self.handle_run_launch_script(request);
+                }
+                if let FileBrowserMessage::UpdateFirmware(path) = msg {
+                    return self.handle_firmware_update_open(path);
+                }
                 // User interaction with left pane makes it active
                 // (exclude async completion callbacks which aren't user-initiated)
                 if !matches!(
@@ -1026,6 +1128,12 @@ impl Ultimate64Browser {
             }
 
             Message::RemoteBrowser(msg) => {
+                if let RemoteBrowserMessage::RunLaunchScript(request) = msg {
+                    return match request {
+                        Ok(request) => self.handle_run_launch_script(request),
+                        Err(e) => Task::done(Message::ShowError(format!("Launch script: {}", e))),
+                    };
+                }
                 // User interaction with right pane makes it active
                 // (exclude async completion callbacks which aren't user-initiated)
                 if !matches!(
@@ -1547,10 +1655,24 @@ impl Ultimate64Browser {
                     .map(Message::MusicPlayer)
             }
 
-            Message::ConfigEditor(msg) => self
-                .config_editor
-                .update(msg, ctx.clone())
-                .map(Message::ConfigEditor),
+            Message::ConfigEditor(msg) => {
+                // A network settings save comes back with the watchdog's
+                // verdict, which may move or lose the connection.
+                let outcome = match &msg {
+                    ConfigEditorMessage::NetworkSaveComplete(Ok((_, outcome))) => {
+                        Some(outcome.clone())
+                    }
+                    _ => None,
+                };
+                let task = self
+                    .config_editor
+                    .update(msg, ctx.clone())
+                    .map(Message::ConfigEditor);
+                match outcome {
+                    Some(outcome) => Task::batch([task, self.handle_network_outcome(outcome)]),
+                    None => task,
+                }
+            }
 
             Message::DeviceProfileManager(msg) => {
                 // Provide streaming frame buffer for screenshot capture
@@ -1561,6 +1683,8 @@ impl Ultimate64Browser {
                     .map(Message::DeviceProfileManager)
             }
 
+            Message::Workspace(msg) => self.handle_workspace(msg),
+
             Message::HostInputChanged(value) => self.handle_host_input_changed(value),
 
             Message::PasswordInputChanged(value) => self.handle_password_input_changed(value),
@@ -1631,6 +1755,14 @@ impl Ultimate64Browser {
 
             Message::ExecuteTemplate => self.handle_execute_template(),
 
+            Message::RunLaunchScript(request) => self.handle_run_launch_script(request),
+
+            Message::LaunchScriptStepped(id, result) => {
+                self.handle_launch_script_stepped(id, result)
+            }
+
+            Message::StopLaunchScript => self.handle_stop_launch_script(),
+
             Message::ShowError(error) => {
                 log::error!("Error: {}", error);
                 self.user_message = Some(UserMessage::Error(error));
@@ -1662,6 +1794,12 @@ impl Ultimate64Browser {
                 if let StreamingMessage::OpenInSeparateWindow = msg {
                     return Task::perform(async {}, |_| Message::OpenStreamingWindow);
                 }
+                if let StreamingMessage::RecordingFinished(ref result) = msg {
+                    self.user_message = Some(match result {
+                        Ok(summary) => UserMessage::Info(format!("Recording saved: {}", summary)),
+                        Err(e) => UserMessage::Error(format!("Recording failed: {}", e)),
+                    });
+                }
                 if let StreamingMessage::ScreenshotComplete(ref result) = msg {
                     match result {
                         Ok(path) => {
@@ -1781,6 +1919,9 @@ impl Ultimate64Browser {
             Message::DropCancel => self.handle_drop_cancel(),
             Message::DropAction(action) => self.handle_drop_action(action),
             Message::DropCompleted(result) => self.handle_drop_completed(result),
+            Message::DropCrtChecked(path, result) => self.handle_drop_crt_checked(path, result),
+            Message::DropConfirmCrt => self.handle_drop_confirm_crt(),
+            Message::DropCancelCrt => self.handle_drop_cancel_crt(),
             Message::DropAbort => self.handle_drop_abort(),
             Message::ShowHelp => self.handle_show_help(),
             Message::HideHelp => self.handle_hide_help(),
@@ -1829,6 +1970,19 @@ impl Ultimate64Browser {
             Message::ResumeMachine => self.handle_resume_machine(),
             Message::PoweroffMachine => self.handle_poweroff_machine(),
             Message::MenuButton => self.handle_menu_button(),
+            Message::FirmwareUpdateProceed => self.handle_firmware_update_proceed(),
+            Message::FirmwareBackupDone(result) => self.handle_firmware_backup_done(result),
+            Message::FirmwareUploadDone(result) => self.handle_firmware_upload_done(result),
+            Message::FirmwareUpdateStarted => self.handle_firmware_update_started(),
+            Message::FirmwareUpdateClose => self.handle_firmware_update_close(),
+            Message::FirmwareUpdateTick => self.handle_firmware_update_tick(),
+            Message::NetworkRecoveryRetry => self.handle_network_recovery_retry(),
+            Message::NetworkRecoveryRetried(outcome) => self.handle_network_outcome(outcome),
+            Message::NetworkRecoverySearch => self.handle_network_recovery_search(),
+            Message::NetworkRecoveryConnect(host) => self.handle_network_recovery_connect(host),
+            Message::NetworkRecoveryClose => self.handle_network_recovery_close(),
+            Message::SaveSnapshot => self.handle_save_snapshot(),
+            Message::SnapshotPathSelected(path) => self.handle_snapshot_path_selected(path),
             Message::MachineCommandCompleted(result) => {
                 self.handle_machine_command_completed(result)
             }
@@ -1866,6 +2020,7 @@ impl Ultimate64Browser {
             }
 
             Message::FontSizeChanged(value) => self.handle_font_size_changed(value),
+            Message::DiscoverySubnetsChanged(value) => self.handle_discovery_subnets_changed(value),
             Message::GameLibraryInputChanged(value) => {
                 self.handle_game_library_input_changed(value)
             }
@@ -1956,6 +2111,19 @@ impl Ultimate64Browser {
         if let Some(ref dropped) = self.pending_drop {
             return self.view_drop_dialog(dropped);
         }
+        if let Some((ref path, ref warnings)) = self.pending_drop_crt {
+            return self.view_drop_crt_dialog(path, warnings);
+        }
+
+        // Firmware update — stays up through backup, upload and reboot.
+        if let Some(ref session) = self.firmware_update {
+            return self.view_firmware_update_dialog(session);
+        }
+
+        // Device lost after a network settings change.
+        if let Some(ref recovery) = self.network_recovery {
+            return self.view_network_recovery_dialog(recovery);
+        }
 
         // Eject A+B confirmation — guards against accidental clicks since
         // there's no undo for clearing a mounted disk.
@@ -1994,6 +2162,7 @@ impl Ultimate64Browser {
                     // The Device tab is hidden (its debug features aren't finished);
                     // drive control now lives in the File Browser's device pane, and
                     // machine control is in the bottom status bar.
+                    self.tab_button("WORKSPACE", Tab::Workspace),
                     self.tab_button("SETTINGS", Tab::Settings),
                 ]
                 .spacing(2),
@@ -2066,6 +2235,14 @@ impl Ultimate64Browser {
                 .view(self.settings.preferences.font_size, self.status.connected)
                 .map(Message::BasicEditor),
             Tab::Device => self.view_device(),
+            Tab::Workspace => self
+                .workspace
+                .view(
+                    self.settings.preferences.font_size,
+                    self.profile_manager.profile_names(),
+                    self.device_profile_manager.profile_names(),
+                )
+                .map(Message::Workspace),
             Tab::Settings => self.view_settings(),
         })
         .padding(10)
@@ -2312,6 +2489,16 @@ impl Ultimate64Browser {
             Subscription::none()
         };
 
+        let firmware_update_tick = if self
+            .firmware_update
+            .as_ref()
+            .is_some_and(|s| s.is_rebooting())
+        {
+            iced::time::every(Duration::from_secs(1)).map(|_| Message::FirmwareUpdateTick)
+        } else {
+            Subscription::none()
+        };
+
         // Refresh the debug-stream capture counters while active.
         let debug_stream_tick = if self.debug_stream.active {
             iced::time::every(Duration::from_millis(500)).map(|_| Message::DeviceDebugStreamTick)
@@ -2327,11 +2514,13 @@ impl Ultimate64Browser {
                 .map(Message::RemoteBrowser),
             self.memory_editor.subscription().map(Message::MemoryEditor),
             self.sid_monitor.subscription().map(Message::Monitor),
+            self.workspace.subscription().map(Message::Workspace),
             keyboard_sub,
             window_events,
             status_check,
             copy_progress_tick,
             toast_tick,
+            firmware_update_tick,
             debug_stream_tick,
         ])
     }
@@ -2682,87 +2871,6 @@ impl Ultimate64Browser {
     }
 }
 
-async fn execute_template_commands(
-    connection: Arc<Mutex<dyn RemoteDevice>>,
-    commands: Vec<String>,
-) -> Result<(), String> {
-    for command in commands {
-        log::info!("Executing: {}", command);
-        let conn = connection.clone();
-
-        if command.starts_with("RESET") {
-            let result = tokio::time::timeout(
-                tokio::time::Duration::from_secs(REST_TIMEOUT_SECS),
-                tokio::task::spawn_blocking(move || {
-                    let conn = conn.lock().unwrap();
-                    conn.reset().map_err(|e| format!("Reset failed: {}", e))
-                }),
-            )
-            .await;
-
-            match result {
-                Ok(Ok(r)) => r?,
-                Ok(Err(e)) => return Err(format!("Task error: {}", e)),
-                Err(_) => return Err("Reset timed out - device may be offline".to_string()),
-            }
-            tokio::time::sleep(tokio::time::Duration::from_secs(3)).await;
-        } else if let Some(text) = command.strip_prefix("TYPE ") {
-            let text = text.to_string();
-            let result = tokio::time::timeout(
-                tokio::time::Duration::from_secs(REST_TIMEOUT_SECS),
-                tokio::task::spawn_blocking(move || {
-                    let conn = conn.lock().unwrap();
-                    conn.type_text(&text)
-                        .map_err(|e| format!("Type failed: {}", e))
-                }),
-            )
-            .await;
-
-            match result {
-                Ok(Ok(r)) => r?,
-                Ok(Err(e)) => return Err(format!("Task error: {}", e)),
-                Err(_) => return Err("Type command timed out - device may be offline".to_string()),
-            }
-            tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
-        } else if command.starts_with("LOAD") {
-            let result = tokio::time::timeout(
-                tokio::time::Duration::from_secs(REST_TIMEOUT_SECS),
-                tokio::task::spawn_blocking(move || {
-                    let conn = conn.lock().unwrap();
-                    conn.type_text("load\"*\",8,1\n")
-                        .map_err(|e| format!("Load failed: {}", e))
-                }),
-            )
-            .await;
-
-            match result {
-                Ok(Ok(r)) => r?,
-                Ok(Err(e)) => return Err(format!("Task error: {}", e)),
-                Err(_) => return Err("Load command timed out - device may be offline".to_string()),
-            }
-            tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
-        } else if command.starts_with("RUN") {
-            let result = tokio::time::timeout(
-                tokio::time::Duration::from_secs(REST_TIMEOUT_SECS),
-                tokio::task::spawn_blocking(move || {
-                    let conn = conn.lock().unwrap();
-                    conn.type_text("run\n")
-                        .map_err(|e| format!("Run failed: {}", e))
-                }),
-            )
-            .await;
-
-            match result {
-                Ok(Ok(r)) => r?,
-                Ok(Err(e)) => return Err(format!("Task error: {}", e)),
-                Err(_) => return Err("Run command timed out - device may be offline".to_string()),
-            }
-        }
-    }
-
-    Ok(())
-}
-
 async fn fetch_status(connection: Arc<Mutex<dyn RemoteDevice>>) -> Result<StatusInfo, DeviceError> {
     // Use spawn_blocking to avoid runtime conflicts with ultimate64 crate
     // Wrap in timeout to prevent hangs when device is offline
@@ -2771,8 +2879,8 @@ async fn fetch_status(connection: Arc<Mutex<dyn RemoteDevice>>) -> Result<Status
         tokio::task::spawn_blocking(move || {
             let conn = connection.lock().unwrap();
 
-            let device_info = match conn.info() {
-                Ok(info) => Some(format!("{} ({})", info.product, info.firmware_version)),
+            let info = match conn.info() {
+                Ok(info) => info,
                 // The ultimate64 crate surfaces HTTP failures as strings; classify
                 // a 403 so the UI can say "wrong password" instead of "offline".
                 Err(e) => return Err(classify_crate_error(&e.to_string())),
@@ -2800,7 +2908,9 @@ async fn fetch_status(connection: Arc<Mutex<dyn RemoteDevice>>) -> Result<Status
 
             Ok(StatusInfo {
                 connected: true,
-                device_info,
+                device_info: Some(format!("{} ({})", info.product, info.firmware_version)),
+                product: Some(info.product),
+                firmware_version: Some(info.firmware_version),
                 mounted_disks,
                 drives,
             })
@@ -3002,6 +3112,15 @@ mod tests {
         );
     }
 
+    #[test]
+    fn drop_actions_for_pc64_program_offers_run() {
+        let actions = DropAction::available_for("p01", &p("game.p01"));
+        assert!(
+            matches!(actions[0], DropAction::RunOnDevice { runner, .. } if runner == "run_prg")
+        );
+        assert!(DropAction::available_for("s00", &p("notes.s00")).is_empty());
+    }
+
     #[test]
     fn drop_actions_for_crt_offers_run_crt() {
         let actions = DropAction::available_for("crt", &p("cart.crt"));
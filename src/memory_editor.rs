@@ -9,7 +9,10 @@ use iced::{
 use std::sync::Arc;
 use std::sync::Mutex;
 
+use crate::cheat_finder::{CheatFilter, CheatSearch, Freeze};
+use crate::flash_backup::{self, FlashBackup, FlashDiff};
 use crate::port64;
+use crate::reu;
 
 // ─────────────────────────────────────────────────────────────────
 //  Memory locations
@@ -228,7 +231,8 @@ pub enum AddressSpace {
     /// Normal 64 KB C64 RAM (REST read_mem / DMAWRITE write)
     #[default]
     C64Ram,
-    /// REU expansion RAM up to 16 MB (SOCKET_CMD_REUWRITE, raw socket)
+    /// REU expansion RAM up to 16 MB (SOCKET_CMD_REUWRITE write, read back
+    /// through the fetch stub in `reu`)
     Reu,
 }
 
@@ -241,6 +245,20 @@ impl std::fmt::Display for AddressSpace {
     }
 }
 
+/// REU size for whole-REU dumps
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReuSize(pub u32);
+
+impl std::fmt::Display for ReuSize {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.0 >= 1 << 20 {
+            write!(f, "{} MB", self.0 >> 20)
+        } else {
+            write!(f, "{} KB", self.0 >> 10)
+        }
+    }
+}
+
 // ─────────────────────────────────────────────────────────────────
 //  Display mode
 // ─────────────────────────────────────────────────────────────────
@@ -252,6 +270,8 @@ pub enum DisplayMode {
     Ascii,
     Decimal,
     Binary,
+    /// 6510 disassembly starting at the read address
+    Disasm,
 }
 
 impl std::fmt::Display for DisplayMode {
@@ -261,6 +281,7 @@ impl std::fmt::Display for DisplayMode {
             DisplayMode::Ascii => write!(f, "ASCII"),
             DisplayMode::Decimal => write!(f, "DEC"),
             DisplayMode::Binary => write!(f, "BIN"),
+            DisplayMode::Disasm => write!(f, "ASM"),
         }
     }
 }
@@ -298,16 +319,26 @@ impl std::fmt::Display for Bookmark {
 //  Undo / redo entry
 // ─────────────────────────────────────────────────────────────────
 
-/// One reversible byte-write in C64 RAM space
+/// One reversible write in C64 RAM space — a single edited byte or the
+/// bytes of one assembled instruction
 #[derive(Debug, Clone)]
 struct UndoEntry {
     address: u16,
-    old_value: u8,
-    new_value: u8,
+    old_bytes: Vec<u8>,
+    new_bytes: Vec<u8>,
     /// Offset inside `memory_data` so we can patch the local copy instantly
     offset: usize,
 }
 
+impl UndoEntry {
+    fn describe(&self, bytes: &[u8]) -> String {
+        match bytes {
+            [value] => format!("${:02X} at ${:04X}", value, self.address),
+            _ => format!("{} bytes at ${:04X}", bytes.len(), self.address),
+        }
+    }
+}
+
 // ─────────────────────────────────────────────────────────────────
 //  Flash info returned by READFLASH
 // ─────────────────────────────────────────────────────────────────
@@ -372,6 +403,14 @@ pub enum MemoryEditorMessage {
     // Byte click / edit
     ByteClicked(usize),
 
+    // Inline assembler (disassembly view)
+    AsmLineClicked(usize),
+    AsmInputChanged(String),
+    AssembleConfirm,
+    AssembleCancel,
+    /// View offset, replaced bytes, assembled bytes
+    AssembleComplete(Result<(usize, Vec<u8>, Vec<u8>), String>),
+
     // Clear / refresh
     ClearMemoryView,
     RefreshMemory,
@@ -380,6 +419,21 @@ pub enum MemoryEditorMessage {
     ToggleWatch,
     WatchTick,
 
+    // Cheat finder
+    ToggleCheatFinder,
+    CheatNewSearch,
+    CheatFilterChanged(CheatFilter),
+    CheatValueChanged(String),
+    CheatApplyFilter,
+    /// `None` for the first snapshot of a search, else the filter to apply
+    CheatSnapshotTaken(Option<(CheatFilter, u8)>, Result<Vec<u8>, String>),
+    CheatReset,
+    CheatFreeze(u16, u8),
+    FreezeValueChanged(usize, String),
+    FreezeToggled(usize),
+    RemoveFreeze(usize),
+    FreezesWritten(Result<(), String>),
+
     // Bookmarks
     BookmarkLabelChanged(String),
     AddBookmark,
@@ -414,6 +468,24 @@ pub enum MemoryEditorMessage {
     },
     ReuWriteComplete(Result<(), String>),
 
+    // ── Whole-REU dump / restore / diff ──────────────────────────
+    ReuSizeChanged(ReuSize),
+    DumpReu,
+    DumpReuPathSelected(Option<std::path::PathBuf>),
+    ReuDumpSaved(Result<String, String>),
+    RestoreReu,
+    RestoreReuPathSelected(Option<std::path::PathBuf>),
+    RestoreReuComplete(Result<String, String>),
+    DiffReu,
+    DiffReuPathSelected(Option<std::path::PathBuf>),
+    DiffSnapshotLoaded(Result<(std::path::PathBuf, Vec<u8>), String>),
+    ReuSessionOpened(Result<reu::StubBackup, String>),
+    ReuWindowFetched(Result<Vec<u8>, String>),
+    ReuSessionClosed(Result<(), String>),
+    CancelReuJob,
+    ReuDiffRangeSelected(u32),
+    CloseReuDiff,
+
     /// SOCKET_CMD_KERNALWRITE — replace the active Kernal ROM image
     KernalWriteClicked,
     KernalWritePathSelected(Option<std::path::PathBuf>),
@@ -425,6 +497,18 @@ pub enum MemoryEditorMessage {
     ReadFlashPage(u32),
     FlashPageComplete(Result<(u32, Vec<u8>), String>),
     FlashPageChanged(String),
+    /// Whole-flash backup to a `.bin` + manifest, resumable
+    BackupFlash,
+    BackupFlashPathSelected(Option<std::path::PathBuf>),
+    /// Pick a backup to resume (`false`) or to compare with the device (`true`)
+    PickFlashBackup(bool),
+    FlashBackupPicked(bool, Option<std::path::PathBuf>),
+    CompareFlashBackups,
+    CompareFlashBackupsPicked(Option<Vec<std::path::PathBuf>>),
+    FlashJobPageRead(Result<(u32, Vec<u8>), String>),
+    CancelFlashJob,
+    FlashDiffPageSelected(u32),
+    CloseFlashDiff,
 }
 
 // ─────────────────────────────────────────────────────────────────
@@ -438,6 +522,75 @@ struct EditingByte {
     new_value_input: String,
 }
 
+/// What a whole-REU read is for
+#[derive(Debug, Clone)]
+enum ReuJobKind {
+    Dump(std::path::PathBuf),
+    Diff {
+        path: std::path::PathBuf,
+        snapshot: Vec<u8>,
+    },
+}
+
+/// Whole-REU read in progress, one window per message
+#[derive(Debug, Clone)]
+struct ReuJob {
+    kind: ReuJobKind,
+    size: u32,
+    data: Vec<u8>,
+    backup: Option<reu::StubBackup>,
+    cancelled: bool,
+    error: Option<String>,
+}
+
+/// Attempts at one flash page before a backup or check stops
+const FLASH_PAGE_RETRIES: u8 = 3;
+/// Pause before a retry, scaled by the number of failed attempts
+const FLASH_RETRY_BACKOFF: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// Whole-flash read in progress, one page per message. Either fills the
+/// missing pages of a backup or compares every page with one.
+#[derive(Debug, Clone)]
+struct FlashJob {
+    backup: FlashBackup,
+    verify: bool,
+    pages: Vec<u32>,
+    next: usize,
+    /// Failed attempts at the current page
+    retries: u8,
+    cancelled: bool,
+    diff: FlashDiff,
+}
+
+/// Pages that differ, from a device check or a backup-to-backup compare
+#[derive(Debug, Clone)]
+struct FlashDiffView {
+    title: String,
+    diff: FlashDiff,
+}
+
+/// Result of comparing the REU against a saved `.reu` snapshot
+#[derive(Debug, Clone)]
+struct ReuDiff {
+    snapshot_name: String,
+    ranges: Vec<reu::DiffRange>,
+}
+
+/// Freeze entry plus its hex value as typed
+#[derive(Debug, Clone)]
+struct FreezeRow {
+    freeze: Freeze,
+    input: String,
+}
+
+/// Disassembly line being re-assembled
+#[derive(Debug, Clone)]
+struct EditingInstruction {
+    offset: usize,
+    input: String,
+    error: Option<String>,
+}
+
 // ─────────────────────────────────────────────────────────────────
 //  Main editor state
 // ─────────────────────────────────────────────────────────────────
@@ -463,14 +616,24 @@ pub struct MemoryEditor {
     search_matches: Vec<usize>,
     selected_location: Option<MemoryLocation>,
     editing_byte: Option<EditingByte>,
+    editing_instruction: Option<EditingInstruction>,
 
-    // Undo / Redo stacks (only for C64 RAM byte writes)
+    // Undo / Redo stacks (only for C64 RAM writes)
     undo_stack: Vec<UndoEntry>,
     redo_stack: Vec<UndoEntry>,
 
     // Watch / live-refresh
     watch_active: bool,
 
+    // Cheat finder
+    cheat_open: bool,
+    cheat_search: Option<CheatSearch>,
+    cheat_filter: CheatFilter,
+    cheat_value_input: String,
+    cheat_busy: bool,
+    freezes: Vec<FreezeRow>,
+    freeze_in_flight: bool,
+
     // Bookmarks
     bookmarks: Vec<Bookmark>,
     bookmark_label_input: String,
@@ -479,10 +642,17 @@ pub struct MemoryEditor {
     // Flash inspector
     flash_info: Option<FlashInfo>,
     flash_page_input: String,
+    flash_job: Option<FlashJob>,
+    flash_diff: Option<FlashDiffView>,
 
     // Kernal write
     kernal_pending_path: Option<std::path::PathBuf>,
 
+    // Whole-REU dump / diff
+    reu_size: ReuSize,
+    reu_job: Option<ReuJob>,
+    reu_diff: Option<ReuDiff>,
+
     // Loading / busy
     is_loading: bool,
     status_message: Option<String>,
@@ -504,15 +674,28 @@ impl MemoryEditor {
             search_matches: Vec::new(),
             selected_location: None,
             editing_byte: None,
+            editing_instruction: None,
             undo_stack: Vec::new(),
             redo_stack: Vec::new(),
             watch_active: false,
+            cheat_open: false,
+            cheat_search: None,
+            cheat_filter: CheatFilter::default(),
+            cheat_value_input: "1".to_string(),
+            cheat_busy: false,
+            freezes: Vec::new(),
+            freeze_in_flight: false,
             bookmarks: Vec::new(),
             bookmark_label_input: String::new(),
             selected_bookmark: None,
             flash_info: None,
             flash_page_input: "0".to_string(),
+            flash_job: None,
+            flash_diff: None,
             kernal_pending_path: None,
+            reu_size: ReuSize(16 << 20),
+            reu_job: None,
+            reu_diff: None,
             is_loading: false,
             status_message: None,
         }
@@ -520,10 +703,12 @@ impl MemoryEditor {
 
     // ── Subscription: watch ticker ───────────────────────────────
 
-    /// Merged into the app's `Subscription::batch`. When watch is on, fires
-    /// `WatchTick` every 500 ms so the UI refreshes live.
+    /// Merged into the app's `Subscription::batch`. When watch is on or a
+    /// freeze is active, fires `WatchTick` every 500 ms so the UI refreshes
+    /// live and frozen addresses get re-written.
     pub fn subscription(&self) -> Subscription<MemoryEditorMessage> {
-        if self.watch_active && self.memory_data.is_some() {
+        let freezing = self.freezes.iter().any(|f| f.freeze.enabled);
+        if (self.watch_active && self.memory_data.is_some()) || freezing {
             iced::time::every(std::time::Duration::from_millis(500))
                 .map(|_| MemoryEditorMessage::WatchTick)
         } else {
@@ -637,9 +822,19 @@ impl MemoryEditor {
                     }
                 }
                 AddressSpace::Reu => {
-                    self.status_message =
-                        Some("REU read not supported — write only via raw socket".to_string());
-                    Task::none()
+                    if let Some(conn) = connection {
+                        self.is_loading = true;
+                        self.status_message = Some("Reading REU…".to_string());
+                        let offset = self.current_address;
+                        let length = self.display_length as usize;
+                        Task::perform(
+                            reu::read_reu_async(conn, offset, length),
+                            MemoryEditorMessage::ReadMemoryComplete,
+                        )
+                    } else {
+                        self.status_message = Some("Not connected to Ultimate64".to_string());
+                        Task::none()
+                    }
                 }
             },
 
@@ -648,9 +843,9 @@ impl MemoryEditor {
                 match result {
                     Ok(data) => {
                         self.status_message = Some(format!(
-                            "Read {} bytes from ${:04X}",
+                            "Read {} bytes from {}",
                             data.len(),
-                            self.current_address
+                            self.address_label(self.current_address)
                         ));
                         self.memory_data = Some(data);
                         self.search_matches.clear();
@@ -673,21 +868,163 @@ impl MemoryEditor {
             }
 
             MemoryEditorMessage::WatchTick => {
-                if self.watch_active && !self.is_loading {
+                let Some(conn) = connection else {
+                    return Task::none();
+                };
+                let mut tasks = Vec::new();
+                let frozen: Vec<(u16, u8)> = self
+                    .freezes
+                    .iter()
+                    .filter(|f| f.freeze.enabled)
+                    .map(|f| (f.freeze.address, f.freeze.value))
+                    .collect();
+                if !frozen.is_empty() && !self.freeze_in_flight {
+                    self.freeze_in_flight = true;
+                    tasks.push(Task::perform(
+                        write_freezes_async(conn.clone(), frozen),
+                        MemoryEditorMessage::FreezesWritten,
+                    ));
+                }
+                if self.watch_active && !self.is_loading && self.memory_data.is_some() {
                     // Silently re-read without updating status_message so it doesn't flicker
-                    if let Some(conn) = connection {
+                    let length = self.display_length;
+                    self.is_loading = true;
+                    if self.address_space == AddressSpace::Reu {
+                        tasks.push(Task::perform(
+                            reu::read_reu_async(conn, self.current_address, length as usize),
+                            MemoryEditorMessage::ReadMemoryComplete,
+                        ));
+                    } else {
                         let address = self.current_address as u16;
-                        let length = self.display_length;
-                        self.is_loading = true;
-                        return Task::perform(
+                        tasks.push(Task::perform(
                             async move { read_memory_async(conn, address, length).await },
                             MemoryEditorMessage::ReadMemoryComplete,
-                        );
+                        ));
+                    }
+                }
+                Task::batch(tasks)
+            }
+
+            // ── Cheat finder ─────────────────────────────────────
+            MemoryEditorMessage::ToggleCheatFinder => {
+                self.cheat_open = !self.cheat_open;
+                Task::none()
+            }
+
+            MemoryEditorMessage::CheatNewSearch => self.take_cheat_snapshot(None, connection),
+
+            MemoryEditorMessage::CheatFilterChanged(filter) => {
+                self.cheat_filter = filter;
+                Task::none()
+            }
+
+            MemoryEditorMessage::CheatValueChanged(value) => {
+                self.cheat_value_input = value
+                    .chars()
+                    .filter(|c| c.is_ascii_hexdigit() || matches!(c, '$' | 'x' | 'X' | 'h' | 'H'))
+                    .take(4)
+                    .collect();
+                Task::none()
+            }
+
+            MemoryEditorMessage::CheatApplyFilter => {
+                let n = if self.cheat_filter.takes_value() {
+                    match parse_length_input(&self.cheat_value_input)
+                        .and_then(|v| u8::try_from(v).ok())
+                    {
+                        Some(n) => n,
+                        None => {
+                            self.status_message = Some("Enter N as 0–255 or $00–$FF".to_string());
+                            return Task::none();
+                        }
+                    }
+                } else {
+                    0
+                };
+                self.take_cheat_snapshot(Some((self.cheat_filter, n)), connection)
+            }
+
+            MemoryEditorMessage::CheatSnapshotTaken(filter, result) => {
+                self.cheat_busy = false;
+                let result = result.and_then(|snapshot| match (filter, &mut self.cheat_search) {
+                    (Some((filter, n)), Some(search)) => search.refine(filter, n, snapshot),
+                    _ => CheatSearch::new(snapshot).map(|s| self.cheat_search = Some(s)),
+                });
+                self.status_message = Some(match (result, &self.cheat_search) {
+                    (Err(e), _) => format!("Snapshot failed: {}", e),
+                    (Ok(()), Some(search)) if search.rounds() == 0 => {
+                        "Snapshot taken — all 65536 addresses are candidates".to_string()
+                    }
+                    (Ok(()), Some(search)) => format!(
+                        "{} candidate(s) left after {} filter(s)",
+                        search.candidate_count(),
+                        search.rounds()
+                    ),
+                    (Ok(()), None) => String::new(),
+                });
+                Task::none()
+            }
+
+            MemoryEditorMessage::CheatReset => {
+                self.cheat_search = None;
+                self.status_message = Some("Cheat search cleared".to_string());
+                Task::none()
+            }
+
+            MemoryEditorMessage::CheatFreeze(address, value) => {
+                if self.freezes.iter().any(|f| f.freeze.address == address) {
+                    self.status_message = Some(format!("${:04X} is already frozen", address));
+                    return Task::none();
+                }
+                self.freezes.push(FreezeRow {
+                    freeze: Freeze {
+                        address,
+                        value,
+                        enabled: true,
+                    },
+                    input: format!("{:02X}", value),
+                });
+                self.status_message = Some(format!("Freezing ${:04X} at ${:02X}", address, value));
+                Task::none()
+            }
+
+            MemoryEditorMessage::FreezeValueChanged(idx, value) => {
+                if let Some(row) = self.freezes.get_mut(idx) {
+                    row.input = value
+                        .chars()
+                        .filter(|c| c.is_ascii_hexdigit())
+                        .take(2)
+                        .collect::<String>()
+                        .to_uppercase();
+                    if let Ok(v) = u8::from_str_radix(&row.input, 16) {
+                        row.freeze.value = v;
                     }
                 }
                 Task::none()
             }
 
+            MemoryEditorMessage::FreezeToggled(idx) => {
+                if let Some(row) = self.freezes.get_mut(idx) {
+                    row.freeze.enabled = !row.freeze.enabled;
+                }
+                Task::none()
+            }
+
+            MemoryEditorMessage::RemoveFreeze(idx) => {
+                if idx < self.freezes.len() {
+                    self.freezes.remove(idx);
+                }
+                Task::none()
+            }
+
+            MemoryEditorMessage::FreezesWritten(result) => {
+                self.freeze_in_flight = false;
+                if let Err(e) = result {
+                    self.status_message = Some(format!("Freeze write failed: {}", e));
+                }
+                Task::none()
+            }
+
             // ── Byte editing ─────────────────────────────────────
             MemoryEditorMessage::ByteClicked(offset) => {
                 if let Some(data) = &self.memory_data {
@@ -711,6 +1048,29 @@ impl MemoryEditor {
             }
 
             MemoryEditorMessage::WriteByteConfirm => {
+                if self.address_space == AddressSpace::Reu {
+                    if let (Some(host), Some(edit)) = (host, &self.editing_byte) {
+                        if let Ok(value) = edit.new_value_input.parse::<u8>() {
+                            let reu_offset = self.current_address.wrapping_add(edit.offset as u32);
+                            let offset = edit.offset;
+                            self.is_loading = true;
+                            self.status_message = Some(format!(
+                                "Writing ${:02X} to REU ${:06X}…",
+                                value, reu_offset
+                            ));
+                            return Task::perform(
+                                async move {
+                                    port64::write_reu(host, password, reu_offset, vec![value])
+                                        .await
+                                        .map(|_| (offset, value))
+                                },
+                                MemoryEditorMessage::WriteByteComplete,
+                            );
+                        }
+                    }
+                    self.editing_byte = None;
+                    return Task::none();
+                }
                 if let (Some(conn), Some(edit)) = (connection, &self.editing_byte) {
                     if let Ok(value) = edit.new_value_input.parse::<u8>() {
                         let address =
@@ -751,15 +1111,22 @@ impl MemoryEditor {
                         if let (Some(data), Some(_edit)) =
                             (&mut self.memory_data, &self.editing_byte)
                         {
-                            if offset < data.len() {
+                            if offset < data.len() && self.address_space == AddressSpace::Reu {
+                                data[offset] = new_value;
+                                self.status_message = Some(format!(
+                                    "Written ${:02X} to REU ${:06X}",
+                                    new_value,
+                                    self.current_address.wrapping_add(offset as u32)
+                                ));
+                            } else if offset < data.len() {
                                 let old_value = data[offset];
                                 let address =
                                     (self.current_address as u16).wrapping_add(offset as u16);
                                 // Push undo entry
                                 self.undo_stack.push(UndoEntry {
                                     address,
-                                    old_value,
-                                    new_value,
+                                    old_bytes: vec![old_value],
+                                    new_bytes: vec![new_value],
                                     offset,
                                 });
                                 self.redo_stack.clear(); // new write invalidates redo
@@ -775,29 +1142,122 @@ impl MemoryEditor {
                 Task::none()
             }
 
+            // ── Inline assembler ─────────────────────────────────
+            MemoryEditorMessage::AsmLineClicked(offset) => {
+                // The assembler writes through the device, which only reaches C64 RAM.
+                if self.address_space == AddressSpace::Reu {
+                    return Task::none();
+                }
+                if let Some(data) = &self.memory_data {
+                    let address = (self.current_address as u16).wrapping_add(offset as u16);
+                    if let Some(insn) = data
+                        .get(offset..)
+                        .and_then(|bytes| crate::mos6510::decode(address, bytes))
+                    {
+                        self.editing_instruction = Some(EditingInstruction {
+                            offset,
+                            input: insn.text(),
+                            error: None,
+                        });
+                    }
+                }
+                Task::none()
+            }
+
+            MemoryEditorMessage::AsmInputChanged(value) => {
+                if let Some(edit) = &mut self.editing_instruction {
+                    edit.input = value;
+                    edit.error = None;
+                }
+                Task::none()
+            }
+
+            MemoryEditorMessage::AssembleConfirm => {
+                let (Some(edit), Some(data)) = (&mut self.editing_instruction, &self.memory_data)
+                else {
+                    return Task::none();
+                };
+                let address = (self.current_address as u16).wrapping_add(edit.offset as u16);
+                let bytes = match crate::mos6510::assemble(address, &edit.input) {
+                    Ok(bytes) => bytes,
+                    Err(e) => {
+                        edit.error = Some(e);
+                        return Task::none();
+                    }
+                };
+                // Undo needs the bytes being replaced, so stay inside the view.
+                let Some(old_bytes) = data.get(edit.offset..edit.offset + bytes.len()) else {
+                    edit.error = Some("Instruction runs past the end of the view".to_string());
+                    return Task::none();
+                };
+                let old_bytes = old_bytes.to_vec();
+                let Some(conn) = connection else {
+                    self.status_message = Some("Not connected to Ultimate64".to_string());
+                    return Task::none();
+                };
+                let offset = edit.offset;
+                self.is_loading = true;
+                self.status_message = Some(format!(
+                    "Assembling {} byte(s) at ${:04X}…",
+                    bytes.len(),
+                    address
+                ));
+                Task::perform(
+                    async move {
+                        write_memory_async(conn, address, bytes.clone())
+                            .await
+                            .map(|_| (offset, old_bytes, bytes))
+                    },
+                    MemoryEditorMessage::AssembleComplete,
+                )
+            }
+
+            MemoryEditorMessage::AssembleCancel => {
+                self.editing_instruction = None;
+                Task::none()
+            }
+
+            MemoryEditorMessage::AssembleComplete(result) => {
+                self.is_loading = false;
+                match result {
+                    Ok((offset, old_bytes, new_bytes)) => {
+                        // One entry for the whole instruction, so a single
+                        // undo puts back every byte it replaced.
+                        let address = (self.current_address as u16).wrapping_add(offset as u16);
+                        self.patch_local(offset, &new_bytes);
+                        self.undo_stack.push(UndoEntry {
+                            address,
+                            old_bytes,
+                            new_bytes,
+                            offset,
+                        });
+                        self.redo_stack.clear();
+                        self.editing_instruction = None;
+                        self.status_message = Some(format!("Assembled at ${:04X}", address));
+                    }
+                    Err(e) => {
+                        if let Some(edit) = &mut self.editing_instruction {
+                            edit.error = Some(e.clone());
+                        }
+                        self.status_message = Some(format!("Assemble failed: {}", e));
+                    }
+                }
+                Task::none()
+            }
+
             // ── Undo ─────────────────────────────────────────────
             MemoryEditorMessage::Undo => {
                 if let (Some(entry), Some(conn)) = (self.undo_stack.pop(), connection) {
                     let address = entry.address;
-                    let restore_value = entry.old_value;
-                    self.redo_stack.push(entry.clone());
+                    let restore = entry.old_bytes.clone();
                     // Patch local copy immediately for snappy UI
-                    if let Some(data) = &mut self.memory_data {
-                        if entry.offset < data.len() {
-                            data[entry.offset] = restore_value;
-                        }
-                    }
-                    self.status_message = Some(format!(
-                        "Undo: restored ${:02X} at ${:04X}",
-                        restore_value, address
-                    ));
+                    self.patch_local(entry.offset, &restore);
+                    self.status_message =
+                        Some(format!("Undo: restored {}", entry.describe(&restore)));
+                    self.redo_stack.push(entry);
                     self.is_loading = true;
                     return Task::perform(
-                        async move {
-                            write_byte_async(conn, address, restore_value)
-                                .await
-                                .map(|_| ())
-                        },
+                        async move { write_memory_async(conn, address, restore).await },
                         |r| match r {
                             Ok(()) => MemoryEditorMessage::DmaWriteComplete(Ok(())),
                             Err(e) => MemoryEditorMessage::DmaWriteComplete(Err(e)),
@@ -810,20 +1270,13 @@ impl MemoryEditor {
             MemoryEditorMessage::Redo => {
                 if let (Some(entry), Some(conn)) = (self.redo_stack.pop(), connection) {
                     let address = entry.address;
-                    let new_value = entry.new_value;
-                    self.undo_stack.push(entry.clone());
-                    if let Some(data) = &mut self.memory_data {
-                        if entry.offset < data.len() {
-                            data[entry.offset] = new_value;
-                        }
-                    }
-                    self.status_message = Some(format!(
-                        "Redo: wrote ${:02X} to ${:04X}",
-                        new_value, address
-                    ));
+                    let bytes = entry.new_bytes.clone();
+                    self.patch_local(entry.offset, &bytes);
+                    self.status_message = Some(format!("Redo: wrote {}", entry.describe(&bytes)));
+                    self.undo_stack.push(entry);
                     self.is_loading = true;
                     return Task::perform(
-                        async move { write_byte_async(conn, address, new_value).await.map(|_| ()) },
+                        async move { write_memory_async(conn, address, bytes).await },
                         |r| match r {
                             Ok(()) => MemoryEditorMessage::DmaWriteComplete(Ok(())),
                             Err(e) => MemoryEditorMessage::DmaWriteComplete(Err(e)),
@@ -881,6 +1334,8 @@ impl MemoryEditor {
             // ── Display mode / search ────────────────────────────
             MemoryEditorMessage::DisplayModeChanged(mode) => {
                 self.display_mode = mode;
+                self.editing_byte = None;
+                self.editing_instruction = None;
                 Task::none()
             }
 
@@ -915,6 +1370,7 @@ impl MemoryEditor {
                 self.memory_data = None;
                 self.search_matches.clear();
                 self.editing_byte = None;
+                self.editing_instruction = None;
                 self.status_message = None;
                 self.watch_active = false;
                 Task::none()
@@ -1099,7 +1555,7 @@ impl MemoryEditor {
                 self.address_input = format!("{:0width$X}", bm.address, width = max_digits);
                 self.length_input = bm.length.to_string();
                 self.status_message = Some(format!("Jumped to bookmark: {}", bm.label));
-                if connection.is_some() && self.address_space == AddressSpace::C64Ram {
+                if connection.is_some() {
                     return self.update_impl(
                         MemoryEditorMessage::ReadMemory,
                         connection,
@@ -1202,60 +1658,280 @@ impl MemoryEditor {
                 Task::none()
             }
 
-            // ── Kernal write ─────────────────────────────────────
-            MemoryEditorMessage::KernalWriteClicked => Task::perform(
+            // ── Whole-REU dump / restore / diff ──────────────────
+            MemoryEditorMessage::ReuSizeChanged(size) => {
+                self.reu_size = size;
+                Task::none()
+            }
+
+            MemoryEditorMessage::DumpReu => {
+                let default_name =
+                    format!("reu_{}.reu", self.reu_size.to_string().replace(' ', ""));
+                Task::perform(
+                    async move {
+                        rfd::AsyncFileDialog::new()
+                            .set_file_name(&default_name)
+                            .add_filter("REU image", &["reu"])
+                            .save_file()
+                            .await
+                            .map(|h| h.path().to_path_buf())
+                    },
+                    MemoryEditorMessage::DumpReuPathSelected,
+                )
+            }
+
+            MemoryEditorMessage::DumpReuPathSelected(path) => match path {
+                Some(path) => self.start_reu_job(ReuJobKind::Dump(path), connection),
+                None => Task::none(),
+            },
+
+            MemoryEditorMessage::ReuDumpSaved(result) => {
+                self.status_message = Some(result.unwrap_or_else(|e| e));
+                Task::none()
+            }
+
+            MemoryEditorMessage::RestoreReu => Task::perform(
                 async {
                     rfd::AsyncFileDialog::new()
-                        .add_filter("Kernal ROM", &["bin", "rom"])
-                        .add_filter("All files", &["*"])
-                        .set_title("Select Kernal ROM image")
+                        .add_filter("REU image", &["reu"])
+                        .set_title("Select .reu image to write to the REU")
                         .pick_file()
                         .await
                         .map(|h| h.path().to_path_buf())
                 },
-                MemoryEditorMessage::KernalWritePathSelected,
+                MemoryEditorMessage::RestoreReuPathSelected,
             ),
 
-            MemoryEditorMessage::KernalWritePathSelected(path_opt) => {
-                if let (Some(path), Some(h), Some(pw)) =
-                    (path_opt, host.clone(), Some(password.clone()))
-                {
-                    self.kernal_pending_path = Some(path.clone());
-                    self.is_loading = true;
-                    self.status_message = Some("Sending Kernal ROM image…".to_string());
-                    return Task::perform(
-                        async move {
-                            let data = std::fs::read(&path)
-                                .map_err(|e| format!("Read ROM failed: {}", e))?;
-                            port64::write_kernal(h, pw, data).await
-                        },
-                        MemoryEditorMessage::KernalWriteComplete,
-                    );
-                }
-                Task::none()
+            MemoryEditorMessage::RestoreReuPathSelected(path) => {
+                let Some(path) = path else {
+                    return Task::none();
+                };
+                let Some(host) = host else {
+                    self.status_message = Some("Not connected to Ultimate64".to_string());
+                    return Task::none();
+                };
+                self.is_loading = true;
+                self.status_message = Some(format!("Writing {} to the REU…", file_label(&path)));
+                Task::perform(
+                    async move {
+                        let data = read_reu_file(&path)?;
+                        let size = ReuSize(data.len() as u32);
+                        reu::restore_reu(host, password, data).await?;
+                        Ok(format!("Restored {} REU from {}", size, file_label(&path)))
+                    },
+                    MemoryEditorMessage::RestoreReuComplete,
+                )
             }
 
-            MemoryEditorMessage::KernalWriteComplete(result) => {
+            MemoryEditorMessage::RestoreReuComplete(result) => {
                 self.is_loading = false;
-                match &result {
-                    Ok(()) => {
-                        self.status_message = Some("Kernal ROM replaced successfully".to_string())
-                    }
-                    Err(e) => self.status_message = Some(format!("Kernal write failed: {}", e)),
-                }
+                self.status_message = Some(match result {
+                    Ok(msg) => msg,
+                    Err(e) => format!("REU restore failed: {}", e),
+                });
                 Task::none()
             }
 
-            // ── Flash inspector ──────────────────────────────────
-            MemoryEditorMessage::ReadFlashInfo => {
-                if let Some(h) = host.clone() {
-                    self.is_loading = true;
-                    self.status_message = Some("Reading flash info…".to_string());
-                    return Task::perform(
-                        async move { port64::flash_info(h, password).await },
-                        MemoryEditorMessage::FlashInfoComplete,
-                    );
-                }
+            MemoryEditorMessage::DiffReu => Task::perform(
+                async {
+                    rfd::AsyncFileDialog::new()
+                        .add_filter("REU image", &["reu"])
+                        .set_title("Select .reu snapshot to compare against")
+                        .pick_file()
+                        .await
+                        .map(|h| h.path().to_path_buf())
+                },
+                MemoryEditorMessage::DiffReuPathSelected,
+            ),
+
+            MemoryEditorMessage::DiffReuPathSelected(path) => match path {
+                Some(path) => Task::perform(
+                    async move { read_reu_file(&path).map(|data| (path, data)) },
+                    MemoryEditorMessage::DiffSnapshotLoaded,
+                ),
+                None => Task::none(),
+            },
+
+            MemoryEditorMessage::DiffSnapshotLoaded(result) => match result {
+                Ok((path, snapshot)) => {
+                    // The snapshot decides how much of the REU to read back.
+                    self.reu_size = ReuSize(snapshot.len() as u32);
+                    self.start_reu_job(ReuJobKind::Diff { path, snapshot }, connection)
+                }
+                Err(e) => {
+                    self.status_message = Some(e);
+                    Task::none()
+                }
+            },
+
+            MemoryEditorMessage::ReuSessionOpened(result) => {
+                let Some(job) = &mut self.reu_job else {
+                    return Task::none();
+                };
+                match result {
+                    Ok(backup) => {
+                        job.backup = Some(backup);
+                        self.next_reu_step(connection)
+                    }
+                    Err(e) => {
+                        self.reu_job = None;
+                        self.is_loading = false;
+                        self.status_message = Some(format!("REU read failed: {}", e));
+                        Task::none()
+                    }
+                }
+            }
+
+            MemoryEditorMessage::ReuWindowFetched(result) => {
+                let Some(job) = &mut self.reu_job else {
+                    return Task::none();
+                };
+                match result {
+                    Ok(window) => {
+                        job.data.extend(window);
+                        self.status_message = Some(format!(
+                            "Reading REU… {}%",
+                            job.data.len() as u64 * 100 / job.size as u64
+                        ));
+                    }
+                    Err(e) => job.error = Some(e),
+                }
+                self.next_reu_step(connection)
+            }
+
+            MemoryEditorMessage::ReuSessionClosed(result) => {
+                self.is_loading = false;
+                let Some(job) = self.reu_job.take() else {
+                    return Task::none();
+                };
+                // A failed close leaves the C64 parked in the stub; say so
+                // even if the data itself came back fine.
+                if let Err(e) = result {
+                    self.status_message = Some(format!("REU session did not close: {}", e));
+                    return Task::none();
+                }
+                if let Some(e) = job.error {
+                    self.status_message = Some(format!("REU read failed: {}", e));
+                    return Task::none();
+                }
+                if job.cancelled {
+                    self.status_message = Some("REU read cancelled".to_string());
+                    return Task::none();
+                }
+                match job.kind {
+                    ReuJobKind::Dump(path) => {
+                        let data = job.data;
+                        Task::perform(
+                            async move {
+                                std::fs::write(&path, &data)
+                                    .map_err(|e| format!("Failed to save: {}", e))?;
+                                Ok(format!(
+                                    "Saved {} REU to {}",
+                                    ReuSize(data.len() as u32),
+                                    file_label(&path)
+                                ))
+                            },
+                            MemoryEditorMessage::ReuDumpSaved,
+                        )
+                    }
+                    ReuJobKind::Diff { path, snapshot } => {
+                        let ranges = reu::diff(&snapshot, &job.data, 16);
+                        let changed: u64 = ranges.iter().map(|r| r.len as u64).sum();
+                        self.status_message = Some(if ranges.is_empty() {
+                            format!("REU matches {}", file_label(&path))
+                        } else {
+                            format!(
+                                "{} changed range(s), {} bytes differ from {}",
+                                ranges.len(),
+                                changed,
+                                file_label(&path)
+                            )
+                        });
+                        self.reu_diff = Some(ReuDiff {
+                            snapshot_name: file_label(&path),
+                            ranges,
+                        });
+                        Task::none()
+                    }
+                }
+            }
+
+            MemoryEditorMessage::CancelReuJob => {
+                if let Some(job) = &mut self.reu_job {
+                    job.cancelled = true;
+                    self.status_message = Some("Cancelling REU read…".to_string());
+                }
+                Task::none()
+            }
+
+            MemoryEditorMessage::ReuDiffRangeSelected(start) => {
+                self.address_space = AddressSpace::Reu;
+                self.current_address = start & !0x0F;
+                self.display_length = 0x100;
+                self.address_input = format!("{:06X}", self.current_address);
+                self.length_input = self.display_length.to_string();
+                self.update_impl(MemoryEditorMessage::ReadMemory, connection, host, password)
+            }
+
+            MemoryEditorMessage::CloseReuDiff => {
+                self.reu_diff = None;
+                Task::none()
+            }
+
+            // ── Kernal write ─────────────────────────────────────
+            MemoryEditorMessage::KernalWriteClicked => Task::perform(
+                async {
+                    rfd::AsyncFileDialog::new()
+                        .add_filter("Kernal ROM", &["bin", "rom"])
+                        .add_filter("All files", &["*"])
+                        .set_title("Select Kernal ROM image")
+                        .pick_file()
+                        .await
+                        .map(|h| h.path().to_path_buf())
+                },
+                MemoryEditorMessage::KernalWritePathSelected,
+            ),
+
+            MemoryEditorMessage::KernalWritePathSelected(path_opt) => {
+                if let (Some(path), Some(h), Some(pw)) =
+                    (path_opt, host.clone(), Some(password.clone()))
+                {
+                    self.kernal_pending_path = Some(path.clone());
+                    self.is_loading = true;
+                    self.status_message = Some("Sending Kernal ROM image…".to_string());
+                    return Task::perform(
+                        async move {
+                            let data = std::fs::read(&path)
+                                .map_err(|e| format!("Read ROM failed: {}", e))?;
+                            port64::write_kernal(h, pw, data).await
+                        },
+                        MemoryEditorMessage::KernalWriteComplete,
+                    );
+                }
+                Task::none()
+            }
+
+            MemoryEditorMessage::KernalWriteComplete(result) => {
+                self.is_loading = false;
+                match &result {
+                    Ok(()) => {
+                        self.status_message = Some("Kernal ROM replaced successfully".to_string())
+                    }
+                    Err(e) => self.status_message = Some(format!("Kernal write failed: {}", e)),
+                }
+                Task::none()
+            }
+
+            // ── Flash inspector ──────────────────────────────────
+            MemoryEditorMessage::ReadFlashInfo => {
+                if let Some(h) = host.clone() {
+                    self.is_loading = true;
+                    self.status_message = Some("Reading flash info…".to_string());
+                    return Task::perform(
+                        async move { port64::flash_info(h, password).await },
+                        MemoryEditorMessage::FlashInfoComplete,
+                    );
+                }
                 Task::none()
             }
 
@@ -1320,9 +1996,460 @@ impl MemoryEditor {
                 }
                 Task::none()
             }
+
+            // ── Flash backup / verify ────────────────────────────
+            MemoryEditorMessage::BackupFlash => {
+                let default_name =
+                    format!("flash_{}.bin", chrono::Local::now().format("%Y%m%d_%H%M%S"));
+                Task::perform(
+                    async move {
+                        rfd::AsyncFileDialog::new()
+                            .set_file_name(&default_name)
+                            .add_filter("Flash image", &["bin"])
+                            .save_file()
+                            .await
+                            .map(|h| h.path().to_path_buf())
+                    },
+                    MemoryEditorMessage::BackupFlashPathSelected,
+                )
+            }
+
+            MemoryEditorMessage::BackupFlashPathSelected(path) => {
+                let (Some(path), Some(fi)) = (path, &self.flash_info) else {
+                    return Task::none();
+                };
+                let device = host.clone().unwrap_or_default();
+                match FlashBackup::create(&path, fi.page_size, fi.page_count, device) {
+                    Ok(backup) => self.start_flash_job(backup, false, host, password),
+                    Err(e) => {
+                        self.status_message = Some(format!("Flash backup failed: {}", e));
+                        Task::none()
+                    }
+                }
+            }
+
+            MemoryEditorMessage::PickFlashBackup(verify) => Task::perform(
+                async move {
+                    rfd::AsyncFileDialog::new()
+                        .add_filter("Flash backup", &["json", "bin"])
+                        .set_title(if verify {
+                            "Select flash backup to compare with the device"
+                        } else {
+                            "Select flash backup to resume"
+                        })
+                        .pick_file()
+                        .await
+                        .map(|h| h.path().to_path_buf())
+                },
+                move |path| MemoryEditorMessage::FlashBackupPicked(verify, path),
+            ),
+
+            MemoryEditorMessage::FlashBackupPicked(verify, path) => {
+                let Some(path) = path else {
+                    return Task::none();
+                };
+                match FlashBackup::open(&path) {
+                    Ok(backup) => self.start_flash_job(backup, verify, host, password),
+                    Err(e) => {
+                        self.status_message = Some(e);
+                        Task::none()
+                    }
+                }
+            }
+
+            MemoryEditorMessage::CompareFlashBackups => Task::perform(
+                async {
+                    rfd::AsyncFileDialog::new()
+                        .add_filter("Flash backup", &["json"])
+                        .set_title("Select two flash backup manifests")
+                        .pick_files()
+                        .await
+                        .map(|files| files.iter().map(|h| h.path().to_path_buf()).collect())
+                },
+                MemoryEditorMessage::CompareFlashBackupsPicked,
+            ),
+
+            MemoryEditorMessage::CompareFlashBackupsPicked(paths) => {
+                let Some(paths) = paths else {
+                    return Task::none();
+                };
+                let [a, b] = paths.as_slice() else {
+                    self.status_message = Some("Select exactly two flash backups".to_string());
+                    return Task::none();
+                };
+                let result = FlashBackup::open(a).and_then(|a| {
+                    FlashBackup::open(b).and_then(|b| {
+                        let diff = flash_backup::diff(&a.manifest, &b.manifest)?;
+                        Ok((format!("{} vs {}", a.name(), b.name()), diff))
+                    })
+                });
+                match result {
+                    Ok((title, diff)) => self.show_flash_diff(title, diff),
+                    Err(e) => self.status_message = Some(e),
+                }
+                Task::none()
+            }
+
+            MemoryEditorMessage::FlashJobPageRead(result) => {
+                let Some(job) = &mut self.flash_job else {
+                    return Task::none();
+                };
+                let page = job.pages[job.next];
+                match result {
+                    Ok((_, data)) => {
+                        if job.verify {
+                            match job.backup.page_differs(page, &data) {
+                                Some(true) => job.diff.changed.push(page),
+                                Some(false) => {}
+                                None => job.diff.unknown.push(page),
+                            }
+                        } else if let Err(e) = job.backup.store_page(page, &data) {
+                            return self.stop_flash_job(e);
+                        }
+                        job.next += 1;
+                        job.retries = 0;
+                    }
+                    // The connection can drop mid-walk; retry the page a few
+                    // times before giving up on the job.
+                    Err(e) => {
+                        job.retries += 1;
+                        if job.retries >= FLASH_PAGE_RETRIES {
+                            return self.stop_flash_job(format!("page {}: {}", page, e));
+                        }
+                    }
+                }
+                self.next_flash_step(host, password)
+            }
+
+            MemoryEditorMessage::CancelFlashJob => {
+                if let Some(job) = &mut self.flash_job {
+                    job.cancelled = true;
+                    self.status_message = Some("Cancelling flash read…".to_string());
+                }
+                Task::none()
+            }
+
+            MemoryEditorMessage::FlashDiffPageSelected(page) => {
+                self.flash_page_input = page.to_string();
+                self.update_impl(
+                    MemoryEditorMessage::ReadFlashPage(page),
+                    connection,
+                    host,
+                    password,
+                )
+            }
+
+            MemoryEditorMessage::CloseFlashDiff => {
+                self.flash_diff = None;
+                Task::none()
+            }
+        }
+    }
+
+    /// Overwrite bytes in the local copy, clipped to what was read.
+    fn patch_local(&mut self, offset: usize, bytes: &[u8]) {
+        if let Some(data) = &mut self.memory_data {
+            for (i, &b) in bytes.iter().enumerate() {
+                if let Some(slot) = data.get_mut(offset + i) {
+                    *slot = b;
+                }
+            }
         }
     }
 
+    /// Read all 64 KB for the cheat finder; `filter` is applied against the
+    /// previous snapshot when it arrives.
+    fn take_cheat_snapshot(
+        &mut self,
+        filter: Option<(CheatFilter, u8)>,
+        connection: Option<Arc<Mutex<dyn RemoteDevice>>>,
+    ) -> Task<MemoryEditorMessage> {
+        let Some(conn) = connection else {
+            self.status_message = Some("Not connected to Ultimate64".to_string());
+            return Task::none();
+        };
+        if self.cheat_busy {
+            return Task::none();
+        }
+        self.cheat_busy = true;
+        self.status_message = Some("Taking 64 KB snapshot…".to_string());
+        Task::perform(
+            async move { read_memory_async(conn, 0x0000, 0x10000).await },
+            move |result| MemoryEditorMessage::CheatSnapshotTaken(filter, result),
+        )
+    }
+
+    /// `$0400` for C64 RAM, `REU $012345` for the REU.
+    fn address_label(&self, address: u32) -> String {
+        match self.address_space {
+            AddressSpace::C64Ram => format!("${:04X}", address),
+            AddressSpace::Reu => format!("REU ${:06X}", address),
+        }
+    }
+
+    /// Begin reading the whole flash into (or against) a backup, one page per
+    /// message via [`Self::next_flash_step`]. A backup only reads the pages it
+    /// is missing, so the same call resumes an interrupted one.
+    fn start_flash_job(
+        &mut self,
+        backup: FlashBackup,
+        verify: bool,
+        host: Option<String>,
+        password: Option<String>,
+    ) -> Task<MemoryEditorMessage> {
+        let Some(fi) = &self.flash_info else {
+            return Task::none();
+        };
+        if self.flash_job.is_some() || self.is_loading {
+            self.status_message = Some("Busy — wait for the current operation".to_string());
+            return Task::none();
+        }
+        if !backup.matches(fi.page_size, fi.page_count) {
+            self.status_message = Some(format!(
+                "{} is {} pages × {} bytes; this device has {} × {}",
+                backup.name(),
+                backup.manifest.page_count,
+                backup.manifest.page_size,
+                fi.page_count,
+                fi.page_size
+            ));
+            return Task::none();
+        }
+        if verify {
+            match backup.check_image() {
+                Ok(bad) if !bad.is_empty() => {
+                    self.status_message = Some(format!(
+                        "{} is damaged: {} page(s) no longer match the manifest",
+                        backup.name(),
+                        bad.len()
+                    ));
+                    return Task::none();
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    self.status_message = Some(e);
+                    return Task::none();
+                }
+            }
+        }
+        // A resume fills the missing pages from whatever device is connected,
+        // so a backup from another device would end up mixing two flashes.
+        if !verify && backup.manifest.device != host.clone().unwrap_or_default() {
+            self.status_message = Some(format!(
+                "{} was taken from {}; connect to that device to resume it",
+                backup.name(),
+                backup.manifest.device
+            ));
+            return Task::none();
+        }
+        if !verify && backup.is_complete() {
+            self.status_message = Some(format!("{} is already complete", backup.name()));
+            return Task::none();
+        }
+        let pages = if verify {
+            (0..fi.page_count).collect()
+        } else {
+            backup.missing_pages()
+        };
+        self.flash_diff = None;
+        self.flash_job = Some(FlashJob {
+            backup,
+            verify,
+            pages,
+            next: 0,
+            retries: 0,
+            cancelled: false,
+            diff: FlashDiff::default(),
+        });
+        self.is_loading = true;
+        self.next_flash_step(host, password)
+    }
+
+    /// Read the next page, or wrap up once every page is done or the job
+    /// was cancelled.
+    fn next_flash_step(
+        &mut self,
+        host: Option<String>,
+        password: Option<String>,
+    ) -> Task<MemoryEditorMessage> {
+        let Some(job) = &mut self.flash_job else {
+            return Task::none();
+        };
+        let Some(host) = host else {
+            return self.stop_flash_job("connection lost".to_string());
+        };
+        if job.cancelled {
+            return self.stop_flash_job("cancelled".to_string());
+        }
+        let Some(&page) = job.pages.get(job.next) else {
+            let job = self.flash_job.take().expect("flash job");
+            self.is_loading = false;
+            if job.verify {
+                self.show_flash_diff(format!("Device vs {}", job.backup.name()), job.diff);
+            } else {
+                self.status_message = Some(match job.backup.save() {
+                    Ok(()) => format!(
+                        "Flash backup complete: {} pages saved to {}",
+                        job.backup.manifest.page_count,
+                        job.backup.name()
+                    ),
+                    Err(e) => format!("Flash backup failed: {}", e),
+                });
+            }
+            return Task::none();
+        };
+        self.status_message = Some(format!(
+            "{} flash… page {} ({}%)",
+            if job.verify {
+                "Verifying"
+            } else {
+                "Backing up"
+            },
+            page,
+            job.next * 100 / job.pages.len()
+        ));
+        let backoff = FLASH_RETRY_BACKOFF * job.retries as u32;
+        Task::perform(
+            async move {
+                if !backoff.is_zero() {
+                    tokio::time::sleep(backoff).await;
+                }
+                port64::flash_page(host, password, page).await
+            },
+            MemoryEditorMessage::FlashJobPageRead,
+        )
+    }
+
+    /// End a flash job early. A backup keeps every page read so far.
+    fn stop_flash_job(&mut self, reason: String) -> Task<MemoryEditorMessage> {
+        self.is_loading = false;
+        let Some(job) = self.flash_job.take() else {
+            return Task::none();
+        };
+        self.status_message = Some(if job.verify {
+            format!("Flash check stopped: {}", reason)
+        } else {
+            let saved = job.backup.save().err();
+            let done = job.backup.manifest.pages.iter().flatten().count();
+            match saved {
+                Some(e) => format!(
+                    "Flash backup stopped: {}; manifest not saved: {}",
+                    reason, e
+                ),
+                None => format!(
+                    "Flash backup stopped ({}) with {}/{} pages — Resume continues from there",
+                    reason, done, job.backup.manifest.page_count
+                ),
+            }
+        });
+        Task::none()
+    }
+
+    fn show_flash_diff(&mut self, title: String, diff: FlashDiff) {
+        if diff == FlashDiff::default() {
+            self.status_message = Some(format!("{}: every page matches", title));
+            return;
+        }
+        self.status_message = Some(format!(
+            "{}: {} page(s) changed, {} not in both",
+            title,
+            diff.changed.len(),
+            diff.unknown.len()
+        ));
+        self.flash_diff = Some(FlashDiffView { title, diff });
+    }
+
+    /// Begin a whole-REU read: open the stub session, then
+    /// [`Self::next_reu_step`] fetches one window per message.
+    fn start_reu_job(
+        &mut self,
+        kind: ReuJobKind,
+        connection: Option<Arc<Mutex<dyn RemoteDevice>>>,
+    ) -> Task<MemoryEditorMessage> {
+        let Some(conn) = connection else {
+            self.status_message = Some("Not connected to Ultimate64".to_string());
+            return Task::none();
+        };
+        if self.reu_job.is_some() || self.is_loading {
+            self.status_message = Some("Busy — wait for the current operation".to_string());
+            return Task::none();
+        }
+        self.watch_active = false;
+        self.reu_diff = None;
+        self.reu_job = Some(ReuJob {
+            kind,
+            size: self.reu_size.0,
+            data: Vec::with_capacity(self.reu_size.0 as usize),
+            backup: None,
+            cancelled: false,
+            error: None,
+        });
+        self.is_loading = true;
+        self.status_message = Some(format!("Reading {} REU…", self.reu_size));
+        Task::perform(
+            reu::open_session_async(conn),
+            MemoryEditorMessage::ReuSessionOpened,
+        )
+    }
+
+    /// Fetch the next window, or close the session once the job is done,
+    /// cancelled or failed.
+    fn next_reu_step(
+        &mut self,
+        connection: Option<Arc<Mutex<dyn RemoteDevice>>>,
+    ) -> Task<MemoryEditorMessage> {
+        let Some(job) = &mut self.reu_job else {
+            return Task::none();
+        };
+        let Some(conn) = connection else {
+            // Nothing to restore through; the stub stays parked.
+            self.reu_job = None;
+            self.is_loading = false;
+            self.status_message = Some("Connection lost during REU read".to_string());
+            return Task::none();
+        };
+        let done = job.data.len() >= job.size as usize;
+        if done || job.cancelled || job.error.is_some() {
+            let Some(backup) = job.backup.clone() else {
+                return Task::done(MemoryEditorMessage::ReuSessionClosed(Ok(())));
+            };
+            return Task::perform(
+                reu::close_session_async(conn, backup),
+                MemoryEditorMessage::ReuSessionClosed,
+            );
+        }
+        let offset = job.data.len() as u32;
+        let length = (job.size as usize - job.data.len()).min(reu::WINDOW_LEN);
+        Task::perform(
+            reu::fetch_async(conn, offset, length),
+            MemoryEditorMessage::ReuWindowFetched,
+        )
+    }
+
+    /// Symbol for an address in the disassembly: a bookmark or quick
+    /// location that starts there, else `Name+$offset` inside the smallest
+    /// enclosing range.
+    fn label_for(&self, address: u16) -> Option<String> {
+        let bookmarks = self
+            .bookmarks
+            .iter()
+            .filter(|b| b.space == BookmarkSpace::C64Ram)
+            .map(|b| (b.label.as_str(), b.address, b.length));
+        let locations = MEMORY_LOCATIONS
+            .iter()
+            .map(|l| (l.name, l.address as u32, l.length));
+        let ranges: Vec<(&str, u32, u32)> = bookmarks.chain(locations).collect();
+        let address = address as u32;
+        if let Some((name, _, _)) = ranges.iter().find(|(_, start, _)| *start == address) {
+            return Some(name.to_string());
+        }
+        ranges
+            .iter()
+            .filter(|(_, start, len)| address > *start && address < start + len)
+            .min_by_key(|(_, _, len)| *len)
+            .map(|(name, start, _)| format!("{}+${:X}", name, address - start))
+    }
+
     // ── Search ───────────────────────────────────────────────────
 
     fn perform_search(&self, data: &[u8]) -> Vec<usize> {
@@ -1389,7 +2516,10 @@ impl MemoryEditor {
             column![
                 self.view_controls(font_size),
                 rule::horizontal(1),
-                if self.memory_data.is_some() {
+                self.view_reu_diff(font_size),
+                if self.cheat_open {
+                    self.view_cheat_finder(font_size)
+                } else if self.memory_data.is_some() {
                     self.view_memory_display(font_size)
                 } else if self.flash_info.is_some() {
                     self.view_flash_inspector(font_size)
@@ -1450,22 +2580,23 @@ impl MemoryEditor {
         .align_y(iced::Alignment::Center);
 
         let read_btn_inner = button(text("Read").size(fs.small))
-            .on_press_maybe(
-                if self.is_loading || self.address_space == AddressSpace::Reu {
-                    None
-                } else {
-                    Some(MemoryEditorMessage::ReadMemory)
-                },
-            )
+            .on_press_maybe(if self.is_loading {
+                None
+            } else {
+                Some(MemoryEditorMessage::ReadMemory)
+            })
             .padding([5, 15]);
 
         let read_btn: Element<'_, MemoryEditorMessage> = if self.address_space == AddressSpace::Reu
         {
             tooltip(
                 read_btn_inner,
-                container(text("REU read not supported by firmware").size(fs.small))
-                    .padding(6)
-                    .style(tooltip_style),
+                container(
+                    text("Briefly parks the C64 in a fetch stub (KERNAL must be banked in)")
+                        .size(fs.small),
+                )
+                .padding(6)
+                .style(tooltip_style),
                 tooltip::Position::Bottom,
             )
             .into()
@@ -1523,7 +2654,8 @@ impl MemoryEditor {
                     DisplayMode::Hex,
                     DisplayMode::Ascii,
                     DisplayMode::Decimal,
-                    DisplayMode::Binary
+                    DisplayMode::Binary,
+                    DisplayMode::Disasm
                 ],
                 Some(self.display_mode),
                 MemoryEditorMessage::DisplayModeChanged,
@@ -1609,6 +2741,14 @@ impl MemoryEditor {
                 button::secondary
             })
             .padding([5, 10]),
+            button(text("🎯 Cheat Finder").size(fs.small))
+                .on_press(MemoryEditorMessage::ToggleCheatFinder)
+                .style(if self.cheat_open {
+                    button::primary
+                } else {
+                    button::secondary
+                })
+                .padding([5, 10]),
             Space::new().width(Length::Fixed(20.0)),
             // Raw-socket DMA tools
             button(text("Write ROM…").size(fs.small))
@@ -1672,11 +2812,236 @@ impl MemoryEditor {
             .into()
         };
 
-        column![first_row, search_row, fill_row, undo_row, bm_row, status_row]
-            .spacing(8)
+        let mut controls = column![first_row, search_row, fill_row, undo_row].spacing(8);
+        if self.address_space == AddressSpace::Reu {
+            controls = controls.push(self.view_reu_tools(fs.small));
+        }
+        controls.push(bm_row).push(status_row).into()
+    }
+
+    // ── Whole-REU tools ──────────────────────────────────────────
+
+    fn view_reu_tools(&self, sf: u32) -> Element<'_, MemoryEditorMessage> {
+        let busy = self.is_loading || self.reu_job.is_some();
+        let sizes: Vec<ReuSize> = reu::REU_SIZES.iter().map(|&s| ReuSize(s)).collect();
+        let mut tools = row![
+            text("REU size:").size(sf),
+            pick_list(
+                sizes,
+                Some(self.reu_size),
+                MemoryEditorMessage::ReuSizeChanged
+            )
+            .text_size(sf)
+            .width(Length::Fixed(90.0)),
+            button(text("Dump REU…").size(sf))
+                .on_press_maybe((!busy).then_some(MemoryEditorMessage::DumpReu))
+                .padding([5, 10]),
+            button(text("Restore .reu…").size(sf))
+                .on_press_maybe((!busy).then_some(MemoryEditorMessage::RestoreReu))
+                .padding([5, 10]),
+            button(text("Diff vs .reu…").size(sf))
+                .on_press_maybe((!busy).then_some(MemoryEditorMessage::DiffReu))
+                .padding([5, 10]),
+        ]
+        .spacing(10)
+        .align_y(iced::Alignment::Center);
+        if self.reu_job.is_some() {
+            tools = tools.push(
+                button(text("Cancel").size(sf))
+                    .on_press(MemoryEditorMessage::CancelReuJob)
+                    .style(button::danger)
+                    .padding([5, 10]),
+            );
+        }
+        tools.into()
+    }
+
+    /// Changed ranges from the last diff; click one to view it.
+    fn view_reu_diff(&self, font_size: u32) -> Element<'_, MemoryEditorMessage> {
+        let fs = crate::styles::FontSizes::from_base(font_size);
+        let Some(diff) = &self.reu_diff else {
+            return Space::new().into();
+        };
+
+        let header = row![
+            text(format!(
+                "REU vs {} — {} changed range(s)",
+                diff.snapshot_name,
+                diff.ranges.len()
+            ))
+            .size(fs.small),
+            Space::new().width(Length::Fill),
+            button(text("Close Diff").size(fs.small))
+                .on_press(MemoryEditorMessage::CloseReuDiff)
+                .padding([5, 10]),
+        ]
+        .spacing(10)
+        .align_y(iced::Alignment::Center);
+
+        let mut list = Row::new().spacing(6);
+        for range in &diff.ranges {
+            list = list.push(
+                button(
+                    text(format!(
+                        "${:06X}–${:06X} ({})",
+                        range.start,
+                        range.start + range.len - 1,
+                        range.len
+                    ))
+                    .size(fs.tiny),
+                )
+                .on_press(MemoryEditorMessage::ReuDiffRangeSelected(range.start))
+                .style(button::secondary)
+                .padding([3, 8]),
+            );
+        }
+
+        column![header, scrollable(list.wrap()).height(Length::Fixed(90.0))]
+            .spacing(6)
             .into()
     }
 
+    // ── Cheat finder ─────────────────────────────────────────────
+
+    fn view_cheat_finder(&self, font_size: u32) -> Element<'_, MemoryEditorMessage> {
+        let fs = crate::styles::FontSizes::from_base(font_size);
+        let dim = iced::Color::from_rgb(0.6, 0.6, 0.6);
+        let addr_color = iced::Color::from_rgb(0.5, 0.6, 0.9);
+
+        let controls = row![
+            button(text("New Search").size(fs.small))
+                .on_press_maybe((!self.cheat_busy).then_some(MemoryEditorMessage::CheatNewSearch))
+                .padding([5, 10]),
+            Space::new().width(Length::Fixed(10.0)),
+            text("Filter:").size(fs.small),
+            pick_list(
+                CheatFilter::ALL.to_vec(),
+                Some(self.cheat_filter),
+                MemoryEditorMessage::CheatFilterChanged,
+            )
+            .text_size(fs.small)
+            .width(Length::Fixed(140.0)),
+            text("N:").size(fs.small),
+            text_input("1", &self.cheat_value_input)
+                .on_input(MemoryEditorMessage::CheatValueChanged)
+                .on_submit(MemoryEditorMessage::CheatApplyFilter)
+                .width(Length::Fixed(50.0))
+                .size(fs.small),
+            button(text("Snapshot & Filter").size(fs.small))
+                .on_press_maybe(
+                    (!self.cheat_busy && self.cheat_search.is_some())
+                        .then_some(MemoryEditorMessage::CheatApplyFilter)
+                )
+                .style(button::primary)
+                .padding([5, 10]),
+            button(text("Reset").size(fs.small))
+                .on_press_maybe(
+                    self.cheat_search
+                        .is_some()
+                        .then_some(MemoryEditorMessage::CheatReset)
+                )
+                .padding([5, 10]),
+        ]
+        .spacing(8)
+        .align_y(iced::Alignment::Center);
+
+        // Candidates: only list them once the search is small enough to read
+        const SHOWN: usize = 200;
+        let candidates: Element<'_, MemoryEditorMessage> = match &self.cheat_search {
+            None => text(
+                "Take a snapshot with New Search, let the value change in the game, \
+                 then pick a filter and take the next snapshot.",
+            )
+            .size(fs.small)
+            .color(dim)
+            .into(),
+            Some(search) => {
+                let count = search.candidate_count();
+                let mut list = Column::new().spacing(2).push(
+                    text(if count > SHOWN {
+                        format!("{} candidates (showing first {})", count, SHOWN)
+                    } else {
+                        format!("{} candidate(s)", count)
+                    })
+                    .size(fs.small),
+                );
+                for c in search.candidates(SHOWN) {
+                    list = list.push(
+                        row![
+                            text(format!("${:04X}", c.address))
+                                .size(fs.tiny)
+                                .width(Length::Fixed(60.0))
+                                .color(addr_color),
+                            text(format!("${:02X} → ${:02X}", c.previous, c.current))
+                                .size(fs.tiny)
+                                .width(Length::Fixed(90.0)),
+                            text(format!("({})", c.current))
+                                .size(fs.tiny)
+                                .width(Length::Fixed(40.0))
+                                .color(dim),
+                            button(text("Freeze").size(fs.tiny))
+                                .on_press(MemoryEditorMessage::CheatFreeze(c.address, c.current))
+                                .padding([2, 8]),
+                        ]
+                        .spacing(8)
+                        .align_y(iced::Alignment::Center),
+                    );
+                }
+                scrollable(list).height(Length::Fill).into()
+            }
+        };
+
+        let mut freezes = Column::new()
+            .spacing(4)
+            .push(text("Freezes (re-written every 500 ms)").size(fs.small));
+        if self.freezes.is_empty() {
+            freezes = freezes.push(text("None yet").size(fs.tiny).color(dim));
+        }
+        for (idx, row) in self.freezes.iter().enumerate() {
+            freezes = freezes.push(
+                row![
+                    text(format!("${:04X}", row.freeze.address))
+                        .size(fs.tiny)
+                        .width(Length::Fixed(50.0))
+                        .color(addr_color),
+                    text("= $").size(fs.tiny),
+                    text_input("00", &row.input)
+                        .on_input(move |v| MemoryEditorMessage::FreezeValueChanged(idx, v))
+                        .width(Length::Fixed(40.0))
+                        .size(fs.tiny),
+                    button(text(if row.freeze.enabled { "On" } else { "Off" }).size(fs.tiny))
+                        .on_press(MemoryEditorMessage::FreezeToggled(idx))
+                        .style(if row.freeze.enabled {
+                            button::primary
+                        } else {
+                            button::secondary
+                        })
+                        .padding([2, 8]),
+                    button(text("✕").size(fs.tiny))
+                        .on_press(MemoryEditorMessage::RemoveFreeze(idx))
+                        .style(button::text)
+                        .padding([2, 6]),
+                ]
+                .spacing(6)
+                .align_y(iced::Alignment::Center),
+            );
+        }
+
+        column![
+            controls,
+            rule::horizontal(1),
+            row![
+                container(candidates).width(Length::FillPortion(3)),
+                rule::vertical(1),
+                scrollable(freezes).width(Length::FillPortion(2)),
+            ]
+            .spacing(10)
+            .height(Length::Fill),
+        ]
+        .spacing(8)
+        .into()
+    }
+
     // ── Bookmark bar ─────────────────────────────────────────────
 
     fn view_bookmark_bar(&self, sf: u32) -> Element<'_, MemoryEditorMessage> {
@@ -1745,6 +3110,16 @@ impl MemoryEditor {
         .spacing(10)
         .align_y(iced::Alignment::Center);
 
+        if self.display_mode == DisplayMode::Disasm {
+            return column![
+                header,
+                rule::horizontal(1),
+                self.view_disassembly(data, font_size)
+            ]
+            .spacing(5)
+            .into();
+        }
+
         let mut rows: Vec<Element<'_, MemoryEditorMessage>> = Vec::new();
 
         // Column headers
@@ -1783,7 +3158,7 @@ impl MemoryEditor {
                     .unwrap_or(false);
 
                 let byte_text = match self.display_mode {
-                    DisplayMode::Hex => format!("{:02X}", byte),
+                    DisplayMode::Hex | DisplayMode::Disasm => format!("{:02X}", byte),
                     DisplayMode::Decimal => format!("{:3}", byte),
                     DisplayMode::Binary => format!("{:08b}", byte),
                     DisplayMode::Ascii => {
@@ -1796,7 +3171,7 @@ impl MemoryEditor {
                 };
 
                 let width = match self.display_mode {
-                    DisplayMode::Hex => 24.0,
+                    DisplayMode::Hex | DisplayMode::Disasm => 24.0,
                     DisplayMode::Decimal => 30.0,
                     DisplayMode::Binary => 70.0,
                     DisplayMode::Ascii => 24.0,
@@ -1936,6 +3311,101 @@ impl MemoryEditor {
         }
     }
 
+    // ── Disassembly view ──────────────────────────────────────────
+
+    fn view_disassembly(&self, data: &[u8], font_size: u32) -> Element<'_, MemoryEditorMessage> {
+        let fs = crate::styles::FontSizes::from_base(font_size);
+        let address_color = iced::Color::from_rgb(0.4, 0.5, 0.9);
+        let dim = iced::Color::from_rgb(0.6, 0.6, 0.6);
+        let illegal_color = iced::Color::from_rgb(0.9, 0.6, 0.2);
+
+        let mut rows: Vec<Element<'_, MemoryEditorMessage>> = Vec::new();
+        let base = self.current_address as u16;
+        for insn in crate::mos6510::disassemble(base, data) {
+            let offset = insn.address.wrapping_sub(base) as usize;
+
+            if let Some(label) = self.label_for(insn.address) {
+                rows.push(
+                    text(format!("{}:", label))
+                        .size(fs.tiny)
+                        .color(address_color)
+                        .into(),
+                );
+            }
+
+            let mut line = Row::new()
+                .push(
+                    text(format!("{:04X}", insn.address))
+                        .size(fs.tiny)
+                        .width(Length::Fixed(50.0))
+                        .color(address_color),
+                )
+                .push(
+                    text(insn.hex())
+                        .size(fs.tiny)
+                        .width(Length::Fixed(80.0))
+                        .color(dim),
+                );
+
+            let editing = self
+                .editing_instruction
+                .as_ref()
+                .filter(|e| e.offset == offset);
+            if let Some(edit) = editing {
+                line = line
+                    .push(
+                        text_input("LDA #$00", &edit.input)
+                            .on_input(MemoryEditorMessage::AsmInputChanged)
+                            .on_submit(MemoryEditorMessage::AssembleConfirm)
+                            .width(Length::Fixed(160.0))
+                            .size(fs.tiny),
+                    )
+                    .push(
+                        button(text("Write").size(fs.tiny))
+                            .on_press_maybe(
+                                (!self.is_loading).then_some(MemoryEditorMessage::AssembleConfirm),
+                            )
+                            .padding([2, 8]),
+                    )
+                    .push(
+                        button(text("Cancel").size(fs.tiny))
+                            .on_press(MemoryEditorMessage::AssembleCancel)
+                            .padding([2, 8]),
+                    );
+                if let Some(err) = &edit.error {
+                    line = line.push(
+                        text(err.as_str())
+                            .size(fs.tiny)
+                            .color(iced::Color::from_rgb(0.9, 0.3, 0.3)),
+                    );
+                }
+            } else {
+                let mut source = text(insn.text()).size(fs.tiny);
+                if insn.illegal {
+                    source = source.color(illegal_color);
+                }
+                let comment = insn
+                    .target()
+                    .and_then(|t| self.label_for(t))
+                    .map(|l| format!("; {}", l))
+                    .unwrap_or_default();
+                line = line
+                    .push(
+                        button(container(source).width(Length::Fixed(160.0)))
+                            .on_press(MemoryEditorMessage::AsmLineClicked(offset))
+                            .padding(0)
+                            .style(button::text),
+                    )
+                    .push(text(comment).size(fs.tiny).color(dim));
+            }
+            rows.push(line.spacing(8).align_y(iced::Alignment::Center).into());
+        }
+
+        scrollable(Column::with_children(rows).spacing(1))
+            .height(Length::Fill)
+            .into()
+    }
+
     // ── Flash inspector view ──────────────────────────────────────
 
     fn view_flash_inspector(&self, font_size: u32) -> Element<'_, MemoryEditorMessage> {
@@ -1961,6 +3431,32 @@ impl MemoryEditor {
         .spacing(10)
         .align_y(iced::Alignment::Center);
 
+        let busy = self.is_loading || self.flash_job.is_some();
+        let mut backup_bar = row![
+            button(text("Back Up Flash…").size(fs.small))
+                .on_press_maybe((!busy).then_some(MemoryEditorMessage::BackupFlash))
+                .padding([5, 10]),
+            button(text("Resume Backup…").size(fs.small))
+                .on_press_maybe((!busy).then_some(MemoryEditorMessage::PickFlashBackup(false)))
+                .padding([5, 10]),
+            button(text("Verify vs Backup…").size(fs.small))
+                .on_press_maybe((!busy).then_some(MemoryEditorMessage::PickFlashBackup(true)))
+                .padding([5, 10]),
+            button(text("Compare Backups…").size(fs.small))
+                .on_press_maybe((!busy).then_some(MemoryEditorMessage::CompareFlashBackups))
+                .padding([5, 10]),
+        ]
+        .spacing(10)
+        .align_y(iced::Alignment::Center);
+        if self.flash_job.is_some() {
+            backup_bar = backup_bar.push(
+                button(text("Cancel").size(fs.small))
+                    .on_press(MemoryEditorMessage::CancelFlashJob)
+                    .style(button::danger)
+                    .padding([5, 10]),
+            );
+        }
+
         let page_controls = row![
             text("Page:").size(fs.small),
             text_input("0", &self.flash_page_input)
@@ -2016,6 +3512,8 @@ impl MemoryEditor {
 
         column![
             info_bar,
+            backup_bar,
+            self.view_flash_diff(fs.small, fs.tiny),
             rule::horizontal(1),
             page_controls,
             rule::horizontal(1),
@@ -2025,6 +3523,52 @@ impl MemoryEditor {
         .into()
     }
 
+    /// Pages that differ in the last check or compare; click one to read it
+    /// from the device.
+    fn view_flash_diff(&self, sf: u32, tf: u32) -> Element<'_, MemoryEditorMessage> {
+        let Some(view) = &self.flash_diff else {
+            return Space::new().into();
+        };
+
+        let header = row![
+            text(format!(
+                "{} — {} changed, {} not in both",
+                view.title,
+                view.diff.changed.len(),
+                view.diff.unknown.len()
+            ))
+            .size(sf),
+            Space::new().width(Length::Fill),
+            button(text("Close Diff").size(sf))
+                .on_press(MemoryEditorMessage::CloseFlashDiff)
+                .padding([5, 10]),
+        ]
+        .spacing(10)
+        .align_y(iced::Alignment::Center);
+
+        let mut list = Row::new().spacing(6);
+        for &page in &view.diff.changed {
+            list = list.push(
+                button(text(format!("Page {}", page)).size(tf))
+                    .on_press(MemoryEditorMessage::FlashDiffPageSelected(page))
+                    .style(button::secondary)
+                    .padding([3, 8]),
+            );
+        }
+        if let (Some(first), Some(last)) = (view.diff.unknown.first(), view.diff.unknown.last()) {
+            // Unread pages come from an unfinished backup; a range is enough.
+            list = list.push(
+                text(format!("Not read: pages {}–{}", first, last))
+                    .size(tf)
+                    .color(iced::Color::from_rgb(0.6, 0.6, 0.6)),
+            );
+        }
+
+        column![header, scrollable(list.wrap()).height(Length::Fixed(90.0))]
+            .spacing(6)
+            .into()
+    }
+
     // ── Quick locations grid ──────────────────────────────────────
 
     fn view_quick_locations(&self, font_size: u32) -> Element<'_, MemoryEditorMessage> {
@@ -2116,7 +3660,7 @@ fn tooltip_style(theme: &iced::Theme) -> container::Style {
 ///   `400h`  → Some(0x400)
 ///   `1FF`   → Some(0x1FF)        auto-hex (contains `F`)
 ///   ``      → None
-fn parse_length_input(input: &str) -> Option<u32> {
+pub(crate) fn parse_length_input(input: &str) -> Option<u32> {
     let trimmed = input.trim();
     if trimmed.is_empty() {
         return None;
@@ -2187,6 +3731,41 @@ async fn write_memory_async(
     crate::api::write_memory_async(connection, address, data).await
 }
 
+/// Write every frozen byte, one REST call each.
+async fn write_freezes_async(
+    connection: Arc<Mutex<dyn RemoteDevice>>,
+    freezes: Vec<(u16, u8)>,
+) -> Result<(), String> {
+    for (address, value) in freezes {
+        write_byte_async(connection.clone(), address, value).await?;
+    }
+    Ok(())
+}
+
+// ─────────────────────────────────────────────────────────────────
+//  .reu files
+// ─────────────────────────────────────────────────────────────────
+
+fn file_label(path: &std::path::Path) -> String {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("file")
+        .to_string()
+}
+
+/// Read a `.reu` image, rejecting lengths that aren't a REU size.
+fn read_reu_file(path: &std::path::Path) -> Result<Vec<u8>, String> {
+    let data = std::fs::read(path).map_err(|e| format!("Failed to read file: {}", e))?;
+    if !reu::is_valid_reu_size(data.len()) {
+        return Err(format!(
+            "{} is {} bytes — not a REU size (128 KB to 16 MB)",
+            file_label(path),
+            data.len()
+        ));
+    }
+    Ok(data)
+}
+
 #[cfg(test)]
 mod tests {
     use super::parse_length_input;
@@ -0,0 +1,476 @@
+//! 6510 disassembler and one-line assembler for the memory editor.
+//!
+//! Decodes all 256 opcodes, including the NMOS "illegal" ones (SLO, LAX,
+//! DCP, …) that C64 code uses routinely. Mnemonics follow the "No More
+//! Secrets" naming; the assembler also accepts the common aliases
+//! (ISB, ASR, AXS, XAA, AHX, KIL, …).
+
+// ─────────────────────────────────────────────────────────────────
+//  Opcode table
+// ─────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddrMode {
+    Implied,
+    Accumulator,
+    Immediate,
+    ZeroPage,
+    ZeroPageX,
+    ZeroPageY,
+    Absolute,
+    AbsoluteX,
+    AbsoluteY,
+    Indirect,
+    IndirectX,
+    IndirectY,
+    Relative,
+    /// Trailing bytes too short to hold the instruction they start.
+    Data,
+}
+
+impl AddrMode {
+    /// Instruction length in bytes, opcode included.
+    pub fn len(self) -> usize {
+        use AddrMode::*;
+        match self {
+            Implied | Accumulator | Data => 1,
+            Immediate | ZeroPage | ZeroPageX | ZeroPageY | IndirectX | IndirectY | Relative => 2,
+            Absolute | AbsoluteX | AbsoluteY | Indirect => 3,
+        }
+    }
+}
+
+use AddrMode::{
+    Absolute as ABS, AbsoluteX as ABX, AbsoluteY as ABY, Accumulator as ACC, Immediate as IMM,
+    Implied as IMP, Indirect as IND, IndirectX as IZX, IndirectY as IZY, Relative as REL,
+    ZeroPage as ZP, ZeroPageX as ZPX, ZeroPageY as ZPY,
+};
+
+#[rustfmt::skip]
+const OPCODES: [(&str, AddrMode); 256] = [
+    // 0x00
+    ("BRK", IMP), ("ORA", IZX), ("JAM", IMP), ("SLO", IZX), ("NOP", ZP),  ("ORA", ZP),  ("ASL", ZP),  ("SLO", ZP),
+    ("PHP", IMP), ("ORA", IMM), ("ASL", ACC), ("ANC", IMM), ("NOP", ABS), ("ORA", ABS), ("ASL", ABS), ("SLO", ABS),
+    // 0x10
+    ("BPL", REL), ("ORA", IZY), ("JAM", IMP), ("SLO", IZY), ("NOP", ZPX), ("ORA", ZPX), ("ASL", ZPX), ("SLO", ZPX),
+    ("CLC", IMP), ("ORA", ABY), ("NOP", IMP), ("SLO", ABY), ("NOP", ABX), ("ORA", ABX), ("ASL", ABX), ("SLO", ABX),
+    // 0x20
+    ("JSR", ABS), ("AND", IZX), ("JAM", IMP), ("RLA", IZX), ("BIT", ZP),  ("AND", ZP),  ("ROL", ZP),  ("RLA", ZP),
+    ("PLP", IMP), ("AND", IMM), ("ROL", ACC), ("ANC", IMM), ("BIT", ABS), ("AND", ABS), ("ROL", ABS), ("RLA", ABS),
+    // 0x30
+    ("BMI", REL), ("AND", IZY), ("JAM", IMP), ("RLA", IZY), ("NOP", ZPX), ("AND", ZPX), ("ROL", ZPX), ("RLA", ZPX),
+    ("SEC", IMP), ("AND", ABY), ("NOP", IMP), ("RLA", ABY), ("NOP", ABX), ("AND", ABX), ("ROL", ABX), ("RLA", ABX),
+    // 0x40
+    ("RTI", IMP), ("EOR", IZX), ("JAM", IMP), ("SRE", IZX), ("NOP", ZP),  ("EOR", ZP),  ("LSR", ZP),  ("SRE", ZP),
+    ("PHA", IMP), ("EOR", IMM), ("LSR", ACC), ("ALR", IMM), ("JMP", ABS), ("EOR", ABS), ("LSR", ABS), ("SRE", ABS),
+    // 0x50
+    ("BVC", REL), ("EOR", IZY), ("JAM", IMP), ("SRE", IZY), ("NOP", ZPX), ("EOR", ZPX), ("LSR", ZPX), ("SRE", ZPX),
+    ("CLI", IMP), ("EOR", ABY), ("NOP", IMP), ("SRE", ABY), ("NOP", ABX), ("EOR", ABX), ("LSR", ABX), ("SRE", ABX),
+    // 0x60
+    ("RTS", IMP), ("ADC", IZX), ("JAM", IMP), ("RRA", IZX), ("NOP", ZP),  ("ADC", ZP),  ("ROR", ZP),  ("RRA", ZP),
+    ("PLA", IMP), ("ADC", IMM), ("ROR", ACC), ("ARR", IMM), ("JMP", IND), ("ADC", ABS), ("ROR", ABS), ("RRA", ABS),
+    // 0x70
+    ("BVS", REL), ("ADC", IZY), ("JAM", IMP), ("RRA", IZY), ("NOP", ZPX), ("ADC", ZPX), ("ROR", ZPX), ("RRA", ZPX),
+    ("SEI", IMP), ("ADC", ABY), ("NOP", IMP), ("RRA", ABY), ("NOP", ABX), ("ADC", ABX), ("ROR", ABX), ("RRA", ABX),
+    // 0x80
+    ("NOP", IMM), ("STA", IZX), ("NOP", IMM), ("SAX", IZX), ("STY", ZP),  ("STA", ZP),  ("STX", ZP),  ("SAX", ZP),
+    ("DEY", IMP), ("NOP", IMM), ("TXA", IMP), ("ANE", IMM), ("STY", ABS), ("STA", ABS), ("STX", ABS), ("SAX", ABS),
+    // 0x90
+    ("BCC", REL), ("STA", IZY), ("JAM", IMP), ("SHA", IZY), ("STY", ZPX), ("STA", ZPX), ("STX", ZPY), ("SAX", ZPY),
+    ("TYA", IMP), ("STA", ABY), ("TXS", IMP), ("TAS", ABY), ("SHY", ABX), ("STA", ABX), ("SHX", ABY), ("SHA", ABY),
+    // 0xA0
+    ("LDY", IMM), ("LDA", IZX), ("LDX", IMM), ("LAX", IZX), ("LDY", ZP),  ("LDA", ZP),  ("LDX", ZP),  ("LAX", ZP),
+    ("TAY", IMP), ("LDA", IMM), ("TAX", IMP), ("LXA", IMM), ("LDY", ABS), ("LDA", ABS), ("LDX", ABS), ("LAX", ABS),
+    // 0xB0
+    ("BCS", REL), ("LDA", IZY), ("JAM", IMP), ("LAX", IZY), ("LDY", ZPX), ("LDA", ZPX), ("LDX", ZPY), ("LAX", ZPY),
+    ("CLV", IMP), ("LDA", ABY), ("TSX", IMP), ("LAS", ABY), ("LDY", ABX), ("LDA", ABX), ("LDX", ABY), ("LAX", ABY),
+    // 0xC0
+    ("CPY", IMM), ("CMP", IZX), ("NOP", IMM), ("DCP", IZX), ("CPY", ZP),  ("CMP", ZP),  ("DEC", ZP),  ("DCP", ZP),
+    ("INY", IMP), ("CMP", IMM), ("DEX", IMP), ("SBX", IMM), ("CPY", ABS), ("CMP", ABS), ("DEC", ABS), ("DCP", ABS),
+    // 0xD0
+    ("BNE", REL), ("CMP", IZY), ("JAM", IMP), ("DCP", IZY), ("NOP", ZPX), ("CMP", ZPX), ("DEC", ZPX), ("DCP", ZPX),
+    ("CLD", IMP), ("CMP", ABY), ("NOP", IMP), ("DCP", ABY), ("NOP", ABX), ("CMP", ABX), ("DEC", ABX), ("DCP", ABX),
+    // 0xE0
+    ("CPX", IMM), ("SBC", IZX), ("NOP", IMM), ("ISC", IZX), ("CPX", ZP),  ("SBC", ZP),  ("INC", ZP),  ("ISC", ZP),
+    ("INX", IMP), ("SBC", IMM), ("NOP", IMP), ("SBC", IMM), ("CPX", ABS), ("SBC", ABS), ("INC", ABS), ("ISC", ABS),
+    // 0xF0
+    ("BEQ", REL), ("SBC", IZY), ("JAM", IMP), ("ISC", IZY), ("NOP", ZPX), ("SBC", ZPX), ("INC", ZPX), ("ISC", ZPX),
+    ("SED", IMP), ("SBC", ABY), ("NOP", IMP), ("ISC", ABY), ("NOP", ABX), ("SBC", ABX), ("INC", ABX), ("ISC", ABX),
+];
+
+/// Mnemonics that only exist as undocumented opcodes.
+const ILLEGAL_MNEMONICS: &[&str] = &[
+    "SLO", "RLA", "SRE", "RRA", "SAX", "LAX", "DCP", "ISC", "ANC", "ALR", "ARR", "ANE", "LXA",
+    "SBX", "SHA", "SHX", "SHY", "TAS", "LAS", "JAM",
+];
+
+/// Alternative names other assemblers use, mapped to ours.
+const ALIASES: &[(&str, &str)] = &[
+    ("ISB", "ISC"),
+    ("INS", "ISC"),
+    ("DCM", "DCP"),
+    ("ASO", "SLO"),
+    ("LSE", "SRE"),
+    ("AXS", "SBX"),
+    ("ASR", "ALR"),
+    ("XAA", "ANE"),
+    ("AHX", "SHA"),
+    ("AXA", "SHA"),
+    ("SHS", "TAS"),
+    ("XAS", "SHX"),
+    ("SAY", "SHY"),
+    ("LAR", "LAS"),
+    ("OAL", "LXA"),
+    ("KIL", "JAM"),
+    ("HLT", "JAM"),
+];
+
+/// True for undocumented opcodes, including the duplicate NOPs and `$EB`.
+pub fn is_illegal(opcode: u8) -> bool {
+    let (mnemonic, _) = OPCODES[opcode as usize];
+    ILLEGAL_MNEMONICS.contains(&mnemonic) || (mnemonic == "NOP" && opcode != 0xEA) || opcode == 0xEB
+}
+
+// ─────────────────────────────────────────────────────────────────
+//  Disassembler
+// ─────────────────────────────────────────────────────────────────
+
+/// One decoded instruction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Instruction {
+    pub address: u16,
+    pub bytes: Vec<u8>,
+    pub mnemonic: &'static str,
+    pub mode: AddrMode,
+    pub illegal: bool,
+}
+
+impl Instruction {
+    /// The address the operand refers to — branch target, jump target or
+    /// memory operand. `None` for implied / immediate instructions.
+    pub fn target(&self) -> Option<u16> {
+        let lo = *self.bytes.get(1)? as u16;
+        match self.mode {
+            ZP | ZPX | ZPY | IZX | IZY => Some(lo),
+            ABS | ABX | ABY | IND => Some(lo | (*self.bytes.get(2)? as u16) << 8),
+            REL => Some(
+                self.address
+                    .wrapping_add(2)
+                    .wrapping_add(lo as u8 as i8 as u16),
+            ),
+            _ => None,
+        }
+    }
+
+    /// Operand in standard assembler syntax (`#$01`, `$D020,X`, `($FB),Y`, …).
+    pub fn operand_text(&self) -> String {
+        let b = |i: usize| self.bytes.get(i).copied().unwrap_or(0);
+        let word = || b(1) as u16 | (b(2) as u16) << 8;
+        match self.mode {
+            IMP => String::new(),
+            ACC => "A".to_string(),
+            IMM => format!("#${:02X}", b(1)),
+            ZP => format!("${:02X}", b(1)),
+            ZPX => format!("${:02X},X", b(1)),
+            ZPY => format!("${:02X},Y", b(1)),
+            ABS => format!("${:04X}", word()),
+            ABX => format!("${:04X},X", word()),
+            ABY => format!("${:04X},Y", word()),
+            IND => format!("(${:04X})", word()),
+            IZX => format!("(${:02X},X)", b(1)),
+            IZY => format!("(${:02X}),Y", b(1)),
+            REL => format!("${:04X}", self.target().unwrap_or(0)),
+            AddrMode::Data => self
+                .bytes
+                .iter()
+                .map(|b| format!("${:02X}", b))
+                .collect::<Vec<_>>()
+                .join(","),
+        }
+    }
+
+    /// `LDA #$01`-style source line, which [`assemble`] accepts back.
+    pub fn text(&self) -> String {
+        let operand = self.operand_text();
+        if operand.is_empty() {
+            self.mnemonic.to_string()
+        } else {
+            format!("{} {}", self.mnemonic, operand)
+        }
+    }
+
+    /// Instruction bytes as `A9 01`.
+    pub fn hex(&self) -> String {
+        self.bytes
+            .iter()
+            .map(|b| format!("{:02X}", b))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// Decode the instruction at the start of `bytes`. `None` when `bytes` is
+/// empty or too short for the opcode's operand.
+pub fn decode(address: u16, bytes: &[u8]) -> Option<Instruction> {
+    let opcode = *bytes.first()?;
+    let (mnemonic, mode) = OPCODES[opcode as usize];
+    let bytes = bytes.get(..mode.len())?.to_vec();
+    Some(Instruction {
+        address,
+        bytes,
+        mnemonic,
+        mode,
+        illegal: is_illegal(opcode),
+    })
+}
+
+/// Disassemble a linear block. A truncated instruction at the end becomes
+/// a `.BYTE` line so every input byte is accounted for.
+pub fn disassemble(base: u16, data: &[u8]) -> Vec<Instruction> {
+    let mut out = Vec::new();
+    let mut offset = 0;
+    while offset < data.len() {
+        let address = base.wrapping_add(offset as u16);
+        let insn = decode(address, &data[offset..]).unwrap_or_else(|| Instruction {
+            address,
+            bytes: data[offset..].to_vec(),
+            mnemonic: ".BYTE",
+            mode: AddrMode::Data,
+            illegal: false,
+        });
+        offset += insn.bytes.len();
+        out.push(insn);
+    }
+    out
+}
+
+// ─────────────────────────────────────────────────────────────────
+//  Assembler
+// ─────────────────────────────────────────────────────────────────
+
+/// Assemble one source line (`LDA #$01`, `STA $D020,X`, `BNE $C010`, …)
+/// for the given address. Numbers are `$hex`, `%binary` or decimal; a
+/// zero-page operand written with four hex digits forces absolute mode.
+pub fn assemble(address: u16, line: &str) -> Result<Vec<u8>, String> {
+    let line = line.split(';').next().unwrap_or_default().trim();
+    if line.is_empty() {
+        return Err("Nothing to assemble".to_string());
+    }
+    let (mnemonic, operand) = line
+        .split_once(char::is_whitespace)
+        .map(|(m, o)| (m, o.trim()))
+        .unwrap_or((line, ""));
+    let mnemonic = mnemonic.to_ascii_uppercase();
+    let mnemonic = ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == mnemonic)
+        .map(|(_, name)| name.to_string())
+        .unwrap_or(mnemonic);
+    if !OPCODES.iter().any(|(m, _)| *m == mnemonic) {
+        return Err(format!("Unknown mnemonic '{}'", mnemonic));
+    }
+    let has = |mode| find_opcode(&mnemonic, mode).is_some();
+    let operand: String = operand.chars().filter(|c| !c.is_whitespace()).collect();
+    let upper = operand.to_ascii_uppercase();
+
+    let (mode, value) = if upper.is_empty() {
+        if has(ACC) {
+            (ACC, 0)
+        } else {
+            (IMP, 0)
+        }
+    } else if upper == "A" && has(ACC) {
+        (ACC, 0)
+    } else if let Some(v) = upper.strip_prefix('#') {
+        (IMM, parse_number(v)?.0)
+    } else if let Some(inner) = upper.strip_prefix('(').and_then(|s| s.strip_suffix(",X)")) {
+        (IZX, parse_number(inner)?.0)
+    } else if let Some(inner) = upper.strip_prefix('(').and_then(|s| s.strip_suffix("),Y")) {
+        (IZY, parse_number(inner)?.0)
+    } else if let Some(inner) = upper.strip_prefix('(').and_then(|s| s.strip_suffix(')')) {
+        (IND, parse_number(inner)?.0)
+    } else if let Some(v) = upper.strip_suffix(",X") {
+        let (value, wide) = parse_number(v)?;
+        let zp = value < 0x100 && !wide && has(ZPX);
+        (if zp { ZPX } else { ABX }, value)
+    } else if let Some(v) = upper.strip_suffix(",Y") {
+        let (value, wide) = parse_number(v)?;
+        let zp = value < 0x100 && !wide && has(ZPY);
+        (if zp { ZPY } else { ABY }, value)
+    } else {
+        let (value, wide) = parse_number(&upper)?;
+        if has(REL) {
+            (REL, value)
+        } else if value < 0x100 && !wide && has(ZP) {
+            (ZP, value)
+        } else {
+            (ABS, value)
+        }
+    };
+
+    let opcode = find_opcode(&mnemonic, mode)
+        .ok_or_else(|| format!("{} does not support that addressing mode", mnemonic))?;
+    let mut bytes = vec![opcode];
+    match mode.len() {
+        2 if mode == REL => {
+            let delta = value as i32 - (address as i32 + 2);
+            let delta = if delta > 0x7FFF {
+                delta - 0x10000
+            } else {
+                delta
+            };
+            let delta = if delta < -0x8000 {
+                delta + 0x10000
+            } else {
+                delta
+            };
+            if !(-128..=127).contains(&delta) {
+                return Err(format!("Branch to ${:04X} is out of range", value));
+            }
+            bytes.push(delta as i8 as u8);
+        }
+        2 => {
+            if value > 0xFF {
+                return Err(format!("Operand ${:X} does not fit in a byte", value));
+            }
+            bytes.push(value as u8);
+        }
+        3 => bytes.extend_from_slice(&value.to_le_bytes()),
+        _ => {}
+    }
+    Ok(bytes)
+}
+
+/// Documented encodings win over undocumented duplicates (NOP → `$EA`,
+/// SBC # → `$E9`).
+fn find_opcode(mnemonic: &str, mode: AddrMode) -> Option<u8> {
+    let matches = |op: &usize| OPCODES[*op] == (mnemonic, mode);
+    (0..256)
+        .filter(matches)
+        .find(|op| !is_illegal(*op as u8))
+        .or_else(|| (0..256).find(matches))
+        .map(|op| op as u8)
+}
+
+/// Parse `$hex`, `%binary` or decimal. The flag is true when the literal
+/// was written wider than a byte (`$0012`), which forces absolute mode.
+fn parse_number(text: &str) -> Result<(u16, bool), String> {
+    let bad = || format!("Bad number '{}'", text);
+    let (digits, radix) = if let Some(hex) = text.strip_prefix('$') {
+        (hex, 16)
+    } else if let Some(bin) = text.strip_prefix('%') {
+        (bin, 2)
+    } else {
+        (text, 10)
+    };
+    let value = u32::from_str_radix(digits, radix).map_err(|_| bad())?;
+    if value > 0xFFFF {
+        return Err(format!("Value '{}' does not fit in 16 bits", text));
+    }
+    let wide = match radix {
+        16 => digits.len() > 2,
+        2 => digits.len() > 8,
+        _ => value > 0xFF,
+    };
+    Ok((value as u16, wide))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn table_lengths_match_legal_instruction_count() {
+        let legal = (0..=255u8).filter(|op| !is_illegal(*op)).count();
+        assert_eq!(legal, 151);
+    }
+
+    #[test]
+    fn disassembles_a_border_flash_loop() {
+        // INC $D020 / JMP $C000
+        let code = [0xEE, 0x20, 0xD0, 0x4C, 0x00, 0xC0];
+        let lines: Vec<String> = disassemble(0xC000, &code)
+            .iter()
+            .map(|i| format!("{:04X} {}", i.address, i.text()))
+            .collect();
+        assert_eq!(lines, ["C000 INC $D020", "C003 JMP $C000"]);
+    }
+
+    #[test]
+    fn decodes_branches_and_illegal_opcodes() {
+        let bne = decode(0x1000, &[0xD0, 0xFE]).unwrap();
+        assert_eq!(bne.text(), "BNE $1000");
+        assert_eq!(bne.target(), Some(0x1000));
+
+        let lax = decode(0x1000, &[0xB3, 0xFB]).unwrap();
+        assert_eq!(lax.text(), "LAX ($FB),Y");
+        assert!(lax.illegal);
+        assert!(decode(0x1000, &[0x1A]).unwrap().illegal); // NOP
+        assert!(!decode(0x1000, &[0xEA]).unwrap().illegal);
+    }
+
+    #[test]
+    fn truncated_tail_becomes_byte_line() {
+        let lines = disassemble(0x2000, &[0xEA, 0xAD, 0x20]);
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[1].text(), ".BYTE $AD,$20");
+        assert_eq!(lines[1].address, 0x2001);
+    }
+
+    #[test]
+    fn assembles_each_addressing_mode() {
+        let cases: &[(&str, &[u8])] = &[
+            ("NOP", &[0xEA]),
+            ("ASL", &[0x0A]),
+            ("ror a", &[0x6A]),
+            ("LDA #$01", &[0xA9, 0x01]),
+            ("LDA #%1010", &[0xA9, 0x0A]),
+            ("LDX #200", &[0xA2, 0xC8]),
+            ("LDA $FB", &[0xA5, 0xFB]),
+            ("LDA $00FB", &[0xAD, 0xFB, 0x00]),
+            ("STA $D020", &[0x8D, 0x20, 0xD0]),
+            ("LDA $FB,X", &[0xB5, 0xFB]),
+            ("LDX $FB,Y", &[0xB6, 0xFB]),
+            ("LDA $FB,Y", &[0xB9, 0xFB, 0x00]),
+            ("STA $0400,X", &[0x9D, 0x00, 0x04]),
+            ("JMP ($0314)", &[0x6C, 0x14, 0x03]),
+            ("LDA ($FB,X)", &[0xA1, 0xFB]),
+            ("STA ( $FB ), Y", &[0x91, 0xFB]),
+            ("SBC #$01", &[0xE9, 0x01]),
+            ("ISB $FB", &[0xE7, 0xFB]),
+            ("LXA #$00", &[0xAB, 0x00]),
+            ("LAX ($FB),Y", &[0xB3, 0xFB]),
+            ("JSR $FFD2 ; CHROUT", &[0x20, 0xD2, 0xFF]),
+        ];
+        for (line, bytes) in cases {
+            assert_eq!(assemble(0xC000, line).as_deref(), Ok(*bytes), "{}", line);
+        }
+    }
+
+    #[test]
+    fn assembles_relative_branches() {
+        assert_eq!(assemble(0xC000, "BNE $C000"), Ok(vec![0xD0, 0xFE]));
+        assert_eq!(assemble(0xC000, "BEQ $C010"), Ok(vec![0xF0, 0x0E]));
+        assert_eq!(assemble(0xFFF0, "BCC $0010"), Ok(vec![0x90, 0x1E]));
+        assert!(assemble(0xC000, "BNE $C100").is_err());
+    }
+
+    #[test]
+    fn rejects_bad_input() {
+        assert!(assemble(0, "").is_err());
+        assert!(assemble(0, "FOO $10").is_err());
+        assert!(assemble(0, "STA #$10").is_err());
+        assert!(assemble(0, "LDA #$100").is_err());
+        assert!(assemble(0, "LDA $10000").is_err());
+    }
+
+    #[test]
+    fn every_opcode_round_trips_through_the_assembler() {
+        for op in 0..=255u8 {
+            let insn = decode(0xC000, &[op, 0x34, 0x12]).unwrap();
+            let bytes = assemble(0xC000, &insn.text()).unwrap();
+            let again = decode(0xC000, &bytes).unwrap();
+            assert_eq!(again.text(), insn.text(), "opcode {:02X}", op);
+        }
+    }
+}
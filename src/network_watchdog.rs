@@ -0,0 +1,387 @@
+//! Rollback watchdog for network settings.
+//!
+//! Changing Ethernet/WiFi settings can cut the app off from the device. Before
+//! such a change is sent, [`record`] reads the values about to be replaced and
+//! works out where the device should answer afterwards. [`watch`] then probes
+//! that address until [`WATCH_TIMEOUT`]. If the device is gone from the
+//! expected address but still answers at the old one, the old values are put
+//! back there; if it answers nowhere, the caller gets a [`Guard`] to walk the
+//! user through recovery.
+
+use crate::config_api;
+use crate::device_profile::ConfigTree;
+use std::time::{Duration, Instant};
+
+/// How long the device gets to come back after a network change
+pub const WATCH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Pause before the first probe, while the device restarts its network stack
+const SETTLE: Duration = Duration::from_secs(3);
+
+const PROBE_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Where the device should answer once the change is in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expected {
+    /// Same address as before
+    Same,
+    /// A new static address
+    Address(String),
+    /// DHCP was switched on; the new lease is not known in advance
+    Unknown,
+}
+
+/// Everything needed to check on, and undo, one network change.
+#[derive(Debug, Clone)]
+pub struct Guard {
+    /// Bare host the change was sent to
+    pub old_host: String,
+    pub expected: Expected,
+    /// Values the change replaced, by category and item
+    pub previous: ConfigTree,
+}
+
+#[derive(Debug, Clone)]
+pub enum Outcome {
+    /// The device answers at this host
+    Reachable(String),
+    /// The device was lost at the expected address, answered at the old one,
+    /// and got its previous values back
+    Reverted(String),
+    /// The device answers nowhere the app knows of
+    Lost(Guard),
+}
+
+impl Outcome {
+    pub fn summary(&self) -> String {
+        match self {
+            Outcome::Reachable(host) => format!("device answers at {}", host),
+            Outcome::Reverted(host) => format!(
+                "device did not answer at its new address; previous network settings restored at {}",
+                host
+            ),
+            Outcome::Lost(guard) => format!(
+                "device no longer answers at {} — {}",
+                guard.expected_label(),
+                guard.previous_values().join(", ")
+            ),
+        }
+    }
+}
+
+impl Guard {
+    /// Address to probe while watching.
+    fn target(&self) -> &str {
+        match &self.expected {
+            Expected::Address(ip) => ip,
+            Expected::Same | Expected::Unknown => &self.old_host,
+        }
+    }
+
+    pub fn expected_label(&self) -> String {
+        match &self.expected {
+            Expected::Same => self.old_host.clone(),
+            Expected::Address(ip) => format!("{} (was {})", ip, self.old_host),
+            Expected::Unknown => format!("{} or a new DHCP address", self.old_host),
+        }
+    }
+
+    /// `Category / Item = value` for each replaced setting, for putting them
+    /// back by hand in the device menu.
+    pub fn previous_values(&self) -> Vec<String> {
+        let mut values: Vec<String> = self
+            .previous
+            .iter()
+            .flat_map(|(category, items)| {
+                items.iter().map(move |(item, value)| {
+                    format!(
+                        "{} / {} = {}",
+                        category,
+                        item,
+                        config_api::format_value(value)
+                    )
+                })
+            })
+            .collect();
+        values.sort();
+        values
+    }
+}
+
+/// Recovery dialog state once the device is lost.
+#[derive(Debug, Clone)]
+pub struct Recovery {
+    pub guard: Guard,
+    /// A retry is probing the device
+    pub checking: bool,
+}
+
+impl Recovery {
+    pub fn new(guard: Guard) -> Self {
+        Self {
+            guard,
+            checking: false,
+        }
+    }
+
+    /// What to try, in order.
+    pub fn steps(&self) -> Vec<String> {
+        let mut steps = vec![
+            "Look at the device itself: the network settings in the Ultimate menu show the address it has now.".to_string(),
+        ];
+        match &self.guard.expected {
+            Expected::Address(ip) => steps.push(format!(
+                "It should be at {}. If that address is on another subnet, move this computer there or connect through a router that reaches it.",
+                ip
+            )),
+            Expected::Unknown => steps.push(
+                "It now takes its address from DHCP; the router's client list or a network search will show it."
+                    .to_string(),
+            ),
+            Expected::Same => steps.push(format!(
+                "Check the cable or WiFi, then try {} again.",
+                self.guard.old_host
+            )),
+        }
+        steps.push("Search the network for Ultimate devices.".to_string());
+        let previous = self.guard.previous_values();
+        if !previous.is_empty() {
+            steps.push(format!(
+                "To undo the change, set these in the device menu: {}",
+                previous.join("; ")
+            ));
+        }
+        steps
+    }
+}
+
+/// The part of a change set that touches network settings.
+pub fn network_changes(changes: &ConfigTree) -> ConfigTree {
+    changes
+        .iter()
+        .filter(|(category, _)| config_api::is_network_related_category(category))
+        .map(|(category, items)| (category.clone(), items.clone()))
+        .collect()
+}
+
+fn is_dhcp_item(item: &str) -> bool {
+    item.to_lowercase().contains("dhcp")
+}
+
+fn is_address_item(item: &str) -> bool {
+    let lower = item.to_lowercase();
+    lower == "static ip" || lower.contains("ip address")
+}
+
+fn is_enabled(value: &serde_json::Value) -> bool {
+    match value {
+        serde_json::Value::Bool(b) => *b,
+        serde_json::Value::String(s) => {
+            matches!(s.to_lowercase().as_str(), "enabled" | "yes" | "on" | "true")
+        }
+        _ => false,
+    }
+}
+
+/// Where the device should answer after `changes` land on categories that
+/// currently hold `current`.
+pub fn expected_host(old_host: &str, current: &ConfigTree, changes: &ConfigTree) -> Expected {
+    for (category, items) in network_changes(changes) {
+        let mut merged = current.get(&category).cloned().unwrap_or_default();
+        merged.extend(items.clone());
+        let dhcp = merged
+            .iter()
+            .find(|(item, _)| is_dhcp_item(item))
+            .map(|(_, value)| is_enabled(value));
+        let touches_address = items
+            .keys()
+            .any(|item| is_dhcp_item(item) || is_address_item(item));
+        if !touches_address {
+            continue;
+        }
+        if dhcp == Some(true) {
+            if items.keys().any(|item| is_dhcp_item(item)) {
+                return Expected::Unknown;
+            }
+            continue;
+        }
+        let address = merged
+            .iter()
+            .find(|(item, _)| is_address_item(item))
+            .map(|(_, value)| config_api::format_value(value));
+        if let Some(address) = address.filter(|a| !a.is_empty() && a != old_host) {
+            return Expected::Address(address);
+        }
+    }
+    Expected::Same
+}
+
+/// Read the values `changes` will replace. `None` when nothing in `changes`
+/// is a network setting.
+pub async fn record(
+    host_url: &str,
+    changes: &ConfigTree,
+    password: Option<String>,
+) -> Result<Option<Guard>, String> {
+    let network = network_changes(changes);
+    if network.is_empty() {
+        return Ok(None);
+    }
+    let mut current = ConfigTree::new();
+    for category in network.keys() {
+        let (_, items) = config_api::fetch_category_items(
+            host_url.to_string(),
+            category.clone(),
+            password.clone(),
+        )
+        .await
+        .map_err(|e| format!("Could not record current '{}' settings: {}", category, e))?;
+        current.insert(
+            category.clone(),
+            items
+                .into_iter()
+                .map(|item| (item.name, item.current_value))
+                .collect(),
+        );
+    }
+    let previous = network
+        .iter()
+        .map(|(category, items)| {
+            let old = &current[category];
+            let replaced = items
+                .keys()
+                .filter_map(|item| old.get(item).map(|value| (item.clone(), value.clone())))
+                .collect();
+            (category.clone(), replaced)
+        })
+        .collect();
+    let old_host = crate::profile_api::bare_host(host_url).to_string();
+    Ok(Some(Guard {
+        expected: expected_host(&old_host, &current, changes),
+        old_host,
+        previous,
+    }))
+}
+
+/// Whether the device answers REST requests at `host`.
+pub async fn probe(host: &str, password: Option<&str>) -> bool {
+    let Ok(client) = crate::net_utils::build_device_client(3) else {
+        return false;
+    };
+    let request = crate::net_utils::with_password(
+        client.get(format!("http://{}/v1/version", host)),
+        password,
+    );
+    crate::net_utils::device_send(request).await.is_ok()
+}
+
+/// Wait for the device to answer where `guard` expects it, reverting at the
+/// old address if it only answers there.
+pub async fn watch(guard: Guard, password: Option<String>) -> Outcome {
+    tokio::time::sleep(SETTLE).await;
+    let target = guard.target().to_string();
+    let started = Instant::now();
+    while started.elapsed() < WATCH_TIMEOUT {
+        if probe(&target, password.as_deref()).await {
+            return Outcome::Reachable(target);
+        }
+        tokio::time::sleep(PROBE_INTERVAL).await;
+    }
+    if target != guard.old_host && probe(&guard.old_host, password.as_deref()).await {
+        log::warn!(
+            "Device lost at {} but answers at {} — restoring previous network settings",
+            target,
+            guard.old_host
+        );
+        let url = format!("http://{}", guard.old_host);
+        match config_api::save_batch_changes(url, guard.previous.clone(), password).await {
+            Ok(_) => return Outcome::Reverted(guard.old_host),
+            Err(e) => log::error!("Restoring network settings failed: {}", e),
+        }
+    }
+    Outcome::Lost(guard)
+}
+
+/// Save `changes`, guarding any network settings among them.
+pub async fn save_guarded(
+    host_url: String,
+    changes: ConfigTree,
+    password: Option<String>,
+) -> Result<(String, Option<Outcome>), String> {
+    let guard = record(&host_url, &changes, password.clone()).await?;
+    let message = config_api::save_batch_changes(host_url, changes, password.clone()).await?;
+    let outcome = match guard {
+        Some(guard) => Some(watch(guard, password).await),
+        None => None,
+    };
+    Ok((message, outcome))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn tree(entries: &[(&str, &str, serde_json::Value)]) -> ConfigTree {
+        let mut tree = ConfigTree::new();
+        for (category, item, value) in entries {
+            tree.entry(category.to_string())
+                .or_default()
+                .insert(item.to_string(), value.clone());
+        }
+        tree
+    }
+
+    #[test]
+    fn expected_host_follows_static_address_and_dhcp() {
+        let current = tree(&[
+            ("Ethernet Settings", "Use DHCP", json!("Disabled")),
+            ("Ethernet Settings", "Static IP", json!("192.168.1.64")),
+        ]);
+        let new_ip = tree(&[("Ethernet Settings", "Static IP", json!("10.0.0.64"))]);
+        assert_eq!(
+            expected_host("192.168.1.64", &current, &new_ip),
+            Expected::Address("10.0.0.64".into())
+        );
+        let dhcp_on = tree(&[("Ethernet Settings", "Use DHCP", json!("Enabled"))]);
+        assert_eq!(
+            expected_host("192.168.1.64", &current, &dhcp_on),
+            Expected::Unknown
+        );
+        let hostname = tree(&[("Network settings", "Host Name", json!("c64"))]);
+        assert_eq!(
+            expected_host("192.168.1.64", &current, &hostname),
+            Expected::Same
+        );
+        // A static address only matters while DHCP is off.
+        let with_dhcp = tree(&[
+            ("Ethernet Settings", "Use DHCP", json!("Enabled")),
+            ("Ethernet Settings", "Static IP", json!("192.168.1.64")),
+        ]);
+        assert_eq!(
+            expected_host("192.168.1.64", &with_dhcp, &new_ip),
+            Expected::Same
+        );
+    }
+
+    #[test]
+    fn network_changes_keep_only_network_categories() {
+        let changes = tree(&[
+            ("Drive A Settings", "Drive", json!("Enabled")),
+            ("WiFi settings", "SSID", json!("home")),
+        ]);
+        let network = network_changes(&changes);
+        assert_eq!(network.len(), 1);
+        assert!(network.contains_key("WiFi settings"));
+
+        let guard = Guard {
+            old_host: "192.168.1.64".into(),
+            expected: Expected::Same,
+            previous: tree(&[("WiFi settings", "SSID", json!("office"))]),
+        };
+        assert_eq!(
+            guard.previous_values(),
+            vec!["WiFi settings / SSID = office"]
+        );
+    }
+}
@@ -0,0 +1,168 @@
+//! Parked-CPU sessions through an injected NMI stub
+//!
+//! The REU reader and machine snapshots both need the C64 held still while
+//! the host works on memory the REST API can't reach directly. Each builds its
+//! own 6502 stub; this module injects it into the cassette buffer, enters it
+//! through an NMI raised by CIA2 Timer A and runs the marker handshake:
+//!
+//! - the stub writes `ACK_READY` once it is parked,
+//! - `REQ_WORK` asks it for its one job, answered with `ACK_DONE`,
+//! - `REQ_EXIT` makes it return with RTI, answered with `ACK_EXITED`.
+//!
+//! The NMI is routed through the KERNAL vector at $0318, so the C64 must be
+//! running with the KERNAL banked in.
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::remote_device::RemoteDevice;
+
+// ─── Layout ───────────────────────────────────────────────────────────────────
+
+/// Cassette buffer — the scratch area the screenshot ROM bypass also uses
+pub const STUB_ADDR: u16 = 0x0340;
+pub const STUB_AREA_LEN: u16 = 0xC0;
+/// Handshake byte (zero page $02 is unused by BASIC and the KERNAL)
+pub const MARKER: u16 = 0x0002;
+const NMI_VECTOR: u16 = 0x0318;
+
+/// Handshake values: host → stub
+pub const REQ_WORK: u8 = 0x01;
+pub const REQ_EXIT: u8 = 0xFF;
+/// Handshake values: stub → host
+pub const ACK_READY: u8 = 0x41;
+pub const ACK_DONE: u8 = 0x42;
+pub const ACK_EXITED: u8 = 0x43;
+
+// ─── Device access ────────────────────────────────────────────────────────────
+
+pub fn read(conn: &dyn RemoteDevice, address: u16, length: usize) -> Result<Vec<u8>, String> {
+    conn.read_mem(address, length as u16)
+        .map_err(|e| format!("Read ${:04X} failed: {}", address, e))
+}
+
+pub fn write(conn: &dyn RemoteDevice, address: u16, data: &[u8]) -> Result<(), String> {
+    conn.write_mem(address, data)
+        .map_err(|e| format!("Write ${:04X} failed: {}", address, e))
+}
+
+/// Poll the marker until it reads `want` or `timeout` passes.
+pub fn wait_for(conn: &dyn RemoteDevice, want: u8, timeout: Duration) -> Result<bool, String> {
+    let start = Instant::now();
+    loop {
+        if read(conn, MARKER, 1)?[0] == want {
+            return Ok(true);
+        }
+        if start.elapsed() >= timeout {
+            return Ok(false);
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+}
+
+// ─── Session ──────────────────────────────────────────────────────────────────
+
+/// What entering the stub overwrites
+#[derive(Debug, Clone)]
+pub struct Backup {
+    pub stub_area: Vec<u8>,
+    pub marker: u8,
+    pub nmi_vector: Vec<u8>,
+    pub cia2_timer: Vec<u8>,
+}
+
+impl Backup {
+    pub fn read(conn: &dyn RemoteDevice) -> Result<Self, String> {
+        Ok(Self {
+            stub_area: read(conn, STUB_ADDR, STUB_AREA_LEN as usize)?,
+            marker: read(conn, MARKER, 1)?[0],
+            nmi_vector: read(conn, NMI_VECTOR, 2)?,
+            cia2_timer: read(conn, 0xDD04, 2)?,
+        })
+    }
+
+    /// Best-effort put-back once the CPU has left the stub.
+    pub fn restore(&self, conn: &dyn RemoteDevice) {
+        let _ = write(conn, 0xDD0D, &[0x01]);
+        let _ = write(conn, 0xDD04, &self.cia2_timer);
+        let _ = write(conn, NMI_VECTOR, &self.nmi_vector);
+        let _ = write(conn, MARKER, &[self.marker]);
+        let _ = write(conn, STUB_ADDR, &self.stub_area);
+    }
+}
+
+/// Inject `stub` and enter it through a one-shot CIA2 Timer A NMI, 2 cycles
+/// out. On failure everything in `backup` is put back; `what` names the stub
+/// in the error.
+pub fn enter(
+    conn: &dyn RemoteDevice,
+    backup: &Backup,
+    stub: &[u8],
+    what: &str,
+) -> Result<(), String> {
+    let entered = (|| -> Result<bool, String> {
+        write(conn, STUB_ADDR, stub)?;
+        write(conn, MARKER, &[0x00])?;
+        write(conn, NMI_VECTOR, &STUB_ADDR.to_le_bytes())?;
+        write(conn, 0xDD04, &[0x02, 0x00])?;
+        write(conn, 0xDD0D, &[0x81])?;
+        write(conn, 0xDD0E, &[0x19])?;
+        wait_for(conn, ACK_READY, Duration::from_secs(1))
+    })();
+    match entered {
+        Ok(true) => Ok(()),
+        Ok(false) => {
+            backup.restore(conn);
+            Err(format!(
+                "{} stub did not start — is the C64 paused or the KERNAL banked out?",
+                what
+            ))
+        }
+        Err(e) => {
+            backup.restore(conn);
+            Err(e)
+        }
+    }
+}
+
+/// Ask the parked stub for its job and wait for it to finish.
+pub fn request(conn: &dyn RemoteDevice, timeout: Duration) -> Result<bool, String> {
+    write(conn, MARKER, &[REQ_WORK])?;
+    wait_for(conn, ACK_DONE, timeout)
+}
+
+/// Release the stub and wait for it to RTI.
+pub fn leave(conn: &dyn RemoteDevice, what: &str) -> Result<(), String> {
+    write(conn, 0xDD0D, &[0x01])?;
+    write(conn, MARKER, &[REQ_EXIT])?;
+    if wait_for(conn, ACK_EXITED, Duration::from_secs(1))? {
+        Ok(())
+    } else {
+        Err(format!("{} stub did not return", what))
+    }
+}
+
+// ─── Async wrapper ────────────────────────────────────────────────────────────
+
+/// Run a stub session on the worker pool. `what` names the session in the
+/// timeout error.
+pub async fn blocking<T: Send + 'static>(
+    connection: Arc<Mutex<dyn RemoteDevice>>,
+    timeout_secs: u64,
+    what: &str,
+    f: impl FnOnce(&dyn RemoteDevice) -> Result<T, String> + Send + 'static,
+) -> Result<T, String> {
+    let result = tokio::time::timeout(
+        Duration::from_secs(timeout_secs),
+        tokio::task::spawn_blocking(move || {
+            let conn = connection.lock().unwrap();
+            f(&*conn)
+        }),
+    )
+    .await;
+    match result {
+        Ok(Ok(r)) => r,
+        Ok(Err(e)) => Err(format!("Task error: {}", e)),
+        Err(_) => Err(format!("{} timed out — device may be offline", what)),
+    }
+}
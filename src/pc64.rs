@@ -0,0 +1,315 @@
+//! PC64 containers (`.P00`, `.S00`, `.U00`, `.R00`).
+//!
+//! The PC64 emulator stored each C64 file on the host with a 26-byte header:
+//! the signature `C64File\0`, the original 16-character PETSCII name
+//! (zero-padded), a zero byte and the REL record length. The extension gives
+//! the file type (`P` = PRG, `S` = SEQ, `U` = USR, `R` = REL); its two digits
+//! only tell apart host files whose short names collide.
+
+use std::path::{Path, PathBuf};
+
+use crate::disk_image::FileType;
+use crate::petscii;
+
+pub const SIGNATURE: &[u8; 8] = b"C64File\0";
+pub const HEADER_LEN: usize = 26;
+
+/// A parsed PC64 container
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pc64File {
+    /// The C64 filename, as displayed
+    pub name: String,
+    /// Raw PETSCII name bytes, zero padding removed
+    pub raw_name: Vec<u8>,
+    pub file_type: FileType,
+    /// REL record length; 0 for other types
+    pub record_len: u8,
+    /// The file itself, load address included for a PRG
+    pub data: Vec<u8>,
+}
+
+/// File type of a PC64 extension (lowercase, no dot): `p00`–`p99` etc.
+/// `u64` is an Ultimate 64 firmware update, not a USR container.
+pub fn container_type(ext: &str) -> Option<FileType> {
+    let bytes = ext.as_bytes();
+    if bytes.len() != 3 || !bytes[1].is_ascii_digit() || !bytes[2].is_ascii_digit() {
+        return None;
+    }
+    if crate::file_types::is_update_file(ext) {
+        return None;
+    }
+    match bytes[0] {
+        b'p' => Some(FileType::Prg),
+        b's' => Some(FileType::Seq),
+        b'u' => Some(FileType::Usr),
+        b'r' => Some(FileType::Rel),
+        _ => None,
+    }
+}
+
+/// File type of the container at `path`, going by its extension
+fn path_type(path: &Path) -> Option<FileType> {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .and_then(|e| container_type(&e.to_lowercase()))
+}
+
+/// Whether `path` has a PC64 extension
+pub fn is_container_path(path: &Path) -> bool {
+    path_type(path).is_some()
+}
+
+/// Parse a container whose extension says it holds a `file_type`
+pub fn parse(data: &[u8], file_type: FileType) -> Result<Pc64File, String> {
+    if data.len() < HEADER_LEN || &data[..8] != SIGNATURE {
+        return Err("Not a PC64 file (no C64File header)".to_string());
+    }
+    let raw_name: Vec<u8> = data[8..24]
+        .iter()
+        .copied()
+        .take_while(|&b| b != 0)
+        .collect();
+    Ok(Pc64File {
+        name: petscii::to_string(&raw_name).trim_end().to_string(),
+        raw_name,
+        file_type,
+        record_len: data[25],
+        data: data[HEADER_LEN..].to_vec(),
+    })
+}
+
+/// Read a container from disk, taking its type from the extension
+pub fn read(path: &Path) -> Result<Pc64File, String> {
+    let file_type =
+        path_type(path).ok_or_else(|| format!("{} is not a PC64 file", path.display()))?;
+    let data = std::fs::read(path).map_err(|e| format!("Failed to read file: {}", e))?;
+    parse(&data, file_type)
+}
+
+/// The C64 name stored in a container, reading only its header. `None` for
+/// anything that isn't a PC64 file.
+pub fn read_name(path: &Path) -> Option<String> {
+    use std::io::Read;
+    if !is_container_path(path) {
+        return None;
+    }
+    let mut header = [0u8; HEADER_LEN];
+    std::fs::File::open(path)
+        .and_then(|mut f| f.read_exact(&mut header))
+        .ok()?;
+    parse(&header, FileType::Prg).ok().map(|f| f.name)
+}
+
+/// `bytes` read from `path`, with the PC64 header stripped when `path` is a
+/// container. Only PRG containers can be run.
+pub fn runnable_payload(path: &Path, bytes: Vec<u8>) -> Result<Vec<u8>, String> {
+    let Some(file_type) = path_type(path) else {
+        return Ok(bytes);
+    };
+    let file = parse(&bytes, file_type)?;
+    if file.file_type != FileType::Prg {
+        return Err(format!(
+            "\"{}\" is a {} file, not a program",
+            file.name, file.file_type
+        ));
+    }
+    Ok(file.data)
+}
+
+/// Build a container: header with `name` (printable ASCII, upper-cased to
+/// PETSCII, cut to 16 characters) followed by `data`. Other characters have
+/// no PETSCII equivalent and are refused.
+pub fn encode(name: &str, record_len: u8, data: &[u8]) -> Result<Vec<u8>, String> {
+    if let Some(c) = name.chars().find(|c| !(' '..='~').contains(c)) {
+        return Err(format!(
+            "\"{}\" has no PETSCII equivalent in \"{}\"",
+            c, name
+        ));
+    }
+    let mut out = Vec::with_capacity(HEADER_LEN + data.len());
+    out.extend_from_slice(SIGNATURE);
+    let mut raw = [0u8; 16];
+    for (slot, b) in raw.iter_mut().zip(name.bytes()) {
+        *slot = b.to_ascii_uppercase();
+    }
+    out.extend_from_slice(&raw);
+    out.push(0);
+    out.push(record_len);
+    out.extend_from_slice(data);
+    Ok(out)
+}
+
+/// PC64's short host name for a C64 name: lowercase letters, digits and `_`
+/// for spaces and dashes, other characters dropped, then cut to eight by
+/// removing underscores, then vowels, then other letters from the right.
+pub fn short_name(name: &str) -> String {
+    let mut chars: Vec<char> = name
+        .to_lowercase()
+        .chars()
+        .filter_map(|c| match c {
+            ' ' | '-' => Some('_'),
+            c if c.is_ascii_alphanumeric() => Some(c),
+            _ => None,
+        })
+        .collect();
+    let classes: [fn(char) -> bool; 3] = [
+        |c| c == '_',
+        |c| "aeiou".contains(c),
+        |c| c.is_ascii_alphabetic(),
+    ];
+    for class in classes {
+        while chars.len() > 8 {
+            match chars.iter().skip(1).rposition(|&c| class(c)) {
+                Some(i) => {
+                    chars.remove(i + 1);
+                }
+                None => break,
+            }
+        }
+    }
+    chars.truncate(8);
+    if chars.is_empty() {
+        "_".to_string()
+    } else {
+        chars.into_iter().collect()
+    }
+}
+
+/// Host extension for a raw file of `file_type`. A raw REL file would lose
+/// its record length, which only the container header keeps.
+fn raw_ext(file_type: FileType) -> Result<&'static str, String> {
+    match file_type {
+        FileType::Prg => Ok("prg"),
+        FileType::Seq => Ok("seq"),
+        FileType::Usr => Ok("usr"),
+        FileType::Rel => {
+            Err("REL files keep their record length only in the container".to_string())
+        }
+        other => Err(format!("PC64 cannot hold {} files", other)),
+    }
+}
+
+/// Write the file inside the container at `path` next to it as
+/// `<C64 NAME>.prg` (or `.seq`/`.usr`), refusing to overwrite.
+pub fn unwrap_to_raw(path: &Path) -> Result<PathBuf, String> {
+    let file = read(path)?;
+    let ext = raw_ext(file.file_type)?;
+    let stem: String = file
+        .name
+        .chars()
+        .map(|c| if "/\\:*?\"<>|".contains(c) { '_' } else { c })
+        .collect();
+    let stem = if stem.trim().is_empty() {
+        path.file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("file")
+            .to_string()
+    } else {
+        stem.trim().to_string()
+    };
+    let target = path
+        .parent()
+        .unwrap_or(Path::new("."))
+        .join(format!("{}.{}", stem, ext));
+    if target.exists() {
+        return Err(format!("{} already exists", target.display()));
+    }
+    std::fs::write(&target, &file.data)
+        .map_err(|e| format!("Failed to write {}: {}", target.display(), e))?;
+    Ok(target)
+}
+
+/// Wrap the raw PRG/SEQ/USR file at `path` in a container next to it,
+/// named from its stem the PC64 way and numbered past any existing one. A raw
+/// REL file doesn't say its record length, so it is refused.
+pub fn wrap_raw(path: &Path) -> Result<PathBuf, String> {
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .unwrap_or_default();
+    let letter = match ext.as_str() {
+        "prg" => 'p',
+        "seq" => 's',
+        "usr" => 'u',
+        "rel" => {
+            return Err(format!(
+                "{} has no record length; REL files can't be wrapped",
+                path.display()
+            ))
+        }
+        _ => return Err(format!("{} is not a PRG, SEQ or USR file", path.display())),
+    };
+    let name: String = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("FILE")
+        .chars()
+        .take(16)
+        .collect();
+    let container = encode(
+        &name,
+        0,
+        &std::fs::read(path).map_err(|e| format!("Failed to read file: {}", e))?,
+    )?;
+    let dir = path.parent().unwrap_or(Path::new("."));
+    let short = short_name(&name);
+    let target = (0..100)
+        .map(|n| dir.join(format!("{}.{}{:02}", short, letter, n)))
+        .find(|candidate| !candidate.exists())
+        .ok_or_else(|| format!("No free {}.{}NN name left", short, letter))?;
+    std::fs::write(&target, container)
+        .map_err(|e| format!("Failed to write {}: {}", target.display(), e))?;
+    Ok(target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_and_parse_roundtrip() {
+        let container = encode("Giana Sisters", 0, &[0x01, 0x08, 0x60]).unwrap();
+        assert_eq!(container.len(), HEADER_LEN + 3);
+        let file = parse(&container, FileType::Prg).unwrap();
+        assert_eq!(file.name, "GIANA SISTERS");
+        assert_eq!(file.data, [0x01, 0x08, 0x60]);
+        assert!(parse(b"01 08 60", FileType::Prg).is_err());
+
+        assert_eq!(container_type("p00"), Some(FileType::Prg));
+        assert_eq!(container_type("s12"), Some(FileType::Seq));
+        assert_eq!(container_type("prg"), None);
+        assert_eq!(container_type("d64"), None);
+        assert_eq!(container_type("u64"), None);
+        assert_eq!(container_type("u00"), Some(FileType::Usr));
+        assert!(!crate::file_types::is_program_file("u64"));
+
+        assert!(encode("Ärger", 0, &[]).is_err());
+        let long = encode("A VERY LONG FILENAME", 0, &[]).unwrap();
+        assert_eq!(&long[8..24], b"A VERY LONG FILE");
+    }
+
+    #[test]
+    fn rel_files_are_refused_both_ways() {
+        let dir = std::env::temp_dir().join(format!("u64_pc64_rel_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let raw = dir.join("records.rel");
+        std::fs::write(&raw, [1, 2, 3]).unwrap();
+        assert!(wrap_raw(&raw).is_err());
+
+        let container = dir.join("records.r00");
+        std::fs::write(&container, encode("RECORDS", 64, &[1, 2, 3]).unwrap()).unwrap();
+        assert_eq!(read(&container).unwrap().record_len, 64);
+        assert!(unwrap_to_raw(&container).is_err());
+        assert!(!dir.join("RECORDS.rel").exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn short_name_drops_spaces_then_vowels() {
+        assert_eq!(short_name("GAME"), "game");
+        assert_eq!(short_name("GIANA SISTERS"), "ginsstrs");
+        assert_eq!(short_name("THE LAST NINJA 2"), "thlstnn2");
+        assert_eq!(short_name("!!!"), "_");
+    }
+}
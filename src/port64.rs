@@ -143,6 +143,7 @@ pub enum StreamKind {
 #[derive(Debug, Clone)]
 pub struct Port64Client {
     host: String,
+    port: u16,
     password: Option<String>,
     timeout: std::time::Duration,
 }
@@ -152,6 +153,7 @@ impl Port64Client {
     pub fn new(host: impl Into<String>, password: Option<String>) -> Self {
         Self {
             host: host.into(),
+            port: PORT,
             password,
             timeout: std::time::Duration::from_secs(TIMEOUT_SECS),
         }
@@ -163,12 +165,18 @@ impl Port64Client {
         self
     }
 
+    /// Override the TCP port (e.g. a simulator on an unprivileged port).
+    pub fn with_port(mut self, port: u16) -> Self {
+        self.port = port;
+        self
+    }
+
     // ── Low-level session plumbing ────────────────────────────────
 
     /// Open a TCP connection and authenticate if a password is set.
     /// Returns the authenticated stream ready to receive commands.
     async fn open(&self) -> Port64Result<TcpStream> {
-        let addr = format!("{}:{}", self.host, PORT);
+        let addr = format!("{}:{}", self.host, self.port);
 
         let stream = tokio::time::timeout(self.timeout, TcpStream::connect(&addr))
             .await
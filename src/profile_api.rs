@@ -10,6 +10,8 @@
 
 use crate::config_api;
 use crate::device_profile::{ConfigTree, DeviceProfile};
+use crate::launch_script;
+use crate::network_watchdog;
 use crate::remote_device::RemoteDevice;
 use std::collections::HashMap;
 use std::path::Path;
@@ -23,13 +25,15 @@ use std::sync::Mutex;
 ///
 /// Flow:
 /// 1. Optionally restore flash baseline first (load_from_flash)
-/// 2. Send the diff settings via REST API
+/// 2. Send the diff settings via REST API, watching network changes
+///    (see [`network_watchdog`])
 /// 3. Mount media according to profile mounts
 /// 4. Optionally reset machine
+/// 5. Run the launch script, if any
 /// Pre-clean modes for profile apply.
 /// 0 = direct (no pre-clean), 1 = full reboot, 2 = load flash defaults + reset.
 pub async fn apply_profile(
-    host: String,
+    mut host: String,
     profile: &DeviceProfile,
     diff: ConfigTree,
     password: Option<String>,
@@ -98,10 +102,31 @@ pub async fn apply_profile(
                 ""
             },
         );
+        let guard = network_watchdog::record(&host, &diff, password.clone()).await?;
         let result = config_api::apply_all_config(host.clone(), diff, password.clone()).await?;
         steps.push(result);
         // Let the device settle after config changes before more requests
         tokio::time::sleep(std::time::Duration::from_millis(1000)).await;
+
+        // Network settings may have moved the device; follow it, or stop
+        // here if it can't be found.
+        if let Some(guard) = guard {
+            let outcome = network_watchdog::watch(guard, password.clone()).await;
+            match &outcome {
+                network_watchdog::Outcome::Reachable(found) => {
+                    host = format!("http://{}", found);
+                }
+                network_watchdog::Outcome::Reverted(_) => {}
+                network_watchdog::Outcome::Lost(_) => {
+                    return Err(format!(
+                        "{}; network settings applied, but the {}",
+                        steps.join(", "),
+                        outcome.summary()
+                    ));
+                }
+            }
+            steps.push(outcome.summary());
+        }
     }
 
     // Step 2b: If the diff contains ROM changes, the Ultimate needs to reboot
@@ -143,6 +168,32 @@ pub async fn apply_profile(
         }
     }
 
+    // Step 5: Launch script
+    if !profile.launch.script.trim().is_empty() {
+        let statements = launch_script::parse(&profile.launch.script)
+            .map_err(|errors| launch_script::describe(&errors))?;
+        let connection = connection.ok_or("Launch script needs a connection")?;
+        let env = launch_script::Env {
+            connection,
+            host: bare_host(&host).to_string(),
+            password: password.clone(),
+            script: None,
+            file: None,
+            cancel: Default::default(),
+        };
+        launch_script::run(&env, &statements, |s| {
+            log::info!(
+                "Profile '{}' script line {}: {}",
+                profile.name,
+                s.line,
+                s.source
+            )
+        })
+        .await
+        .map_err(|e| format!("Launch script: {}", e))?;
+        steps.push(format!("Ran launch script ({} lines)", statements.len()));
+    }
+
     Ok(format!(
         "Applied profile '{}': {}",
         profile.name,
@@ -152,11 +203,7 @@ pub async fn apply_profile(
 
 /// Extract bare host (IP or hostname) from a URL like "http://192.168.1.91".
 /// The api.rs functions expect just the host — they prepend http:// themselves.
-pub fn bare_host_pub(host_url: &str) -> &str {
-    bare_host(host_url)
-}
-
-fn bare_host(host_url: &str) -> &str {
+pub(crate) fn bare_host(host_url: &str) -> &str {
     host_url
         .strip_prefix("http://")
         .or_else(|| host_url.strip_prefix("https://"))
@@ -17,8 +17,8 @@ use crate::profile_repo::{self, ProfileEntry, ProfileRepo};
 use crate::remote_device::RemoteDevice;
 use iced::{
     widget::{
-        button, column, container, pick_list, row, rule, scrollable, text, text_input, toggler,
-        tooltip, Column, Row, Space,
+        button, column, container, pick_list, row, rule, scrollable, text, text_editor, text_input,
+        toggler, tooltip, Column, Row, Space,
     },
     Element, Length, Task,
 };
@@ -149,6 +149,7 @@ pub enum ProfileManagerMessage {
     // Launch settings
     RestoreBaselineChanged(bool),
     ResetAfterApplyChanged(bool),
+    LaunchScriptEdited(text_editor::Action),
 
     // Save / Delete
     SaveProfile,
@@ -227,6 +228,8 @@ pub struct ProfileManager {
     search_filter: String,
     new_category_input: String,
     tags_input: String,
+    /// Editor buffer for the profile's launch script
+    launch_script: text_editor::Content,
 
     // Streaming frame buffer reference for screenshot capture
     streaming_frame: Option<Arc<std::sync::Mutex<Option<crate::streaming::NativeFrame>>>>,
@@ -319,6 +322,7 @@ impl ProfileManager {
             search_filter: String::new(),
             new_category_input: String::new(),
             tags_input: String::new(),
+            launch_script: text_editor::Content::new(),
             streaming_frame: None,
             pending_screenshot: None,
             original_profile_dir: None,
@@ -347,6 +351,20 @@ impl ProfileManager {
         self.streaming_frame = Some(frame);
     }
 
+    /// Names of the profiles in the repository (for the workspace picker).
+    pub fn profile_names(&self) -> Vec<String> {
+        self.profiles.iter().map(|p| p.name.clone()).collect()
+    }
+
+    /// Path of the named profile and the stored baseline to diff it against,
+    /// so the workspace can apply it to other devices.
+    pub fn apply_source(&self, name: &str) -> Option<(PathBuf, Option<ConfigTree>)> {
+        self.profiles
+            .iter()
+            .find(|p| p.name == name)
+            .map(|p| (p.path.clone(), self.baseline_config.clone()))
+    }
+
     pub fn update_impl(
         &mut self,
         message: ProfileManagerMessage,
@@ -426,6 +444,7 @@ impl ProfileManager {
                 profile.profile_mode = ProfileMode::Overlay;
                 self.current_profile = Some(profile);
                 self.tags_input.clear();
+                self.launch_script = text_editor::Content::new();
                 self.save_category = "uncategorized".to_string();
                 self.original_profile_dir = None; // brand new — nothing to replace
                 self.pending_screenshot = None;
@@ -509,6 +528,8 @@ impl ProfileManager {
                             profile.setting_count()
                         ));
                         self.tags_input = profile.tags.join(", ");
+                        self.launch_script =
+                            text_editor::Content::with_text(&profile.launch.script);
                         self.original_cfg_content = Some(cfg_content);
                         self.current_profile = Some(profile);
                         self.is_dirty = true;
@@ -562,6 +583,8 @@ impl ProfileManager {
                             profile.setting_count()
                         ));
                         self.tags_input = profile.tags.join(", ");
+                        self.launch_script =
+                            text_editor::Content::with_text(&profile.launch.script);
                         self.current_profile = Some(profile);
                         self.is_dirty = true;
                         self.view_mode = ViewMode::ProfileEditor;
@@ -602,6 +625,8 @@ impl ProfileManager {
                             profile.setting_count()
                         ));
                         self.tags_input = profile.tags.join(", ");
+                        self.launch_script =
+                            text_editor::Content::with_text(&profile.launch.script);
                         self.current_profile = Some(profile);
                         self.is_dirty = true;
                         self.view_mode = ViewMode::ProfileEditor;
@@ -644,6 +669,8 @@ impl ProfileManager {
                             profile.setting_count()
                         ));
                         self.tags_input = profile.tags.join(", ");
+                        self.launch_script =
+                            text_editor::Content::with_text(&profile.launch.script);
                         self.current_profile = Some(profile);
                         self.is_dirty = is_clone; // Clone needs saving, loaded profile doesn't
                         self.pending_screenshot = None;
@@ -793,7 +820,7 @@ impl ProfileManager {
                     is_loading: true,
                     error: None,
                 });
-                let bare = crate::profile_api::bare_host_pub(&host).to_string();
+                let bare = crate::profile_api::bare_host(&host).to_string();
                 let path = initial.clone();
                 Task::perform(
                     async move {
@@ -815,7 +842,7 @@ impl ProfileManager {
                     picker.path_input = path.clone();
                     picker.error = None;
                 }
-                let bare = crate::profile_api::bare_host_pub(&host).to_string();
+                let bare = crate::profile_api::bare_host(&host).to_string();
                 Task::perform(
                     async move {
                         let entries =
@@ -1100,6 +1127,17 @@ impl ProfileManager {
                 }
                 Task::none()
             }
+            ProfileManagerMessage::LaunchScriptEdited(action) => {
+                let edit = action.is_edit();
+                self.launch_script.perform(action);
+                if let Some(profile) = &mut self.current_profile {
+                    if edit {
+                        profile.launch.script = self.launch_script.text();
+                        self.is_dirty = true;
+                    }
+                }
+                Task::none()
+            }
 
             // ── Save ──
             ProfileManagerMessage::SaveProfile => {
@@ -2593,29 +2631,40 @@ impl ProfileManager {
         let mounts_section = self.view_mounts_section(profile, fs);
 
         // Launch
-        let launch_section = container(
-            column![
-                text("LAUNCH").size(fs.tiny),
-                row![
-                    toggler(profile.launch.restore_baseline_first)
-                        .on_toggle(ProfileManagerMessage::RestoreBaselineChanged)
-                        .size(fs.normal),
-                    text("Restore baseline first").size(fs.small),
-                ]
-                .spacing(5)
-                .align_y(iced::Alignment::Center),
-                row![
-                    toggler(profile.launch.reset_after_apply)
-                        .on_toggle(ProfileManagerMessage::ResetAfterApplyChanged)
-                        .size(fs.normal),
-                    text("Reset after apply").size(fs.small),
-                ]
-                .spacing(5)
-                .align_y(iced::Alignment::Center),
+        let mut launch_col = column![
+            text("LAUNCH").size(fs.tiny),
+            row![
+                toggler(profile.launch.restore_baseline_first)
+                    .on_toggle(ProfileManagerMessage::RestoreBaselineChanged)
+                    .size(fs.normal),
+                text("Restore baseline first").size(fs.small),
             ]
-            .spacing(4),
-        )
-        .padding(8);
+            .spacing(5)
+            .align_y(iced::Alignment::Center),
+            row![
+                toggler(profile.launch.reset_after_apply)
+                    .on_toggle(ProfileManagerMessage::ResetAfterApplyChanged)
+                    .size(fs.normal),
+                text("Reset after apply").size(fs.small),
+            ]
+            .spacing(5)
+            .align_y(iced::Alignment::Center),
+            text("Launch script").size(fs.small),
+            text_editor(&self.launch_script)
+                .placeholder("RESET\nLOAD 8\nRUN")
+                .on_action(ProfileManagerMessage::LaunchScriptEdited)
+                .size(fs.small)
+                .height(Length::Fixed(90.0)),
+        ]
+        .spacing(4);
+        if let Err(errors) = crate::launch_script::parse(&profile.launch.script) {
+            launch_col = launch_col.push(
+                text(crate::launch_script::describe(&errors))
+                    .size(fs.tiny)
+                    .color(iced::Color::from_rgb(0.9, 0.5, 0.3)),
+            );
+        }
+        let launch_section = container(launch_col).padding(8);
 
         // Baseline status
         let has_schema = self.config_schema.is_some();
@@ -24,6 +24,9 @@ impl Profile {
 pub struct ProfileManager {
     pub profiles: Vec<Profile>,
     pub active_profile: String,
+    /// Devices in the multi-device workspace. Shared by all profiles.
+    #[serde(default)]
+    pub workspace: Vec<crate::workspace::WorkspaceDevice>,
 }
 
 impl Default for ProfileManager {
@@ -31,6 +34,7 @@ impl Default for ProfileManager {
         Self {
             profiles: vec![Profile::new("Default".to_string())],
             active_profile: "Default".to_string(),
+            workspace: Vec::new(),
         }
     }
 }
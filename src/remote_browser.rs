@@ -74,6 +74,8 @@ pub enum RemoteBrowserMessage {
     CloseContentPreview,
     /// Game Mode launcher messages — delegated to [`crate::game_mode::GameMode`].
     Game(crate::game_mode::GameModeMessage),
+    /// A game's launch script, read and ready; handled by the app
+    RunLaunchScript(Result<crate::launch_script::LaunchRequest, String>),
     // Transfer progress (polled by subscription)
     ProgressTick,
 
@@ -294,6 +296,38 @@ impl RemoteBrowser {
         }
     }
 
+    /// Read a game's sidecar launch script (locally or over FTP) and pass it
+    /// up to the app, attached to the game's run file.
+    fn load_game_script(
+        &self,
+        script: String,
+        file: String,
+        local: bool,
+    ) -> Task<RemoteBrowserMessage> {
+        use crate::launch_script::{self, LaunchRequest, Location};
+        if local {
+            let request = launch_script::read_local(
+                std::path::Path::new(&script),
+                Some(std::path::Path::new(&file)),
+            );
+            return Task::done(RemoteBrowserMessage::RunLaunchScript(request));
+        }
+        let host = self.host_address.clone().unwrap_or_default();
+        let password = self.password.clone();
+        Task::perform(
+            async move {
+                let (_, bytes) = download_file_ftp_preview(host, script.clone(), password).await?;
+                Ok(LaunchRequest {
+                    name: remote_basename(&script),
+                    source: String::from_utf8_lossy(&bytes).into_owned(),
+                    script: Some(Location::Device(script)),
+                    file: Some(Location::Device(file)),
+                })
+            },
+            RemoteBrowserMessage::RunLaunchScript,
+        )
+    }
+
     /// Launch a game that lives on the local filesystem: read its bytes and
     /// upload+run them on the device (`.prg`/`.crt`/`.sid`), or upload+mount+
     /// autoload for a disk image. Device-resident games use the path-based run
@@ -981,6 +1015,12 @@ impl RemoteBrowser {
                     return task;
                 };
                 self.status_message = Some(format!("Launching {}…", remote_basename(&launch.path)));
+                if let Some(script) = launch.script {
+                    return Task::batch([
+                        task,
+                        self.load_game_script(script, launch.path, launch.local),
+                    ]);
+                }
                 let ext = launch.path.rsplit('.').next().unwrap_or("").to_lowercase();
                 if launch.local {
                     // Local game: upload its bytes to the device and run.
@@ -999,6 +1039,8 @@ impl RemoteBrowser {
                 }
             }
 
+            RemoteBrowserMessage::RunLaunchScript(_) => Task::none(),
+
             RemoteBrowserMessage::ProgressTick => {
                 if let Ok(guard) = self.transfer_progress.lock() {
                     if let Some(ref progress) = *guard {
@@ -1909,7 +1951,10 @@ impl RemoteBrowser {
 
                 let can_show_disk_info = {
                     let lower = entry.name.to_lowercase();
-                    lower.ends_with(".d64") || lower.ends_with(".d71")
+                    lower.ends_with(".d64")
+                        || lower.ends_with(".d71")
+                        || lower.ends_with(".g64")
+                        || lower.ends_with(".g71")
                 };
                 let ext_for_disk = entry.name.rsplit('.').next().unwrap_or("").to_lowercase();
                 let _is_disk_image = crate::file_types::is_disk_image(&ext_for_disk);
@@ -2529,7 +2574,7 @@ impl RemoteBrowser {
         let tiny = fs.tiny;
 
         let header = row![
-            text(format!("{} - ", disk_info.kind)).size(small),
+            text(format!("{} - ", disk_info.kind_label())).size(small),
             text(format!("\"{}\"", disk_info.name)).size(normal),
             Space::new().width(Length::Fill),
             text(format!("{} {}", disk_info.disk_id, disk_info.dos_type)).size(small),
@@ -2600,9 +2645,27 @@ impl RemoteBrowser {
                 .into()
             };
 
+        // GCR images: readable/unreadable summary, per-sector errors on hover
+        let gcr_summary: Element<'_, RemoteBrowserMessage> = match &disk_info.gcr {
+            Some(report) if report.bad_sectors.is_empty() && report.density.is_empty() => {
+                text(report.summary()).size(tiny).into()
+            }
+            Some(report) => tooltip(
+                text(report.summary())
+                    .size(tiny)
+                    .color(iced::Color::from_rgb(0.9, 0.6, 0.3)),
+                text(report.details()).size(tiny),
+                tooltip::Position::Top,
+            )
+            .style(crate::styles::subtle_tooltip)
+            .into(),
+            None => Space::new().width(0).into(),
+        };
+
         let footer = row![
             text(format!("{} BLOCKS FREE", disk_info.blocks_free)).size(small),
             Space::new().width(Length::Fill),
+            gcr_summary,
             text(format!("{} files", disk_info.entries.len())).size(tiny),
         ]
         .spacing(10);
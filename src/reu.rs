@@ -0,0 +1,311 @@
+//! REU read-back via an injected fetch stub
+//!
+//! The firmware can write REU memory (`CMD_REUWRITE`) but offers no way to
+//! read it. A fetch stub is parked through [`crate::nmi_stub`] and, on
+//! request, has the REU controller fetch a block into a C64 RAM window that
+//! the REST API can read. Everything touched is backed up first and restored
+//! when the session closes.
+//!
+//! The C64 must be running with the KERNAL banked in — the same limit as the
+//! screenshot bypass. The REU registers are left as the last fetch set them.
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::nmi_stub::{
+    self, read, write, ACK_DONE, ACK_EXITED, ACK_READY, MARKER, REQ_EXIT, REQ_WORK, STUB_ADDR,
+};
+use crate::remote_device::RemoteDevice;
+
+// ─── Layout ───────────────────────────────────────────────────────────────────
+
+/// Transfer parameters, mirrored into $DF02–$DF0A by the stub
+const PARAMS_ADDR: u16 = 0x03C0;
+/// C64 RAM the REU fetches into; clear of ROM so the REST read sees RAM
+pub const WINDOW_ADDR: u16 = 0x1000;
+pub const WINDOW_LEN: usize = 0x8000;
+
+/// REU sizes the Ultimate can emulate, in bytes
+pub const REU_SIZES: [u32; 8] = [
+    128 << 10,
+    256 << 10,
+    512 << 10,
+    1 << 20,
+    2 << 20,
+    4 << 20,
+    8 << 20,
+    16 << 20,
+];
+
+/// Check a `.reu` file length is one of the REU sizes
+pub fn is_valid_reu_size(len: usize) -> bool {
+    REU_SIZES.contains(&(len as u32))
+}
+
+/// Build the fetch stub. The stub:
+/// saves A/X/Y and $01, maps I/O in, acknowledges CIA2, reports ready, then
+/// loops on the marker — `REQ_WORK` copies the 9 parameter bytes into the
+/// REU registers and executes an immediate REU→C64 fetch; `REQ_EXIT` restores
+/// everything and returns with RTI.
+fn build_fetch_stub() -> Vec<u8> {
+    let mut c: Vec<u8> = Vec::with_capacity(80);
+    let [marker, _] = MARKER.to_le_bytes();
+
+    // Save A/X/Y, save $01 and map I/O in with the ROMs out ($01 = $35)
+    c.extend_from_slice(&[0x48, 0x8A, 0x48, 0x98, 0x48]);
+    c.extend_from_slice(&[0xA5, 0x01, 0x48, 0xA9, 0x35, 0x85, 0x01]);
+    // LDA $DD0D — acknowledge the CIA2 NMI
+    c.extend_from_slice(&[0xAD, 0x0D, 0xDD]);
+    c.extend_from_slice(&[0xA9, ACK_READY, 0x85, marker]);
+
+    // loop: LDA marker / CMP #REQ_WORK / BEQ fetch / CMP #REQ_EXIT / BNE loop
+    let loop_at = c.len();
+    c.extend_from_slice(&[0xA5, marker, 0xC9, REQ_WORK, 0xF0, 0x00]);
+    let beq_fetch = c.len() - 1;
+    c.extend_from_slice(&[0xC9, REQ_EXIT, 0xD0, 0x00]);
+    let bne_loop = c.len() - 1;
+    c[bne_loop] = (loop_at as isize - c.len() as isize) as u8;
+
+    // exit: restore $01, report, restore Y/X/A, RTI
+    c.extend_from_slice(&[0x68, 0x85, 0x01]);
+    c.extend_from_slice(&[0xA9, ACK_EXITED, 0x85, marker]);
+    c.extend_from_slice(&[0x68, 0xA8, 0x68, 0xAA, 0x68, 0x40]);
+
+    // fetch: LDX #9 / copy: LDA params-1,X / STA $DF01,X / DEX / BNE copy
+    c[beq_fetch] = (c.len() - beq_fetch - 1) as u8;
+    let [plo, phi] = (PARAMS_ADDR - 1).to_le_bytes();
+    c.extend_from_slice(&[
+        0xA2, 0x09, 0xBD, plo, phi, 0x9D, 0x01, 0xDF, 0xCA, 0xD0, 0xF7,
+    ]);
+    // LDA #$91 / STA $DF01 — execute, no $FF00 trigger, REU → C64
+    c.extend_from_slice(&[0xA9, 0x91, 0x8D, 0x01, 0xDF]);
+    c.extend_from_slice(&[0xA9, ACK_DONE, 0x85, marker]);
+    let [llo, lhi] = (STUB_ADDR + loop_at as u16).to_le_bytes();
+    c.extend_from_slice(&[0x4C, llo, lhi]);
+
+    c
+}
+
+/// REU register values for a fetch of `length` bytes from `offset` into the
+/// window: C64 address, REU address + bank, length, IRQ mask, address control.
+fn fetch_params(offset: u32, length: u16) -> [u8; 9] {
+    let [clo, chi] = WINDOW_ADDR.to_le_bytes();
+    let [rlo, rhi, bank, _] = offset.to_le_bytes();
+    let [llo, lhi] = length.to_le_bytes();
+    [clo, chi, rlo, rhi, bank, llo, lhi, 0x00, 0x00]
+}
+
+// ─── Session ──────────────────────────────────────────────────────────────────
+
+/// Everything the stub overwrites, restored by [`close_session`]
+#[derive(Debug, Clone)]
+pub struct StubBackup {
+    stub: nmi_stub::Backup,
+    window: Vec<u8>,
+}
+
+/// Back up, inject the stub and enter it. On success the C64 is parked in
+/// the stub until [`close_session`].
+pub fn open_session(conn: &dyn RemoteDevice) -> Result<StubBackup, String> {
+    let backup = StubBackup {
+        stub: nmi_stub::Backup::read(conn)?,
+        window: read(conn, WINDOW_ADDR, WINDOW_LEN)?,
+    };
+    nmi_stub::enter(conn, &backup.stub, &build_fetch_stub(), "REU fetch")?;
+    Ok(backup)
+}
+
+/// Fetch `length` bytes (≤ one window) of REU memory at `offset`.
+pub fn fetch(conn: &dyn RemoteDevice, offset: u32, length: usize) -> Result<Vec<u8>, String> {
+    if length == 0 || length > WINDOW_LEN {
+        return Err(format!("REU fetch length {} out of range", length));
+    }
+    write(conn, PARAMS_ADDR, &fetch_params(offset, length as u16))?;
+    if !nmi_stub::request(conn, Duration::from_secs(2))? {
+        return Err(format!("REU fetch at ${:06X} timed out", offset));
+    }
+    read(conn, WINDOW_ADDR, length)
+}
+
+/// Release the C64 from the stub and put back everything it touched.
+pub fn close_session(conn: &dyn RemoteDevice, backup: &StubBackup) -> Result<(), String> {
+    // The window is restored while the CPU is still parked in the stub.
+    write(conn, WINDOW_ADDR, &backup.window)?;
+    let left = nmi_stub::leave(conn, "REU fetch");
+    backup.stub.restore(conn);
+    left
+}
+
+/// Read `length` bytes of REU memory in one session.
+pub fn read_reu(conn: &dyn RemoteDevice, offset: u32, length: usize) -> Result<Vec<u8>, String> {
+    let backup = open_session(conn)?;
+    let mut out = Vec::with_capacity(length);
+    let result = (|| -> Result<(), String> {
+        while out.len() < length {
+            let take = (length - out.len()).min(WINDOW_LEN);
+            out.extend(fetch(conn, offset.wrapping_add(out.len() as u32), take)?);
+        }
+        Ok(())
+    })();
+    let closed = close_session(conn, &backup);
+    result?;
+    closed?;
+    Ok(out)
+}
+
+// ─── Diff ─────────────────────────────────────────────────────────────────────
+
+/// A run of REU bytes that differ from the snapshot
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DiffRange {
+    pub start: u32,
+    pub len: u32,
+}
+
+/// Differing runs between a snapshot and the current contents. Runs closer
+/// than `merge_gap` bytes are joined; bytes past the end of the shorter side
+/// count as different.
+pub fn diff(snapshot: &[u8], current: &[u8], merge_gap: u32) -> Vec<DiffRange> {
+    let len = snapshot.len().max(current.len());
+    let mut ranges: Vec<DiffRange> = Vec::new();
+    for i in 0..len {
+        if snapshot.get(i) == current.get(i) {
+            continue;
+        }
+        let i = i as u32;
+        match ranges.last_mut() {
+            Some(r) if i - (r.start + r.len) <= merge_gap => r.len = i + 1 - r.start,
+            _ => ranges.push(DiffRange { start: i, len: 1 }),
+        }
+    }
+    ranges
+}
+
+// ─── Async wrappers ───────────────────────────────────────────────────────────
+
+async fn blocking<T: Send + 'static>(
+    connection: Arc<Mutex<dyn RemoteDevice>>,
+    timeout_secs: u64,
+    f: impl FnOnce(&dyn RemoteDevice) -> Result<T, String> + Send + 'static,
+) -> Result<T, String> {
+    nmi_stub::blocking(connection, timeout_secs, "REU access", f).await
+}
+
+pub async fn read_reu_async(
+    connection: Arc<Mutex<dyn RemoteDevice>>,
+    offset: u32,
+    length: usize,
+) -> Result<Vec<u8>, String> {
+    blocking(connection, 30, move |conn| read_reu(conn, offset, length)).await
+}
+
+pub async fn open_session_async(
+    connection: Arc<Mutex<dyn RemoteDevice>>,
+) -> Result<StubBackup, String> {
+    blocking(connection, 15, open_session).await
+}
+
+pub async fn fetch_async(
+    connection: Arc<Mutex<dyn RemoteDevice>>,
+    offset: u32,
+    length: usize,
+) -> Result<Vec<u8>, String> {
+    blocking(connection, 15, move |conn| fetch(conn, offset, length)).await
+}
+
+pub async fn close_session_async(
+    connection: Arc<Mutex<dyn RemoteDevice>>,
+    backup: StubBackup,
+) -> Result<(), String> {
+    blocking(connection, 15, move |conn| close_session(conn, &backup)).await
+}
+
+/// Write a whole `.reu` image back through `CMD_REUWRITE`, 32 KB at a time.
+pub async fn restore_reu(
+    host: String,
+    password: Option<String>,
+    data: Vec<u8>,
+) -> Result<(), String> {
+    let client = crate::port64::Port64Client::new(host, password);
+    for (n, chunk) in data.chunks(WINDOW_LEN).enumerate() {
+        let offset = (n * WINDOW_LEN) as u32;
+        client
+            .reu_write(offset, chunk)
+            .await
+            .map_err(|e| format!("REU write at ${:06X} failed: {}", offset, e))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mos6510;
+
+    #[test]
+    fn stub_disassembles_and_branches_land_on_code() {
+        let stub = build_fetch_stub();
+        assert!(stub.len() < (PARAMS_ADDR - STUB_ADDR) as usize);
+        let listing = mos6510::disassemble(STUB_ADDR, &stub);
+        assert!(listing.iter().all(|i| !i.illegal));
+        let starts: Vec<u16> = listing.iter().map(|i| i.address).collect();
+        for i in &listing {
+            if i.mode == mos6510::AddrMode::Relative {
+                let target = i.target().unwrap();
+                assert!(
+                    starts.contains(&target),
+                    "{} lands mid-instruction",
+                    i.text()
+                );
+            }
+        }
+        let text: Vec<String> = listing.iter().map(|i| i.text()).collect();
+        assert!(text.contains(&"STA $DF01,X".to_string()));
+        assert!(text.contains(&"LDA $03BF,X".to_string()));
+        assert_eq!(text.iter().filter(|t| *t == "RTI").count(), 1);
+        assert_eq!(
+            listing.last().unwrap().text(),
+            format!("JMP ${:04X}", STUB_ADDR + 19)
+        );
+    }
+
+    #[test]
+    fn fetch_params_split_the_24_bit_offset() {
+        assert_eq!(
+            fetch_params(0x12_3456, 0x8000),
+            [0x00, 0x10, 0x56, 0x34, 0x12, 0x00, 0x80, 0x00, 0x00]
+        );
+    }
+
+    #[test]
+    fn diff_merges_close_runs() {
+        let a = vec![0u8; 64];
+        let mut b = a.clone();
+        b[3] = 1;
+        b[5] = 1;
+        b[40] = 1;
+        assert_eq!(
+            diff(&a, &b, 0),
+            [
+                DiffRange { start: 3, len: 1 },
+                DiffRange { start: 5, len: 1 },
+                DiffRange { start: 40, len: 1 }
+            ]
+        );
+        assert_eq!(
+            diff(&a, &b, 4),
+            [
+                DiffRange { start: 3, len: 3 },
+                DiffRange { start: 40, len: 1 }
+            ]
+        );
+        assert_eq!(diff(&a, &a[..60], 0), [DiffRange { start: 60, len: 4 }]);
+    }
+
+    #[test]
+    fn reu_sizes() {
+        assert!(is_valid_reu_size(16 << 20));
+        assert!(is_valid_reu_size(128 << 10));
+        assert!(!is_valid_reu_size(100_000));
+    }
+}
@@ -73,6 +73,19 @@ pub fn autoload_mounted_disk(conn: &dyn RemoteDevice, device_num: &str) -> Resul
     autoload_with(conn, device_num, READY_TIMEOUT, LOAD_TIMEOUT)
 }
 
+/// Reset the machine and wait for BASIC's `READY.` prompt. Launch scripts use
+/// this for their `RESET` step.
+pub fn reset_to_ready(conn: &dyn RemoteDevice) -> Result<(), String> {
+    reset_with(conn, READY_TIMEOUT).map(|_| ())
+}
+
+/// Type `LOAD"*",<dev>,1` at the prompt and wait for the load to finish.
+/// Launch scripts use this for their `LOAD` step.
+pub fn load_and_wait(conn: &dyn RemoteDevice, device_num: &str) -> Result<(), String> {
+    let baseline = ready_count(&conn.read_mem(SCREEN_BASE, SCREEN_LEN).unwrap_or_default());
+    load_with(conn, device_num, baseline, LOAD_TIMEOUT)
+}
+
 /// Core of [`autoload_mounted_disk`] with injectable timeouts (tests pass tiny
 /// values so they don't wait out the real multi-second caps).
 fn autoload_with(
@@ -81,31 +94,46 @@ fn autoload_with(
     ready_timeout: Duration,
     load_timeout: Duration,
 ) -> Result<(), String> {
+    let baseline = reset_with(conn, ready_timeout)?;
+    load_with(conn, device_num, baseline, load_timeout)?;
+
+    conn.type_text("run\n")
+        .map_err(|e| format!("Type RUN failed: {}", e))?;
+    Ok(())
+}
+
+/// Reset, then wait for the boot `READY.` prompt. Returns how many are on
+/// screen so a *fresh* one can be detected after a load.
+fn reset_with(conn: &dyn RemoteDevice, ready_timeout: Duration) -> Result<usize, String> {
     conn.reset().map_err(|e| format!("Reset failed: {}", e))?;
 
-    // Wait for the boot `READY.` prompt, then note how many are on screen so we
-    // can detect a *fresh* one after the load.
-    let baseline = match wait_for_ready(conn, 1, ready_timeout) {
-        Some(_) => ready_count(&conn.read_mem(SCREEN_BASE, SCREEN_LEN).unwrap_or_default()),
+    match wait_for_ready(conn, 1, ready_timeout) {
+        Some(_) => Ok(ready_count(
+            &conn.read_mem(SCREEN_BASE, SCREEN_LEN).unwrap_or_default(),
+        )),
         None => {
             // Screen unreadable — fall back to the old fixed delay.
             std::thread::sleep(FALLBACK_BOOT);
-            0
+            Ok(0)
         }
-    };
+    }
+}
 
+/// Type the LOAD and wait for a `READY.` beyond the `baseline` count. If the
+/// screen can't be read, fall back to a fixed wait so RUN isn't sent mid-load.
+fn load_with(
+    conn: &dyn RemoteDevice,
+    device_num: &str,
+    baseline: usize,
+    load_timeout: Duration,
+) -> Result<(), String> {
     let load_cmd = format!("load\"*\",{},1\n", device_num);
     conn.type_text(&load_cmd)
         .map_err(|e| format!("Type LOAD failed: {}", e))?;
 
-    // Wait for the load to finish: a fresh `READY.` beyond the boot one. If the
-    // screen can't be read, fall back to a fixed wait so RUN isn't sent mid-load.
     if wait_for_ready(conn, baseline + 1, load_timeout).is_none() {
         std::thread::sleep(Duration::from_secs(5));
     }
-
-    conn.type_text("run\n")
-        .map_err(|e| format!("Type RUN failed: {}", e))?;
     Ok(())
 }
 
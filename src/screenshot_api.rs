@@ -887,6 +887,104 @@ fn overlay_sprites_on_buf(
     }
 }
 
+// ── frame composition ─────────────────────────────────────────────────────────
+
+/// Size of a composed frame: 320×200 plus a 32 px border
+pub(crate) const FRAME_WIDTH: usize = 384;
+pub(crate) const FRAME_HEIGHT: usize = 264;
+
+/// Render the screen, blank it, add the border and overlay sprites.
+/// Returns a `FRAME_WIDTH`×`FRAME_HEIGHT` RGB buffer.
+fn compose_frame(
+    vic: &VicState,
+    screen_mem: &[u8],
+    color_mem: &[u8],
+    bitmap_mem: Option<&[u8]>,
+    char_rom: Option<&[u8]>,
+    sprites: &[Sprite],
+    sprite_data: &[Option<Vec<u8>>],
+) -> Vec<u8> {
+    // ── render ────────────────────────────────────────────────────────────────
+    let screen_rgb: Vec<u8> = match (vic.bmm, vic.mcm, vic.ecm) {
+        (true, true, _) => render_mc_bitmap(vic, bitmap_mem.unwrap(), screen_mem, color_mem),
+        (true, false, _) => render_hires_bitmap(vic, bitmap_mem.unwrap(), screen_mem),
+        (false, _, true) => render_ecm(vic, screen_mem, color_mem, char_rom.unwrap()),
+        (false, true, _) => render_mc_text(vic, screen_mem, color_mem, char_rom.unwrap()),
+        _ => render_text(vic, screen_mem, color_mem, char_rom.unwrap()),
+    };
+
+    // ── display blanking (RSEL/CSEL/DEN) ─────────────────────────────────────
+    let mut screen_rgb = screen_rgb;
+    apply_blanking(&mut screen_rgb, vic);
+
+    // ── border ────────────────────────────────────────────────────────────────
+    let border = add_border(&screen_rgb, vic.border_color);
+    let border_size = 32usize;
+    let mut final_rgb = border;
+
+    let enabled_count = sprites.iter().filter(|s| s.enabled).count();
+    if enabled_count > 0 {
+        overlay_sprites_on_buf(
+            &mut final_rgb,
+            FRAME_WIDTH,
+            FRAME_HEIGHT,
+            border_size,
+            sprites,
+            sprite_data,
+            vic,
+        );
+    }
+
+    final_rgb
+}
+
+/// Render a frame from a memory image instead of the live machine, as the VIC
+/// would see it: RAM everywhere except the character ROM in banks 0 and 2.
+/// `ram` is the full 64 KB, `vic_regs` $D000–$D02E, `color_mem` $D800–$DBE7.
+pub(crate) fn render_from_memory(
+    ram: &[u8],
+    vic_regs: &[u8],
+    cia2_pa: u8,
+    color_mem: &[u8],
+) -> Vec<u8> {
+    let vic = VicState::from_regs(vic_regs, cia2_pa);
+    let slice = |addr: u16, len: usize| -> Vec<u8> {
+        (0..len)
+            .map(|i| ram[(addr as usize + i) & 0xFFFF])
+            .collect()
+    };
+
+    let screen_mem = slice(vic.screen_mem_addr, 1024);
+    let (bitmap_mem, char_rom) = if vic.bmm {
+        (Some(slice(vic.bitmap_mem_addr, 8000)), None)
+    } else if (vic.vic_bank == 0x0000 || vic.vic_bank == 0x8000)
+        && (0x1000..0x2000).contains(&vic.char_mem_offset)
+    {
+        (None, Some(embedded_char_rom()))
+    } else {
+        (None, Some(slice(vic.char_mem_addr, 2048)))
+    };
+
+    let mut sprites = parse_sprites(&vic);
+    for (i, sprite) in sprites.iter_mut().enumerate() {
+        sprite.data_addr = vic.vic_bank + screen_mem[0x3F8 + i] as u16 * 64;
+    }
+    let sprite_data: Vec<Option<Vec<u8>>> = sprites
+        .iter()
+        .map(|s| s.enabled.then(|| slice(s.data_addr, 64)))
+        .collect();
+
+    compose_frame(
+        &vic,
+        &screen_mem,
+        color_mem,
+        bitmap_mem.as_deref(),
+        char_rom.as_deref(),
+        &sprites,
+        &sprite_data,
+    )
+}
+
 // ── public entry point ────────────────────────────────────────────────────────
 
 /// Capture a screenshot from the Ultimate 64 **without** starting video streaming.
@@ -903,17 +1001,31 @@ pub fn capture_screenshot_via_api(host: &str, password: Option<String>) -> Resul
     log::info!("screenshot_api: freezing machine on {}", host);
     api.pause().ok(); // ignore error – may already be paused
 
-    let result = capture_inner(&api);
+    let result = capture_frame(&api);
 
     log::info!("screenshot_api: resuming machine");
     if let Err(e) = api.resume() {
         log::warn!("screenshot_api: resume failed: {}", e);
     }
 
+    save_frame(result?)
+}
+
+/// Capture the composed RGB frame (`FRAME_WIDTH` × `FRAME_HEIGHT`) without
+/// saving it, for callers that compare or process the image themselves.
+///
+/// This is a **blocking** function; wrap it in `tokio::task::spawn_blocking`.
+pub fn capture_frame_via_api(host: &str, password: Option<String>) -> Result<Vec<u8>, String> {
+    let api = U64Api::new(host, password);
+    api.pause().ok();
+    let result = capture_frame(&api);
+    if let Err(e) = api.resume() {
+        log::warn!("screenshot_api: resume failed: {}", e);
+    }
     result
 }
 
-fn capture_inner(api: &U64Api) -> Result<String, String> {
+fn capture_frame(api: &U64Api) -> Result<Vec<u8>, String> {
     // ── read VIC-II registers ─────────────────────────────────────────────────
     let vic_regs = api.read_mem(0xD000, 0x30)?;
     let cia2_pa = api.read_mem(0xDD00, 1)?[0];
@@ -953,33 +1065,6 @@ fn capture_inner(api: &U64Api) -> Result<String, String> {
         (None, Some(cr))
     };
 
-    // ── render ────────────────────────────────────────────────────────────────
-    let screen_rgb: Vec<u8> = match (vic.bmm, vic.mcm, vic.ecm) {
-        (true, true, _) => render_mc_bitmap(
-            &vic,
-            bitmap_mem.as_deref().unwrap(),
-            &screen_mem,
-            &color_mem,
-        ),
-        (true, false, _) => render_hires_bitmap(&vic, bitmap_mem.as_deref().unwrap(), &screen_mem),
-        (false, _, true) => render_ecm(&vic, &screen_mem, &color_mem, char_rom.as_deref().unwrap()),
-        (false, true, _) => {
-            render_mc_text(&vic, &screen_mem, &color_mem, char_rom.as_deref().unwrap())
-        }
-        _ => render_text(&vic, &screen_mem, &color_mem, char_rom.as_deref().unwrap()),
-    };
-
-    // ── display blanking (RSEL/CSEL/DEN) ─────────────────────────────────────
-    let mut screen_rgb = screen_rgb;
-    apply_blanking(&mut screen_rgb, &vic);
-
-    // ── border ────────────────────────────────────────────────────────────────
-    let border = add_border(&screen_rgb, vic.border_color);
-    let out_w = 384usize;
-    let out_h = 264usize;
-    let border_size = 32usize;
-    let mut final_rgb = border;
-
     // ── sprites ───────────────────────────────────────────────────────────────
     let sprite_pointers = &screen_mem[0x3F8..0x400];
     let mut sprites = parse_sprites(&vic);
@@ -1004,17 +1089,19 @@ fn capture_inner(api: &U64Api) -> Result<String, String> {
         }
     }
 
-    if enabled_count > 0 {
-        overlay_sprites_on_buf(
-            &mut final_rgb,
-            out_w,
-            out_h,
-            border_size,
-            &sprites,
-            &sprite_data,
-            &vic,
-        );
-    }
+    Ok(compose_frame(
+        &vic,
+        &screen_mem,
+        &color_mem,
+        bitmap_mem.as_deref(),
+        char_rom.as_deref(),
+        &sprites,
+        &sprite_data,
+    ))
+}
+
+fn save_frame(final_rgb: Vec<u8>) -> Result<String, String> {
+    let (out_w, out_h) = (FRAME_WIDTH, FRAME_HEIGHT);
 
     // ── save ──────────────────────────────────────────────────────────────────
     let timestamp = SystemTime::now()
@@ -90,6 +90,10 @@ pub struct Preferences {
     /// settings loading.
     #[serde(default)]
     pub game_library_roots: Vec<String>,
+    /// Subnets searched by device discovery on top of the local /24
+    /// (`10.0.0.0/16`, ...), for larger or routed networks.
+    #[serde(default)]
+    pub discovery_subnets: Vec<String>,
 }
 
 fn default_true() -> bool {
@@ -128,6 +132,7 @@ impl Default for AppSettings {
                 last_active_tab: None,
                 use_gpu_video_shader: true,
                 game_library_roots: Vec::new(),
+                discovery_subnets: Vec::new(),
             },
         }
     }
@@ -7,11 +7,18 @@
 //! - **VIC-II** ($D000) — video, sprites, raster, colour registers
 //! - **CIA #1 / #2** ($DC00 / $DD00) — timers, TOD clock, port states
 //!
+//! The SID panel can also capture: it polls the chip back-to-back, logs
+//! every register change with a timestamp, draws the voices as a piano
+//! roll and exports a 25-bytes-per-frame register dump.
 
 use crate::remote_device::RemoteDevice;
 use iced::{
-    widget::{button, column, container, pick_list, row, rule, scrollable, text, Column, Space},
-    Element, Length, Subscription, Task,
+    widget::{
+        button,
+        canvas::{self, Canvas, Frame, Geometry, Path, Stroke},
+        column, container, pick_list, row, rule, scrollable, text, Column, Space,
+    },
+    Element, Length, Point, Rectangle, Size, Subscription, Task,
 };
 use std::sync::Arc;
 use std::sync::Mutex;
@@ -171,12 +178,19 @@ impl SidVoice {
         self.freq_reg as f64 * clock / 16_777_216.0
     }
 
-    fn note_name(&self, clock: f64) -> String {
+    /// MIDI note number, `None` below the audible range
+    fn midi_note(&self, clock: f64) -> Option<i32> {
         let hz = self.freq_hz(clock);
         if hz < 16.0 {
-            return "---".into();
+            return None;
         }
-        let midi = (69.0 + 12.0 * (hz / 440.0).log2()).round() as i32;
+        Some((69.0 + 12.0 * (hz / 440.0).log2()).round() as i32)
+    }
+
+    fn note_name(&self, clock: f64) -> String {
+        let Some(midi) = self.midi_note(clock) else {
+            return "---".into();
+        };
         let names = [
             "C", "C#", "D", "D#", "E", "F", "F#", "G", "G#", "A", "A#", "B",
         ];
@@ -293,6 +307,185 @@ impl SidState {
     }
 }
 
+// ─────────────────────────────────────────────────────────────────
+//  Register capture
+// ─────────────────────────────────────────────────────────────────
+
+/// Writable SID registers ($00–$18) — what a replayer needs per frame
+const SID_WRITABLE: usize = 0x19;
+
+/// One observed register change
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct RegisterWrite {
+    time_ms: u64,
+    register: u8,
+    value: u8,
+}
+
+/// A gated note held by one voice, for the piano roll
+#[derive(Debug, Clone, PartialEq)]
+struct NoteSpan {
+    start_ms: u64,
+    /// `None` while the note is still sounding
+    end_ms: Option<u64>,
+    midi: i32,
+    name: String,
+}
+
+/// Register writes seen while capturing, reconstructed by diffing
+/// back-to-back reads of the SID block. Writes that land between two polls
+/// and are overwritten before the next one are invisible — the log is as
+/// fine-grained as the poll rate allows.
+struct SidCapture {
+    sid_address: SidAddress,
+    tv_standard: TvStandard,
+    started: std::time::Instant,
+    last: Option<[u8; SID_WRITABLE]>,
+    writes: Vec<RegisterWrite>,
+    notes: [Vec<NoteSpan>; 3],
+    polls: u64,
+    elapsed_ms: u64,
+    log: Option<std::io::BufWriter<std::fs::File>>,
+    running: bool,
+}
+
+impl SidCapture {
+    fn new(
+        sid_address: SidAddress,
+        tv_standard: TvStandard,
+        log: Option<std::fs::File>,
+    ) -> std::io::Result<Self> {
+        let mut log = log.map(std::io::BufWriter::new);
+        if let Some(w) = &mut log {
+            use std::io::Write;
+            writeln!(w, "# Ultimate64 Manager SID register log")?;
+            writeln!(w, "# sid={} clock={}", sid_address, tv_standard)?;
+            writeln!(w, "# time_ms register value")?;
+        }
+        Ok(Self {
+            sid_address,
+            tv_standard,
+            started: std::time::Instant::now(),
+            last: None,
+            writes: Vec::new(),
+            notes: Default::default(),
+            polls: 0,
+            elapsed_ms: 0,
+            log,
+            running: true,
+        })
+    }
+
+    fn clock(&self) -> f64 {
+        match self.tv_standard {
+            TvStandard::Pal => PAL_CLOCK,
+            TvStandard::Ntsc => NTSC_CLOCK,
+        }
+    }
+
+    /// Diff one register read against the previous one. The first read
+    /// logs every register so the log starts from a known state.
+    fn record(&mut self, time_ms: u64, regs: &[u8]) -> std::io::Result<()> {
+        if regs.len() < SID_WRITABLE {
+            return Ok(());
+        }
+        let mut now = [0u8; SID_WRITABLE];
+        now.copy_from_slice(&regs[..SID_WRITABLE]);
+        self.polls += 1;
+        self.elapsed_ms = time_ms;
+
+        for (register, &value) in now.iter().enumerate() {
+            if self.last.is_some_and(|last| last[register] == value) {
+                continue;
+            }
+            let write = RegisterWrite {
+                time_ms,
+                register: register as u8,
+                value,
+            };
+            if let Some(w) = &mut self.log {
+                use std::io::Write;
+                writeln!(
+                    w,
+                    "{} {:04X} {:02X}",
+                    time_ms,
+                    self.sid_address.base() + register as u16,
+                    value
+                )?;
+            }
+            self.writes.push(write);
+        }
+        self.last = Some(now);
+
+        let clock = self.clock();
+        for (v, spans) in self.notes.iter_mut().enumerate() {
+            let voice = SidVoice::from_regs(&now, v * 7);
+            let sounding = voice
+                .midi_note(clock)
+                .filter(|_| voice.gate())
+                .map(|midi| (midi, voice.note_name(clock)));
+            let open = spans.last_mut().filter(|s| s.end_ms.is_none());
+            match (open, sounding) {
+                (Some(span), Some((midi, _))) if span.midi == midi => {}
+                (open, sounding) => {
+                    if let Some(span) = open {
+                        span.end_ms = Some(time_ms);
+                    }
+                    if let Some((midi, name)) = sounding {
+                        spans.push(NoteSpan {
+                            start_ms: time_ms,
+                            end_ms: None,
+                            midi,
+                            name,
+                        });
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn stop(&mut self) -> std::io::Result<()> {
+        self.running = false;
+        for span in self.notes.iter_mut().flatten() {
+            span.end_ms.get_or_insert(self.elapsed_ms);
+        }
+        match self.log.take() {
+            Some(mut w) => std::io::Write::flush(&mut w),
+            None => Ok(()),
+        }
+    }
+
+    /// Frames per second of the exported dump (one frame per video frame)
+    fn frame_rate(&self) -> u64 {
+        match self.tv_standard {
+            TvStandard::Pal => 50,
+            TvStandard::Ntsc => 60,
+        }
+    }
+
+    /// Raw register dump: registers $00–$18 (25 bytes) per video frame,
+    /// sampled from the write log at 50 Hz (PAL) or 60 Hz (NTSC). This is
+    /// the plain frame layout register-stream players replay. Frame 0 is the
+    /// first read: before its REST round trip returns nothing is known.
+    fn frame_dump(&self) -> Vec<u8> {
+        let rate = self.frame_rate();
+        let first_ms = self.writes.first().map_or(0, |w| w.time_ms);
+        let frames = self.elapsed_ms.saturating_sub(first_ms) * rate / 1000 + 1;
+        let mut state = [0u8; SID_WRITABLE];
+        let mut pending = self.writes.iter().peekable();
+        let mut out = Vec::with_capacity(frames as usize * SID_WRITABLE);
+        for frame in 0..frames {
+            let frame_ms = first_ms + frame * 1000 / rate;
+            while let Some(w) = pending.next_if(|w| w.time_ms <= frame_ms) {
+                state[w.register as usize] = w.value;
+            }
+            out.extend_from_slice(&state);
+        }
+        out
+    }
+}
+
 // ─────────────────────────────────────────────────────────────────
 //  Decoded VIC-II state
 // ─────────────────────────────────────────────────────────────────
@@ -483,6 +676,15 @@ pub enum SidMonitorMessage {
     SidDataReceived(Result<Vec<u8>, String>),
     VicDataReceived(Result<Vec<u8>, String>),
     CiaDataReceived(Result<(Vec<u8>, Vec<u8>), String>),
+
+    // Register capture
+    StartCapture,
+    CapturePathSelected(Option<std::path::PathBuf>),
+    CaptureDataReceived(Result<Vec<u8>, String>),
+    StopCapture,
+    ExportDump,
+    ExportDumpPathSelected(Option<std::path::PathBuf>),
+    ExportDumpComplete(Result<String, String>),
 }
 
 // ─────────────────────────────────────────────────────────────────
@@ -506,6 +708,9 @@ pub struct SidMonitor {
     is_loading: bool,
     poll_count: u64, // how many polls have completed
     status: String,
+
+    // Register capture (kept after stopping so it can be exported)
+    capture: Option<SidCapture>,
 }
 
 impl SidMonitor {
@@ -522,6 +727,7 @@ impl SidMonitor {
             is_loading: false,
             poll_count: 0,
             status: String::new(),
+            capture: None,
         }
     }
 
@@ -546,6 +752,9 @@ impl SidMonitor {
             }
 
             SidMonitorMessage::SidAddressChanged(addr) => {
+                if self.capturing() {
+                    return Task::none();
+                }
                 self.sid_address = addr;
                 self.sid = None;
                 Task::none()
@@ -571,7 +780,8 @@ impl SidMonitor {
             }
 
             SidMonitorMessage::Tick => {
-                if self.is_loading {
+                // A running capture keeps the SID panel fresh on its own.
+                if self.is_loading || (self.capturing() && self.active_panel == MonitorPanel::Sid) {
                     return Task::none();
                 }
                 let Some(conn) = connection else {
@@ -650,9 +860,162 @@ impl SidMonitor {
                 }
                 Task::none()
             }
+
+            // ── Register capture ─────────────────────────────────
+            SidMonitorMessage::StartCapture => {
+                if connection.is_none() || self.capturing() {
+                    return Task::none();
+                }
+                let default_name = format!(
+                    "sid_{:04X}_{}.txt",
+                    self.sid_address.base(),
+                    chrono::Local::now().format("%Y%m%d_%H%M%S")
+                );
+                Task::perform(
+                    async move {
+                        rfd::AsyncFileDialog::new()
+                            .set_title("Save SID register log")
+                            .set_file_name(&default_name)
+                            .add_filter("Register log", &["txt"])
+                            .save_file()
+                            .await
+                            .map(|h| h.path().to_path_buf())
+                    },
+                    SidMonitorMessage::CapturePathSelected,
+                )
+            }
+
+            SidMonitorMessage::CapturePathSelected(path) => {
+                let Some(path) = path else {
+                    return Task::none();
+                };
+                let capture = std::fs::File::create(&path)
+                    .and_then(|f| SidCapture::new(self.sid_address, self.tv_standard, Some(f)));
+                match capture {
+                    Ok(capture) => {
+                        self.capture = Some(capture);
+                        self.status = format!("Capturing to {}", path.display());
+                        self.next_capture_read(connection)
+                    }
+                    Err(e) => {
+                        self.status = format!("Cannot create log: {}", e);
+                        Task::none()
+                    }
+                }
+            }
+
+            SidMonitorMessage::CaptureDataReceived(result) => {
+                let Some(capture) = self.capture.as_mut().filter(|c| c.running) else {
+                    return Task::none();
+                };
+                let data = match result {
+                    Ok(data) => data,
+                    Err(e) => {
+                        self.status = format!("Capture stopped: {}", e);
+                        if let Err(e) = capture.stop() {
+                            self.status = format!("Capture log write failed: {}", e);
+                        }
+                        return Task::none();
+                    }
+                };
+                let time_ms = capture.started.elapsed().as_millis() as u64;
+                if let Err(e) = capture.record(time_ms, &data) {
+                    self.status = format!("Capture log write failed: {}", e);
+                    let _ = capture.stop();
+                    return Task::none();
+                }
+                self.status = format!(
+                    "Capturing — {} writes, {:.0} polls/s",
+                    capture.writes.len(),
+                    capture.polls as f64 * 1000.0 / time_ms.max(1) as f64
+                );
+                self.poll_count += 1;
+                self.sid = Some(SidState::from_bytes(&data));
+                // Straight into the next read: the poll rate is whatever
+                // the REST round trip sustains.
+                self.next_capture_read(connection)
+            }
+
+            SidMonitorMessage::StopCapture => {
+                if let Some(capture) = self.capture.as_mut().filter(|c| c.running) {
+                    self.status = match capture.stop() {
+                        Ok(()) => format!(
+                            "Captured {} writes in {:.1} s ({} polls)",
+                            capture.writes.len(),
+                            capture.elapsed_ms as f64 / 1000.0,
+                            capture.polls
+                        ),
+                        Err(e) => format!("Capture log write failed: {}", e),
+                    };
+                }
+                Task::none()
+            }
+
+            SidMonitorMessage::ExportDump => {
+                let Some(capture) = self.capture.as_ref().filter(|c| !c.running) else {
+                    return Task::none();
+                };
+                let default_name = format!("sid_{:04X}.dmp", capture.sid_address.base());
+                Task::perform(
+                    async move {
+                        rfd::AsyncFileDialog::new()
+                            .set_title("Export SID register dump")
+                            .set_file_name(&default_name)
+                            .add_filter("SID register dump", &["dmp"])
+                            .save_file()
+                            .await
+                            .map(|h| h.path().to_path_buf())
+                    },
+                    SidMonitorMessage::ExportDumpPathSelected,
+                )
+            }
+
+            SidMonitorMessage::ExportDumpPathSelected(path) => {
+                if let (Some(path), Some(capture)) = (path, &self.capture) {
+                    let dump = capture.frame_dump();
+                    let rate = capture.frame_rate();
+                    return Task::perform(
+                        async move {
+                            std::fs::write(&path, &dump)
+                                .map_err(|e| format!("Export failed: {}", e))?;
+                            Ok(format!(
+                                "Exported {} frames @ {} Hz to {}",
+                                dump.len() / SID_WRITABLE,
+                                rate,
+                                path.file_name().and_then(|n| n.to_str()).unwrap_or("file")
+                            ))
+                        },
+                        SidMonitorMessage::ExportDumpComplete,
+                    );
+                }
+                Task::none()
+            }
+
+            SidMonitorMessage::ExportDumpComplete(result) => {
+                self.status = result.unwrap_or_else(|e| e);
+                Task::none()
+            }
         }
     }
 
+    fn capturing(&self) -> bool {
+        self.capture.as_ref().is_some_and(|c| c.running)
+    }
+
+    fn next_capture_read(
+        &self,
+        connection: Option<Arc<Mutex<dyn RemoteDevice>>>,
+    ) -> Task<SidMonitorMessage> {
+        let (Some(conn), Some(capture)) = (connection, &self.capture) else {
+            return Task::none();
+        };
+        let addr = capture.sid_address.base();
+        Task::perform(
+            async move { read_bytes(conn, addr, SID_REG_LEN).await },
+            SidMonitorMessage::CaptureDataReceived,
+        )
+    }
+
     // ─────────────────────────────────────────────────────────────
     //  View
     // ─────────────────────────────────────────────────────────────
@@ -757,6 +1120,26 @@ impl SidMonitor {
                 )
                 .text_size(sf)
                 .width(Length::Fixed(65.0)),
+                if self.capturing() {
+                    button(text("■ Stop capture").size(sf))
+                        .on_press(SidMonitorMessage::StopCapture)
+                        .style(button::danger)
+                        .padding([5, 12])
+                } else {
+                    button(text("● Capture…").size(sf))
+                        .on_press(SidMonitorMessage::StartCapture)
+                        .style(button::secondary)
+                        .padding([5, 12])
+                },
+                button(text("Export .dmp…").size(sf))
+                    .on_press_maybe(
+                        self.capture
+                            .as_ref()
+                            .filter(|c| !c.running && !c.writes.is_empty())
+                            .map(|_| SidMonitorMessage::ExportDump)
+                    )
+                    .style(button::secondary)
+                    .padding([5, 12]),
             ]
             .spacing(8)
             .align_y(iced::Alignment::Center)
@@ -962,19 +1345,51 @@ impl SidMonitor {
             .padding(8)
             .width(Length::Fill);
 
-        scrollable(
-            column![
-                text(format!("SID @ {} — {}", self.sid_address, self.tv_standard)).size(sf),
-                rule::horizontal(1),
-                voices_block,
-                rule::horizontal(1),
-                filter_row,
-            ]
-            .spacing(8)
-            .width(Length::Fill),
-        )
-        .height(Length::Fill)
-        .into()
+        let mut content = column![
+            text(format!("SID @ {} — {}", self.sid_address, self.tv_standard)).size(sf),
+            rule::horizontal(1),
+            voices_block,
+            rule::horizontal(1),
+            filter_row,
+        ]
+        .spacing(8)
+        .width(Length::Fill);
+
+        if let Some(capture) = &self.capture {
+            let legend = row(VOICE_COLOURS.iter().enumerate().map(|(v, &colour)| {
+                let current = capture.notes[v]
+                    .last()
+                    .filter(|n| n.end_ms.is_none())
+                    .map(|n| n.name.as_str())
+                    .unwrap_or("---");
+                text(format!("V{} {}", v + 1, current))
+                    .size(mf)
+                    .color(colour)
+                    .into()
+            }))
+            .spacing(16);
+            content = content.push(rule::horizontal(1)).push(
+                container(
+                    column![
+                        row![
+                            text("Piano roll").size(sf),
+                            Space::new().width(Length::Fill),
+                            legend,
+                        ]
+                        .align_y(iced::Alignment::Center),
+                        Canvas::new(PianoRoll { capture })
+                            .width(Length::Fill)
+                            .height(Length::Fixed(180.0)),
+                    ]
+                    .spacing(6),
+                )
+                .style(section_style)
+                .padding(8)
+                .width(Length::Fill),
+            );
+        }
+
+        scrollable(content).height(Length::Fill).into()
     }
 
     // ── VIC-II panel ─────────────────────────────────────────────
@@ -1197,6 +1612,85 @@ impl SidMonitor {
     }
 }
 
+/// Voice colours shared by the piano roll and its legend
+const VOICE_COLOURS: [iced::Color; 3] = [
+    iced::Color::from_rgb(0.35, 0.8, 1.0),
+    iced::Color::from_rgb(0.4, 1.0, 0.5),
+    iced::Color::from_rgb(1.0, 0.7, 0.3),
+];
+
+/// Seconds of capture the piano roll shows (the most recent ones)
+const PIANO_ROLL_WINDOW_MS: u64 = 10_000;
+
+/// Note timelines of a capture: time left→right, pitch bottom→top.
+struct PianoRoll<'a> {
+    capture: &'a SidCapture,
+}
+
+impl canvas::Program<SidMonitorMessage> for PianoRoll<'_> {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &(),
+        renderer: &iced::Renderer,
+        _theme: &iced::Theme,
+        bounds: Rectangle,
+        _cursor: iced::mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let mut frame = Frame::new(renderer, bounds.size());
+        let (w, h) = (bounds.width, bounds.height);
+        let end_ms = self.capture.elapsed_ms.max(1);
+        let start_ms = end_ms.saturating_sub(PIANO_ROLL_WINDOW_MS);
+        let visible = |n: &&NoteSpan| n.end_ms.unwrap_or(end_ms) >= start_ms;
+
+        let (lo, hi) = self
+            .capture
+            .notes
+            .iter()
+            .flatten()
+            .filter(visible)
+            .fold((i32::MAX, i32::MIN), |(lo, hi), n| {
+                (lo.min(n.midi), hi.max(n.midi))
+            });
+        if lo > hi || w < 2.0 || h < 2.0 {
+            return vec![frame.into_geometry()];
+        }
+        // At least two octaves so a single note doesn't fill the height.
+        let lo = lo.min(hi - 24).max(0);
+        let rows = (hi - lo + 1) as f32;
+        let row_h = h / rows;
+        let x_of = |ms: u64| (ms.saturating_sub(start_ms)) as f32 / (end_ms - start_ms) as f32 * w;
+
+        // Octave lines on every C
+        for midi in lo..=hi {
+            if midi % 12 == 0 {
+                let y = h - (midi - lo + 1) as f32 * row_h;
+                frame.stroke(
+                    &Path::line(Point::new(0.0, y + row_h), Point::new(w, y + row_h)),
+                    Stroke::default()
+                        .with_color(iced::Color::from_rgba(1.0, 1.0, 1.0, 0.12))
+                        .with_width(1.0),
+                );
+            }
+        }
+
+        for (v, spans) in self.capture.notes.iter().enumerate() {
+            for n in spans.iter().filter(visible) {
+                let x0 = x_of(n.start_ms);
+                let x1 = x_of(n.end_ms.unwrap_or(end_ms)).max(x0 + 2.0);
+                let y = h - (n.midi - lo + 1) as f32 * row_h;
+                frame.fill_rectangle(
+                    Point::new(x0, y),
+                    Size::new(x1 - x0, row_h.max(2.0)),
+                    VOICE_COLOURS[v],
+                );
+            }
+        }
+        vec![frame.into_geometry()]
+    }
+}
+
 /// Render one CIA chip block. Free function to avoid closure lifetime issues.
 fn cia_block_view(
     cia: &CiaState,
@@ -1420,3 +1914,111 @@ impl crate::tab::TabController for SidMonitor {
         self.update_impl(message, ctx.connection)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Register block with voice 1 at frequency register `freq`, gated.
+    fn regs_with_v1(freq: u16, gate: bool) -> Vec<u8> {
+        let mut r = vec![0u8; SID_REG_LEN as usize];
+        r[0] = freq as u8;
+        r[1] = (freq >> 8) as u8;
+        r[4] = 0x10 | gate as u8;
+        r[0x18] = 0x0F;
+        r
+    }
+
+    #[test]
+    fn capture_logs_initial_state_then_changes() {
+        let mut c = SidCapture::new(SidAddress::D400, TvStandard::Pal, None).unwrap();
+        c.record(0, &regs_with_v1(0x1CD6, true)).unwrap();
+        assert_eq!(c.writes.len(), SID_WRITABLE);
+
+        c.record(20, &regs_with_v1(0x1CD6, true)).unwrap();
+        assert_eq!(c.writes.len(), SID_WRITABLE);
+
+        c.record(40, &regs_with_v1(0x1CD6, false)).unwrap();
+        assert_eq!(
+            c.writes.last(),
+            Some(&RegisterWrite {
+                time_ms: 40,
+                register: 4,
+                value: 0x10
+            })
+        );
+    }
+
+    #[test]
+    fn capture_writes_a_timestamped_log() {
+        let path = std::env::temp_dir().join(format!("sidlog_{}.txt", std::process::id()));
+        let file = std::fs::File::create(&path).unwrap();
+        let mut c = SidCapture::new(SidAddress::D500, TvStandard::Ntsc, Some(file)).unwrap();
+        c.record(0, &regs_with_v1(0x1000, false)).unwrap();
+        c.record(17, &regs_with_v1(0x1000, true)).unwrap();
+        c.stop().unwrap();
+
+        let log = std::fs::read_to_string(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert!(log.starts_with("# Ultimate64 Manager SID register log\n# sid=$D500 clock=NTSC"));
+        assert!(log.contains("\n0 D518 0F\n"));
+        assert!(log.ends_with("\n17 D504 11\n"));
+    }
+
+    #[test]
+    fn gated_notes_become_piano_roll_spans() {
+        let mut c = SidCapture::new(SidAddress::D400, TvStandard::Pal, None).unwrap();
+        // $1CD6 ≈ 440 Hz on PAL → A4; $1125 ≈ 262 Hz → C4
+        c.record(0, &regs_with_v1(0x1CD6, true)).unwrap();
+        c.record(100, &regs_with_v1(0x1CD6, true)).unwrap();
+        c.record(200, &regs_with_v1(0x1125, true)).unwrap();
+        c.record(300, &regs_with_v1(0x1125, false)).unwrap();
+        c.record(400, &regs_with_v1(0x1CD6, true)).unwrap();
+        c.stop().unwrap();
+
+        let spans: Vec<_> = c.notes[0]
+            .iter()
+            .map(|n| (n.start_ms, n.end_ms, n.name.as_str()))
+            .collect();
+        assert_eq!(
+            spans,
+            [
+                (0, Some(200), "A4"),
+                (200, Some(300), "C4"),
+                (400, Some(400), "A4")
+            ]
+        );
+        assert!(c.notes[1].is_empty());
+    }
+
+    #[test]
+    fn frame_dump_samples_state_per_video_frame() {
+        let mut c = SidCapture::new(SidAddress::D400, TvStandard::Pal, None).unwrap();
+        c.record(0, &regs_with_v1(0x0100, false)).unwrap();
+        c.record(30, &regs_with_v1(0x0100, true)).unwrap();
+        c.record(45, &regs_with_v1(0x0200, true)).unwrap();
+        c.stop().unwrap();
+
+        let dump = c.frame_dump();
+        // 45 ms at 50 Hz → frames at 0, 20 and 40 ms
+        assert_eq!(dump.len(), 3 * SID_WRITABLE);
+        let frame = |n: usize| &dump[n * SID_WRITABLE..(n + 1) * SID_WRITABLE];
+        assert_eq!(frame(0)[4], 0x10);
+        assert_eq!(frame(1)[4], 0x10);
+        assert_eq!(frame(2)[4], 0x11);
+        assert_eq!(frame(2)[1], 0x01);
+        assert_eq!(frame(2)[0x18], 0x0F);
+
+        // The first read lands a round trip after the start: frame 0 is that
+        // read, not silence, and later frames count from it.
+        let mut c = SidCapture::new(SidAddress::D400, TvStandard::Pal, None).unwrap();
+        let first = regs_with_v1(0x0100, true);
+        c.record(130, &first).unwrap();
+        c.record(150, &regs_with_v1(0x0200, true)).unwrap();
+        c.stop().unwrap();
+        let dump = c.frame_dump();
+        assert_eq!(dump.len(), 2 * SID_WRITABLE);
+        assert_eq!(&dump[..SID_WRITABLE], &first[..SID_WRITABLE]);
+        assert_eq!(dump[SID_WRITABLE + 1], 0x02);
+    }
+}
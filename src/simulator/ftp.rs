@@ -0,0 +1,319 @@
+//! Passive-mode FTP server over the simulator's SD card folder.
+//!
+//! Covers the commands `suppaftp` issues for `ftp_ops` (login, TYPE, PASV /
+//! EPSV, LIST/NLST, RETR, STOR, SIZE, CWD/CDUP/PWD, MKD/RMD, DELE,
+//! RNFR/RNTO, QUIT). Listings use the Unix `ls -l` layout that
+//! [`crate::ftp_ops::parse_ftp_line`] expects.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{IpAddr, TcpListener, TcpStream};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use super::{normalize, Simulator};
+
+/// How long a data command waits for the client to open the data connection.
+const DATA_ACCEPT_TIMEOUT: Duration = Duration::from_secs(10);
+
+struct Session {
+    sim: Simulator,
+    control: TcpStream,
+    cwd: String,
+    user: Option<String>,
+    logged_in: bool,
+    passive: Option<TcpListener>,
+    rename_from: Option<String>,
+}
+
+pub(super) fn serve(stream: TcpStream, sim: Simulator) {
+    let _ = stream.set_read_timeout(Some(Duration::from_secs(300)));
+    let reader = match stream.try_clone() {
+        Ok(s) => BufReader::new(s),
+        Err(_) => return,
+    };
+    let mut session = Session {
+        sim,
+        control: stream,
+        cwd: "/".into(),
+        user: None,
+        logged_in: false,
+        passive: None,
+        rename_from: None,
+    };
+    if session.reply(220, "Ultimate simulator FTP ready").is_err() {
+        return;
+    }
+    for line in reader.lines() {
+        let Ok(line) = line else { return };
+        let line = line.trim_end();
+        let (verb, arg) = line.split_once(' ').unwrap_or((line, ""));
+        let verb = verb.to_ascii_uppercase();
+        log::debug!(
+            "simulator: FTP {} {}",
+            verb,
+            if verb == "PASS" { "***" } else { arg }
+        );
+        let result = session.command(&verb, arg);
+        if result.is_err() || verb == "QUIT" {
+            return;
+        }
+    }
+}
+
+impl Session {
+    fn reply(&mut self, code: u16, text: &str) -> std::io::Result<()> {
+        self.control
+            .write_all(format!("{} {}\r\n", code, text).as_bytes())
+    }
+
+    /// Resolve an argument against the working directory.
+    fn device_path(&self, arg: &str) -> String {
+        if arg.starts_with('/') {
+            normalize(arg)
+        } else {
+            normalize(&format!("{}/{}", self.cwd, arg))
+        }
+    }
+
+    fn command(&mut self, verb: &str, arg: &str) -> std::io::Result<()> {
+        match verb {
+            "USER" => {
+                self.user = Some(arg.to_string());
+                if self.sim.requires_password() {
+                    self.reply(331, "Password required")
+                } else {
+                    self.logged_in = true;
+                    self.reply(230, "Logged in")
+                }
+            }
+            "PASS" => {
+                if self.user.is_none() {
+                    return self.reply(503, "Login with USER first");
+                }
+                if self.sim.password_ok(Some(arg)) {
+                    self.logged_in = true;
+                    self.reply(230, "Logged in")
+                } else {
+                    self.reply(530, "Login incorrect")
+                }
+            }
+            "QUIT" => self.reply(221, "Goodbye"),
+            "NOOP" => self.reply(200, "OK"),
+            "SYST" => self.reply(215, "UNIX Type: L8"),
+            "FEAT" => self
+                .control
+                .write_all(b"211-Features:\r\n SIZE\r\n PASV\r\n EPSV\r\n211 End\r\n"),
+            _ if !self.logged_in => self.reply(530, "Not logged in"),
+            "TYPE" | "MODE" | "STRU" | "OPTS" => self.reply(200, "OK"),
+            "PWD" | "XPWD" => {
+                let cwd = self.cwd.clone();
+                self.reply(257, &format!("\"{}\" is the current directory", cwd))
+            }
+            "CWD" | "XCWD" => {
+                let target = self.device_path(arg);
+                if self.sim.sd_path(&target).is_dir() {
+                    self.cwd = target;
+                    self.reply(250, "Directory changed")
+                } else {
+                    self.reply(550, "No such directory")
+                }
+            }
+            "CDUP" | "XCUP" => {
+                self.cwd = self.device_path("..");
+                self.reply(250, "Directory changed")
+            }
+            "PASV" => self.open_passive(false),
+            "EPSV" => self.open_passive(true),
+            "LIST" | "NLST" => {
+                // Ignore `ls`-style flags some clients send.
+                let arg = arg
+                    .split_whitespace()
+                    .filter(|a| !a.starts_with('-'))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                let target = self.sim.sd_path(&self.device_path(&arg));
+                match listing(&target, verb == "NLST") {
+                    Ok(text) => self.send_data(text.as_bytes()),
+                    Err(e) => self.reply(550, &e),
+                }
+            }
+            "RETR" => match std::fs::read(self.sim.sd_path(&self.device_path(arg))) {
+                Ok(data) => self.send_data(&data),
+                Err(e) => self.reply(550, &e.to_string()),
+            },
+            "STOR" => {
+                let target = self.device_path(arg);
+                self.receive_data(&target)
+            }
+            "SIZE" => match std::fs::metadata(self.sim.sd_path(&self.device_path(arg))) {
+                Ok(meta) if meta.is_file() => self.reply(213, &meta.len().to_string()),
+                _ => self.reply(550, "No such file"),
+            },
+            "MKD" | "XMKD" => {
+                let target = self.device_path(arg);
+                match std::fs::create_dir(self.sim.sd_path(&target)) {
+                    Ok(()) => self.reply(257, &format!("\"{}\" created", target)),
+                    Err(e) => self.reply(550, &e.to_string()),
+                }
+            }
+            "RMD" | "XRMD" => {
+                let target = self.sim.sd_path(&self.device_path(arg));
+                self.fs_result(std::fs::remove_dir(target))
+            }
+            "DELE" => {
+                let target = self.sim.sd_path(&self.device_path(arg));
+                self.fs_result(std::fs::remove_file(target))
+            }
+            "RNFR" => {
+                let source = self.device_path(arg);
+                if self.sim.sd_path(&source).exists() {
+                    self.rename_from = Some(source);
+                    self.reply(350, "Ready for RNTO")
+                } else {
+                    self.reply(550, "No such file")
+                }
+            }
+            "RNTO" => match self.rename_from.take() {
+                Some(source) => {
+                    let from = self.sim.sd_path(&source);
+                    let to = self.sim.sd_path(&self.device_path(arg));
+                    self.fs_result(std::fs::rename(from, to))
+                }
+                None => self.reply(503, "RNFR first"),
+            },
+            _ => self.reply(502, "Command not implemented"),
+        }
+    }
+
+    fn fs_result(&mut self, result: std::io::Result<()>) -> std::io::Result<()> {
+        match result {
+            Ok(()) => self.reply(250, "OK"),
+            Err(e) => self.reply(550, &e.to_string()),
+        }
+    }
+
+    fn open_passive(&mut self, extended: bool) -> std::io::Result<()> {
+        let ip = self.control.local_addr()?.ip();
+        let listener = TcpListener::bind((ip, 0))?;
+        let port = listener.local_addr()?.port();
+        self.passive = Some(listener);
+        if extended {
+            return self.reply(
+                229,
+                &format!("Entering Extended Passive Mode (|||{}|)", port),
+            );
+        }
+        let IpAddr::V4(v4) = ip else {
+            return self.reply(425, "Use EPSV for IPv6");
+        };
+        let o = v4.octets();
+        self.reply(
+            227,
+            &format!(
+                "Entering Passive Mode ({},{},{},{},{},{})",
+                o[0],
+                o[1],
+                o[2],
+                o[3],
+                port >> 8,
+                port & 0xFF
+            ),
+        )
+    }
+
+    /// Wait for the client to connect to the PASV port.
+    fn accept_data(&mut self) -> Option<TcpStream> {
+        let listener = self.passive.take()?;
+        listener.set_nonblocking(true).ok()?;
+        let started = Instant::now();
+        loop {
+            match listener.accept() {
+                Ok((stream, _)) => {
+                    stream.set_nonblocking(false).ok()?;
+                    return Some(stream);
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                    if started.elapsed() > DATA_ACCEPT_TIMEOUT {
+                        return None;
+                    }
+                    std::thread::sleep(Duration::from_millis(5));
+                }
+                Err(_) => return None,
+            }
+        }
+    }
+
+    fn send_data(&mut self, data: &[u8]) -> std::io::Result<()> {
+        if self.passive.is_none() {
+            return self.reply(425, "Use PASV first");
+        }
+        self.reply(150, "Opening data connection")?;
+        let Some(mut data_stream) = self.accept_data() else {
+            return self.reply(425, "Data connection timed out");
+        };
+        let sent = data_stream.write_all(data);
+        drop(data_stream);
+        match sent {
+            Ok(()) => self.reply(226, "Transfer complete"),
+            Err(_) => self.reply(426, "Transfer aborted"),
+        }
+    }
+
+    fn receive_data(&mut self, target: &str) -> std::io::Result<()> {
+        if self.passive.is_none() {
+            return self.reply(425, "Use PASV first");
+        }
+        self.reply(150, "Ready to receive")?;
+        let Some(mut data_stream) = self.accept_data() else {
+            return self.reply(425, "Data connection timed out");
+        };
+        let mut data = Vec::new();
+        if data_stream.read_to_end(&mut data).is_err() {
+            return self.reply(426, "Transfer aborted");
+        }
+        match std::fs::write(self.sim.sd_path(target), &data) {
+            Ok(()) => self.reply(226, "Transfer complete"),
+            Err(e) => self.reply(550, &e.to_string()),
+        }
+    }
+}
+
+/// `ls -l`-style listing of a directory (or a single file).
+fn listing(path: &Path, names_only: bool) -> Result<String, String> {
+    let line = |name: &str, meta: &std::fs::Metadata| {
+        if names_only {
+            return format!("{}\r\n", name);
+        }
+        let modified: chrono::DateTime<chrono::Local> = meta
+            .modified()
+            .map(Into::into)
+            .unwrap_or_else(|_| chrono::Local::now());
+        format!(
+            "{}rw-rw-rw-   1 user     ftp  {:>10} {} {}\r\n",
+            if meta.is_dir() { 'd' } else { '-' },
+            if meta.is_dir() { 0 } else { meta.len() },
+            modified.format("%b %d %H:%M"),
+            name
+        )
+    };
+    let meta = std::fs::metadata(path).map_err(|_| "No such file or directory".to_string())?;
+    if meta.is_file() {
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        return Ok(line(&name, &meta));
+    }
+    let mut entries: Vec<_> = std::fs::read_dir(path)
+        .map_err(|e| e.to_string())?
+        .filter_map(|e| e.ok())
+        .collect();
+    entries.sort_by_key(|e| e.file_name());
+    Ok(entries
+        .iter()
+        .filter_map(|e| {
+            let meta = e.metadata().ok()?;
+            Some(line(&e.file_name().to_string_lossy(), &meta))
+        })
+        .collect())
+}
@@ -0,0 +1,1346 @@
+//! Local Ultimate device simulator.
+//!
+//! Serves the three network faces of an Ultimate 64 on localhost so the app,
+//! the CLI and `integration.rs`-style tests can run without real hardware:
+//!
+//! * **REST** (`rest.rs`) — the `/v1/...` endpoints the tabs and the
+//!   `ultimate64` crate call: memory, machine control, runners, drives,
+//!   configs and stream start/stop.
+//! * **Port 64** (`port64.rs`) — the binary DMA protocol spoken by
+//!   [`crate::port64::Port64Client`].
+//! * **FTP** (`ftp.rs`) — a passive-mode server over a *virtual SD card*, a
+//!   plain directory on disk laid out like the device (`/SD`, `/Usb0`,
+//!   `/Temp`).
+//!
+//! All three share one [`Machine`]: a flat 64 KB RAM model with a BASIC
+//! boot screen, a tiny line interpreter for the keyboard buffer (enough for
+//! `LOAD"*",8,1` / `RUN` autoloads), two drives and a config tree.
+//! [`Simulator`] also implements [`RemoteDevice`] directly, so a tab can be
+//! driven in-process without any sockets.
+//!
+//! The app and `ultimate64::Rest` always talk to ports 80/21/64, so
+//! `ultimate64-manager simulate` binds those by default (on Linux this needs
+//! `CAP_NET_BIND_SERVICE` or a lowered `net.ipv4.ip_unprivileged_port_start`).
+//! Tests bind ephemeral ports via [`SimConfig::ephemeral`].
+
+mod ftp;
+mod port64;
+mod rest;
+
+use std::collections::BTreeMap;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use anyhow::Result;
+use ultimate64::drives::{Drive, DriveType, MountMode};
+use ultimate64::DeviceInfo;
+
+use crate::remote_device::RemoteDevice;
+
+pub const DEFAULT_HTTP_PORT: u16 = 80;
+pub const DEFAULT_FTP_PORT: u16 = 21;
+
+/// Product string reported by `/v1/info` and `CMD_IDENTIFY`.
+pub const PRODUCT: &str = "Ultimate 64 (simulated)";
+const FIRMWARE_VERSION: &str = "3.12";
+const API_VERSION: &str = "0.1";
+
+/// Top-level folders created on a fresh virtual SD card.
+const SD_FOLDERS: &[&str] = &["SD", "Usb0", "Temp"];
+
+/// Cap for the call log so a long-running simulator doesn't grow unbounded.
+const MAX_LOG: usize = 1000;
+
+// ─── Configuration ───────────────────────────────────────────────────────────
+
+/// Where the simulator listens and which folder backs its SD card.
+#[derive(Debug, Clone)]
+pub struct SimConfig {
+    pub bind: IpAddr,
+    pub http_port: u16,
+    pub ftp_port: u16,
+    pub port64_port: u16,
+    pub sd_root: PathBuf,
+    /// Network password; when set every service requires it.
+    pub password: Option<String>,
+}
+
+impl SimConfig {
+    /// The real device ports on localhost.
+    pub fn new(sd_root: PathBuf) -> Self {
+        Self {
+            bind: IpAddr::V4(Ipv4Addr::LOCALHOST),
+            http_port: DEFAULT_HTTP_PORT,
+            ftp_port: DEFAULT_FTP_PORT,
+            port64_port: crate::port64::PORT,
+            sd_root,
+            password: None,
+        }
+    }
+
+    /// OS-assigned ports on localhost — for tests and unprivileged runs.
+    #[allow(dead_code)]
+    pub fn ephemeral(sd_root: PathBuf) -> Self {
+        Self {
+            http_port: 0,
+            ftp_port: 0,
+            port64_port: 0,
+            ..Self::new(sd_root)
+        }
+    }
+
+    /// Shift all three ports by `offset` (80 → 8080 with an offset of 8000).
+    pub fn with_port_offset(mut self, offset: u16) -> Result<Self, String> {
+        let shift = |p: u16| {
+            p.checked_add(offset)
+                .ok_or_else(|| format!("Port offset {} is too large", offset))
+        };
+        self.http_port = shift(self.http_port)?;
+        self.ftp_port = shift(self.ftp_port)?;
+        self.port64_port = shift(self.port64_port)?;
+        Ok(self)
+    }
+}
+
+/// `<data dir>/ultimate64-manager/simulator-sd` — the default SD card folder.
+pub fn default_sd_root() -> Result<PathBuf, String> {
+    Ok(dirs::data_dir()
+        .or_else(dirs::home_dir)
+        .ok_or_else(|| "Could not determine data directory".to_string())?
+        .join("ultimate64-manager")
+        .join("simulator-sd"))
+}
+
+// ─── Config tree ─────────────────────────────────────────────────────────────
+
+/// `(category, item, default, allowed values)` — an empty value list means
+/// free text. A small but representative slice of the real tree, covering
+/// the categories the drive and profile code reads.
+const DEFAULT_CONFIG: &[(&str, &str, &str, &[&str])] = &[
+    (
+        "U64 Specific Settings",
+        "System Mode",
+        "PAL",
+        &["PAL", "NTSC", "PAL-60", "NTSC-50"],
+    ),
+    (
+        "U64 Specific Settings",
+        "Turbo Control",
+        "Off",
+        &["Off", "Manual", "U64 Turbo Registers", "TurboEnable Bit"],
+    ),
+    (
+        "C64 and Cartridge Settings",
+        "RAM Expansion Unit",
+        "Disabled",
+        &["Disabled", "Enabled"],
+    ),
+    (
+        "C64 and Cartridge Settings",
+        "REU Size",
+        "16 MB",
+        &[
+            "128 KB", "256 KB", "512 KB", "1 MB", "2 MB", "4 MB", "8 MB", "16 MB",
+        ],
+    ),
+    (
+        "Drive A Settings",
+        "Drive",
+        "Enabled",
+        &["Disabled", "Enabled"],
+    ),
+    (
+        "Drive A Settings",
+        "Drive Type",
+        "1541",
+        &["1541", "1571", "1581"],
+    ),
+    (
+        "Drive A Settings",
+        "Drive Bus ID",
+        "8",
+        &["8", "9", "10", "11"],
+    ),
+    (
+        "Drive B Settings",
+        "Drive",
+        "Disabled",
+        &["Disabled", "Enabled"],
+    ),
+    (
+        "Drive B Settings",
+        "Drive Type",
+        "1541",
+        &["1541", "1571", "1581"],
+    ),
+    (
+        "Drive B Settings",
+        "Drive Bus ID",
+        "9",
+        &["8", "9", "10", "11"],
+    ),
+    ("Network settings", "Host Name", "Ultimate-64-Sim", &[]),
+];
+
+pub(crate) type ConfigValues = BTreeMap<String, BTreeMap<String, serde_json::Value>>;
+
+fn default_config() -> ConfigValues {
+    let mut tree = ConfigValues::new();
+    for (category, item, default, _) in DEFAULT_CONFIG {
+        tree.entry(category.to_string())
+            .or_default()
+            .insert(item.to_string(), serde_json::json!(default));
+    }
+    tree
+}
+
+/// Allowed values for an item, `None` for unknown items.
+fn config_values(category: &str, item: &str) -> Option<&'static [&'static str]> {
+    DEFAULT_CONFIG
+        .iter()
+        .find(|(c, i, _, _)| c.eq_ignore_ascii_case(category) && i.eq_ignore_ascii_case(item))
+        .map(|(_, _, _, values)| *values)
+}
+
+fn config_default(category: &str, item: &str) -> Option<&'static str> {
+    DEFAULT_CONFIG
+        .iter()
+        .find(|(c, i, _, _)| c.eq_ignore_ascii_case(category) && i.eq_ignore_ascii_case(item))
+        .map(|(_, _, default, _)| *default)
+}
+
+// ─── Machine model ───────────────────────────────────────────────────────────
+
+/// Screen codes for the text the boot screen and the line interpreter print.
+fn screen_code(c: char) -> u8 {
+    let c = c.to_ascii_uppercase();
+    match c as u32 {
+        0x40..=0x5F => (c as u32 - 0x40) as u8,
+        0x20..=0x3F => c as u8,
+        _ => 0x20,
+    }
+}
+
+/// Keyboard-buffer PETSCII → the ASCII the line interpreter matches on.
+fn petscii_to_ascii(b: u8) -> Option<char> {
+    match b {
+        0x20..=0x5F => Some(b as char),
+        0x61..=0x7A => Some((b - 0x20) as char),
+        0xC1..=0xDA => Some((b - 0x80) as char),
+        _ => None,
+    }
+}
+
+const SCREEN: usize = 0x0400;
+const COLOR_RAM: usize = 0xD800;
+const COLUMNS: usize = 40;
+const ROWS: usize = 25;
+const KEYBOARD_BUFFER: usize = 0x0277;
+const KEYBOARD_COUNT: usize = 0x00C6;
+
+/// One of the two emulated drives.
+#[derive(Debug, Clone)]
+pub(crate) struct SimDrive {
+    pub letter: char,
+    pub image_name: Option<String>,
+    pub image_path: Option<String>,
+    pub image: Option<Vec<u8>>,
+    pub mode: String,
+}
+
+impl SimDrive {
+    fn new(letter: char) -> Self {
+        Self {
+            letter,
+            image_name: None,
+            image_path: None,
+            image: None,
+            mode: "readwrite".into(),
+        }
+    }
+
+    fn category(&self) -> String {
+        format!("Drive {} Settings", self.letter.to_ascii_uppercase())
+    }
+}
+
+/// Everything the simulated machine remembers. Guarded by one mutex shared
+/// by all services.
+pub(crate) struct Machine {
+    pub ram: Vec<u8>,
+    pub reu: Vec<u8>,
+    pub kernal: Option<Vec<u8>>,
+    pub drives: Vec<SimDrive>,
+    pub config: ConfigValues,
+    flash: ConfigValues,
+    pub debugreg: u8,
+    pub paused: bool,
+    /// Active streams: name → destination.
+    pub streams: BTreeMap<String, String>,
+    /// What the machine was last told to run, if anything.
+    pub running: Option<String>,
+    cursor_row: usize,
+    cursor_col: usize,
+    calls: Vec<String>,
+}
+
+impl Machine {
+    fn new() -> Self {
+        let mut m = Self {
+            ram: vec![0; 0x10000],
+            reu: Vec::new(),
+            kernal: None,
+            drives: vec![SimDrive::new('a'), SimDrive::new('b')],
+            config: default_config(),
+            flash: default_config(),
+            debugreg: 0,
+            paused: false,
+            streams: BTreeMap::new(),
+            running: None,
+            cursor_row: 0,
+            cursor_col: 0,
+            calls: Vec::new(),
+        };
+        m.cold_boot();
+        m.calls.clear();
+        m
+    }
+
+    pub fn log(&mut self, call: impl Into<String>) {
+        if self.calls.len() >= MAX_LOG {
+            self.calls.remove(0);
+        }
+        let call = call.into();
+        log::info!("simulator: {}", call);
+        self.calls.push(call);
+    }
+
+    /// Power-on state: cleared RAM, BASIC pointers and the familiar banner.
+    fn cold_boot(&mut self) {
+        self.ram.iter_mut().for_each(|b| *b = 0);
+        // TXTTAB/VARTAB with an empty program at $0801.
+        self.ram[0x2B..0x2F].copy_from_slice(&[0x01, 0x08, 0x03, 0x08]);
+        self.ram[0x0302..0x0304].copy_from_slice(&[0x83, 0xA4]);
+        self.ram[0xD020] = 14;
+        self.ram[0xD021] = 6;
+        self.ram[SCREEN..SCREEN + COLUMNS * ROWS].fill(0x20);
+        self.ram[COLOR_RAM..COLOR_RAM + COLUMNS * ROWS].fill(14);
+        self.cursor_row = 1;
+        self.cursor_col = 0;
+        self.print_line("    **** COMMODORE 64 BASIC V2 ****");
+        self.cursor_row += 1;
+        self.print_line(" 64K RAM SYSTEM  38911 BASIC BYTES FREE");
+        self.cursor_row += 1;
+        self.print_line("READY.");
+        self.running = None;
+        self.paused = false;
+    }
+
+    pub fn reset(&mut self) {
+        self.log("reset");
+        self.cold_boot();
+    }
+
+    fn scroll_if_needed(&mut self) {
+        while self.cursor_row >= ROWS {
+            self.ram
+                .copy_within(SCREEN + COLUMNS..SCREEN + COLUMNS * ROWS, SCREEN);
+            let last = SCREEN + COLUMNS * (ROWS - 1);
+            self.ram[last..last + COLUMNS].fill(0x20);
+            self.cursor_row -= 1;
+        }
+    }
+
+    fn put_char(&mut self, c: char) {
+        self.scroll_if_needed();
+        let at = SCREEN + self.cursor_row * COLUMNS + self.cursor_col;
+        self.ram[at] = screen_code(c);
+        self.cursor_col += 1;
+        if self.cursor_col == COLUMNS {
+            self.cursor_col = 0;
+            self.cursor_row += 1;
+        }
+    }
+
+    fn print_line(&mut self, text: &str) {
+        text.chars().for_each(|c| self.put_char(c));
+        self.cursor_col = 0;
+        self.cursor_row += 1;
+        self.scroll_if_needed();
+    }
+
+    pub fn read(&self, address: u16, length: usize) -> Result<Vec<u8>, String> {
+        let start = address as usize;
+        if start + length > self.ram.len() {
+            return Err(format!(
+                "Read of {} bytes at ${:04X} runs past $FFFF",
+                length, address
+            ));
+        }
+        Ok(self.ram[start..start + length].to_vec())
+    }
+
+    /// DMA write. A write that covers the keyboard count at `$C6` makes the
+    /// line interpreter consume the keyboard buffer, like the KERNAL would.
+    pub fn write(&mut self, address: u16, data: &[u8]) -> Result<(), String> {
+        let start = address as usize;
+        if start + data.len() > self.ram.len() {
+            return Err(format!(
+                "Write of {} bytes at ${:04X} runs past $FFFF",
+                data.len(),
+                address
+            ));
+        }
+        self.ram[start..start + data.len()].copy_from_slice(data);
+        if (start..start + data.len()).contains(&KEYBOARD_COUNT) {
+            self.drain_keyboard();
+        }
+        Ok(())
+    }
+
+    /// Put up to ten PETSCII keys in the buffer and process them.
+    pub fn type_keys(&mut self, keys: &[u8]) {
+        for chunk in keys.chunks(10) {
+            self.ram[KEYBOARD_BUFFER..KEYBOARD_BUFFER + chunk.len()].copy_from_slice(chunk);
+            self.ram[KEYBOARD_COUNT] = chunk.len() as u8;
+            self.drain_keyboard();
+        }
+    }
+
+    fn drain_keyboard(&mut self) {
+        let count = (self.ram[KEYBOARD_COUNT] as usize).min(10);
+        self.ram[KEYBOARD_COUNT] = 0;
+        for i in 0..count {
+            let key = self.ram[KEYBOARD_BUFFER + i];
+            if key == 0x0D {
+                self.enter_line();
+            } else if let Some(c) = petscii_to_ascii(key) {
+                self.put_char(c);
+            }
+        }
+    }
+
+    /// RETURN: run the screen line under the cursor through the interpreter.
+    fn enter_line(&mut self) {
+        let row = self.cursor_row.min(ROWS - 1);
+        let at = SCREEN + row * COLUMNS;
+        let line: String = self.ram[at..at + COLUMNS]
+            .iter()
+            .map(|&code| match code {
+                0x00..=0x1F => (code + 0x40) as char,
+                0x20..=0x3F => code as char,
+                _ => ' ',
+            })
+            .collect();
+        self.cursor_col = 0;
+        self.cursor_row = row + 1;
+        self.scroll_if_needed();
+        self.execute(line.trim());
+    }
+
+    fn execute(&mut self, line: &str) {
+        let compact: String = line.chars().filter(|c| *c != ' ').collect();
+        if compact.is_empty() {
+            return;
+        }
+        self.log(format!("basic({})", line));
+        if let Some(args) = compact.strip_prefix("LOAD") {
+            self.basic_load(args);
+        } else if compact == "RUN" {
+            // The program "takes over" — no READY. until the next reset.
+            self.running = Some(self.running.take().unwrap_or_else(|| "BASIC".into()));
+            return;
+        } else if compact == "NEW" {
+            self.ram[0x0801..0x0803].fill(0);
+            self.ram[0x2D..0x2F].copy_from_slice(&[0x03, 0x08]);
+        } else {
+            self.print_line("?SYNTAX  ERROR");
+        }
+        self.print_line("READY.");
+    }
+
+    /// `LOAD"NAME",dev[,1]` against the mounted image.
+    fn basic_load(&mut self, args: &str) {
+        let mut parts = args.split(',');
+        let name = parts.next().unwrap_or("").trim_matches('"').to_string();
+        let device: u8 = parts.next().and_then(|d| d.parse().ok()).unwrap_or(1);
+        let absolute = parts.next() == Some("1");
+
+        let Some(drive) = self
+            .drives
+            .iter()
+            .position(|d| self.drive_enabled(d) && self.drive_bus_id(d) == device)
+        else {
+            self.print_line("?DEVICE NOT PRESENT  ERROR");
+            return;
+        };
+        self.print_line(&format!("SEARCHING FOR {}", name));
+        let found = self.drives[drive]
+            .image
+            .as_deref()
+            .and_then(|img| find_prg(img, &name));
+        let Some((file, data)) = found else {
+            self.print_line("?FILE NOT FOUND  ERROR");
+            return;
+        };
+        self.print_line("LOADING");
+        let mut data = data;
+        if !absolute {
+            data[0..2].copy_from_slice(&0x0801u16.to_le_bytes());
+        }
+        match self.load_prg(&data) {
+            Ok((start, end)) => {
+                self.log(format!("load({}) ${:04X}-${:04X}", file, start, end));
+                self.running = Some(file);
+            }
+            Err(_) => self.print_line("?OUT OF MEMORY  ERROR"),
+        }
+    }
+
+    /// DMA-load a PRG at its own load address. Returns `(start, end)`.
+    pub fn load_prg(&mut self, prg: &[u8]) -> Result<(u16, u16), String> {
+        if prg.len() < 3 {
+            return Err("PRG too short".into());
+        }
+        let start = u16::from_le_bytes([prg[0], prg[1]]);
+        let end = start as usize + prg.len() - 2;
+        if end > 0x10000 {
+            return Err(format!("PRG at ${:04X} runs past $FFFF", start));
+        }
+        self.ram[start as usize..end].copy_from_slice(&prg[2..]);
+        // The loader updates the end-of-program pointers like the KERNAL.
+        let end = end as u16;
+        self.ram[0xAE..0xB0].copy_from_slice(&end.to_le_bytes());
+        if start == 0x0801 {
+            self.ram[0x2D..0x2F].copy_from_slice(&end.to_le_bytes());
+        }
+        Ok((start, end))
+    }
+
+    pub fn run_prg(&mut self, name: &str, prg: &[u8]) -> Result<(), String> {
+        self.cold_boot();
+        let (start, end) = self.load_prg(prg)?;
+        self.log(format!("run_prg({}) ${:04X}-${:04X}", name, start, end));
+        self.running = Some(name.to_string());
+        Ok(())
+    }
+
+    /// Start anything the simulator can't execute (CRT, SID, MOD): logged
+    /// and remembered as the running program.
+    pub fn start(&mut self, runner: &str, name: &str, len: usize) {
+        self.log(format!("{}({},{})", runner, name, len));
+        self.running = Some(name.to_string());
+    }
+
+    pub fn drive_index(&self, letter: &str) -> Option<usize> {
+        self.drives
+            .iter()
+            .position(|d| letter.eq_ignore_ascii_case(&d.letter.to_string()))
+    }
+
+    fn drive_setting(&self, drive: &SimDrive, item: &str) -> String {
+        self.config
+            .get(&drive.category())
+            .and_then(|c| c.get(item))
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string()
+    }
+
+    fn drive_enabled(&self, drive: &SimDrive) -> bool {
+        self.drive_setting(drive, "Drive") == "Enabled"
+    }
+
+    fn drive_bus_id(&self, drive: &SimDrive) -> u8 {
+        self.drive_setting(drive, "Drive Bus ID")
+            .parse()
+            .unwrap_or(8)
+    }
+
+    pub fn set_drive_setting(&mut self, index: usize, item: &str, value: &str) {
+        let category = self.drives[index].category();
+        self.config
+            .entry(category)
+            .or_default()
+            .insert(item.to_string(), serde_json::json!(value));
+    }
+
+    pub fn mount(&mut self, index: usize, name: &str, path: &str, image: Vec<u8>, mode: &str) {
+        let drive = &mut self.drives[index];
+        drive.image_name = Some(name.to_string());
+        drive.image_path = Some(path.to_string());
+        drive.image = Some(image);
+        drive.mode = mode.to_string();
+        let letter = drive.letter;
+        self.log(format!("mount({},{},{})", letter, name, mode));
+    }
+
+    pub fn unmount(&mut self, index: usize) {
+        let drive = &mut self.drives[index];
+        drive.image_name = None;
+        drive.image_path = None;
+        drive.image = None;
+        let letter = drive.letter;
+        self.log(format!("remove({})", letter));
+    }
+
+    pub fn drive_list(&self) -> BTreeMap<String, Drive> {
+        self.drives
+            .iter()
+            .map(|d| {
+                let drive_type = match self.drive_setting(d, "Drive Type").as_str() {
+                    "1571" => DriveType::CBM1571,
+                    "1581" => DriveType::CBM1581,
+                    _ => DriveType::CBM1541,
+                };
+                let rom = format!("{}.rom", drive_type);
+                (
+                    d.letter.to_string(),
+                    Drive {
+                        bus_id: self.drive_bus_id(d),
+                        enabled: self.drive_enabled(d),
+                        drive_type: Some(drive_type),
+                        last_error: None,
+                        rom: Some(rom),
+                        image_file: Some(d.image_name.clone().unwrap_or_default()),
+                        image_path: Some(d.image_path.clone().unwrap_or_default()),
+                    },
+                )
+            })
+            .collect()
+    }
+
+    pub fn info(&self) -> DeviceInfo {
+        let hostname = self
+            .config
+            .get("Network settings")
+            .and_then(|c| c.get("Host Name"))
+            .and_then(|v| v.as_str())
+            .unwrap_or("Ultimate-64-Sim")
+            .to_string();
+        DeviceInfo {
+            product: PRODUCT.into(),
+            firmware_version: FIRMWARE_VERSION.into(),
+            fpga_version: "121".into(),
+            core_version: Some("1.45".into()),
+            hostname,
+            unique_id: Some("51A1A7".into()),
+        }
+    }
+
+    /// Set one config item, validating enum values.
+    pub fn set_config(
+        &mut self,
+        category: &str,
+        item: &str,
+        value: serde_json::Value,
+    ) -> Result<(), String> {
+        let (category, item) = self
+            .config
+            .iter()
+            .find(|(c, _)| c.eq_ignore_ascii_case(category))
+            .and_then(|(c, items)| {
+                items
+                    .keys()
+                    .find(|i| i.eq_ignore_ascii_case(item))
+                    .map(|i| (c.clone(), i.clone()))
+            })
+            .ok_or_else(|| format!("No config item {}/{}", category, item))?;
+        let text = match &value {
+            serde_json::Value::String(s) => s.clone(),
+            other => other.to_string(),
+        };
+        if let Some(values) = config_values(&category, &item) {
+            if !values.is_empty() && !values.contains(&text.as_str()) {
+                return Err(format!("'{}' is not a valid value for {}", text, item));
+            }
+        }
+        self.log(format!("config({}/{}={})", category, item, text));
+        if let Some(v) = self
+            .config
+            .get_mut(&category)
+            .and_then(|items| items.get_mut(&item))
+        {
+            *v = serde_json::json!(text);
+        }
+        Ok(())
+    }
+
+    /// `configs:save_to_flash` / `load_from_flash` / `reset_to_default`.
+    pub fn flash_operation(&mut self, operation: &str) -> Result<(), String> {
+        match operation {
+            "save_to_flash" => self.flash = self.config.clone(),
+            "load_from_flash" => self.config = self.flash.clone(),
+            "reset_to_default" => self.config = default_config(),
+            other => return Err(format!("Unknown config operation '{}'", other)),
+        }
+        self.log(format!("configs:{}", operation));
+        Ok(())
+    }
+
+    /// `{category: {item: {current, default, values}}}` for one category
+    /// (all items, or just `item`).
+    pub fn config_details(&self, category: &str, item: Option<&str>) -> Option<serde_json::Value> {
+        let (name, items) = self
+            .config
+            .iter()
+            .find(|(c, _)| c.eq_ignore_ascii_case(category))?;
+        let mut out = serde_json::Map::new();
+        for (key, current) in items {
+            if item.is_some_and(|i| i != "*" && !i.eq_ignore_ascii_case(key)) {
+                continue;
+            }
+            let mut details = serde_json::json!({
+                "current": current,
+                "default": config_default(name, key).unwrap_or_default(),
+            });
+            if let Some(values) = config_values(name, key).filter(|v| !v.is_empty()) {
+                details["values"] = serde_json::json!(values);
+            }
+            out.insert(key.clone(), details);
+        }
+        if out.is_empty() {
+            return None;
+        }
+        Some(serde_json::json!({ name.clone(): out }))
+    }
+
+    pub fn write_reu(&mut self, offset: usize, data: &[u8]) {
+        const REU_MAX: usize = 16 * 1024 * 1024;
+        let end = (offset + data.len()).min(REU_MAX);
+        if end > self.reu.len() {
+            self.reu.resize(end, 0);
+        }
+        if offset < end {
+            self.reu[offset..end].copy_from_slice(&data[..end - offset]);
+        }
+        self.log(format!("reu_write(${:06X},{})", offset, data.len()));
+    }
+}
+
+/// First PRG on a disk image matching a LOAD name (`*` and `NAME*` patterns).
+fn find_prg(image: &[u8], name: &str) -> Option<(String, Vec<u8>)> {
+    use crate::disk_image::{self, FileType};
+
+    let info = disk_image::read_disk_info_from_bytes(image).ok()?;
+    let matches = |entry: &str| match name.strip_suffix('*') {
+        Some(prefix) => entry.starts_with(prefix),
+        None => entry == name,
+    };
+    let index = info
+        .entries
+        .iter()
+        .position(|e| e.file_type == FileType::Prg && matches(&e.name))?;
+    let (file, _, bytes) = disk_image::extract_file(image, index).ok()?;
+    (bytes.len() >= 3).then_some((file, bytes))
+}
+
+// ─── Shared handle ───────────────────────────────────────────────────────────
+
+/// Cheap-to-clone handle on the simulated device: the machine plus the SD
+/// card folder and password every service checks.
+#[derive(Clone)]
+pub struct Simulator {
+    machine: Arc<Mutex<Machine>>,
+    sd_root: PathBuf,
+    password: Option<String>,
+}
+
+impl Simulator {
+    /// Create the simulator, making the SD card folder (and its standard
+    /// top-level folders) if needed.
+    pub fn new(sd_root: PathBuf, password: Option<String>) -> Result<Self, String> {
+        for folder in SD_FOLDERS {
+            std::fs::create_dir_all(sd_root.join(folder))
+                .map_err(|e| format!("Create {}: {}", sd_root.join(folder).display(), e))?;
+        }
+        Ok(Self {
+            machine: Arc::new(Mutex::new(Machine::new())),
+            sd_root,
+            password: password.filter(|p| !p.is_empty()),
+        })
+    }
+
+    pub(crate) fn machine(&self) -> std::sync::MutexGuard<'_, Machine> {
+        self.machine.lock().unwrap_or_else(|p| p.into_inner())
+    }
+
+    pub fn sd_root(&self) -> &Path {
+        &self.sd_root
+    }
+
+    /// Ordered log of device-level operations (`"reset"`, `"run_prg(…)"`…).
+    #[allow(dead_code)]
+    pub fn calls(&self) -> Vec<String> {
+        self.machine().calls.clone()
+    }
+
+    /// A copy of the full 64 KB of RAM.
+    #[allow(dead_code)]
+    pub fn ram(&self) -> Vec<u8> {
+        self.machine().ram.clone()
+    }
+
+    /// What the machine was last told to run.
+    #[allow(dead_code)]
+    pub fn running(&self) -> Option<String> {
+        self.machine().running.clone()
+    }
+
+    pub(crate) fn password_ok(&self, given: Option<&str>) -> bool {
+        match &self.password {
+            Some(expected) => given == Some(expected.as_str()),
+            None => true,
+        }
+    }
+
+    pub(crate) fn requires_password(&self) -> bool {
+        self.password.is_some()
+    }
+
+    /// Map a device path (`/Usb0/games/x.d64`) onto the SD card folder.
+    /// `..` never climbs above the root, and only plain names are joined, so
+    /// a drive prefix or `\` root on Windows can't replace it either.
+    pub fn sd_path(&self, device_path: &str) -> PathBuf {
+        let mut path = self.sd_root.clone();
+        for part in normalize(device_path).split('/') {
+            for component in Path::new(part).components() {
+                if let Component::Normal(name) = component {
+                    path.push(name);
+                }
+            }
+        }
+        path
+    }
+
+    pub(crate) fn read_sd_file(&self, device_path: &str) -> Result<Vec<u8>, String> {
+        std::fs::read(self.sd_path(device_path)).map_err(|e| format!("{}: {}", device_path, e))
+    }
+
+    /// Mount a device-side image path, or an uploaded image when `data` is set.
+    pub(crate) fn mount(
+        &self,
+        drive: &str,
+        device_path: &str,
+        data: Option<Vec<u8>>,
+        mode: &str,
+    ) -> Result<(), String> {
+        let name = device_path
+            .rsplit('/')
+            .next()
+            .unwrap_or(device_path)
+            .to_string();
+        let image = match data {
+            Some(d) => d,
+            None => self.read_sd_file(device_path)?,
+        };
+        let mut m = self.machine();
+        let index = m
+            .drive_index(drive)
+            .ok_or_else(|| format!("No drive '{}'", drive))?;
+        m.mount(index, &name, device_path, image, mode);
+        Ok(())
+    }
+
+    /// Reset, then autoload the image in drive A the way the app does.
+    pub(crate) fn autoload(&self) {
+        let mut m = self.machine();
+        m.reset();
+        m.type_keys(b"LOAD\"*\",8,1\r");
+        m.type_keys(b"RUN\r");
+    }
+}
+
+/// Normalize a device path: absolute, `/`-separated, `.`/`..` resolved.
+pub(crate) fn normalize(path: &str) -> String {
+    let mut parts: Vec<&str> = Vec::new();
+    for part in path.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            p => parts.push(p),
+        }
+    }
+    format!("/{}", parts.join("/"))
+}
+
+impl RemoteDevice for Simulator {
+    fn info(&self) -> Result<DeviceInfo> {
+        Ok(self.machine().info())
+    }
+    fn drive_list(&self) -> Result<std::collections::HashMap<String, Drive>> {
+        Ok(self.machine().drive_list().into_iter().collect())
+    }
+    fn read_mem(&self, address: u16, length: u16) -> Result<Vec<u8>> {
+        self.machine()
+            .read(address, length as usize)
+            .map_err(anyhow::Error::msg)
+    }
+    fn write_mem(&self, address: u16, data: &[u8]) -> Result<()> {
+        self.machine()
+            .write(address, data)
+            .map_err(anyhow::Error::msg)
+    }
+    fn reset(&self) -> Result<()> {
+        self.machine().reset();
+        Ok(())
+    }
+    fn reboot(&self) -> Result<()> {
+        let mut m = self.machine();
+        m.log("reboot");
+        m.cold_boot();
+        Ok(())
+    }
+    fn poweroff(&self) -> Result<()> {
+        self.machine().log("poweroff");
+        Ok(())
+    }
+    fn menu(&self) -> Result<()> {
+        self.machine().log("menu_button");
+        Ok(())
+    }
+    fn run_prg(&self, data: &[u8]) -> Result<()> {
+        self.machine()
+            .run_prg("upload.prg", data)
+            .map_err(anyhow::Error::msg)
+    }
+    fn run_crt(&self, data: &[u8]) -> Result<()> {
+        self.machine().start("run_crt", "upload.crt", data.len());
+        Ok(())
+    }
+    fn sid_play(&self, siddata: &[u8], songnr: Option<u8>) -> Result<()> {
+        let name = format!("upload.sid#{}", songnr.unwrap_or(0));
+        self.machine().start("sidplay", &name, siddata.len());
+        Ok(())
+    }
+    fn mod_play(&self, moddata: &[u8]) -> Result<()> {
+        self.machine().start("modplay", "upload.mod", moddata.len());
+        Ok(())
+    }
+    fn type_text(&self, s: &str) -> Result<()> {
+        let keys: Vec<u8> = s
+            .chars()
+            .map(|c| match c {
+                '\n' => 0x0D,
+                c if c.is_ascii() => c.to_ascii_uppercase() as u8,
+                _ => b'?',
+            })
+            .collect();
+        self.machine().type_keys(&keys);
+        Ok(())
+    }
+    fn mount_disk_image(
+        &self,
+        path: &Path,
+        drive: String,
+        mount_mode: MountMode,
+        run: bool,
+    ) -> Result<()> {
+        let data = std::fs::read(path)?;
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        self.mount(&drive, &name, Some(data), &mount_mode.to_string())
+            .map_err(anyhow::Error::msg)?;
+        if run {
+            self.autoload();
+        }
+        Ok(())
+    }
+}
+
+// ─── Servers ─────────────────────────────────────────────────────────────────
+
+/// The running REST, FTP and port-64 services. Dropping it stops them.
+pub struct SimServer {
+    simulator: Simulator,
+    pub http_addr: SocketAddr,
+    pub ftp_addr: SocketAddr,
+    pub port64_addr: SocketAddr,
+    stop: Arc<AtomicBool>,
+}
+
+impl SimServer {
+    /// Bind all three services and start serving on background threads.
+    pub fn start(config: SimConfig) -> Result<Self, String> {
+        let simulator = Simulator::new(config.sd_root.clone(), config.password.clone())?;
+        let bind = |port: u16, what: &str| {
+            TcpListener::bind((config.bind, port))
+                .map_err(|e| format!("Bind {} on {}:{}: {}", what, config.bind, port, e))
+        };
+        let http = bind(config.http_port, "REST")?;
+        let ftp = bind(config.ftp_port, "FTP")?;
+        let port64 = bind(config.port64_port, "port 64")?;
+        let addr = |l: &TcpListener| l.local_addr().map_err(|e| e.to_string());
+        let server = Self {
+            http_addr: addr(&http)?,
+            ftp_addr: addr(&ftp)?,
+            port64_addr: addr(&port64)?,
+            simulator,
+            stop: Arc::new(AtomicBool::new(false)),
+        };
+        server.spawn_acceptor(http, rest::serve);
+        server.spawn_acceptor(ftp, ftp::serve);
+        server.spawn_acceptor(port64, port64::serve);
+        Ok(server)
+    }
+
+    pub fn simulator(&self) -> &Simulator {
+        &self.simulator
+    }
+
+    /// `http://127.0.0.1:<port>` — what the pool-free `api` helpers take.
+    pub fn http_url(&self) -> String {
+        format!("http://{}", self.http_addr)
+    }
+
+    /// One thread per listener, one per connection — the real device serves
+    /// connections one at a time, so concurrency here is never the bottleneck.
+    fn spawn_acceptor(&self, listener: TcpListener, handler: fn(TcpStream, Simulator)) {
+        let simulator = self.simulator.clone();
+        let stop = self.stop.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                if stop.load(Ordering::Relaxed) {
+                    break;
+                }
+                match stream {
+                    Ok(stream) => {
+                        let sim = simulator.clone();
+                        std::thread::spawn(move || handler(stream, sim));
+                    }
+                    Err(e) => log::warn!("simulator: accept failed: {}", e),
+                }
+            }
+        });
+    }
+}
+
+impl Drop for SimServer {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        // Wake each blocking accept() so its thread sees the stop flag.
+        for addr in [self.http_addr, self.ftp_addr, self.port64_addr] {
+            let _ = TcpStream::connect_timeout(&addr, std::time::Duration::from_millis(200));
+        }
+    }
+}
+
+/// Blocking entry point for `ultimate64-manager simulate`: serve until killed.
+pub fn run_forever(config: SimConfig) -> Result<(), String> {
+    let server = SimServer::start(config)?;
+    println!(
+        "Simulating an Ultimate 64 — SD card: {}",
+        server.simulator().sd_root().display()
+    );
+    println!("  REST    {}", server.http_url());
+    println!("  FTP     {}", server.ftp_addr);
+    println!("  port64  {}", server.port64_addr);
+    if server.http_addr.port() == DEFAULT_HTTP_PORT {
+        println!("Connect the app to host {}", server.http_addr.ip());
+    }
+    println!("Press Ctrl+C to stop.");
+    loop {
+        std::thread::park();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+
+    /// Fresh SD card folder per test.
+    pub(super) fn scratch(name: &str) -> PathBuf {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        let dir = std::env::temp_dir().join(format!(
+            "u64sim_{}_{}_{}",
+            name,
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn screen_text(ram: &[u8]) -> String {
+        ram[SCREEN..SCREEN + COLUMNS * ROWS]
+            .chunks(COLUMNS)
+            .map(|row| {
+                row.iter()
+                    .map(|&c| match c {
+                        0x00..=0x1F => (c + 0x40) as char,
+                        0x20..=0x3F => c as char,
+                        _ => '?',
+                    })
+                    .collect::<String>()
+                    .trim_end()
+                    .to_string()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn boots_to_ready_and_builds_sd_card() {
+        let root = scratch("boot");
+        let sim = Simulator::new(root.clone(), None).unwrap();
+        assert!(root.join("Usb0").is_dir() && root.join("Temp").is_dir());
+        let text = screen_text(&sim.ram());
+        assert!(text.contains("COMMODORE 64 BASIC V2"));
+        assert!(text.contains("READY."));
+        assert_eq!(sim.read_mem(0x2B, 2).unwrap(), vec![0x01, 0x08]);
+        assert!(sim.calls().is_empty());
+    }
+
+    #[test]
+    fn sd_paths_cannot_escape_the_root() {
+        let sim = Simulator::new(scratch("paths"), None).unwrap();
+        assert_eq!(normalize("/Usb0/../../etc/./passwd"), "/etc/passwd");
+        assert!(sim
+            .sd_path("../../../etc/passwd")
+            .starts_with(sim.sd_root()));
+        assert_eq!(
+            sim.sd_path("/Usb0/a.prg"),
+            sim.sd_root().join("Usb0").join("a.prg")
+        );
+        for path in ["C:\\Windows\\win.ini", "\\\\server\\share", "/Usb0/\\etc"] {
+            assert!(sim.sd_path(path).starts_with(sim.sd_root()), "{}", path);
+        }
+    }
+
+    #[test]
+    fn typed_load_and_run_autoloads_the_mounted_disk() {
+        let sim = Simulator::new(scratch("autoload"), None).unwrap();
+        let mut disk = crate::disk_image::build_blank_d64("SIM", "01");
+        crate::disk_image::insert_file(
+            &mut disk,
+            "HELLO",
+            crate::disk_image::FileType::Prg,
+            &[0x00, 0xC0, 0xA9, 0x01, 0x60],
+        )
+        .unwrap();
+        let path = sim.sd_root().join("Usb0").join("hello.d64");
+        std::fs::write(&path, &disk).unwrap();
+
+        sim.mount_disk_image(&path, "a".into(), MountMode::ReadOnly, false)
+            .unwrap();
+        // Same sequence as run_ops::autoload_mounted_disk.
+        sim.reset().unwrap();
+        sim.type_text("load\"*\",8,1\n").unwrap();
+        assert_eq!(sim.read_mem(0xC000, 3).unwrap(), vec![0xA9, 0x01, 0x60]);
+        let text = screen_text(&sim.ram());
+        assert_eq!(text.matches("READY.").count(), 2, "{}", text);
+        sim.type_text("run\n").unwrap();
+        assert_eq!(sim.running().as_deref(), Some("HELLO"));
+    }
+
+    #[test]
+    fn drives_follow_the_config_tree() {
+        let sim = Simulator::new(scratch("drives"), None).unwrap();
+        let drives = sim.drive_list().unwrap();
+        assert!(drives["a"].enabled && !drives["b"].enabled);
+        assert_eq!(drives["a"].bus_id, 8);
+        sim.type_text("load\"x\",9\n").unwrap();
+        assert!(screen_text(&sim.ram()).contains("?DEVICE NOT PRESENT"));
+
+        let mut m = sim.machine();
+        m.set_config("drive b settings", "drive", serde_json::json!("Enabled"))
+            .unwrap();
+        assert!(m
+            .set_config("Drive B Settings", "Drive Type", serde_json::json!("1542"))
+            .is_err());
+        drop(m);
+        assert!(sim.drive_list().unwrap()["b"].enabled);
+    }
+
+    #[test]
+    fn config_flash_round_trip() {
+        let sim = Simulator::new(scratch("flash"), None).unwrap();
+        let mut m = sim.machine();
+        m.set_config(
+            "U64 Specific Settings",
+            "System Mode",
+            serde_json::json!("NTSC"),
+        )
+        .unwrap();
+        m.flash_operation("save_to_flash").unwrap();
+        m.flash_operation("reset_to_default").unwrap();
+        assert_eq!(m.config["U64 Specific Settings"]["System Mode"], "PAL");
+        m.flash_operation("load_from_flash").unwrap();
+        assert_eq!(m.config["U64 Specific Settings"]["System Mode"], "NTSC");
+        let details = m
+            .config_details("u64 specific settings", Some("System Mode"))
+            .unwrap();
+        assert_eq!(
+            details["U64 Specific Settings"]["System Mode"]["default"],
+            "PAL"
+        );
+    }
+
+    // ── Over the wire, through the app's own clients ──────────────────────
+
+    fn block_on<F: std::future::Future>(fut: F) -> F::Output {
+        tokio::runtime::Runtime::new().unwrap().block_on(fut)
+    }
+
+    #[test]
+    fn rest_serves_the_api_helpers() {
+        let server = SimServer::start(SimConfig::ephemeral(scratch("rest"))).unwrap();
+        let url = server.http_url();
+
+        block_on(crate::api::writemem_async(
+            &url,
+            0xC000,
+            vec![1, 2, 3],
+            None,
+        ))
+        .unwrap();
+        assert_eq!(server.simulator().ram()[0xC000..0xC003], [1, 2, 3]);
+
+        let categories = block_on(crate::config_api::fetch_categories(url.clone(), None)).unwrap();
+        assert!(categories.contains(&"Drive A Settings".to_string()));
+        let (_, items) = block_on(crate::config_api::fetch_category_items(
+            url.clone(),
+            "U64 Specific Settings".into(),
+            None,
+        ))
+        .unwrap();
+        assert!(items.iter().any(|i| i.name == "System Mode"
+            && i.option_type == crate::config_editor::ConfigOptionType::Enum));
+
+        let prg = vec![0x01, 0x08, 0x0B, 0x08, 0x0A, 0x00, 0x99, 0x00, 0x00, 0x00];
+        block_on(crate::api::upload_runner_async(&url, "run_prg", prg, None)).unwrap();
+        assert_eq!(server.simulator().ram()[0x0805], 0x99);
+        assert!(server.simulator().running().is_some());
+
+        let value = block_on(async {
+            crate::api::write_debugreg_async(&url, 0x3F, None).await?;
+            crate::api::read_debugreg_async(&url, None).await
+        })
+        .unwrap();
+        assert_eq!(value, 0x3F);
+    }
+
+    #[test]
+    fn rest_mounts_uploads_and_sd_card_paths() {
+        let server = SimServer::start(SimConfig::ephemeral(scratch("mount"))).unwrap();
+        let url = server.http_url();
+        let disk = crate::disk_image::build_blank_d64("SIM", "01");
+        let local = server.simulator().sd_root().join("Usb0").join("game.d64");
+        std::fs::write(&local, &disk).unwrap();
+
+        block_on(crate::api::upload_mount_disk_async(
+            &url, &local, "b", "readonly", None,
+        ))
+        .unwrap();
+        let drives = server.simulator().drive_list().unwrap();
+        assert_eq!(drives["b"].image_file.as_deref(), Some("game.d64"));
+
+        // PUT ?image= resolves against the SD card.
+        block_on(async {
+            let client = crate::net_utils::build_device_client(5)?;
+            let req = client
+                .put(format!("{}/v1/drives/a:mount", url))
+                .query(&[("image", "/Usb0/game.d64"), ("mode", "readwrite")]);
+            crate::net_utils::device_send(req).await?;
+            Ok::<_, String>(())
+        })
+        .unwrap();
+        let drives = server.simulator().drive_list().unwrap();
+        assert_eq!(drives["a"].image_path.as_deref(), Some("/Usb0/game.d64"));
+    }
+
+    #[test]
+    fn rest_rejects_a_wrong_password() {
+        let mut config = SimConfig::ephemeral(scratch("password"));
+        config.password = Some("secret".into());
+        let server = SimServer::start(config).unwrap();
+        let url = server.http_url();
+        let err = block_on(async {
+            let client = crate::net_utils::build_device_client(5).unwrap();
+            let req = crate::net_utils::with_password(
+                client.get(format!("{}/v1/version", url)),
+                Some("wrong"),
+            );
+            crate::net_utils::device_send(req).await.map(|_| ())
+        })
+        .unwrap_err();
+        assert_eq!(err, crate::device_error::DeviceError::Unauthorized);
+        block_on(crate::api::reset_machine_async(&url, Some("secret"))).unwrap();
+    }
+
+    #[test]
+    fn port64_speaks_the_client_protocol() {
+        let mut config = SimConfig::ephemeral(scratch("port64"));
+        config.password = Some("pw".into());
+        let server = SimServer::start(config).unwrap();
+        let client = crate::port64::Port64Client::new("127.0.0.1", Some("pw".into()))
+            .with_port(server.port64_addr.port());
+
+        block_on(async {
+            client.dma_write(0x0400, &[8, 5, 12, 12, 15]).await.unwrap();
+            assert_eq!(client.identify().await.unwrap(), PRODUCT);
+            let geometry = client.flash_geometry().await.unwrap();
+            assert_eq!(geometry.total_bytes(), 64 * 1024);
+            let page = client.flash_read_page(1).await.unwrap();
+            assert_eq!(page.len(), 4096);
+            client.debug_register(Some(0x12)).await.unwrap();
+            assert_eq!(client.debug_register(None).await.unwrap(), 0x12);
+            client.reu_write(0x010000, &[0xAA; 4]).await.unwrap();
+        });
+        let sim = server.simulator();
+        assert_eq!(sim.ram()[0x0400..0x0405], [8, 5, 12, 12, 15]);
+        assert_eq!(sim.machine().reu[0x010000..0x010004], [0xAA; 4]);
+
+        let intruder = crate::port64::Port64Client::new("127.0.0.1", Some("nope".into()))
+            .with_port(server.port64_addr.port());
+        assert!(matches!(
+            block_on(intruder.reset()),
+            Err(crate::port64::Port64Error::AuthFailed)
+        ));
+    }
+
+    #[test]
+    fn ftp_round_trip_matches_the_listing_parser() {
+        use suppaftp::FtpStream;
+
+        let server = SimServer::start(SimConfig::ephemeral(scratch("ftp"))).unwrap();
+        let mut ftp = FtpStream::connect(server.ftp_addr).unwrap();
+        ftp.login("anonymous", "anonymous").unwrap();
+        ftp.transfer_type(suppaftp::types::FileType::Binary)
+            .unwrap();
+        ftp.mkdir("/Usb0/games").unwrap();
+        ftp.cwd("/Usb0/games").unwrap();
+        assert_eq!(ftp.pwd().unwrap(), "/Usb0/games");
+        ftp.put_file("hello.prg", &mut &[0x01, 0x08, 0x60][..])
+            .unwrap();
+        assert_eq!(ftp.size("hello.prg").unwrap(), 3);
+
+        let entries: Vec<_> = ftp
+            .list(Some("/Usb0"))
+            .unwrap()
+            .iter()
+            .filter_map(|l| crate::ftp_ops::parse_ftp_line(l, "/Usb0"))
+            .collect();
+        assert_eq!(entries.len(), 1);
+        assert!(entries[0].is_dir && entries[0].path == "/Usb0/games");
+
+        ftp.rename("hello.prg", "renamed.prg").unwrap();
+        let data = ftp.retr_as_buffer("renamed.prg").unwrap().into_inner();
+        assert_eq!(data, vec![0x01, 0x08, 0x60]);
+        assert!(server
+            .simulator()
+            .sd_path("/Usb0/games/renamed.prg")
+            .is_file());
+        ftp.rm("renamed.prg").unwrap();
+        ftp.cdup().unwrap();
+        ftp.rmdir("games").unwrap();
+        ftp.quit().unwrap();
+    }
+}
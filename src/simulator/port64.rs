@@ -0,0 +1,197 @@
+//! Server side of the port-64 binary protocol (see [`crate::port64`]).
+//!
+//! Commands are read back-to-back until the client closes. With a password
+//! set the first command must be `AUTHENTICATE`; anything else drops the
+//! connection, matching `socket_dma.cc`.
+
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::time::Duration;
+
+use super::Simulator;
+use crate::port64::*;
+
+/// Flash geometry reported to `CMD_READFLASH`: 16 pages of 4 KB.
+const FLASH_PAGE_SIZE: u32 = 4096;
+const FLASH_PAGE_COUNT: u32 = 16;
+
+pub(super) fn serve(mut stream: TcpStream, sim: Simulator) {
+    let _ = stream.set_read_timeout(Some(Duration::from_secs(30)));
+    let _ = stream.set_nodelay(true);
+    let mut authenticated = !sim.requires_password();
+    loop {
+        let mut header = [0u8; 4];
+        if stream.read_exact(&mut header).is_err() {
+            return;
+        }
+        let cmd = u16::from_le_bytes([header[0], header[1]]);
+        let mut len = u16::from_le_bytes([header[2], header[3]]) as usize;
+
+        if cmd == CMD_WAIT {
+            // `len` is the delay in milliseconds; no payload follows.
+            std::thread::sleep(Duration::from_millis(len.min(5000) as u64));
+            continue;
+        }
+        if matches!(cmd, CMD_MOUNT_IMG | CMD_RUN_IMG | CMD_RUN_CRT) {
+            let mut hi = [0u8; 1];
+            if stream.read_exact(&mut hi).is_err() {
+                return;
+            }
+            len |= (hi[0] as usize) << 16;
+        }
+        let mut payload = vec![0u8; len];
+        if stream.read_exact(&mut payload).is_err() {
+            return;
+        }
+
+        if cmd == CMD_AUTHENTICATE {
+            authenticated = sim.password_ok(std::str::from_utf8(&payload).ok());
+            let _ = stream.write_all(&[authenticated as u8]);
+            if !authenticated {
+                return;
+            }
+            continue;
+        }
+        if !authenticated {
+            log::debug!("simulator: port64 command {:#06X} before AUTHENTICATE", cmd);
+            return;
+        }
+        match handle(&sim, cmd, &payload) {
+            Ok(Some(reply)) => {
+                if stream.write_all(&reply).is_err() {
+                    return;
+                }
+                // Flash pages are read until EOF.
+                if cmd == CMD_READFLASH && payload.first() == Some(&2) {
+                    return;
+                }
+            }
+            Ok(None) => {}
+            Err(e) => log::warn!("simulator: port64 command {:#06X}: {}", cmd, e),
+        }
+    }
+}
+
+/// Run one command; `Some(bytes)` is the reply to send back.
+fn handle(sim: &Simulator, cmd: u16, payload: &[u8]) -> Result<Option<Vec<u8>>, String> {
+    let address = || {
+        payload
+            .get(..2)
+            .map(|a| u16::from_le_bytes([a[0], a[1]]))
+            .ok_or_else(|| "Missing address".to_string())
+    };
+    match cmd {
+        CMD_DMA => {
+            let (start, end) = sim.machine().load_prg(payload)?;
+            sim.machine()
+                .log(format!("dma_load ${:04X}-${:04X}", start, end));
+        }
+        CMD_DMARUN => sim.machine().run_prg("dma.prg", payload)?,
+        CMD_DMAJUMP => {
+            let target = address()?;
+            let mut m = sim.machine();
+            let (start, end) = m.load_prg(&payload[2..])?;
+            m.log(format!(
+                "dma_jump ${:04X}-${:04X} -> ${:04X}",
+                start, end, target
+            ));
+            m.running = Some(format!("${:04X}", target));
+        }
+        CMD_DMAWRITE => {
+            let addr = address()?;
+            sim.machine().write(addr, &payload[2..])?;
+        }
+        CMD_KEYB => sim.machine().type_keys(payload),
+        CMD_RESET => sim.machine().reset(),
+        CMD_POWEROFF => sim.machine().log("poweroff"),
+        CMD_REUWRITE => {
+            if payload.len() < 3 {
+                return Err("Missing REU offset".into());
+            }
+            let offset =
+                payload[0] as usize | (payload[1] as usize) << 8 | (payload[2] as usize) << 16;
+            sim.machine().write_reu(offset, &payload[3..]);
+        }
+        CMD_KERNALWRITE => {
+            let rom = payload.get(2..).unwrap_or_default().to_vec();
+            let mut m = sim.machine();
+            m.log(format!("kernal_write({})", rom.len()));
+            m.kernal = Some(rom);
+        }
+        CMD_MOUNT_IMG | CMD_RUN_IMG => {
+            // The device parks uploads in /Temp before mounting them.
+            let path = "/Temp/tcpimage.d64";
+            std::fs::write(sim.sd_path(path), payload).map_err(|e| e.to_string())?;
+            sim.mount("a", path, Some(payload.to_vec()), "readwrite")?;
+            if cmd == CMD_RUN_IMG {
+                sim.autoload();
+            }
+        }
+        CMD_RUN_CRT => {
+            let path = "/Temp/tcpimage.crt";
+            std::fs::write(sim.sd_path(path), payload).map_err(|e| e.to_string())?;
+            sim.machine()
+                .start("run_crt", "tcpimage.crt", payload.len());
+        }
+        CMD_IDENTIFY => {
+            let title = super::PRODUCT.as_bytes();
+            let mut reply = vec![title.len() as u8];
+            reply.extend_from_slice(title);
+            return Ok(Some(reply));
+        }
+        CMD_READFLASH => {
+            return match payload.first() {
+                Some(0) => Ok(Some(FLASH_PAGE_SIZE.to_le_bytes().to_vec())),
+                Some(1) => Ok(Some(FLASH_PAGE_COUNT.to_le_bytes().to_vec())),
+                Some(2) if payload.len() >= 4 => {
+                    let page = u32::from_le_bytes([payload[1], payload[2], payload[3], 0]);
+                    if page >= FLASH_PAGE_COUNT {
+                        return Err(format!("No flash page {}", page));
+                    }
+                    // Deterministic contents so backups can be verified.
+                    Ok(Some(
+                        (0..FLASH_PAGE_SIZE)
+                            .map(|i| (page as u8).wrapping_mul(31) ^ i as u8)
+                            .collect(),
+                    ))
+                }
+                _ => Err("Bad READFLASH sub-command".into()),
+            };
+        }
+        CMD_DEBUG_REG => {
+            let mut m = sim.machine();
+            let current = m.debugreg;
+            if let Some(&v) = payload.first() {
+                m.debugreg = v;
+            }
+            return Ok(Some(vec![current]));
+        }
+        CMD_VICSTREAM_ON | CMD_AUDIOSTREAM_ON | CMD_DEBUGSTREAM_ON => {
+            let name = match cmd {
+                CMD_VICSTREAM_ON => "video",
+                CMD_AUDIOSTREAM_ON => "audio",
+                _ => "debug",
+            };
+            let dest = String::from_utf8_lossy(payload.get(2..).unwrap_or_default()).into_owned();
+            let mut m = sim.machine();
+            m.log(format!("stream_start({},{})", name, dest));
+            m.streams.insert(name.to_string(), dest);
+        }
+        CMD_VICSTREAM_OFF | CMD_AUDIOSTREAM_OFF | CMD_DEBUGSTREAM_OFF => {
+            let name = match cmd {
+                CMD_VICSTREAM_OFF => "video",
+                CMD_AUDIOSTREAM_OFF => "audio",
+                _ => "debug",
+            };
+            let mut m = sim.machine();
+            m.log(format!("stream_stop({})", name));
+            m.streams.remove(name);
+        }
+        CMD_LOADSIDCRT | CMD_LOADBOOTCRT => {
+            sim.machine()
+                .log(format!("load_crt_rom({:#06X},{})", cmd, payload.len()));
+        }
+        other => return Err(format!("Unknown command {:#06X}", other)),
+    }
+    Ok(None)
+}
@@ -0,0 +1,575 @@
+//! Minimal HTTP/1.1 server for the simulator's `/v1` REST API.
+//!
+//! One request per connection (`Connection: close`), like the device's
+//! embedded server. Bodies may arrive with `Content-Length` or chunked
+//! (the `ultimate64` crate streams multipart uploads); multipart form data
+//! is parsed just far enough to pull out the uploaded file and text fields.
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpStream;
+use std::time::Duration;
+
+use super::Simulator;
+
+/// Largest body we accept — comfortably above a 16 MB REU image.
+const MAX_BODY: usize = 32 * 1024 * 1024;
+
+struct Request {
+    method: String,
+    path: String,
+    query: Vec<(String, String)>,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl Request {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    fn param(&self, name: &str) -> Option<&str> {
+        self.query
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+struct Reply {
+    status: u16,
+    content_type: &'static str,
+    body: Vec<u8>,
+}
+
+impl Reply {
+    fn json(value: serde_json::Value) -> Self {
+        Self {
+            status: 200,
+            content_type: "application/json",
+            body: value.to_string().into_bytes(),
+        }
+    }
+
+    fn ok() -> Self {
+        Self::json(serde_json::json!({ "errors": [] }))
+    }
+
+    fn error(status: u16, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            ..Self::json(serde_json::json!({ "errors": [message.into()] }))
+        }
+    }
+
+    fn binary(body: Vec<u8>) -> Self {
+        Self {
+            status: 200,
+            content_type: "application/octet-stream",
+            body,
+        }
+    }
+}
+
+impl From<Result<(), String>> for Reply {
+    fn from(r: Result<(), String>) -> Self {
+        match r {
+            Ok(()) => Reply::ok(),
+            Err(e) => Reply::error(400, e),
+        }
+    }
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        413 => "Payload Too Large",
+        _ => "Error",
+    }
+}
+
+pub(super) fn serve(stream: TcpStream, sim: Simulator) {
+    let _ = stream.set_read_timeout(Some(Duration::from_secs(30)));
+    let mut reader = BufReader::new(match stream.try_clone() {
+        Ok(s) => s,
+        Err(_) => return,
+    });
+    let reply = match read_request(&mut reader) {
+        Ok(Some(req)) => {
+            log::debug!("simulator: {} {}", req.method, req.path);
+            if !sim.password_ok(req.header("X-password")) {
+                Reply::error(403, "Forbidden")
+            } else {
+                route(&sim, &req)
+            }
+        }
+        Ok(None) => return,
+        Err(e) => Reply::error(400, e),
+    };
+    let mut stream = stream;
+    let head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        reply.status,
+        reason(reply.status),
+        reply.content_type,
+        reply.body.len()
+    );
+    let _ = stream.write_all(head.as_bytes());
+    let _ = stream.write_all(&reply.body);
+    let _ = stream.flush();
+}
+
+// ─── Request parsing ─────────────────────────────────────────────────────────
+
+fn read_line(reader: &mut impl BufRead) -> Result<String, String> {
+    let mut line = String::new();
+    reader.read_line(&mut line).map_err(|e| e.to_string())?;
+    Ok(line.trim_end_matches(['\r', '\n']).to_string())
+}
+
+fn decode(s: &str) -> String {
+    let s = s.replace('+', " ");
+    urlencoding::decode(&s).map(|c| c.into_owned()).unwrap_or(s)
+}
+
+fn read_request(reader: &mut impl BufRead) -> Result<Option<Request>, String> {
+    let request_line = read_line(reader)?;
+    if request_line.is_empty() {
+        return Ok(None);
+    }
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_uppercase();
+    let target = parts.next().ok_or("Malformed request line")?;
+    let (raw_path, raw_query) = target.split_once('?').unwrap_or((target, ""));
+    let query = raw_query
+        .split('&')
+        .filter(|p| !p.is_empty())
+        .map(|p| {
+            let (k, v) = p.split_once('=').unwrap_or((p, ""));
+            (decode(k), decode(v))
+        })
+        .collect();
+
+    let mut headers = Vec::new();
+    loop {
+        let line = read_line(reader)?;
+        if line.is_empty() {
+            break;
+        }
+        if let Some((k, v)) = line.split_once(':') {
+            headers.push((k.trim().to_string(), v.trim().to_string()));
+        }
+    }
+    let mut req = Request {
+        method,
+        path: urlencoding::decode(raw_path)
+            .map(|c| c.into_owned())
+            .unwrap_or_else(|_| raw_path.to_string()),
+        query,
+        headers,
+        body: Vec::new(),
+    };
+
+    if req
+        .header("Transfer-Encoding")
+        .is_some_and(|t| t.eq_ignore_ascii_case("chunked"))
+    {
+        req.body = read_chunked(reader)?;
+    } else if let Some(len) = req.header("Content-Length") {
+        let len: usize = len.parse().map_err(|_| "Bad Content-Length")?;
+        if len > MAX_BODY {
+            return Err(format!("Body of {} bytes is too large", len));
+        }
+        let mut body = vec![0; len];
+        reader.read_exact(&mut body).map_err(|e| e.to_string())?;
+        req.body = body;
+    }
+    Ok(Some(req))
+}
+
+fn read_chunked(reader: &mut impl BufRead) -> Result<Vec<u8>, String> {
+    let mut body = Vec::new();
+    loop {
+        let size_line = read_line(reader)?;
+        let size_hex = size_line.split(';').next().unwrap_or("").trim();
+        let size = usize::from_str_radix(size_hex, 16).map_err(|_| "Bad chunk size")?;
+        if size == 0 {
+            // Skip trailers up to the final blank line.
+            while !read_line(reader)?.is_empty() {}
+            return Ok(body);
+        }
+        if body.len() + size > MAX_BODY {
+            return Err("Chunked body is too large".into());
+        }
+        let start = body.len();
+        body.resize(start + size, 0);
+        reader
+            .read_exact(&mut body[start..])
+            .map_err(|e| e.to_string())?;
+        read_line(reader)?;
+    }
+}
+
+/// One part of a `multipart/form-data` body.
+struct FormPart {
+    name: String,
+    filename: Option<String>,
+    data: Vec<u8>,
+}
+
+fn find(haystack: &[u8], needle: &[u8], from: usize) -> Option<usize> {
+    haystack
+        .get(from..)?
+        .windows(needle.len())
+        .position(|w| w == needle)
+        .map(|p| p + from)
+}
+
+fn parse_multipart(req: &Request) -> Vec<FormPart> {
+    let Some(boundary) = req
+        .header("Content-Type")
+        .and_then(|ct| ct.split("boundary=").nth(1))
+        .map(|b| format!("--{}", b.trim_matches('"')))
+    else {
+        return Vec::new();
+    };
+    let body = &req.body;
+    let delimiter = boundary.as_bytes();
+    let mut parts = Vec::new();
+    let mut pos = match find(body, delimiter, 0) {
+        Some(p) => p + delimiter.len(),
+        None => return parts,
+    };
+    while let Some(next) = find(body, delimiter, pos) {
+        let part = &body[pos..next];
+        pos = next + delimiter.len();
+        let part = part.strip_prefix(b"\r\n").unwrap_or(part);
+        let Some(split) = find(part, b"\r\n\r\n", 0) else {
+            continue;
+        };
+        let head = String::from_utf8_lossy(&part[..split]);
+        let data = &part[split + 4..];
+        let data = data.strip_suffix(b"\r\n").unwrap_or(data);
+        let attr = |key: &str| {
+            let needle = format!("{}=\"", key);
+            head.find(&needle).and_then(|i| {
+                let rest = &head[i + needle.len()..];
+                rest.find('"').map(|end| rest[..end].to_string())
+            })
+        };
+        parts.push(FormPart {
+            name: attr(" name").unwrap_or_default(),
+            filename: attr("filename"),
+            data: data.to_vec(),
+        });
+    }
+    parts
+}
+
+// ─── Routing ─────────────────────────────────────────────────────────────────
+
+fn route(sim: &Simulator, req: &Request) -> Reply {
+    let Some(resource) = req.path.strip_prefix("/v1/") else {
+        return Reply::error(404, format!("No such endpoint {}", req.path));
+    };
+    let (target, action) = match resource.split_once(':') {
+        Some((t, a)) => (t, Some(a)),
+        None => (resource, None),
+    };
+    let mut segments = target.split('/');
+    let area = segments.next().unwrap_or_default();
+    let rest: Vec<&str> = segments.collect();
+    let method = req.method.as_str();
+
+    match (area, action) {
+        ("version", None) => {
+            Reply::json(serde_json::json!({ "version": super::API_VERSION, "errors": [] }))
+        }
+        ("info", None) => {
+            let info = sim.machine().info();
+            let mut json = serde_json::to_value(info).unwrap_or_default();
+            json["errors"] = serde_json::json!([]);
+            Reply::json(json)
+        }
+        ("machine", Some(action)) => machine(sim, req, action),
+        ("runners", Some(runner)) => runners(sim, req, runner),
+        ("drives", None) => {
+            let drives: Vec<serde_json::Value> = sim
+                .machine()
+                .drive_list()
+                .into_iter()
+                .map(|(letter, drive)| serde_json::json!({ letter: drive }))
+                .collect();
+            Reply::json(serde_json::json!({ "drives": drives, "errors": [] }))
+        }
+        ("drives", Some(action)) if rest.len() == 1 => drives(sim, req, rest[0], action),
+        ("configs", Some(operation)) if method == "PUT" => {
+            sim.machine().flash_operation(operation).into()
+        }
+        ("configs", None) => configs(sim, req, &rest),
+        ("streams", Some(action)) if rest.len() == 1 => {
+            let mut m = sim.machine();
+            match action {
+                "start" => {
+                    let dest = req.param("ip").unwrap_or_default().to_string();
+                    m.log(format!("stream_start({},{})", rest[0], dest));
+                    m.streams.insert(rest[0].to_string(), dest);
+                }
+                "stop" => {
+                    m.log(format!("stream_stop({})", rest[0]));
+                    m.streams.remove(rest[0]);
+                }
+                other => return Reply::error(404, format!("Unknown stream action {}", other)),
+            }
+            Reply::ok()
+        }
+        _ => Reply::error(404, format!("No such endpoint {}", req.path)),
+    }
+}
+
+fn parse_hex_u16(s: Option<&str>) -> Result<u16, String> {
+    let s = s.ok_or("Missing address")?;
+    let s = s.trim_start_matches("0x").trim_start_matches('$');
+    u16::from_str_radix(s, 16).map_err(|_| format!("Bad address '{}'", s))
+}
+
+fn machine(sim: &Simulator, req: &Request, action: &str) -> Reply {
+    let mut m = sim.machine();
+    match (req.method.as_str(), action) {
+        ("GET", "readmem") => {
+            let address = match parse_hex_u16(req.param("address")) {
+                Ok(a) => a,
+                Err(e) => return Reply::error(400, e),
+            };
+            let length = req
+                .param("length")
+                .and_then(|l| l.parse::<usize>().ok())
+                .unwrap_or(256);
+            match m.read(address, length) {
+                Ok(data) => Reply::binary(data),
+                Err(e) => Reply::error(400, e),
+            }
+        }
+        ("PUT" | "POST", "writemem") => {
+            let address = match parse_hex_u16(req.param("address")) {
+                Ok(a) => a,
+                Err(e) => return Reply::error(400, e),
+            };
+            // PUT carries up to 128 bytes as a hex `data` parameter.
+            let data = match req.param("data") {
+                Some(hex) => match (0..hex.len())
+                    .step_by(2)
+                    .map(|i| u8::from_str_radix(hex.get(i..i + 2).unwrap_or("x"), 16))
+                    .collect::<Result<Vec<u8>, _>>()
+                {
+                    Ok(d) => d,
+                    Err(_) => return Reply::error(400, "Bad hex data"),
+                },
+                None => req.body.clone(),
+            };
+            m.write(address, &data).into()
+        }
+        ("PUT", "reset") => {
+            m.reset();
+            Reply::ok()
+        }
+        ("PUT", "reboot") => {
+            m.log("reboot");
+            m.cold_boot();
+            Reply::ok()
+        }
+        ("PUT", "pause") | ("PUT", "resume") => {
+            m.paused = action == "pause";
+            m.log(action);
+            Reply::ok()
+        }
+        ("PUT", "poweroff") | ("PUT", "menu_button") => {
+            m.log(action);
+            Reply::ok()
+        }
+        ("GET", "debugreg") => {
+            // Kept to a single field: `api::parse_debugreg_value` reads the
+            // first `"key": value` pair.
+            Reply::json(serde_json::json!({ "value": m.debugreg }))
+        }
+        ("PUT", "debugreg") => match req
+            .param("value")
+            .and_then(|v| u8::from_str_radix(v, 16).ok())
+        {
+            Some(v) => {
+                m.debugreg = v;
+                m.log(format!("debugreg=${:02X}", v));
+                Reply::ok()
+            }
+            None => Reply::error(400, "Bad value"),
+        },
+        _ => Reply::error(
+            405,
+            format!("{} machine:{} not supported", req.method, action),
+        ),
+    }
+}
+
+/// Runner payload: the body of a POST, or `?file=` on the SD card for a PUT.
+fn runner_payload(sim: &Simulator, req: &Request) -> Result<(String, Vec<u8>), String> {
+    match (req.method.as_str(), req.param("file")) {
+        ("PUT", Some(file)) => {
+            let name = file.rsplit('/').next().unwrap_or(file).to_string();
+            Ok((name, sim.read_sd_file(file)?))
+        }
+        ("POST", _) => {
+            let parts = parse_multipart(req);
+            match parts.into_iter().find(|p| p.filename.is_some()) {
+                Some(p) => Ok((p.filename.unwrap_or_default(), p.data)),
+                None => Ok(("upload".to_string(), req.body.clone())),
+            }
+        }
+        _ => Err("Runner needs ?file= (PUT) or a body (POST)".into()),
+    }
+}
+
+fn runners(sim: &Simulator, req: &Request, runner: &str) -> Reply {
+    let (name, data) = match runner_payload(sim, req) {
+        Ok(p) => p,
+        Err(e) => return Reply::error(400, e),
+    };
+    let mut m = sim.machine();
+    match runner {
+        "run_prg" => m.run_prg(&name, &data).into(),
+        "load_prg" => {
+            m.cold_boot();
+            match m.load_prg(&data) {
+                Ok((start, end)) => {
+                    m.log(format!("load_prg({}) ${:04X}-${:04X}", name, start, end));
+                    Reply::ok()
+                }
+                Err(e) => Reply::error(400, e),
+            }
+        }
+        "run_crt" | "sidplay" | "modplay" => {
+            let name = match req.param("songnr") {
+                Some(song) => format!("{}#{}", name, song),
+                None => name,
+            };
+            m.start(runner, &name, data.len());
+            Reply::ok()
+        }
+        other => Reply::error(404, format!("Unknown runner {}", other)),
+    }
+}
+
+fn drives(sim: &Simulator, req: &Request, drive: &str, action: &str) -> Reply {
+    let index = match sim.machine().drive_index(drive) {
+        Some(i) => i,
+        None => return Reply::error(404, format!("No drive '{}'", drive)),
+    };
+    match action {
+        "mount" => {
+            let parts = parse_multipart(req);
+            let field = |name: &str| {
+                parts
+                    .iter()
+                    .find(|p| p.name == name)
+                    .map(|p| String::from_utf8_lossy(&p.data).into_owned())
+            };
+            let mode = req
+                .param("mode")
+                .map(String::from)
+                .or_else(|| field("mode"))
+                .unwrap_or_else(|| "readwrite".into());
+            let result = match (req.param("image"), parts.iter().find(|p| p.name == "file")) {
+                (Some(image), _) => sim.mount(drive, image, None, &mode),
+                (None, Some(file)) => sim.mount(
+                    drive,
+                    file.filename.as_deref().unwrap_or("upload.d64"),
+                    Some(file.data.clone()),
+                    &mode,
+                ),
+                (None, None) if !req.body.is_empty() => {
+                    sim.mount(drive, "upload.d64", Some(req.body.clone()), &mode)
+                }
+                (None, None) => Err("Mount needs ?image= or an uploaded file".into()),
+            };
+            result.into()
+        }
+        "remove" => {
+            sim.machine().unmount(index);
+            Reply::ok()
+        }
+        "set_mode" => match req.param("mode") {
+            Some(mode @ ("1541" | "1571" | "1581")) => {
+                let mut m = sim.machine();
+                m.set_drive_setting(index, "Drive Type", mode);
+                m.log(format!("set_mode({},{})", drive, mode));
+                Reply::ok()
+            }
+            _ => Reply::error(400, "mode must be 1541, 1571 or 1581"),
+        },
+        "on" | "off" => {
+            let mut m = sim.machine();
+            let value = if action == "on" {
+                "Enabled"
+            } else {
+                "Disabled"
+            };
+            m.set_drive_setting(index, "Drive", value);
+            m.log(format!("drive_{}({})", action, drive));
+            Reply::ok()
+        }
+        "reset" => {
+            sim.machine().log(format!("drive_reset({})", drive));
+            Reply::ok()
+        }
+        other => Reply::error(404, format!("Unknown drive action {}", other)),
+    }
+}
+
+fn configs(sim: &Simulator, req: &Request, path: &[&str]) -> Reply {
+    let mut m = sim.machine();
+    match (req.method.as_str(), path) {
+        ("GET", []) => {
+            let categories: Vec<&String> = m.config.keys().collect();
+            Reply::json(serde_json::json!({ "categories": categories, "errors": [] }))
+        }
+        ("GET", [category]) | ("GET", [category, _]) => {
+            let item = path.get(1).copied();
+            match m.config_details(category, item.or(Some("*"))) {
+                Some(mut json) => {
+                    json["errors"] = serde_json::json!([]);
+                    Reply::json(json)
+                }
+                None => Reply::error(404, format!("No config {}", path.join("/"))),
+            }
+        }
+        ("PUT", [category, item]) => match req.param("value") {
+            Some(value) => m
+                .set_config(category, item, serde_json::json!(value))
+                .into(),
+            None => Reply::error(400, "Missing value"),
+        },
+        ("POST", []) => {
+            let tree: serde_json::Value = match serde_json::from_slice(&req.body) {
+                Ok(v) => v,
+                Err(e) => return Reply::error(400, format!("Bad JSON: {}", e)),
+            };
+            let Some(categories) = tree.as_object() else {
+                return Reply::error(400, "Expected a JSON object");
+            };
+            for (category, items) in categories {
+                for (item, value) in items.as_object().into_iter().flatten() {
+                    if let Err(e) = m.set_config(category, item, value.clone()) {
+                        return Reply::error(400, e);
+                    }
+                }
+            }
+            Reply::ok()
+        }
+        _ => Reply::error(405, format!("{} configs not supported", req.method)),
+    }
+}
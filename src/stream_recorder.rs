@@ -0,0 +1,954 @@
+//! Stream recorder — captures the VIC video stream and the audio stream to disk.
+//!
+//! Two output formats:
+//! - **AVI**: uncompressed 24-bit DIB video + 16-bit stereo PCM, one file.
+//! - **Frame sequence**: a folder of numbered PNGs + `audio.wav` + a
+//!   `recording.txt` with the exact frame rate and an ffmpeg command line.
+//!
+//! The recorder is fed the raw UDP payloads exactly as they arrive on the
+//! video and audio sockets, so the live stream threads and a packet capture
+//! drive it the same way. Frame timing comes from the stream: the video
+//! standard (PAL/NTSC) is taken from the frame height, frames the network
+//! dropped are filled by repeating the next complete frame, and lost audio
+//! packets are padded with silence, so video and audio stay in step.
+
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{BufWriter, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+
+use crate::streaming::{
+    AudioResampler, AUDIO_HEADER_SIZE, AUDIO_SAMPLE_RATE, AUDIO_SAMPLE_RATE_NTSC,
+    AUDIO_SAMPLE_RATE_PAL, HEADER_SIZE, VIC_HEIGHT, VIC_WIDTH,
+};
+use crate::video_scaling::C64_PALETTE;
+
+/// Longest run of missing frames we fill in; anything larger is treated as a
+/// stream restart rather than packet loss.
+const MAX_FRAME_GAP: u16 = 50;
+/// Same idea for audio packets (~4 ms each).
+const MAX_AUDIO_GAP: u16 = 250;
+/// Bytes reserved at the start of an AVI for the header lists. The headers are
+/// written last (when frame counts are known) and padded out with a JUNK chunk.
+const AVI_HEADER_RESERVED: u64 = 2048;
+/// RIFF sizes and idx1 offsets are 32-bit, so an AVI (or WAV) ends just short
+/// of 4 GiB — about 4½ minutes of PAL video. Recording stops there.
+const RIFF_MAX_BYTES: u64 = u32::MAX as u64;
+
+// ─── Format / timing ─────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RecordFormat {
+    /// Uncompressed AVI (video + audio in one file).
+    #[default]
+    Avi,
+    /// Lossless PNG frame sequence plus a WAV file.
+    FrameSequence,
+}
+
+impl std::fmt::Display for RecordFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RecordFormat::Avi => write!(f, "AVI"),
+            RecordFormat::FrameSequence => write!(f, "PNG + WAV"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VideoStandard {
+    Pal,
+    Ntsc,
+}
+
+impl VideoStandard {
+    /// The Ultimate streams 272 lines per frame on PAL and 240 on NTSC.
+    fn from_height(lines: u32) -> Self {
+        if lines <= 250 {
+            VideoStandard::Ntsc
+        } else {
+            VideoStandard::Pal
+        }
+    }
+
+    pub fn height(self) -> u32 {
+        match self {
+            VideoStandard::Pal => VIC_HEIGHT,
+            VideoStandard::Ntsc => 240,
+        }
+    }
+
+    /// Frame rate as (rate, scale): CPU clock / cycles per frame.
+    pub fn frame_rate(self) -> (u32, u32) {
+        match self {
+            VideoStandard::Pal => (985_248, 63 * 312),
+            VideoStandard::Ntsc => (1_022_727, 65 * 263),
+        }
+    }
+
+    pub fn fps(self) -> f64 {
+        let (rate, scale) = self.frame_rate();
+        rate as f64 / scale as f64
+    }
+
+    /// Native sample rate of the audio stream for this machine clock.
+    fn audio_input_rate(self) -> f64 {
+        match self {
+            VideoStandard::Pal => AUDIO_SAMPLE_RATE_PAL,
+            VideoStandard::Ntsc => AUDIO_SAMPLE_RATE_NTSC,
+        }
+    }
+}
+
+impl std::fmt::Display for VideoStandard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VideoStandard::Pal => write!(f, "PAL"),
+            VideoStandard::Ntsc => write!(f, "NTSC"),
+        }
+    }
+}
+
+/// What ended up on disk.
+#[derive(Debug, Clone)]
+pub struct RecordingSummary {
+    pub path: PathBuf,
+    pub standard: VideoStandard,
+    /// Frames written, including fill-ins for dropped frames.
+    pub frames: u64,
+    /// How many of `frames` were fill-ins.
+    pub repeated_frames: u64,
+    /// Stereo sample frames written at 48 kHz.
+    pub audio_frames: u64,
+}
+
+impl RecordingSummary {
+    pub fn describe(&self) -> String {
+        let secs = self.frames as f64 / self.standard.fps();
+        let mut s = format!(
+            "{} ({}, {} frames, {:.1}s video, {:.1}s audio",
+            self.path.display(),
+            self.standard,
+            self.frames,
+            secs,
+            self.audio_frames as f64 / AUDIO_SAMPLE_RATE as f64
+        );
+        if self.repeated_frames > 0 {
+            s.push_str(&format!(", {} dropped frames filled", self.repeated_frames));
+        }
+        s.push(')');
+        s
+    }
+}
+
+// ─── Video packet assembly ───────────────────────────────────────────────────
+
+/// Rebuilds whole frames (one palette index per pixel) from video packets.
+struct FrameAssembler {
+    pixels: Vec<u8>,
+}
+
+/// A frame finished by the packet carrying the frame-sync bit.
+struct CompletedFrame {
+    number: u16,
+    height: u32,
+}
+
+impl FrameAssembler {
+    fn new() -> Self {
+        Self {
+            pixels: vec![0; (VIC_WIDTH * VIC_HEIGHT) as usize],
+        }
+    }
+
+    fn push(&mut self, packet: &[u8]) -> Option<CompletedFrame> {
+        if packet.len() < HEADER_SIZE {
+            return None;
+        }
+        let frame = u16::from_le_bytes([packet[2], packet[3]]);
+        let line_raw = u16::from_le_bytes([packet[4], packet[5]]);
+        let pixels_in_line = u16::from_le_bytes([packet[6], packet[7]]) as usize;
+        let lines_in_packet = packet[8] as usize;
+        let line_num = (line_raw & 0x7FFF) as usize;
+        let payload = &packet[HEADER_SIZE..];
+        let bytes_per_line = pixels_in_line / 2;
+        let width = VIC_WIDTH as usize;
+
+        for l in 0..lines_in_packet {
+            let y = line_num + l;
+            if y >= VIC_HEIGHT as usize {
+                continue;
+            }
+            let start = l * bytes_per_line;
+            let Some(src) = payload.get(start..(start + bytes_per_line).min(payload.len())) else {
+                break;
+            };
+            for (x, &packed) in src.iter().enumerate().take(width / 2) {
+                // Low nibble is the left pixel.
+                self.pixels[y * width + x * 2] = packed & 0x0F;
+                self.pixels[y * width + x * 2 + 1] = packed >> 4;
+            }
+        }
+
+        (line_raw & 0x8000 != 0).then(|| CompletedFrame {
+            number: frame,
+            height: ((line_num + lines_in_packet) as u32).min(VIC_HEIGHT),
+        })
+    }
+}
+
+// ─── Recorder ────────────────────────────────────────────────────────────────
+
+enum Sink {
+    Avi(AviWriter),
+    Frames(FrameSequenceWriter),
+}
+
+/// Turns raw stream packets into a recording. Not thread-safe by itself; the
+/// live stream hands packets to it through [`spawn_writer`].
+pub struct StreamRecorder {
+    path: PathBuf,
+    sink: Sink,
+    assembler: FrameAssembler,
+    /// Locked in by the first complete frame.
+    standard: Option<VideoStandard>,
+    last_frame: Option<u16>,
+    last_audio_seq: Option<u16>,
+    /// Stream-rate samples received before the standard (and so the input
+    /// rate for the resampler) is known.
+    pending_audio: Vec<f32>,
+    resampler: Option<AudioResampler>,
+    /// 48 kHz interleaved stereo waiting to be written.
+    audio: VecDeque<f32>,
+    frames: u64,
+    repeated_frames: u64,
+    audio_frames: u64,
+}
+
+impl StreamRecorder {
+    /// Start a recording at `path`: a file for AVI, a folder (created if
+    /// missing) for a frame sequence.
+    pub fn create(path: &Path, format: RecordFormat) -> Result<Self, String> {
+        let sink = match format {
+            RecordFormat::Avi => Sink::Avi(AviWriter::create(path)?),
+            RecordFormat::FrameSequence => Sink::Frames(FrameSequenceWriter::create(path)?),
+        };
+        Ok(Self {
+            path: path.to_path_buf(),
+            sink,
+            assembler: FrameAssembler::new(),
+            standard: None,
+            last_frame: None,
+            last_audio_seq: None,
+            pending_audio: Vec::new(),
+            resampler: None,
+            audio: VecDeque::new(),
+            frames: 0,
+            repeated_frames: 0,
+            audio_frames: 0,
+        })
+    }
+
+    /// Feed one video UDP payload (12-byte header + packed pixels).
+    pub fn push_video_packet(&mut self, packet: &[u8]) -> Result<(), String> {
+        let Some(done) = self.assembler.push(packet) else {
+            return Ok(());
+        };
+        let standard = match self.standard {
+            Some(s) => s,
+            None => {
+                let s = VideoStandard::from_height(done.height);
+                log::info!("Recording: {} stream ({:.4} fps)", s, s.fps());
+                self.standard = Some(s);
+                self.start_resampler(s);
+                s
+            }
+        };
+
+        let copies = match self.last_frame {
+            Some(last) => match done.number.wrapping_sub(last) {
+                0 => 1,
+                gap if gap > MAX_FRAME_GAP => 1,
+                gap => gap,
+            },
+            None => 1,
+        };
+        self.last_frame = Some(done.number);
+
+        let rgb = self.frame_rgb(standard);
+        for _ in 0..copies {
+            self.flush_audio()?;
+            match &mut self.sink {
+                Sink::Avi(w) => w.write_frame(&rgb, standard)?,
+                Sink::Frames(w) => w.write_frame(&rgb, standard)?,
+            }
+            self.frames += 1;
+        }
+        self.repeated_frames += u64::from(copies - 1);
+        Ok(())
+    }
+
+    /// Feed one audio UDP payload (2-byte sequence + i16 stereo samples).
+    pub fn push_audio_packet(&mut self, packet: &[u8]) -> Result<(), String> {
+        if packet.len() <= AUDIO_HEADER_SIZE {
+            return Ok(());
+        }
+        let seq = u16::from_le_bytes([packet[0], packet[1]]);
+        let samples: Vec<f32> = packet[AUDIO_HEADER_SIZE..]
+            .chunks_exact(2)
+            .map(|c| i16::from_le_bytes([c[0], c[1]]) as f32 / 32768.0)
+            .collect();
+
+        // Lost packets become silence of the same length.
+        if let Some(last) = self.last_audio_seq {
+            let gap = seq.wrapping_sub(last).wrapping_sub(1);
+            if gap > 0 && gap <= MAX_AUDIO_GAP {
+                let silence = vec![0.0; samples.len() * gap as usize];
+                self.queue_audio(&silence);
+            }
+        }
+        self.last_audio_seq = Some(seq);
+        self.queue_audio(&samples);
+        Ok(())
+    }
+
+    /// Flush what's buffered and close the files.
+    pub fn finish(mut self) -> Result<RecordingSummary, String> {
+        let standard = match self.standard {
+            Some(s) => s,
+            None => {
+                // Audio-only (or empty) capture: assume PAL for the timing.
+                self.start_resampler(VideoStandard::Pal);
+                VideoStandard::Pal
+            }
+        };
+        self.flush_audio()?;
+        match self.sink {
+            Sink::Avi(w) => w.finish(standard)?,
+            Sink::Frames(w) => w.finish(standard, self.frames)?,
+        }
+        Ok(RecordingSummary {
+            path: self.path,
+            standard,
+            frames: self.frames,
+            repeated_frames: self.repeated_frames,
+            audio_frames: self.audio_frames,
+        })
+    }
+
+    fn start_resampler(&mut self, standard: VideoStandard) {
+        let mut resampler =
+            AudioResampler::new(standard.audio_input_rate(), AUDIO_SAMPLE_RATE as f64);
+        resampler.process_stereo(&self.pending_audio, &mut self.audio);
+        self.pending_audio = Vec::new();
+        self.resampler = Some(resampler);
+    }
+
+    fn queue_audio(&mut self, samples: &[f32]) {
+        match &mut self.resampler {
+            Some(r) => r.process_stereo(samples, &mut self.audio),
+            None => self.pending_audio.extend_from_slice(samples),
+        }
+    }
+
+    fn flush_audio(&mut self) -> Result<(), String> {
+        let pairs = self.audio.len() / 2;
+        if pairs == 0 {
+            return Ok(());
+        }
+        let mut pcm = Vec::with_capacity(pairs * 4);
+        for s in self.audio.drain(..pairs * 2) {
+            let v = (s * 32767.0).clamp(-32768.0, 32767.0) as i16;
+            pcm.extend_from_slice(&v.to_le_bytes());
+        }
+        match &mut self.sink {
+            Sink::Avi(w) => w.write_audio(&pcm)?,
+            Sink::Frames(w) => w.write_audio(&pcm)?,
+        }
+        self.audio_frames += pairs as u64;
+        Ok(())
+    }
+
+    /// Current frame as packed RGB rows, top to bottom.
+    fn frame_rgb(&self, standard: VideoStandard) -> Vec<u8> {
+        let n = (VIC_WIDTH * standard.height()) as usize;
+        let mut rgb = Vec::with_capacity(n * 3);
+        for &idx in &self.assembler.pixels[..n] {
+            rgb.extend_from_slice(&C64_PALETTE[(idx & 0x0F) as usize]);
+        }
+        rgb
+    }
+}
+
+// ─── AVI writer ──────────────────────────────────────────────────────────────
+
+struct AviWriter {
+    out: BufWriter<File>,
+    /// (fourcc, offset from the `movi` fourcc, size) for idx1.
+    index: Vec<([u8; 4], u32, u32)>,
+    pos: u64,
+    video_frames: u32,
+    audio_bytes: u64,
+    max_chunk: u32,
+    height: u32,
+    /// Size the finished file may not exceed
+    max_bytes: u64,
+}
+
+fn io_err(e: std::io::Error) -> String {
+    format!("Recording write failed: {}", e)
+}
+
+fn fourcc_chunk(buf: &mut Vec<u8>, id: &[u8; 4], body: &[u8]) {
+    buf.extend_from_slice(id);
+    buf.extend_from_slice(&(body.len() as u32).to_le_bytes());
+    buf.extend_from_slice(body);
+    if body.len() % 2 == 1 {
+        buf.push(0);
+    }
+}
+
+fn list_chunk(buf: &mut Vec<u8>, kind: &[u8; 4], body: &[u8]) {
+    let mut inner = kind.to_vec();
+    inner.extend_from_slice(body);
+    fourcc_chunk(buf, b"LIST", &inner);
+}
+
+fn le32(v: &mut Vec<u8>, x: u32) {
+    v.extend_from_slice(&x.to_le_bytes());
+}
+
+fn le16(v: &mut Vec<u8>, x: u16) {
+    v.extend_from_slice(&x.to_le_bytes());
+}
+
+impl AviWriter {
+    fn create(path: &Path) -> Result<Self, String> {
+        let file =
+            File::create(path).map_err(|e| format!("Cannot create {}: {}", path.display(), e))?;
+        let mut out = BufWriter::new(file);
+        // Placeholder header; `finish` rewrites it once the counts are known.
+        out.write_all(&vec![0u8; AVI_HEADER_RESERVED as usize + 12])
+            .map_err(io_err)?;
+        Ok(Self {
+            out,
+            index: Vec::new(),
+            pos: AVI_HEADER_RESERVED + 12,
+            video_frames: 0,
+            audio_bytes: 0,
+            max_chunk: 0,
+            height: VIC_HEIGHT,
+            max_bytes: RIFF_MAX_BYTES,
+        })
+    }
+
+    fn write_chunk(&mut self, id: [u8; 4], body: &[u8]) -> Result<(), String> {
+        let offset = (self.pos - (AVI_HEADER_RESERVED + 8)) as u32;
+        let mut buf = Vec::with_capacity(body.len() + 9);
+        fourcc_chunk(&mut buf, &id, body);
+        // The file as `finish` would leave it: this chunk plus the idx1 list
+        let projected = self.pos + buf.len() as u64 + (self.index.len() as u64 + 1) * 16 + 8;
+        if projected > self.max_bytes {
+            return Err(format!(
+                "AVI reached the 4 GiB limit after {} frames; use PNG + WAV for longer captures",
+                self.video_frames
+            ));
+        }
+        self.out.write_all(&buf).map_err(io_err)?;
+        self.pos += buf.len() as u64;
+        self.index.push((id, offset, body.len() as u32));
+        self.max_chunk = self.max_chunk.max(body.len() as u32);
+        Ok(())
+    }
+
+    fn write_frame(&mut self, rgb: &[u8], standard: VideoStandard) -> Result<(), String> {
+        // DIBs are BGR, bottom-up.
+        let row = VIC_WIDTH as usize * 3;
+        let mut dib = Vec::with_capacity(rgb.len());
+        for line in rgb.chunks_exact(row).rev() {
+            for px in line.chunks_exact(3) {
+                dib.extend_from_slice(&[px[2], px[1], px[0]]);
+            }
+        }
+        self.height = standard.height();
+        self.write_chunk(*b"00db", &dib)?;
+        self.video_frames += 1;
+        Ok(())
+    }
+
+    fn write_audio(&mut self, pcm: &[u8]) -> Result<(), String> {
+        self.write_chunk(*b"01wb", pcm)?;
+        self.audio_bytes += pcm.len() as u64;
+        Ok(())
+    }
+
+    fn finish(mut self, standard: VideoStandard) -> Result<(), String> {
+        const AVIIF_KEYFRAME: u32 = 0x10;
+        let mut idx = Vec::with_capacity(self.index.len() * 16);
+        for (id, offset, size) in &self.index {
+            idx.extend_from_slice(id);
+            le32(&mut idx, AVIIF_KEYFRAME);
+            le32(&mut idx, *offset);
+            le32(&mut idx, *size);
+        }
+        let mut tail = Vec::new();
+        fourcc_chunk(&mut tail, b"idx1", &idx);
+        self.out.write_all(&tail).map_err(io_err)?;
+        let movi_end = self.pos;
+        let file_len = movi_end + tail.len() as u64;
+
+        let header = self.header(standard, movi_end, file_len);
+        self.out.seek(SeekFrom::Start(0)).map_err(io_err)?;
+        self.out.write_all(&header).map_err(io_err)?;
+        self.out.flush().map_err(io_err)
+    }
+
+    /// RIFF header, hdrl list, JUNK padding and the `movi` list header.
+    fn header(&self, standard: VideoStandard, movi_end: u64, file_len: u64) -> Vec<u8> {
+        let (rate, scale) = standard.frame_rate();
+        let width = VIC_WIDTH;
+        let height = self.height;
+        let frame_bytes = width * height * 3;
+        let bytes_per_sec = AUDIO_SAMPLE_RATE * 4;
+
+        let mut avih = Vec::new();
+        le32(
+            &mut avih,
+            (1_000_000u64 * scale as u64 / rate as u64) as u32,
+        );
+        le32(
+            &mut avih,
+            (frame_bytes as f64 * standard.fps()) as u32 + bytes_per_sec,
+        );
+        le32(&mut avih, 0); // padding granularity
+        le32(&mut avih, 0x10 | 0x100); // AVIF_HASINDEX | AVIF_ISINTERLEAVED
+        le32(&mut avih, self.video_frames);
+        le32(&mut avih, 0); // initial frames
+        le32(&mut avih, 2); // streams
+        le32(&mut avih, self.max_chunk);
+        le32(&mut avih, width);
+        le32(&mut avih, height);
+        avih.extend_from_slice(&[0u8; 16]);
+
+        let strh = |kind: &[u8; 4], scale: u32, rate: u32, length: u32, sample: u32| {
+            let mut h = Vec::new();
+            h.extend_from_slice(kind);
+            le32(&mut h, 0); // handler
+            le32(&mut h, 0); // flags
+            le16(&mut h, 0); // priority
+            le16(&mut h, 0); // language
+            le32(&mut h, 0); // initial frames
+            le32(&mut h, scale);
+            le32(&mut h, rate);
+            le32(&mut h, 0); // start
+            le32(&mut h, length);
+            le32(&mut h, self.max_chunk);
+            le32(&mut h, u32::MAX); // quality: default
+            le32(&mut h, sample);
+            le16(&mut h, 0);
+            le16(&mut h, 0);
+            le16(&mut h, width as u16);
+            le16(&mut h, height as u16);
+            h
+        };
+
+        let mut bih = Vec::new();
+        le32(&mut bih, 40);
+        le32(&mut bih, width);
+        le32(&mut bih, height); // positive: bottom-up
+        le16(&mut bih, 1);
+        le16(&mut bih, 24);
+        le32(&mut bih, 0); // BI_RGB
+        le32(&mut bih, frame_bytes);
+        bih.extend_from_slice(&[0u8; 16]);
+
+        let mut wfx = Vec::new();
+        le16(&mut wfx, 1); // PCM
+        le16(&mut wfx, 2);
+        le32(&mut wfx, AUDIO_SAMPLE_RATE);
+        le32(&mut wfx, bytes_per_sec);
+        le16(&mut wfx, 4);
+        le16(&mut wfx, 16);
+        le16(&mut wfx, 0);
+
+        let mut vids = Vec::new();
+        fourcc_chunk(
+            &mut vids,
+            b"strh",
+            &strh(b"vids", scale, rate, self.video_frames, 0),
+        );
+        fourcc_chunk(&mut vids, b"strf", &bih);
+        let mut auds = Vec::new();
+        fourcc_chunk(
+            &mut auds,
+            b"strh",
+            &strh(b"auds", 4, bytes_per_sec, (self.audio_bytes / 4) as u32, 4),
+        );
+        fourcc_chunk(&mut auds, b"strf", &wfx);
+
+        let mut hdrl = Vec::new();
+        fourcc_chunk(&mut hdrl, b"avih", &avih);
+        list_chunk(&mut hdrl, b"strl", &vids);
+        list_chunk(&mut hdrl, b"strl", &auds);
+
+        let mut out = Vec::with_capacity(AVI_HEADER_RESERVED as usize + 12);
+        out.extend_from_slice(b"RIFF");
+        le32(&mut out, (file_len - 8) as u32);
+        out.extend_from_slice(b"AVI ");
+        list_chunk(&mut out, b"hdrl", &hdrl);
+        let junk = AVI_HEADER_RESERVED as usize - out.len() - 8;
+        fourcc_chunk(&mut out, b"JUNK", &vec![0u8; junk]);
+        out.extend_from_slice(b"LIST");
+        le32(&mut out, (movi_end - AVI_HEADER_RESERVED - 8) as u32);
+        out.extend_from_slice(b"movi");
+        out
+    }
+}
+
+// ─── Frame sequence writer ───────────────────────────────────────────────────
+
+struct FrameSequenceWriter {
+    dir: PathBuf,
+    wav: BufWriter<File>,
+    audio_bytes: u64,
+    next_frame: u64,
+}
+
+impl FrameSequenceWriter {
+    fn create(dir: &Path) -> Result<Self, String> {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Cannot create {}: {}", dir.display(), e))?;
+        let wav_path = dir.join("audio.wav");
+        let file = File::create(&wav_path)
+            .map_err(|e| format!("Cannot create {}: {}", wav_path.display(), e))?;
+        let mut wav = BufWriter::new(file);
+        wav.write_all(&wav_header(0)).map_err(io_err)?;
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            wav,
+            audio_bytes: 0,
+            next_frame: 0,
+        })
+    }
+
+    fn write_frame(&mut self, rgb: &[u8], standard: VideoStandard) -> Result<(), String> {
+        self.next_frame += 1;
+        let path = self.dir.join(format!("frame_{:06}.png", self.next_frame));
+        image::RgbImage::from_raw(VIC_WIDTH, standard.height(), rgb.to_vec())
+            .ok_or_else(|| "Frame buffer has the wrong size".to_string())?
+            .save(&path)
+            .map_err(|e| format!("Failed to save {}: {}", path.display(), e))
+    }
+
+    fn write_audio(&mut self, pcm: &[u8]) -> Result<(), String> {
+        if 44 + self.audio_bytes + pcm.len() as u64 > RIFF_MAX_BYTES {
+            return Err("audio.wav reached the 4 GiB limit".to_string());
+        }
+        self.wav.write_all(pcm).map_err(io_err)?;
+        self.audio_bytes += pcm.len() as u64;
+        Ok(())
+    }
+
+    fn finish(mut self, standard: VideoStandard, frames: u64) -> Result<(), String> {
+        self.wav.seek(SeekFrom::Start(0)).map_err(io_err)?;
+        self.wav
+            .write_all(&wav_header(self.audio_bytes as u32))
+            .map_err(io_err)?;
+        self.wav.flush().map_err(io_err)?;
+
+        let (rate, scale) = standard.frame_rate();
+        let info = format!(
+            "standard={}\nframes={}\nframe_rate={}/{}\nfps={:.6}\naudio=audio.wav (48000 Hz, 16-bit stereo)\n\n\
+             ffmpeg -framerate {}/{} -i frame_%06d.png -i audio.wav -c:v ffv1 -c:a flac capture.mkv\n",
+            standard,
+            frames,
+            rate,
+            scale,
+            standard.fps(),
+            rate,
+            scale
+        );
+        std::fs::write(self.dir.join("recording.txt"), info).map_err(io_err)
+    }
+}
+
+/// 44-byte canonical WAV header for 48 kHz 16-bit stereo PCM.
+fn wav_header(data_len: u32) -> Vec<u8> {
+    let mut h = Vec::with_capacity(44);
+    h.extend_from_slice(b"RIFF");
+    le32(&mut h, 36 + data_len);
+    h.extend_from_slice(b"WAVEfmt ");
+    le32(&mut h, 16);
+    le16(&mut h, 1);
+    le16(&mut h, 2);
+    le32(&mut h, AUDIO_SAMPLE_RATE);
+    le32(&mut h, AUDIO_SAMPLE_RATE * 4);
+    le16(&mut h, 4);
+    le16(&mut h, 16);
+    h.extend_from_slice(b"data");
+    le32(&mut h, data_len);
+    h
+}
+
+// ─── Live capture plumbing ───────────────────────────────────────────────────
+
+/// A packet copied off one of the stream sockets.
+pub enum RecorderPacket {
+    Video(Vec<u8>),
+    Audio(Vec<u8>),
+}
+
+/// Shared slot the stream threads forward packets through while a recording
+/// is active. Taking the sender out ends the recording.
+pub type RecorderTap = Arc<Mutex<Option<mpsc::Sender<RecorderPacket>>>>;
+
+/// Forward a packet to the recorder if one is listening.
+pub fn tap_packet(tap: &RecorderTap, packet: impl FnOnce() -> RecorderPacket) {
+    if let Ok(guard) = tap.lock() {
+        if let Some(tx) = guard.as_ref() {
+            let _ = tx.send(packet());
+        }
+    }
+}
+
+/// Run the recorder on its own thread so disk I/O never stalls the sockets.
+/// The thread finishes the file once every sender is dropped, or as soon as a
+/// write fails — the headers are still patched so what was written so far
+/// stays playable — and then returns the failure.
+pub fn spawn_writer(
+    mut recorder: StreamRecorder,
+    rx: mpsc::Receiver<RecorderPacket>,
+) -> thread::JoinHandle<Result<RecordingSummary, String>> {
+    thread::spawn(move || {
+        let mut failure = None;
+        for packet in rx {
+            let written = match packet {
+                RecorderPacket::Video(p) => recorder.push_video_packet(&p),
+                RecorderPacket::Audio(p) => recorder.push_audio_packet(&p),
+            };
+            if let Err(e) = written {
+                failure = Some(e);
+                break;
+            }
+        }
+        let finished = recorder.finish();
+        match (failure, finished) {
+            (None, finished) => finished,
+            (Some(e), Ok(summary)) => Err(format!("{} — kept {}", e, summary.describe())),
+            (Some(e), Err(_)) => Err(e),
+        }
+    })
+}
+
+/// `Videos/Ultimate64/u64_capture_<timestamp>[.avi]`, falling back to home.
+pub fn default_output_path(format: RecordFormat) -> Result<PathBuf, String> {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|e| e.to_string())?
+        .as_secs();
+    let base = dirs::video_dir()
+        .or_else(dirs::home_dir)
+        .ok_or_else(|| "Could not find Videos or Home directory".to_string())?
+        .join("Ultimate64");
+    std::fs::create_dir_all(&base)
+        .map_err(|e| format!("Failed to create capture directory: {}", e))?;
+    Ok(match format {
+        RecordFormat::Avi => base.join(format!("u64_capture_{}.avi", timestamp)),
+        RecordFormat::FrameSequence => base.join(format!("u64_capture_{}", timestamp)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Video packets for one frame, 4 lines per packet like the device sends,
+    /// every pixel set to `color`.
+    fn frame_packets(frame: u16, height: u16, color: u8) -> Vec<Vec<u8>> {
+        let mut out = Vec::new();
+        let mut line = 0u16;
+        let mut seq = 0u16;
+        while line < height {
+            let lines = 4.min(height - line);
+            let mut p = Vec::new();
+            p.extend_from_slice(&seq.to_le_bytes());
+            p.extend_from_slice(&frame.to_le_bytes());
+            let sync = if line + lines >= height { 0x8000 } else { 0 };
+            p.extend_from_slice(&(line | sync).to_le_bytes());
+            p.extend_from_slice(&(VIC_WIDTH as u16).to_le_bytes());
+            p.push(lines as u8);
+            p.push(4);
+            p.extend_from_slice(&0u16.to_le_bytes());
+            p.extend(std::iter::repeat_n(
+                color | (color << 4),
+                192 * lines as usize,
+            ));
+            out.push(p);
+            line += lines;
+            seq = seq.wrapping_add(1);
+        }
+        out
+    }
+
+    fn audio_packet(seq: u16, value: i16) -> Vec<u8> {
+        let mut p = seq.to_le_bytes().to_vec();
+        for _ in 0..384 {
+            p.extend_from_slice(&value.to_le_bytes());
+        }
+        p
+    }
+
+    /// Scratch directory unique to this test process and test name.
+    fn scratch(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("u64_rec_{}_{}", std::process::id(), name));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn u32_at(b: &[u8], at: usize) -> u32 {
+        u32::from_le_bytes(b[at..at + 4].try_into().unwrap())
+    }
+
+    #[test]
+    fn standard_follows_frame_height() {
+        assert_eq!(VideoStandard::from_height(272), VideoStandard::Pal);
+        assert_eq!(VideoStandard::from_height(240), VideoStandard::Ntsc);
+        assert!((VideoStandard::Pal.fps() - 50.1245).abs() < 0.001);
+        assert!((VideoStandard::Ntsc.fps() - 59.826).abs() < 0.001);
+    }
+
+    #[test]
+    fn avi_from_packets_has_valid_structure() {
+        let path = scratch("cap").join("cap.avi");
+        let mut rec = StreamRecorder::create(&path, RecordFormat::Avi).unwrap();
+        for n in 0..3u16 {
+            for p in frame_packets(n, 272, 6) {
+                rec.push_video_packet(&p).unwrap();
+            }
+            for s in 0..13u16 {
+                rec.push_audio_packet(&audio_packet(n * 13 + s, 1000))
+                    .unwrap();
+            }
+        }
+        let summary = rec.finish().unwrap();
+        assert_eq!(summary.standard, VideoStandard::Pal);
+        assert_eq!(summary.frames, 3);
+        assert!(summary.audio_frames > 0);
+
+        let avi = std::fs::read(&path).unwrap();
+        assert_eq!(&avi[0..4], b"RIFF");
+        assert_eq!(u32_at(&avi, 4) as usize, avi.len() - 8);
+        assert_eq!(&avi[8..12], b"AVI ");
+        // avih: total frames, width, height
+        assert_eq!(&avi[24..28], b"avih");
+        assert_eq!(u32_at(&avi, 32 + 16), 3);
+        assert_eq!(u32_at(&avi, 32 + 32), VIC_WIDTH);
+        assert_eq!(u32_at(&avi, 32 + 36), 272);
+        let movi = AVI_HEADER_RESERVED as usize;
+        assert_eq!(&avi[movi..movi + 4], b"LIST");
+        assert_eq!(&avi[movi + 8..movi + 12], b"movi");
+        // First chunk is the first frame; blue is C64_PALETTE[6], stored as BGR.
+        assert_eq!(&avi[movi + 12..movi + 16], b"00db");
+        let blue = C64_PALETTE[6];
+        assert_eq!(&avi[movi + 20..movi + 23], &[blue[2], blue[1], blue[0]]);
+        // idx1 closes the file with one entry per chunk.
+        let movi_len = u32_at(&avi, movi + 4) as usize;
+        let idx = movi + 8 + movi_len;
+        assert_eq!(&avi[idx..idx + 4], b"idx1");
+        let entries = u32_at(&avi, idx + 4) as usize / 16;
+        assert!(entries >= 4);
+        // Index offsets are relative to the movi fourcc.
+        assert_eq!(&avi[idx + 8..idx + 12], b"00db");
+        let off = u32_at(&avi, idx + 16) as usize;
+        assert_eq!(&avi[movi + 8 + off..movi + 12 + off], b"00db");
+    }
+
+    #[test]
+    fn dropped_frames_are_filled_and_ntsc_detected() {
+        let path = scratch("ntsc").join("ntsc.avi");
+        let mut rec = StreamRecorder::create(&path, RecordFormat::Avi).unwrap();
+        for n in [10u16, 11, 14] {
+            for p in frame_packets(n, 240, 1) {
+                rec.push_video_packet(&p).unwrap();
+            }
+        }
+        let summary = rec.finish().unwrap();
+        assert_eq!(summary.standard, VideoStandard::Ntsc);
+        assert_eq!(summary.frames, 5);
+        assert_eq!(summary.repeated_frames, 2);
+        let avi = std::fs::read(&path).unwrap();
+        assert_eq!(u32_at(&avi, 32 + 36), 240);
+    }
+
+    #[test]
+    fn lost_audio_packets_become_silence() {
+        let path = scratch("seq").join("seq");
+        let mut rec = StreamRecorder::create(&path, RecordFormat::FrameSequence).unwrap();
+        for p in frame_packets(0, 272, 2) {
+            rec.push_video_packet(&p).unwrap();
+        }
+        rec.push_audio_packet(&audio_packet(0, 2000)).unwrap();
+        rec.push_audio_packet(&audio_packet(3, 2000)).unwrap();
+        let summary = rec.finish().unwrap();
+
+        // 4 packets' worth of 192 frames, resampled up by 48000/47983.
+        assert!((768..=770).contains(&summary.audio_frames));
+        let wav = std::fs::read(path.join("audio.wav")).unwrap();
+        assert_eq!(&wav[0..4], b"RIFF");
+        assert_eq!(u32_at(&wav, 40) as usize, wav.len() - 44);
+        assert_eq!(u32_at(&wav, 40) as u64, summary.audio_frames * 4);
+
+        let png = image::open(path.join("frame_000001.png"))
+            .unwrap()
+            .to_rgb8();
+        assert_eq!(png.dimensions(), (VIC_WIDTH, 272));
+        assert_eq!(png.get_pixel(100, 100).0, C64_PALETTE[2]);
+        let info = std::fs::read_to_string(path.join("recording.txt")).unwrap();
+        assert!(info.contains("frame_rate=985248/19656"));
+    }
+
+    #[test]
+    fn writer_thread_finishes_when_tap_closes() {
+        let path = scratch("live").join("live.avi");
+        let rec = StreamRecorder::create(&path, RecordFormat::Avi).unwrap();
+        let (tx, rx) = mpsc::channel();
+        let tap: RecorderTap = Arc::new(Mutex::new(Some(tx)));
+        let handle = spawn_writer(rec, rx);
+        for p in frame_packets(0, 272, 5) {
+            tap_packet(&tap, || RecorderPacket::Video(p.clone()));
+        }
+        tap.lock().unwrap().take();
+        let summary = handle.join().unwrap().unwrap();
+        assert_eq!(summary.frames, 1);
+    }
+
+    #[test]
+    fn size_limit_stops_the_writer_with_a_playable_file() {
+        let path = scratch("limit").join("limit.avi");
+        let mut rec = StreamRecorder::create(&path, RecordFormat::Avi).unwrap();
+        let frame_chunk = (VIC_WIDTH * 272 * 3) as u64 + 8;
+        if let Sink::Avi(w) = &mut rec.sink {
+            w.max_bytes = w.pos + frame_chunk * 2 + 100;
+        }
+        let (tx, rx) = mpsc::channel();
+        let handle = spawn_writer(rec, rx);
+        for n in 0..5u16 {
+            for p in frame_packets(n, 272, 1) {
+                let _ = tx.send(RecorderPacket::Video(p));
+            }
+        }
+        let err = handle.join().unwrap().unwrap_err();
+        assert!(err.contains("4 GiB limit after 2 frames"), "{}", err);
+        assert!(err.contains("2 frames, "), "{}", err);
+
+        let avi = std::fs::read(&path).unwrap();
+        assert_eq!(&avi[0..4], b"RIFF");
+        assert_eq!(u32_at(&avi, 4) as usize, avi.len() - 8);
+        assert_eq!(u32_at(&avi, 32 + 16), 2);
+    }
+}
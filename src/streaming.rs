@@ -13,6 +13,7 @@ use iced::{
 use crate::net_utils::get_local_ip;
 use crate::settings::StreamControlMethod;
 use crate::stream_control::{send_stop_command, send_stream_command};
+use crate::stream_recorder::{RecordFormat, RecorderPacket, RecorderTap, RecordingSummary};
 use crate::video_scaling::C64_PALETTE;
 
 use crate::remote_device::RemoteDevice;
@@ -38,15 +39,14 @@ pub const VIC_HEIGHT: u32 = 272;
 
 // Audio constants
 const AUDIO_PORT_OFFSET: u16 = 1; // Audio port = video port + 1
-const AUDIO_SAMPLE_RATE: u32 = 48000; // Output sample rate (what cpal uses)
-                                      // const AUDIO_SAMPLES_PER_PACKET: usize = 192 * 4; // 768 samples (384 stereo pairs)
-const AUDIO_HEADER_SIZE: usize = 2; // Just sequence number
+pub(crate) const AUDIO_SAMPLE_RATE: u32 = 48000; // Output sample rate (what cpal uses)
+                                                 // const AUDIO_SAMPLES_PER_PACKET: usize = 192 * 4; // 768 samples (384 stereo pairs)
+pub(crate) const AUDIO_HEADER_SIZE: usize = 2; // Just sequence number
 const AUDIO_BUFFER_SIZE: usize = AUDIO_SAMPLE_RATE as usize; // ~1 second buffer
 
 // Derived from the C64's clock frequencies
-const AUDIO_SAMPLE_RATE_PAL: f64 = 47982.8869047619;
-#[allow(dead_code)]
-const AUDIO_SAMPLE_RATE_NTSC: f64 = 47940.3408482143;
+pub(crate) const AUDIO_SAMPLE_RATE_PAL: f64 = 47982.8869047619;
+pub(crate) const AUDIO_SAMPLE_RATE_NTSC: f64 = 47940.3408482143;
 
 // Jitter buffer settings
 const JITTER_MIN_FRAMES: usize = 4800; // 100ms
@@ -67,7 +67,7 @@ const JITTER_BUFFER_MAX_SAMPLES: usize = AUDIO_SAMPLE_RATE as usize * 2; // 1s s
 //     uint16_t encoding;      // 10-11
 //     char payload[768];      // 12+
 // }
-const HEADER_SIZE: usize = 12;
+pub(crate) const HEADER_SIZE: usize = 12;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum StreamMode {
@@ -132,11 +132,15 @@ pub enum StreamingMessage {
     ToggleVirtualKeyboard,
     VkSend(u8),     // inject one PETSCII byte
     VkModifier(u8), // toggle SHIFT / C= / CTRL (see virtual_keyboard::MOD_*)
+    // Recording
+    RecordFormatChanged(RecordFormat),
+    ToggleRecording,
+    RecordingFinished(Result<String, String>),
 }
 
 /// Simple linear resampler for converting Ultimate64's ~47983 Hz to 48000 Hz
 /// This prevents audio drift that would otherwise cause buffer underrun/overflow
-struct AudioResampler {
+pub(crate) struct AudioResampler {
     step: f64, // input_rate / output_rate  (NOT the other way)
     pos: f64,
     last_left: f32,
@@ -144,7 +148,7 @@ struct AudioResampler {
 }
 
 impl AudioResampler {
-    fn new(input_rate: f64, output_rate: f64) -> Self {
+    pub(crate) fn new(input_rate: f64, output_rate: f64) -> Self {
         Self {
             step: input_rate / output_rate,
             pos: 0.0,
@@ -153,7 +157,7 @@ impl AudioResampler {
         }
     }
 
-    fn process_stereo(&mut self, input: &[f32], output: &mut VecDeque<f32>) {
+    pub(crate) fn process_stereo(&mut self, input: &[f32], output: &mut VecDeque<f32>) {
         for chunk in input.chunks_exact(2) {
             let left = chunk[0];
             let right = chunk[1];
@@ -475,6 +479,11 @@ pub struct VideoStreaming {
     // API password for REST API fallback
     pub api_password: Option<String>,
     pub stream_control_method: StreamControlMethod, // Stream control method for communicating with Ultimate64
+    // Recording: the stream threads copy packets into `recorder_tap` while a
+    // writer thread owned by `recording` turns them into a file.
+    pub record_format: RecordFormat,
+    recorder_tap: RecorderTap,
+    recording: Option<thread::JoinHandle<Result<RecordingSummary, String>>>,
 }
 
 impl Default for VideoStreaming {
@@ -521,7 +530,50 @@ impl VideoStreaming {
             ultimate_host: None,
             api_password: None,
             stream_control_method: StreamControlMethod::default(),
+            record_format: RecordFormat::default(),
+            recorder_tap: Arc::new(Mutex::new(None)),
+            recording: None,
+        }
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recording.is_some()
+    }
+
+    /// Open the output file and start forwarding stream packets to it.
+    fn start_recording(&mut self) -> Result<(), String> {
+        let path = crate::stream_recorder::default_output_path(self.record_format)?;
+        let recorder = crate::stream_recorder::StreamRecorder::create(&path, self.record_format)?;
+        let (tx, rx) = std::sync::mpsc::channel();
+        self.recording = Some(crate::stream_recorder::spawn_writer(recorder, rx));
+        if let Ok(mut tap) = self.recorder_tap.lock() {
+            *tap = Some(tx);
         }
+        log::info!("Recording {} to {}", self.record_format, path.display());
+        Ok(())
+    }
+
+    /// Close the tap and wait (off the UI thread) for the writer to finish.
+    fn stop_recording(&mut self) -> Task<StreamingMessage> {
+        if let Ok(mut tap) = self.recorder_tap.lock() {
+            tap.take();
+        }
+        let Some(handle) = self.recording.take() else {
+            return Task::none();
+        };
+        Task::perform(
+            async move {
+                tokio::task::spawn_blocking(move || {
+                    handle
+                        .join()
+                        .unwrap_or_else(|_| Err("Recorder thread panicked".to_string()))
+                })
+                .await
+                .unwrap_or_else(|e| Err(e.to_string()))
+                .map(|summary| summary.describe())
+            },
+            StreamingMessage::RecordingFinished,
+        )
     }
 
     /// Set the Ultimate64 host for stream control
@@ -563,10 +615,38 @@ impl VideoStreaming {
                 Task::none()
             }
             StreamingMessage::StopStream => {
+                let finish = self.stop_recording();
                 self.stop_stream();
+                finish
+            }
+            StreamingMessage::RecordFormatChanged(format) => {
+                self.record_format = format;
+                Task::none()
+            }
+            StreamingMessage::ToggleRecording => {
+                if self.is_recording() {
+                    self.stop_recording()
+                } else if !self.is_streaming {
+                    Task::done(StreamingMessage::RecordingFinished(Err(
+                        "Start the stream before recording".to_string(),
+                    )))
+                } else {
+                    match self.start_recording() {
+                        Ok(()) => Task::none(),
+                        Err(e) => Task::done(StreamingMessage::RecordingFinished(Err(e))),
+                    }
+                }
+            }
+            StreamingMessage::RecordingFinished(_result) => {
+                // Handled by main app for user message display
                 Task::none()
             }
             StreamingMessage::FrameUpdate => {
+                // The writer thread only ends by itself when a write failed;
+                // collect its error so the UI stops showing "recording".
+                if self.recording.as_ref().is_some_and(|h| h.is_finished()) {
+                    return self.stop_recording();
+                }
                 // Sample the latest native frame once per tick (not per view()).
                 // Skip everything if the frame hasn't changed since last tick.
                 let latest = self.frame_buffer.lock().ok().and_then(|fb| fb.clone());
@@ -992,6 +1072,18 @@ impl VideoStreaming {
             },
             fs,
         );
+        let rec = overlay_button(
+            if self.is_recording() { "⏹" } else { "⏺" },
+            self.is_streaming
+                .then_some(StreamingMessage::ToggleRecording),
+            self.is_recording(),
+            if self.is_recording() {
+                "Stop recording"
+            } else {
+                "Record video + audio to Videos"
+            },
+            fs,
+        );
         let full = overlay_button(
             "⛶",
             Some(StreamingMessage::ToggleFullscreen),
@@ -1032,6 +1124,7 @@ impl VideoStreaming {
         let bar = row![
             live_stop,
             shot,
+            rec,
             full,
             popout,
             Space::new().width(Length::Fill),
@@ -1352,12 +1445,50 @@ impl VideoStreaming {
         ]
         .spacing(6);
 
-        // Right panel — SOURCE + DISPLAY (the console now lives in the bottom bar).
+        let format_button = |label: &'static str, format: RecordFormat| {
+            button(text(label).size(fs.small))
+                .on_press_maybe(
+                    (!self.is_recording()).then_some(StreamingMessage::RecordFormatChanged(format)),
+                )
+                .padding([4, 8])
+                .width(Length::Fill)
+                .style(if self.record_format == format {
+                    button::primary
+                } else {
+                    button::secondary
+                })
+        };
+
+        // RECORD — output format (the ⏺ overlay button starts/stops)
+        let record_section = column![
+            text("RECORD").size(fs.small).color(dim),
+            row![
+                format_button("AVI", RecordFormat::Avi),
+                format_button("PNG + WAV", RecordFormat::FrameSequence),
+            ]
+            .spacing(4),
+            text(if self.is_recording() {
+                "● Recording…"
+            } else {
+                "Saved to Videos/Ultimate64"
+            })
+            .size(fs.tiny)
+            .color(dim),
+        ]
+        .spacing(6);
+
+        // Right panel — SOURCE + DISPLAY + RECORD (the console lives in the bottom bar).
         let right_panel = container(
-            column![mode_section, rule::horizontal(1), scale_section,]
-                .spacing(12)
-                .padding(10)
-                .width(Length::Fixed(210.0)),
+            column![
+                mode_section,
+                rule::horizontal(1),
+                scale_section,
+                rule::horizontal(1),
+                record_section,
+            ]
+            .spacing(12)
+            .padding(10)
+            .width(Length::Fixed(210.0)),
         )
         .height(Length::Fill);
 
@@ -1447,6 +1578,7 @@ impl VideoStreaming {
         let frame_buffer = self.frame_buffer.clone();
         let stop_signal = self.stop_signal.clone();
         let packets_counter = self.packets_received.clone();
+        let recorder_tap = self.recorder_tap.clone();
 
         log::info!("Starting video stream... mode={:?}, port={}", mode, port);
         self.stop_signal.store(false, Ordering::Relaxed);
@@ -1593,6 +1725,9 @@ impl VideoStreaming {
                         if let Ok(mut p) = packets_counter.lock() {
                             *p += 1;
                         }
+                        crate::stream_recorder::tap_packet(&recorder_tap, || {
+                            RecorderPacket::Video(recv_buf[..size].to_vec())
+                        });
 
                         let line_raw = u16::from_le_bytes([recv_buf[4], recv_buf[5]]);
                         let pixels_in_line =
@@ -1700,6 +1835,7 @@ impl VideoStreaming {
         let stop_signal = self.stop_signal.clone();
         let stop_signal_net = self.stop_signal.clone();
         let audio_packets_counter = self.audio_packets_received.clone();
+        let recorder_tap = self.recorder_tap.clone();
 
         // Start audio output thread using cpal
         let audio_handle = thread::spawn(move || {
@@ -2100,6 +2236,9 @@ impl VideoStreaming {
                         if let Ok(mut p) = audio_packets_counter.lock() {
                             *p += 1;
                         }
+                        crate::stream_recorder::tap_packet(&recorder_tap, || {
+                            RecorderPacket::Audio(recv_buf[..size].to_vec())
+                        });
 
                         // Parse sequence number for gap detection
                         let packet_seq = u16::from_le_bytes([recv_buf[0], recv_buf[1]]);
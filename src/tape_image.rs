@@ -0,0 +1,525 @@
+//! Tape container library for T64 archives and raw TAP pulse files
+//!
+//! Provides functionality to:
+//! - List and extract T64 entries as PRG files (load address + data)
+//! - Decode the standard CBM ROM loader headers in a TAP file and list the
+//!   file names found (turbo-loaded programs carry no CBM headers)
+
+use std::fs;
+use std::path::Path;
+
+use crate::disk_image::FileType;
+use crate::petscii;
+
+/// Tape container type
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TapeKind {
+    T64,
+    Tap,
+}
+
+impl std::fmt::Display for TapeKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TapeKind::T64 => write!(f, "T64"),
+            TapeKind::Tap => write!(f, "TAP"),
+        }
+    }
+}
+
+/// A file found in a tape container
+#[derive(Debug, Clone)]
+pub struct TapeEntry {
+    pub name: String,
+    /// Raw PETSCII bytes of the filename (16 bytes, space- or $A0-padded)
+    pub raw_name: Vec<u8>,
+    pub file_type: FileType,
+    pub start_address: u16,
+    /// One past the last loaded byte, as stored in the header
+    pub end_address: u16,
+    /// T64 only: byte offset of the file data and its corrected length
+    data: Option<(usize, usize)>,
+}
+
+impl TapeEntry {
+    /// Payload size in bytes (without the load address)
+    pub fn len(&self) -> usize {
+        match self.data {
+            Some((_, len)) => len,
+            None => self.end_address.saturating_sub(self.start_address) as usize,
+        }
+    }
+
+    /// Size in 254-byte disk blocks, as a 1541 directory would show it
+    pub fn size_blocks(&self) -> u16 {
+        (self.len() + 2).div_ceil(254) as u16
+    }
+
+    /// Format as a C64-style directory line
+    pub fn format_line(&self) -> String {
+        format!(
+            "{:>4}  \"{:<16}\" {} ${:04X}-${:04X}",
+            self.size_blocks(),
+            self.name,
+            self.file_type,
+            self.start_address,
+            self.end_address
+        )
+    }
+}
+
+/// Information about a tape container
+#[derive(Debug, Clone)]
+pub struct TapeInfo {
+    pub kind: TapeKind,
+    pub name: String,
+    pub entries: Vec<TapeEntry>,
+    /// TAP only: which loader the headers were recognised for, if any
+    pub loader: Option<&'static str>,
+}
+
+impl TapeInfo {
+    /// Format the header line like a C64 directory listing
+    pub fn format_header(&self) -> String {
+        format!("0 \"{}\" {}", self.name, self.kind)
+    }
+
+    /// Footer: the entry count, or the loader verdict for TAP files
+    pub fn format_footer(&self) -> String {
+        match (self.kind, self.loader) {
+            (TapeKind::Tap, None) => "NO CBM ROM LOADER HEADERS.".to_string(),
+            (TapeKind::Tap, Some(loader)) => format!("{} FILES, {}.", self.entries.len(), loader),
+            (TapeKind::T64, _) => format!("{} FILES.", self.entries.len()),
+        }
+    }
+
+    /// Get all lines formatted like a C64 directory listing
+    #[allow(dead_code)]
+    pub fn format_listing(&self) -> Vec<String> {
+        let mut lines = vec![self.format_header()];
+        lines.extend(self.entries.iter().map(TapeEntry::format_line));
+        lines.push(self.format_footer());
+        lines
+    }
+}
+
+/// Read tape information from a file path (T64 or TAP by content)
+pub fn read_tape_info(path: &Path) -> Result<TapeInfo, String> {
+    let data = fs::read(path).map_err(|e| format!("Failed to read file: {}", e))?;
+    let mut info = read_tape_info_from_bytes(&data)?;
+    if info.kind == TapeKind::Tap {
+        // TAP files carry no name of their own.
+        info.name = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_uppercase())
+            .unwrap_or_default();
+    }
+    Ok(info)
+}
+
+/// Read tape information from raw bytes
+pub fn read_tape_info_from_bytes(data: &[u8]) -> Result<TapeInfo, String> {
+    if data.starts_with(TAP_SIGNATURE) {
+        read_tap(data)
+    } else if data.starts_with(b"C64") {
+        read_t64(data)
+    } else {
+        Err("Not a T64 or TAP file".to_string())
+    }
+}
+
+// ─── T64 ──────────────────────────────────────────────────────────────────────
+
+const T64_HEADER_LEN: usize = 0x40;
+const T64_ENTRY_LEN: usize = 0x20;
+
+fn read_t64(data: &[u8]) -> Result<TapeInfo, String> {
+    if data.len() < T64_HEADER_LEN {
+        return Err("T64 header truncated".to_string());
+    }
+    let word = |at: usize| u16::from_le_bytes([data[at], data[at + 1]]);
+    let max_entries = word(0x22) as usize;
+    let used_entries = word(0x24) as usize;
+    let name = petscii::to_string(&data[0x28..0x40]).trim().to_string();
+
+    // Some writers leave max_entries at 0; fall back to what fits.
+    let slots = if max_entries == 0 {
+        used_entries.max(1)
+    } else {
+        max_entries
+    };
+    let mut raw = Vec::new();
+    for slot in 0..slots {
+        let at = T64_HEADER_LEN + slot * T64_ENTRY_LEN;
+        let Some(e) = data.get(at..at + T64_ENTRY_LEN) else {
+            break;
+        };
+        // Entry type 0 = free slot; 1 = normal file; 3 = memory snapshot
+        // (which some tools also use for plain PRGs).
+        if e[0] == 0 {
+            continue;
+        }
+        let offset = u32::from_le_bytes([e[8], e[9], e[10], e[11]]) as usize;
+        if offset >= data.len() {
+            continue;
+        }
+        let raw_name = e[0x10..0x20].to_vec();
+        let name_len = raw_name
+            .iter()
+            .rposition(|&b| b != 0x20 && b != 0xA0 && b != 0)
+            .map_or(0, |p| p + 1);
+        raw.push(TapeEntry {
+            name: petscii::to_string(&raw_name[..name_len]),
+            raw_name,
+            // $8x = closed CBM file type; plain 1 (or 0) means PRG.
+            file_type: match e[1] {
+                0x80..=0x87 => FileType::from_byte(e[1]),
+                _ => FileType::Prg,
+            },
+            start_address: u16::from_le_bytes([e[2], e[3]]),
+            end_address: u16::from_le_bytes([e[4], e[5]]),
+            data: Some((offset, 0)),
+        });
+    }
+
+    // Many T64 writers store a bogus end address (famously $C3C6), so the
+    // real length is also bounded by where the next entry's data starts.
+    let mut offsets: Vec<usize> = raw.iter().filter_map(|e| e.data.map(|d| d.0)).collect();
+    offsets.sort_unstable();
+    for entry in &mut raw {
+        let Some((offset, _)) = entry.data else {
+            continue;
+        };
+        let next = offsets
+            .iter()
+            .copied()
+            .find(|&o| o > offset)
+            .unwrap_or(data.len());
+        let by_header = entry.end_address.wrapping_sub(entry.start_address) as usize;
+        let available = next - offset;
+        let len = if by_header == 0 || by_header > available {
+            available
+        } else {
+            by_header
+        };
+        entry.data = Some((offset, len));
+        entry.end_address = entry.start_address.wrapping_add(len as u16);
+    }
+
+    Ok(TapeInfo {
+        kind: TapeKind::T64,
+        name,
+        entries: raw,
+        loader: None,
+    })
+}
+
+/// Extract T64 entry `index` as `(name, prg_bytes)`, the PRG starting with
+/// its 2-byte load address — ready for `run_prg` or writing to disk.
+pub fn extract_t64_prg(data: &[u8], index: usize) -> Result<(String, Vec<u8>), String> {
+    let info = read_t64(data)?;
+    let entry = info
+        .entries
+        .get(index)
+        .ok_or_else(|| format!("No T64 entry #{}", index))?;
+    let (offset, len) = entry.data.ok_or("Entry has no data")?;
+    let mut prg = entry.start_address.to_le_bytes().to_vec();
+    prg.extend_from_slice(&data[offset..offset + len]);
+    Ok((entry.name.clone(), prg))
+}
+
+/// The entry `LOAD"*"` would pick: the first PRG in the archive.
+pub fn first_t64_prg(data: &[u8]) -> Result<(String, Vec<u8>), String> {
+    let info = read_t64(data)?;
+    let index = info
+        .entries
+        .iter()
+        .position(|e| e.file_type == FileType::Prg)
+        .ok_or("T64 contains no PRG entries")?;
+    extract_t64_prg(data, index)
+}
+
+// ─── TAP ──────────────────────────────────────────────────────────────────────
+
+const TAP_SIGNATURE: &[u8] = b"C64-TAPE-RAW";
+const TAP_HEADER_LEN: usize = 0x14;
+
+/// CBM ROM loader pulse classes. TAP bytes are pulse lengths in units of 8
+/// cycles; the nominal pulses are ≈$30 (short), ≈$42 (medium) and ≈$56
+/// (long). The boundaries sit halfway between them.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Pulse {
+    Short,
+    Medium,
+    Long,
+    /// Pauses and turbo-loader pulses
+    Other,
+}
+
+fn classify(cycles_div8: u32) -> Pulse {
+    match cycles_div8 {
+        0x24..=0x38 => Pulse::Short,
+        0x39..=0x4B => Pulse::Medium,
+        0x4C..=0x64 => Pulse::Long,
+        _ => Pulse::Other,
+    }
+}
+
+/// Pulse lengths (in cycles / 8) from the TAP body. Version 0 encodes a
+/// pause as a lone zero; version 1 follows the zero with a 24-bit cycle count.
+fn tap_pulses(data: &[u8]) -> Vec<u32> {
+    let version = data.get(0x0C).copied().unwrap_or(0);
+    let mut out = Vec::with_capacity(data.len());
+    let mut i = TAP_HEADER_LEN;
+    while i < data.len() {
+        let b = data[i];
+        i += 1;
+        if b != 0 {
+            out.push(b as u32);
+        } else if version == 0 {
+            out.push(0x100);
+        } else if let Some(n) = data.get(i..i + 3) {
+            out.push(u32::from_le_bytes([n[0], n[1], n[2], 0]) / 8);
+            i += 3;
+        } else {
+            break;
+        }
+    }
+    out
+}
+
+/// Group ROM-loader bytes into blocks. A byte is a (long, medium) marker
+/// followed by nine bit pairs — (short, medium) = 0, (medium, short) = 1 —
+/// eight data bits LSB first plus an odd-parity bit. A block ends at the
+/// (long, short) end-of-data marker or wherever the pattern breaks.
+fn decode_rom_blocks(pulses: &[Pulse]) -> Vec<Vec<u8>> {
+    let mut blocks = Vec::new();
+    let mut current = Vec::new();
+    let mut i = 0;
+    while i + 1 < pulses.len() {
+        if pulses[i] != Pulse::Long || pulses[i + 1] != Pulse::Medium {
+            if !current.is_empty() {
+                blocks.push(std::mem::take(&mut current));
+            }
+            i += 1;
+            continue;
+        }
+        let bits: Option<Vec<u8>> = pulses.get(i + 2..i + 20).and_then(|p| {
+            p.chunks(2)
+                .map(|pair| match pair {
+                    [Pulse::Short, Pulse::Medium] => Some(0),
+                    [Pulse::Medium, Pulse::Short] => Some(1),
+                    _ => None,
+                })
+                .collect()
+        });
+        match bits {
+            Some(bits) if bits.iter().sum::<u8>() % 2 == 1 => {
+                let byte = bits[..8]
+                    .iter()
+                    .enumerate()
+                    .fold(0u8, |acc, (n, &bit)| acc | bit << n);
+                current.push(byte);
+                i += 20;
+            }
+            _ => {
+                if !current.is_empty() {
+                    blocks.push(std::mem::take(&mut current));
+                }
+                i += 2;
+            }
+        }
+    }
+    if !current.is_empty() {
+        blocks.push(current);
+    }
+    blocks
+}
+
+/// Countdown that opens the first copy of every ROM-loader block.
+const FIRST_COPY_SYNC: [u8; 9] = [0x89, 0x88, 0x87, 0x86, 0x85, 0x84, 0x83, 0x82, 0x81];
+
+fn read_tap(data: &[u8]) -> Result<TapeInfo, String> {
+    if data.len() < TAP_HEADER_LEN {
+        return Err("TAP header truncated".to_string());
+    }
+    let pulses: Vec<Pulse> = tap_pulses(data).into_iter().map(classify).collect();
+    let mut entries = Vec::new();
+    for block in decode_rom_blocks(&pulses) {
+        // Sync countdown + the 192-byte header block. Only the first copy
+        // is listed; the repeat (countdown $09..$01) is identical.
+        let Some(header) = block.strip_prefix(&FIRST_COPY_SYNC[..]) else {
+            continue;
+        };
+        if header.len() < 21 {
+            continue;
+        }
+        let file_type = match header[0] {
+            1 | 3 => FileType::Prg,
+            4 => FileType::Seq,
+            _ => continue,
+        };
+        let raw_name = header[5..21].to_vec();
+        let name_len = raw_name
+            .iter()
+            .rposition(|&b| b != 0x20 && b != 0xA0 && b != 0)
+            .map_or(0, |p| p + 1);
+        entries.push(TapeEntry {
+            name: petscii::to_string(&raw_name[..name_len]),
+            raw_name,
+            file_type,
+            start_address: u16::from_le_bytes([header[1], header[2]]),
+            end_address: u16::from_le_bytes([header[3], header[4]]),
+            data: None,
+        });
+    }
+    let loader = (!entries.is_empty()).then_some("CBM ROM LOADER");
+    Ok(TapeInfo {
+        kind: TapeKind::Tap,
+        name: String::new(),
+        entries,
+        loader,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn t64(entries: &[(&str, u16, &[u8])], bogus_end: bool) -> Vec<u8> {
+        let mut out = vec![0u8; T64_HEADER_LEN + entries.len() * T64_ENTRY_LEN];
+        out[..19].copy_from_slice(b"C64 tape image file");
+        out[0x20] = 0x01;
+        out[0x21] = 0x01;
+        out[0x22] = entries.len() as u8;
+        out[0x24] = entries.len() as u8;
+        out[0x28..0x40].copy_from_slice(b"TEST TAPE               ");
+        for (i, (name, load, body)) in entries.iter().enumerate() {
+            let offset = out.len();
+            let e = T64_HEADER_LEN + i * T64_ENTRY_LEN;
+            out[e] = 1;
+            out[e + 1] = 0x82;
+            out[e + 2..e + 4].copy_from_slice(&load.to_le_bytes());
+            let end = if bogus_end {
+                0xC3C6
+            } else {
+                load + body.len() as u16
+            };
+            out[e + 4..e + 6].copy_from_slice(&end.to_le_bytes());
+            out[e + 8..e + 12].copy_from_slice(&(offset as u32).to_le_bytes());
+            let mut padded = [0x20u8; 16];
+            padded[..name.len()].copy_from_slice(name.as_bytes());
+            out[e + 0x10..e + 0x20].copy_from_slice(&padded);
+            out.extend_from_slice(body);
+        }
+        out
+    }
+
+    #[test]
+    fn t64_lists_and_extracts_entries() {
+        let data = t64(
+            &[("GAME", 0x0801, &[1, 2, 3]), ("LEVEL", 0xC000, &[9; 300])],
+            false,
+        );
+        let info = read_tape_info_from_bytes(&data).unwrap();
+        assert_eq!(info.kind, TapeKind::T64);
+        assert_eq!(info.name, "TEST TAPE");
+        assert_eq!(info.entries.len(), 2);
+        assert_eq!(info.entries[0].name, "GAME");
+        assert_eq!(info.entries[1].end_address, 0xC000 + 300);
+        assert_eq!(info.entries[1].size_blocks(), 2);
+
+        let (name, prg) = extract_t64_prg(&data, 1).unwrap();
+        assert_eq!(name, "LEVEL");
+        assert_eq!(&prg[..2], &[0x00, 0xC0]);
+        assert_eq!(prg.len(), 302);
+        assert_eq!(first_t64_prg(&data).unwrap().1, vec![0x01, 0x08, 1, 2, 3]);
+        assert!(extract_t64_prg(&data, 2).is_err());
+    }
+
+    #[test]
+    fn t64_bogus_end_address_is_bounded_by_the_data() {
+        let data = t64(&[("A", 0x0801, &[1, 2]), ("B", 0x1000, &[3, 4, 5])], true);
+        let info = read_tape_info_from_bytes(&data).unwrap();
+        assert_eq!(info.entries[0].len(), 2);
+        assert_eq!(info.entries[1].len(), 3);
+        assert_eq!(extract_t64_prg(&data, 0).unwrap().1, vec![0x01, 0x08, 1, 2]);
+    }
+
+    /// Encode bytes the way the KERNAL tape routines write them.
+    fn rom_block(bytes: &[u8]) -> Vec<u8> {
+        const S: u8 = 0x30;
+        const M: u8 = 0x42;
+        const L: u8 = 0x56;
+        let mut out = vec![S; 200]; // leader
+        for &b in bytes {
+            out.extend_from_slice(&[L, M]);
+            let mut ones = 0;
+            for n in 0..8 {
+                let bit = b >> n & 1;
+                ones += bit;
+                out.extend_from_slice(if bit == 1 { &[M, S] } else { &[S, M] });
+            }
+            out.extend_from_slice(if ones % 2 == 0 { &[M, S] } else { &[S, M] });
+        }
+        out.extend_from_slice(&[L, S]);
+        out
+    }
+
+    fn header_block(sync: &[u8], kind: u8, name: &str, start: u16, end: u16) -> Vec<u8> {
+        let mut block = sync.to_vec();
+        block.push(kind);
+        block.extend_from_slice(&start.to_le_bytes());
+        block.extend_from_slice(&end.to_le_bytes());
+        let mut padded = [0x20u8; 187];
+        padded[..name.len()].copy_from_slice(name.as_bytes());
+        block.extend_from_slice(&padded);
+        rom_block(&block)
+    }
+
+    fn tap(body: &[u8]) -> Vec<u8> {
+        let mut out = TAP_SIGNATURE.to_vec();
+        out.extend_from_slice(&[1, 0, 0, 0]);
+        out.extend_from_slice(&(body.len() as u32).to_le_bytes());
+        out.extend_from_slice(body);
+        out
+    }
+
+    #[test]
+    fn tap_decodes_rom_loader_headers() {
+        let repeat: Vec<u8> = FIRST_COPY_SYNC.iter().map(|b| b & 0x7F).collect();
+        let mut body = header_block(&FIRST_COPY_SYNC, 3, "HELLO", 0x0801, 0x0900);
+        body.extend(header_block(&repeat, 3, "HELLO", 0x0801, 0x0900));
+        body.extend_from_slice(&[0, 0x40, 0x42, 0x0F]); // v1 pause
+        body.extend(header_block(&FIRST_COPY_SYNC, 4, "DATA", 0x033C, 0x03FC));
+
+        let info = read_tape_info_from_bytes(&tap(&body)).unwrap();
+        assert_eq!(info.kind, TapeKind::Tap);
+        assert_eq!(info.loader, Some("CBM ROM LOADER"));
+        let names: Vec<_> = info
+            .entries
+            .iter()
+            .map(|e| (e.name.as_str(), e.file_type, e.start_address))
+            .collect();
+        assert_eq!(
+            names,
+            [
+                ("HELLO", FileType::Prg, 0x0801),
+                ("DATA", FileType::Seq, 0x033C)
+            ]
+        );
+        assert_eq!(info.entries[0].len(), 0xFF);
+    }
+
+    #[test]
+    fn tap_without_rom_headers_reports_no_loader() {
+        let info = read_tape_info_from_bytes(&tap(&[0x20; 5000])).unwrap();
+        assert!(info.entries.is_empty());
+        assert_eq!(info.format_footer(), "NO CBM ROM LOADER HEADERS.");
+    }
+
+    #[test]
+    fn rejects_unknown_data() {
+        assert!(read_tape_info_from_bytes(b"hello").is_err());
+    }
+}
@@ -5,9 +5,39 @@ use std::fs;
 pub struct DiskTemplate {
     pub name: String,
     pub description: String,
+    /// Launch script (see [`crate::launch_script`])
+    #[serde(default)]
+    pub script: String,
+    /// Pre-script command list (`RESET`, `TYPE ...`), still read from older
+    /// custom template files
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub commands: Vec<String>,
 }
 
+impl DiskTemplate {
+    fn new(name: &str, description: &str, script: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            description: description.to_string(),
+            script: script.to_string(),
+            commands: Vec::new(),
+        }
+    }
+
+    /// The script to run, converting a legacy command list if that's all
+    /// the template has.
+    pub fn script_source(&self) -> String {
+        if !self.script.trim().is_empty() || self.commands.is_empty() {
+            return self.script.clone();
+        }
+        self.commands
+            .iter()
+            .map(|c| c.replace('\n', "\\n"))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
 impl std::fmt::Display for DiskTemplate {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.name)
@@ -32,43 +62,23 @@ impl TemplateManager {
 
     fn load_default_templates(&mut self) {
         self.templates = vec![
-            DiskTemplate {
-                name: "Load & Run First".to_string(),
-                description: "Reset, load first program, and run".to_string(),
-                commands: vec![
-                    "RESET".to_string(),
-                    "TYPE load\"*\",8,1\n".to_string(),
-                    "TYPE run\n".to_string(),
-                ],
-            },
-            DiskTemplate {
-                name: "List Directory".to_string(),
-                description: "Load and list disk directory".to_string(),
-                commands: vec![
-                    "RESET".to_string(),
-                    "TYPE load\"$\",8\n".to_string(),
-                    "TYPE list\n".to_string(),
-                ],
-            },
-            DiskTemplate {
-                name: "JiffyDOS Fast Load".to_string(),
-                description: "Use JiffyDOS fast load".to_string(),
-                commands: vec![
-                    "RESET".to_string(),
-                    "TYPE @8\n".to_string(),
-                    "TYPE //*\n".to_string(),
-                ],
-            },
-            DiskTemplate {
-                name: "Reset Only".to_string(),
-                description: "Just reset the machine".to_string(),
-                commands: vec!["RESET".to_string()],
-            },
-            DiskTemplate {
-                name: "Run".to_string(),
-                description: "Just run an already loaded program".to_string(),
-                commands: vec!["TYPE run\n".to_string()],
-            },
+            DiskTemplate::new(
+                "Load & Run First",
+                "Reset, load first program, and run",
+                "RESET\nLOAD 8\nRUN\n",
+            ),
+            DiskTemplate::new(
+                "List Directory",
+                "Load and list disk directory",
+                "RESET\nTYPE load\"$\",8\\n\nTYPE list\\n\n",
+            ),
+            DiskTemplate::new(
+                "JiffyDOS Fast Load",
+                "Use JiffyDOS fast load",
+                "RESET\nTYPE @8\\n\nTYPE //*\\n\n",
+            ),
+            DiskTemplate::new("Reset Only", "Just reset the machine", "RESET\n"),
+            DiskTemplate::new("Run", "Just run an already loaded program", "RUN\n"),
         ];
     }
 
@@ -90,6 +100,13 @@ impl TemplateManager {
                 let contents = fs::read_to_string(&path)?;
                 if let Ok(template) = serde_json::from_str::<DiskTemplate>(&contents) {
                     log::info!("Loaded custom template: {}", template.name);
+                    if let Err(errors) = crate::launch_script::parse(&template.script_source()) {
+                        log::warn!(
+                            "Template '{}' has script errors:\n{}",
+                            template.name,
+                            crate::launch_script::describe(&errors)
+                        );
+                    }
                     self.templates.push(template);
                 }
             }
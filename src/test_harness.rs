@@ -0,0 +1,616 @@
+//! Automated C64 test runs for unattended regression suites.
+//!
+//! A suite is a JSON file of test cases, each a list of steps run in order
+//! against the device:
+//!
+//! ```json
+//! {
+//!   "name": "Release 1.2",
+//!   "tests": [
+//!     {
+//!       "name": "title screen",
+//!       "steps": [
+//!         { "run": "build/game.d64" },
+//!         { "wait_text": { "text": "PRESS FIRE", "timeout_secs": 60 } },
+//!         { "assert_memory": { "address": "$0810", "equals": [3] } },
+//!         { "assert_screen": { "golden": "golden/title.png", "tolerance": 20 } }
+//!       ]
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! Waits poll screen or memory the way [`crate::run_ops`] waits for
+//! `READY.`. Screens are compared pixel by pixel against a golden PNG from
+//! the same renderer as [`crate::screenshot_api`]; a missing golden is
+//! recorded from the current screen (and the test fails so someone reviews
+//! it). Results come out as JUnit XML.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use serde::Deserialize;
+use ultimate64::drives::MountMode;
+
+use crate::remote_device::RemoteDevice;
+use crate::screenshot_api::{FRAME_HEIGHT, FRAME_WIDTH};
+
+/// Interval between screen / memory polls
+pub const POLL: Duration = Duration::from_millis(200);
+
+fn default_timeout() -> u64 {
+    10
+}
+
+// ─── Suite file ──────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Suite {
+    pub name: String,
+    pub tests: Vec<TestCase>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TestCase {
+    pub name: String,
+    pub steps: Vec<Step>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Step {
+    /// Run a local PRG, CRT or disk image (disks autoload from drive 8)
+    Run(String),
+    /// Type into the keyboard buffer; `\n` presses RETURN
+    Type(String),
+    Reset,
+    SleepMs(u64),
+    /// Wait until a line of the text screen contains `text`
+    WaitText {
+        text: String,
+        #[serde(default = "default_timeout")]
+        timeout_secs: u64,
+    },
+    /// Wait until memory at `address` holds `equals`
+    WaitMemory {
+        address: String,
+        equals: Vec<u8>,
+        #[serde(default = "default_timeout")]
+        timeout_secs: u64,
+    },
+    /// Memory at `address` must hold `equals` right now
+    AssertMemory {
+        address: String,
+        equals: Vec<u8>,
+    },
+    /// The screen must match `golden` with at most `tolerance` pixels off
+    AssertScreen {
+        golden: String,
+        #[serde(default)]
+        tolerance: usize,
+    },
+}
+
+impl Suite {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text =
+            std::fs::read_to_string(path).map_err(|e| format!("Read {}: {}", path.display(), e))?;
+        serde_json::from_str(&text).map_err(|e| format!("Invalid suite {}: {}", path.display(), e))
+    }
+}
+
+impl std::fmt::Display for Step {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Step::Run(file) => write!(f, "run {}", file),
+            Step::Type(text) => write!(f, "type {:?}", text),
+            Step::Reset => write!(f, "reset"),
+            Step::SleepMs(ms) => write!(f, "sleep {} ms", ms),
+            Step::WaitText { text, .. } => write!(f, "wait for text {:?}", text),
+            Step::WaitMemory {
+                address, equals, ..
+            } => write!(f, "wait for {} = {}", address, hex(equals)),
+            Step::AssertMemory { address, equals } => {
+                write!(f, "assert {} = {}", address, hex(equals))
+            }
+            Step::AssertScreen { golden, .. } => write!(f, "compare screen with {}", golden),
+        }
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|b| format!("{:02X}", b))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// ─── Results ─────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome {
+    Passed,
+    /// An assertion or wait didn't hold
+    Failed(String),
+    /// The device or a file couldn't be used
+    Error(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct CaseResult {
+    pub name: String,
+    pub outcome: Outcome,
+    pub duration: Duration,
+}
+
+impl CaseResult {
+    pub fn summary(&self) -> String {
+        let secs = self.duration.as_secs_f64();
+        match &self.outcome {
+            Outcome::Passed => format!("PASS  {} ({:.1}s)", self.name, secs),
+            Outcome::Failed(msg) => format!("FAIL  {} ({:.1}s): {}", self.name, secs, msg),
+            Outcome::Error(msg) => format!("ERROR {} ({:.1}s): {}", self.name, secs, msg),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SuiteResult {
+    pub name: String,
+    pub cases: Vec<CaseResult>,
+    pub duration: Duration,
+}
+
+impl SuiteResult {
+    pub fn failures(&self) -> usize {
+        self.count(|o| matches!(o, Outcome::Failed(_)))
+    }
+
+    pub fn errors(&self) -> usize {
+        self.count(|o| matches!(o, Outcome::Error(_)))
+    }
+
+    fn count(&self, f: impl Fn(&Outcome) -> bool) -> usize {
+        self.cases.iter().filter(|c| f(&c.outcome)).count()
+    }
+
+    /// JUnit XML as CI servers read it
+    pub fn to_junit(&self) -> String {
+        let (tests, failures, errors) = (self.cases.len(), self.failures(), self.errors());
+        let time = self.duration.as_secs_f64();
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml.push_str(&format!(
+            "<testsuites tests=\"{}\" failures=\"{}\" errors=\"{}\" time=\"{:.3}\">\n",
+            tests, failures, errors, time
+        ));
+        xml.push_str(&format!(
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\" time=\"{:.3}\">\n",
+            escape(&self.name),
+            tests,
+            failures,
+            errors,
+            time
+        ));
+        for case in &self.cases {
+            let open = format!(
+                "    <testcase name=\"{}\" classname=\"{}\" time=\"{:.3}\"",
+                escape(&case.name),
+                escape(&self.name),
+                case.duration.as_secs_f64()
+            );
+            match &case.outcome {
+                Outcome::Passed => xml.push_str(&format!("{}/>\n", open)),
+                Outcome::Failed(msg) | Outcome::Error(msg) => {
+                    let tag = if matches!(case.outcome, Outcome::Failed(_)) {
+                        "failure"
+                    } else {
+                        "error"
+                    };
+                    xml.push_str(&format!(
+                        "{}>\n      <{} message=\"{}\"/>\n    </testcase>\n",
+                        open,
+                        tag,
+                        escape(msg)
+                    ));
+                }
+            }
+        }
+        xml.push_str("  </testsuite>\n</testsuites>\n");
+        xml
+    }
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+// ─── Screen text ─────────────────────────────────────────────────────────────
+
+/// Screen RAM address from the VIC bank ($DD00) and $D018.
+pub(crate) fn screen_base(conn: &dyn RemoteDevice) -> Result<u16, String> {
+    let d018 = read(conn, 0xD018, 1)?[0];
+    let dd00 = read(conn, 0xDD00, 1)?[0];
+    let bank = (3 - (dd00 & 0x03) as u16) * 0x4000;
+    Ok(bank + (d018 >> 4) as u16 * 0x400)
+}
+
+/// Screen codes to 25 lines of text, reverse video ignored. Capitals from the
+/// lower/upper case set ($41-$5A) read as letters too.
+pub(crate) fn screen_lines(codes: &[u8]) -> Vec<String> {
+    codes
+        .chunks(40)
+        .take(25)
+        .map(|row| {
+            row.iter()
+                .map(|&c| match c & 0x7F {
+                    0x00 => '@',
+                    c @ 0x01..=0x1A => (b'A' + c - 1) as char,
+                    0x1B => '[',
+                    0x1D => ']',
+                    c @ 0x20..=0x3F => c as char,
+                    c @ 0x41..=0x5A => c as char,
+                    _ => ' ',
+                })
+                .collect()
+        })
+        .collect()
+}
+
+// ─── Runner ──────────────────────────────────────────────────────────────────
+
+/// What a suite runs against
+pub struct Rig<'a> {
+    pub conn: &'a dyn RemoteDevice,
+    /// Grab the screen as RGB, `FRAME_WIDTH` × `FRAME_HEIGHT`
+    pub capture: &'a dyn Fn() -> Result<Vec<u8>, String>,
+    /// Relative paths in the suite resolve against this folder
+    pub base_dir: PathBuf,
+    pub poll: Duration,
+}
+
+fn read(conn: &dyn RemoteDevice, address: u16, length: usize) -> Result<Vec<u8>, String> {
+    conn.read_mem(address, length as u16)
+        .map_err(|e| format!("Read ${:04X} failed: {}", address, e))
+}
+
+fn parse_address(address: &str) -> Result<u16, Outcome> {
+    crate::memory_editor::parse_length_input(address)
+        .and_then(|v| u16::try_from(v).ok())
+        .ok_or_else(|| Outcome::Error(format!("Invalid address '{}'", address)))
+}
+
+/// Poll `check` until it reports success or `timeout` passes. Read errors
+/// count as "not yet" so a busy bus during a load doesn't fail the test.
+fn wait_until(
+    rig: &Rig,
+    timeout: Duration,
+    what: &str,
+    mut check: impl FnMut() -> Result<bool, String>,
+) -> Result<(), Outcome> {
+    let deadline = Instant::now() + timeout;
+    let mut last_error = None;
+    loop {
+        match check() {
+            Ok(true) => return Ok(()),
+            Ok(false) => {}
+            Err(e) => last_error = Some(e),
+        }
+        if Instant::now() >= deadline {
+            let mut msg = format!(
+                "Timed out after {}s waiting for {}",
+                timeout.as_secs(),
+                what
+            );
+            if let Some(e) = last_error {
+                msg.push_str(&format!(" (last error: {})", e));
+            }
+            return Err(Outcome::Failed(msg));
+        }
+        std::thread::sleep(rig.poll);
+    }
+}
+
+fn run_file(rig: &Rig, file: &str) -> Result<(), Outcome> {
+    let path = rig.base_dir.join(file);
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .unwrap_or_default();
+    let device = |r: anyhow::Result<()>| r.map_err(|e| Outcome::Error(e.to_string()));
+    match ext.as_str() {
+        "d64" | "d71" | "d81" | "g64" | "g71" => {
+            device(
+                rig.conn
+                    .mount_disk_image(&path, "a".into(), MountMode::ReadWrite, false),
+            )?;
+            crate::run_ops::autoload_mounted_disk(rig.conn, "8").map_err(Outcome::Error)
+        }
+        "prg" | "crt" => {
+            let data = std::fs::read(&path)
+                .map_err(|e| Outcome::Error(format!("Read {}: {}", path.display(), e)))?;
+            if ext == "prg" {
+                device(rig.conn.run_prg(&data))
+            } else {
+                device(rig.conn.run_crt(&data))
+            }
+        }
+        _ => Err(Outcome::Error(format!(
+            "Don't know how to run .{} files",
+            ext
+        ))),
+    }
+}
+
+/// Compare the current screen with a golden PNG. On a mismatch the capture
+/// is saved next to it as `<name>.actual.png`.
+fn compare_screen(rig: &Rig, golden: &str, tolerance: usize) -> Result<(), Outcome> {
+    let golden_path = rig.base_dir.join(golden);
+    let frame = (rig.capture)().map_err(Outcome::Error)?;
+    let save = |path: &Path| -> Result<(), Outcome> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| Outcome::Error(e.to_string()))?;
+        }
+        image::RgbImage::from_raw(FRAME_WIDTH as u32, FRAME_HEIGHT as u32, frame.clone())
+            .ok_or_else(|| Outcome::Error("Captured frame has the wrong size".to_string()))?
+            .save(path)
+            .map_err(|e| Outcome::Error(format!("Save {}: {}", path.display(), e)))
+    };
+
+    if !golden_path.exists() {
+        save(&golden_path)?;
+        return Err(Outcome::Failed(format!(
+            "No golden image — recorded the current screen to {}; review it and re-run",
+            golden_path.display()
+        )));
+    }
+    let expected = image::open(&golden_path)
+        .map_err(|e| Outcome::Error(format!("Open {}: {}", golden_path.display(), e)))?
+        .to_rgb8();
+    if expected.dimensions() != (FRAME_WIDTH as u32, FRAME_HEIGHT as u32) {
+        return Err(Outcome::Error(format!(
+            "{} is {}×{}, expected {}×{}",
+            golden,
+            expected.width(),
+            expected.height(),
+            FRAME_WIDTH,
+            FRAME_HEIGHT
+        )));
+    }
+    let differing = expected
+        .as_raw()
+        .chunks(3)
+        .zip(frame.chunks(3))
+        .filter(|(a, b)| a != b)
+        .count();
+    if differing <= tolerance {
+        return Ok(());
+    }
+    let actual = golden_path.with_extension("actual.png");
+    save(&actual)?;
+    Err(Outcome::Failed(format!(
+        "{} pixels differ from {} (tolerance {}); capture saved to {}",
+        differing,
+        golden,
+        tolerance,
+        actual.display()
+    )))
+}
+
+fn run_step(rig: &Rig, step: &Step) -> Result<(), Outcome> {
+    let conn = rig.conn;
+    match step {
+        Step::Run(file) => run_file(rig, file),
+        Step::Type(text) => conn
+            .type_text(text)
+            .map_err(|e| Outcome::Error(format!("Type failed: {}", e))),
+        Step::Reset => conn
+            .reset()
+            .map_err(|e| Outcome::Error(format!("Reset failed: {}", e))),
+        Step::SleepMs(ms) => {
+            std::thread::sleep(Duration::from_millis(*ms));
+            Ok(())
+        }
+        Step::WaitText { text, timeout_secs } => {
+            let needle = text.to_uppercase();
+            wait_until(
+                rig,
+                Duration::from_secs(*timeout_secs),
+                &format!("{:?} on screen", text),
+                || {
+                    let codes = read(conn, screen_base(conn)?, 1000)?;
+                    Ok(screen_lines(&codes).iter().any(|l| l.contains(&needle)))
+                },
+            )
+        }
+        Step::WaitMemory {
+            address,
+            equals,
+            timeout_secs,
+        } => {
+            let addr = parse_address(address)?;
+            let mut last = Vec::new();
+            let result = wait_until(
+                rig,
+                Duration::from_secs(*timeout_secs),
+                &format!("{} = {}", address, hex(equals)),
+                || {
+                    last = read(conn, addr, equals.len())?;
+                    Ok(last == *equals)
+                },
+            );
+            result.map_err(|o| match o {
+                Outcome::Failed(msg) if !last.is_empty() => {
+                    Outcome::Failed(format!("{}; last read {}", msg, hex(&last)))
+                }
+                o => o,
+            })
+        }
+        Step::AssertMemory { address, equals } => {
+            let addr = parse_address(address)?;
+            let actual = read(conn, addr, equals.len()).map_err(Outcome::Error)?;
+            if actual == *equals {
+                Ok(())
+            } else {
+                Err(Outcome::Failed(format!(
+                    "{} is {}, expected {}",
+                    address,
+                    hex(&actual),
+                    hex(equals)
+                )))
+            }
+        }
+        Step::AssertScreen { golden, tolerance } => compare_screen(rig, golden, *tolerance),
+    }
+}
+
+fn run_case(rig: &Rig, case: &TestCase) -> CaseResult {
+    let start = Instant::now();
+    let mut outcome = Outcome::Passed;
+    for (i, step) in case.steps.iter().enumerate() {
+        if let Err(o) = run_step(rig, step) {
+            outcome = match o {
+                Outcome::Failed(msg) => {
+                    Outcome::Failed(format!("step {} ({}): {}", i + 1, step, msg))
+                }
+                Outcome::Error(msg) => {
+                    Outcome::Error(format!("step {} ({}): {}", i + 1, step, msg))
+                }
+                Outcome::Passed => Outcome::Passed,
+            };
+            break;
+        }
+    }
+    CaseResult {
+        name: case.name.clone(),
+        outcome,
+        duration: start.elapsed(),
+    }
+}
+
+/// Run every case in order; `progress` sees each result as it finishes.
+/// Blocking.
+pub fn run_suite(suite: &Suite, rig: &Rig, mut progress: impl FnMut(&CaseResult)) -> SuiteResult {
+    let start = Instant::now();
+    let mut cases = Vec::with_capacity(suite.tests.len());
+    for case in &suite.tests {
+        let result = run_case(rig, case);
+        progress(&result);
+        cases.push(result);
+    }
+    SuiteResult {
+        name: suite.name.clone(),
+        cases,
+        duration: start.elapsed(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::remote_device::mock::MockDevice;
+
+    fn rig<'a>(
+        dev: &'a MockDevice,
+        capture: &'a dyn Fn() -> Result<Vec<u8>, String>,
+        base_dir: PathBuf,
+    ) -> Rig<'a> {
+        Rig {
+            conn: dev,
+            capture,
+            base_dir,
+            poll: Duration::from_millis(1),
+        }
+    }
+
+    #[test]
+    fn suite_runs_steps_and_reports_junit() {
+        let suite: Suite = serde_json::from_str(
+            r#"{
+                "name": "Smoke <1>",
+                "tests": [
+                    { "name": "boots", "steps": [
+                        "reset",
+                        { "type": "run\n" },
+                        { "wait_memory": { "address": "$D020", "equals": [170, 170], "timeout_secs": 0 } }
+                    ] },
+                    { "name": "lives", "steps": [
+                        { "assert_memory": { "address": "$0810", "equals": [3] } },
+                        "reset"
+                    ] },
+                    { "name": "title", "steps": [
+                        { "wait_text": { "text": "press fire", "timeout_secs": 0 } }
+                    ] }
+                ]
+            }"#,
+        )
+        .unwrap();
+        let dev = MockDevice::new();
+        let capture = || Err("no screen".to_string());
+        let mut seen = Vec::new();
+        let result = run_suite(&suite, &rig(&dev, &capture, PathBuf::new()), |c| {
+            seen.push(c.summary())
+        });
+
+        assert_eq!(result.cases[0].outcome, Outcome::Passed);
+        assert_eq!(
+            result.cases[1].outcome,
+            Outcome::Failed("step 1 (assert $0810 = 03): $0810 is AA, expected 03".into())
+        );
+        assert!(matches!(&result.cases[2].outcome, Outcome::Failed(m) if m.contains("Timed out")));
+        assert_eq!(seen.len(), 3);
+        // The failing case stops before its reset
+        let resets = dev.calls().iter().filter(|c| *c == "reset").count();
+        assert_eq!(resets, 1);
+
+        let xml = result.to_junit();
+        assert!(xml.contains(
+            "<testsuite name=\"Smoke &lt;1&gt;\" tests=\"3\" failures=\"2\" errors=\"0\""
+        ));
+        assert!(xml.contains("<testcase name=\"boots\" classname=\"Smoke &lt;1&gt;\""));
+        assert!(xml.contains("<failure message=\"step 1 (assert $0810 = 03)"));
+    }
+
+    #[test]
+    fn screens_decode_and_compare_with_golden() {
+        let mut codes = vec![0x20u8; 1000];
+        codes[40..46].copy_from_slice(&[0x12, 0x05, 0x01, 0x04, 0x19, 0x2E]);
+        codes[46] = 0x81; // reversed A
+        assert_eq!(screen_lines(&codes)[1].trim_end(), "READY.A");
+        // $4F is a capital O in the lower/upper case set
+        codes[80..82].copy_from_slice(&[0x4F, 0x0B]);
+        codes[82] = 0xDA; // reversed Z
+        assert_eq!(screen_lines(&codes)[2].trim_end(), "OKZ");
+
+        let dir = std::env::temp_dir().join(format!("u64_harness_{}", std::process::id()));
+        let dev = MockDevice::new();
+        let frame = vec![0x40u8; FRAME_WIDTH * FRAME_HEIGHT * 3];
+        let capture = || Ok(frame.clone());
+        let rig = rig(&dev, &capture, dir.clone());
+
+        // First run records the golden and fails for review; the next passes
+        assert!(matches!(
+            compare_screen(&rig, "g/title.png", 0),
+            Err(Outcome::Failed(_))
+        ));
+        assert_eq!(compare_screen(&rig, "g/title.png", 0), Ok(()));
+
+        let mut changed = frame.clone();
+        changed[..6].fill(0xFF);
+        let capture = || Ok(changed.clone());
+        let rig = Rig {
+            capture: &capture,
+            ..rig
+        };
+        assert_eq!(compare_screen(&rig, "g/title.png", 2), Ok(()));
+        let err = compare_screen(&rig, "g/title.png", 1).unwrap_err();
+        assert!(matches!(err, Outcome::Failed(m) if m.starts_with("2 pixels differ")));
+        assert!(dir.join("g/title.actual.png").exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
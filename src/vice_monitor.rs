@@ -0,0 +1,741 @@
+//! VICE binary monitor server backed by the real device.
+//!
+//! `ultimate64-manager monitor` listens on port 6502 and speaks the binary
+//! monitor protocol VICE serves with `-binarymonitor`, so IDE debuggers and
+//! other tools written against VICE can inspect and patch a running
+//! Ultimate 64. Requests map onto the device like this:
+//!
+//! * memory get / set → `RemoteDevice::read_mem` / `write_mem` (DMA, so the
+//!   program keeps running)
+//! * registers get / set → the CPU is parked in the snapshot stub
+//!   ([`machine_snapshot::Parked`]) and stays there until `exit`; this is the
+//!   "stopped" state VICE reports with its stopped / resumed events
+//! * reset → `Port64Client::reset`
+//! * autostart → `dma_run` / `dma_load` for PRGs, `run_image` / `mount_image`
+//!   for disk images and `run_crt` for cartridges
+//! * keyboard feed → `RemoteDevice::type_text`
+//!
+//! Checkpoints, stepping, resources, display and palette grabs, drive
+//! memspaces and the other commands the hardware can't do get an error
+//! response rather than a dropped connection. One client is served at a
+//! time, like VICE; a client that disconnects while the CPU is stopped
+//! releases it.
+
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use crate::machine_snapshot::{self, Parked};
+use crate::port64::Port64Client;
+use crate::remote_device::RemoteDevice;
+
+/// Port VICE's binary monitor listens on
+pub const DEFAULT_PORT: u16 = 6502;
+
+const STX: u8 = 0x02;
+const API_VERSION: u8 = 0x02;
+/// Largest request body accepted: a full 64 KB memory set plus its header
+const MAX_BODY: usize = 0x10000 + 0x10;
+/// Request ID carried by unsolicited events
+const EVENT_ID: u32 = 0xFFFF_FFFF;
+/// `read_mem` / `write_mem` take a 16-bit length
+const CHUNK: usize = 0x8000;
+
+// ─── Command and response types ──────────────────────────────────────────────
+
+const CMD_MEMORY_GET: u8 = 0x01;
+const CMD_MEMORY_SET: u8 = 0x02;
+const CMD_CHECKPOINT_GET: u8 = 0x11;
+const CMD_CHECKPOINT_SET: u8 = 0x12;
+const CMD_CHECKPOINT_DELETE: u8 = 0x13;
+const CMD_CHECKPOINT_LIST: u8 = 0x14;
+const CMD_CHECKPOINT_TOGGLE: u8 = 0x15;
+const CMD_CONDITION_SET: u8 = 0x22;
+const CMD_REGISTERS_GET: u8 = 0x31;
+const CMD_REGISTERS_SET: u8 = 0x32;
+const CMD_DUMP: u8 = 0x41;
+const CMD_UNDUMP: u8 = 0x42;
+const CMD_RESOURCE_GET: u8 = 0x51;
+const CMD_RESOURCE_SET: u8 = 0x52;
+const CMD_ADVANCE_INSTRUCTIONS: u8 = 0x71;
+const CMD_KEYBOARD_FEED: u8 = 0x72;
+const CMD_EXECUTE_UNTIL_RETURN: u8 = 0x73;
+const CMD_PING: u8 = 0x81;
+const CMD_BANKS_AVAILABLE: u8 = 0x82;
+const CMD_REGISTERS_AVAILABLE: u8 = 0x83;
+const CMD_DISPLAY_GET: u8 = 0x84;
+const CMD_VICE_INFO: u8 = 0x85;
+const CMD_PALETTE_GET: u8 = 0x91;
+const CMD_JOYPORT_SET: u8 = 0xA2;
+const CMD_USERPORT_SET: u8 = 0xB2;
+const CMD_EXIT: u8 = 0xAA;
+const CMD_QUIT: u8 = 0xBB;
+const CMD_RESET: u8 = 0xCC;
+const CMD_AUTOSTART: u8 = 0xDD;
+
+/// Checkpoint list replies with this type, then a count
+const RESPONSE_CHECKPOINT_LIST: u8 = 0x14;
+const RESPONSE_REGISTER_INFO: u8 = 0x31;
+const EVENT_STOPPED: u8 = 0x62;
+const EVENT_RESUMED: u8 = 0x63;
+
+// ─── Error codes ─────────────────────────────────────────────────────────────
+
+const ERR_OK: u8 = 0x00;
+const ERR_OBJECT_MISSING: u8 = 0x01;
+const ERR_INVALID_MEMSPACE: u8 = 0x02;
+const ERR_CMD_LENGTH: u8 = 0x80;
+const ERR_INVALID_PARAMETER: u8 = 0x81;
+const ERR_API_VERSION: u8 = 0x82;
+const ERR_INVALID_COMMAND: u8 = 0x83;
+const ERR_FAILURE: u8 = 0x8F;
+
+/// Why a request failed: the error byte sent back, and detail for the log
+#[derive(Debug)]
+struct Failure {
+    code: u8,
+    detail: String,
+}
+
+impl Failure {
+    fn new(code: u8, detail: impl Into<String>) -> Self {
+        Self {
+            code,
+            detail: detail.into(),
+        }
+    }
+}
+
+impl From<String> for Failure {
+    fn from(detail: String) -> Self {
+        Self::new(ERR_FAILURE, detail)
+    }
+}
+
+// ─── Wire format ─────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Request {
+    api_version: u8,
+    id: u32,
+    command: u8,
+    body: Vec<u8>,
+}
+
+/// Read one request: STX, API version, body length, request ID, command,
+/// body.
+fn read_request(reader: &mut impl Read) -> std::io::Result<Request> {
+    let mut header = [0u8; 11];
+    reader.read_exact(&mut header)?;
+    let invalid = |msg: &str| std::io::Error::new(std::io::ErrorKind::InvalidData, msg);
+    if header[0] != STX {
+        return Err(invalid("request does not start with STX"));
+    }
+    let len = u32::from_le_bytes([header[2], header[3], header[4], header[5]]) as usize;
+    if len > MAX_BODY {
+        return Err(invalid("request body too large"));
+    }
+    let mut body = vec![0u8; len];
+    reader.read_exact(&mut body)?;
+    Ok(Request {
+        api_version: header[1],
+        id: u32::from_le_bytes([header[6], header[7], header[8], header[9]]),
+        command: header[10],
+        body,
+    })
+}
+
+/// Frame a response: STX, API version, body length, type, error, request ID,
+/// body.
+fn response(kind: u8, error: u8, id: u32, body: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(12 + body.len());
+    out.extend_from_slice(&[STX, API_VERSION]);
+    out.extend_from_slice(&(body.len() as u32).to_le_bytes());
+    out.extend_from_slice(&[kind, error]);
+    out.extend_from_slice(&id.to_le_bytes());
+    out.extend_from_slice(body);
+    out
+}
+
+/// Little-endian reader over a request body; running short is a length error.
+struct Body<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Body<'a> {
+    fn bytes(&mut self, n: usize) -> Result<&'a [u8], Failure> {
+        if self.data.len() < n {
+            return Err(Failure::new(ERR_CMD_LENGTH, "request body too short"));
+        }
+        let (head, rest) = self.data.split_at(n);
+        self.data = rest;
+        Ok(head)
+    }
+
+    fn u8(&mut self) -> Result<u8, Failure> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, Failure> {
+        let b = self.bytes(2)?;
+        Ok(u16::from_le_bytes([b[0], b[1]]))
+    }
+
+    fn rest(&mut self) -> &'a [u8] {
+        std::mem::take(&mut self.data)
+    }
+}
+
+// ─── Registers and banks ─────────────────────────────────────────────────────
+
+/// Register IDs as VICE numbers them for the 6510
+const REG_A: u8 = 0x00;
+const REG_X: u8 = 0x01;
+const REG_Y: u8 = 0x02;
+const REG_PC: u8 = 0x03;
+const REG_SP: u8 = 0x04;
+const REG_FLAGS: u8 = 0x05;
+const REG_ZERO: u8 = 0x37;
+const REG_ONE: u8 = 0x38;
+
+/// `(id, bits, name)` — the cycle and raster line counters VICE also lists
+/// can't be read from the hardware
+const REGISTERS: &[(u8, u8, &str)] = &[
+    (REG_A, 8, "A"),
+    (REG_X, 8, "X"),
+    (REG_Y, 8, "Y"),
+    (REG_PC, 16, "PC"),
+    (REG_SP, 8, "SP"),
+    (REG_FLAGS, 8, "FL"),
+    (REG_ZERO, 8, "00"),
+    (REG_ONE, 8, "01"),
+];
+
+/// Both banks are the DMA view of memory
+const BANKS: &[(u16, &str)] = &[(0, "default"), (1, "cpu")];
+
+/// The stub's stack pointer still counts the 3-byte NMI frame; debuggers see
+/// the program's own.
+const NMI_FRAME: u8 = 3;
+
+fn register_value(cpu: &machine_snapshot::CpuState, id: u8) -> u16 {
+    match id {
+        REG_A => cpu.a as u16,
+        REG_X => cpu.x as u16,
+        REG_Y => cpu.y as u16,
+        REG_PC => cpu.pc,
+        REG_SP => cpu.sp.wrapping_add(NMI_FRAME) as u16,
+        REG_FLAGS => cpu.p as u16,
+        REG_ZERO => cpu.ddr as u16,
+        _ => cpu.port as u16,
+    }
+}
+
+fn registers_body(cpu: &machine_snapshot::CpuState) -> Vec<u8> {
+    let mut body = (REGISTERS.len() as u16).to_le_bytes().to_vec();
+    for &(id, _, _) in REGISTERS {
+        body.extend_from_slice(&[3, id]);
+        body.extend_from_slice(&register_value(cpu, id).to_le_bytes());
+    }
+    body
+}
+
+// ─── Session ─────────────────────────────────────────────────────────────────
+
+/// What the server drives
+#[derive(Clone)]
+pub struct Device {
+    pub connection: Arc<Mutex<dyn RemoteDevice>>,
+    pub port64: Port64Client,
+    /// Runtime the async port-64 calls are driven on
+    pub runtime: tokio::runtime::Handle,
+}
+
+/// One client connection
+struct Session {
+    device: Device,
+    parked: Option<Parked>,
+    /// Events to send ahead of / after the current response
+    before: Vec<Vec<u8>>,
+    after: Vec<Vec<u8>>,
+    quit: bool,
+}
+
+impl Session {
+    fn new(device: Device) -> Self {
+        Self {
+            device,
+            parked: None,
+            before: Vec::new(),
+            after: Vec::new(),
+            quit: false,
+        }
+    }
+
+    /// Answer one request with its response, plus any events it raised.
+    fn handle(&mut self, req: &Request) -> Vec<Vec<u8>> {
+        if req.api_version == 0 || req.api_version > API_VERSION {
+            return vec![response(req.command, ERR_API_VERSION, req.id, &[])];
+        }
+        let mut body = Body { data: &req.body };
+        let result = match req.command {
+            CMD_PING => Ok((CMD_PING, Vec::new())),
+            CMD_MEMORY_GET => self.memory_get(&mut body),
+            CMD_MEMORY_SET => self.memory_set(&mut body),
+            CMD_REGISTERS_GET => self.registers_get(&mut body),
+            CMD_REGISTERS_SET => self.registers_set(&mut body),
+            CMD_REGISTERS_AVAILABLE => registers_available(&mut body),
+            CMD_BANKS_AVAILABLE => Ok((CMD_BANKS_AVAILABLE, banks_available())),
+            CMD_VICE_INFO => Ok((CMD_VICE_INFO, vec![4, 3, 8, 0, 0, 4, 0, 0, 0, 0])),
+            CMD_CHECKPOINT_LIST => Ok((RESPONSE_CHECKPOINT_LIST, 0u32.to_le_bytes().to_vec())),
+            CMD_CHECKPOINT_GET
+            | CMD_CHECKPOINT_DELETE
+            | CMD_CHECKPOINT_TOGGLE
+            | CMD_CONDITION_SET => Err(Failure::new(ERR_OBJECT_MISSING, "no checkpoints")),
+            CMD_KEYBOARD_FEED => self.keyboard_feed(&mut body),
+            CMD_EXIT => self.release().map(|_| (CMD_EXIT, Vec::new())),
+            CMD_QUIT => {
+                self.quit = true;
+                self.release().map(|_| (CMD_QUIT, Vec::new()))
+            }
+            CMD_RESET => self.reset(&mut body),
+            CMD_AUTOSTART => self.autostart(&mut body),
+            CMD_CHECKPOINT_SET
+            | CMD_DUMP
+            | CMD_UNDUMP
+            | CMD_RESOURCE_GET
+            | CMD_RESOURCE_SET
+            | CMD_ADVANCE_INSTRUCTIONS
+            | CMD_EXECUTE_UNTIL_RETURN
+            | CMD_DISPLAY_GET
+            | CMD_PALETTE_GET
+            | CMD_JOYPORT_SET
+            | CMD_USERPORT_SET => Err(Failure::new(ERR_FAILURE, "not supported by the hardware")),
+            _ => Err(Failure::new(ERR_INVALID_COMMAND, "unknown command")),
+        };
+
+        let mut frames = std::mem::take(&mut self.before);
+        match result {
+            Ok((kind, body)) => frames.push(response(kind, ERR_OK, req.id, &body)),
+            Err(f) => {
+                log::info!(
+                    "monitor: command {:#04X} failed ({:#04X}): {}",
+                    req.command,
+                    f.code,
+                    f.detail
+                );
+                frames.push(response(req.command, f.code, req.id, &[]));
+            }
+        }
+        frames.append(&mut self.after);
+        frames
+    }
+
+    /// Stop the CPU if it is running, reporting the stop to the client.
+    fn park(&mut self) -> Result<(), Failure> {
+        if self.parked.is_none() {
+            let conn = self.device.connection.lock().unwrap();
+            let parked = Parked::park(&*conn)?;
+            let pc = parked.cpu(&*conn).map(|c| c.pc).unwrap_or(0);
+            self.before
+                .push(response(EVENT_STOPPED, ERR_OK, EVENT_ID, &pc.to_le_bytes()));
+            self.parked = Some(parked);
+        }
+        Ok(())
+    }
+
+    /// Let a stopped CPU continue.
+    fn release(&mut self) -> Result<(), Failure> {
+        if let Some(parked) = self.parked.take() {
+            let conn = self.device.connection.lock().unwrap();
+            let pc = parked.cpu(&*conn).map(|c| c.pc).unwrap_or(0);
+            parked.resume(&*conn)?;
+            self.after
+                .push(response(EVENT_RESUMED, ERR_OK, EVENT_ID, &pc.to_le_bytes()));
+        }
+        Ok(())
+    }
+
+    /// Side effects flag, start, end (inclusive), memspace and bank. Only the
+    /// main CPU memspace exists here.
+    fn memory_range(body: &mut Body) -> Result<(u16, usize), Failure> {
+        let _side_effects = body.u8()?;
+        let start = body.u16()?;
+        let end = body.u16()?;
+        let memspace = body.u8()?;
+        let bank = body.u16()?;
+        if memspace != 0 {
+            return Err(Failure::new(
+                ERR_INVALID_MEMSPACE,
+                "drive memory is not reachable over DMA",
+            ));
+        }
+        if !BANKS.iter().any(|&(id, _)| id == bank) {
+            return Err(Failure::new(
+                ERR_INVALID_PARAMETER,
+                format!("unknown bank {}", bank),
+            ));
+        }
+        if end < start {
+            return Err(Failure::new(ERR_INVALID_PARAMETER, "end before start"));
+        }
+        Ok((start, (end - start) as usize + 1))
+    }
+
+    fn memory_get(&mut self, body: &mut Body) -> Result<(u8, Vec<u8>), Failure> {
+        let (start, len) = Self::memory_range(body)?;
+        let conn = self.device.connection.lock().unwrap();
+        let mut data = Vec::with_capacity(len);
+        for offset in (0..len).step_by(CHUNK) {
+            let address = start + offset as u16;
+            let n = CHUNK.min(len - offset);
+            let chunk = match self.parked.as_mut() {
+                Some(parked) => parked.read(&*conn, address, n)?,
+                None => conn
+                    .read_mem(address, n as u16)
+                    .map_err(|e| format!("Read ${:04X} failed: {}", address, e))?,
+            };
+            data.extend(chunk);
+        }
+        // A full 64 KB read wraps the length to 0, as in VICE
+        let mut out = (len as u16).to_le_bytes().to_vec();
+        out.extend(data);
+        Ok((CMD_MEMORY_GET, out))
+    }
+
+    fn memory_set(&mut self, body: &mut Body) -> Result<(u8, Vec<u8>), Failure> {
+        let (start, len) = Self::memory_range(body)?;
+        let data = body.rest();
+        if data.len() != len {
+            return Err(Failure::new(
+                ERR_CMD_LENGTH,
+                format!("{} bytes for a {}-byte range", data.len(), len),
+            ));
+        }
+        let conn = self.device.connection.lock().unwrap();
+        for (i, chunk) in data.chunks(CHUNK).enumerate() {
+            let address = start + (i * CHUNK) as u16;
+            match self.parked.as_mut() {
+                Some(parked) => parked.write(&*conn, address, chunk)?,
+                None => conn
+                    .write_mem(address, chunk)
+                    .map_err(|e| format!("Write ${:04X} failed: {}", address, e))?,
+            }
+        }
+        Ok((CMD_MEMORY_SET, Vec::new()))
+    }
+
+    fn registers_get(&mut self, body: &mut Body) -> Result<(u8, Vec<u8>), Failure> {
+        check_memspace(body.u8()?)?;
+        self.park()?;
+        let conn = self.device.connection.lock().unwrap();
+        let parked = self.parked.as_ref().expect("parked above");
+        let cpu = parked.cpu(&*conn)?;
+        Ok((RESPONSE_REGISTER_INFO, registers_body(&cpu)))
+    }
+
+    /// Memspace, count, then `(size, id, value)` items.
+    fn registers_set(&mut self, body: &mut Body) -> Result<(u8, Vec<u8>), Failure> {
+        check_memspace(body.u8()?)?;
+        let count = body.u16()?;
+        let mut changes = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            let size = body.u8()? as usize;
+            if size < 3 {
+                return Err(Failure::new(ERR_CMD_LENGTH, "register item too short"));
+            }
+            let mut item = Body {
+                data: body.bytes(size)?,
+            };
+            let id = item.u8()?;
+            if !REGISTERS.iter().any(|&(r, _, _)| r == id) {
+                return Err(Failure::new(
+                    ERR_OBJECT_MISSING,
+                    format!("no register {:#04X}", id),
+                ));
+            }
+            changes.push((id, item.u16()?));
+        }
+
+        self.park()?;
+        let conn = self.device.connection.lock().unwrap();
+        let parked = self.parked.as_ref().expect("parked above");
+        let mut cpu = parked.cpu(&*conn)?;
+        for (id, value) in changes {
+            let byte = value as u8;
+            match id {
+                REG_A => cpu.a = byte,
+                REG_X => cpu.x = byte,
+                REG_Y => cpu.y = byte,
+                REG_PC => cpu.pc = value,
+                REG_SP => cpu.sp = byte.wrapping_sub(NMI_FRAME),
+                REG_FLAGS => cpu.p = byte,
+                REG_ZERO => cpu.ddr = byte,
+                _ => cpu.port = byte,
+            }
+        }
+        parked.set_cpu(&*conn, &cpu)?;
+        Ok((RESPONSE_REGISTER_INFO, registers_body(&cpu)))
+    }
+
+    /// Length-prefixed text for the keyboard buffer.
+    fn keyboard_feed(&mut self, body: &mut Body) -> Result<(u8, Vec<u8>), Failure> {
+        let len = body.u8()? as usize;
+        let text = String::from_utf8_lossy(body.bytes(len)?).into_owned();
+        self.device
+            .connection
+            .lock()
+            .unwrap()
+            .type_text(&text)
+            .map_err(|e| format!("Type failed: {}", e))?;
+        Ok((CMD_KEYBOARD_FEED, Vec::new()))
+    }
+
+    /// Reset type 0 (soft) and 1 (hard) reset the C64; 8–11 would reset a
+    /// drive, which port 64 can't do.
+    fn reset(&mut self, body: &mut Body) -> Result<(u8, Vec<u8>), Failure> {
+        let kind = body.u8()?;
+        if kind > 1 {
+            return Err(Failure::new(
+                ERR_INVALID_PARAMETER,
+                format!("reset type {} is not supported", kind),
+            ));
+        }
+        // The reset clears the stub along with everything else
+        if self.parked.take().is_some() {
+            self.after.push(response(
+                EVENT_RESUMED,
+                ERR_OK,
+                EVENT_ID,
+                &0xFCE2u16.to_le_bytes(),
+            ));
+        }
+        self.device
+            .runtime
+            .block_on(self.device.port64.reset())
+            .map_err(String::from)?;
+        Ok((CMD_RESET, Vec::new()))
+    }
+
+    /// Run-after flag, file index, then a length-prefixed path on this
+    /// machine. The Ultimate always starts the first file of a disk image.
+    fn autostart(&mut self, body: &mut Body) -> Result<(u8, Vec<u8>), Failure> {
+        let run = body.u8()? != 0;
+        let index = body.u16()?;
+        let len = body.u8()? as usize;
+        let name = String::from_utf8_lossy(body.bytes(len)?).into_owned();
+        let path = Path::new(&name);
+        let ext = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_lowercase())
+            .unwrap_or_default();
+        let disk = matches!(ext.as_str(), "d64" | "d71" | "d81" | "g64" | "g71");
+        if !disk && !matches!(ext.as_str(), "prg" | "crt") {
+            return Err(Failure::new(
+                ERR_INVALID_PARAMETER,
+                format!("can't autostart .{} files", ext),
+            ));
+        }
+        if disk && index > 0 {
+            return Err(Failure::new(
+                ERR_INVALID_PARAMETER,
+                "only the first file of a disk image can be started",
+            ));
+        }
+        let data = std::fs::read(path)
+            .map_err(|e| Failure::new(ERR_OBJECT_MISSING, format!("{}: {}", name, e)))?;
+
+        // The loader needs the CPU running
+        self.release()?;
+        let port64 = &self.device.port64;
+        let sent = self.device.runtime.block_on(async {
+            match (ext.as_str(), run) {
+                ("prg", true) => port64.dma_run(&data).await,
+                ("prg", false) => port64.dma_load(&data).await,
+                ("crt", _) => port64.run_crt(&data).await,
+                (_, true) => port64.run_image(&data).await,
+                (_, false) => port64.mount_image(&data).await,
+            }
+        });
+        sent.map_err(String::from)?;
+        Ok((CMD_AUTOSTART, Vec::new()))
+    }
+}
+
+fn check_memspace(memspace: u8) -> Result<(), Failure> {
+    if memspace == 0 {
+        Ok(())
+    } else {
+        Err(Failure::new(
+            ERR_INVALID_MEMSPACE,
+            "drive CPUs are not reachable",
+        ))
+    }
+}
+
+fn registers_available(body: &mut Body) -> Result<(u8, Vec<u8>), Failure> {
+    check_memspace(body.u8()?)?;
+    let mut out = (REGISTERS.len() as u16).to_le_bytes().to_vec();
+    for &(id, bits, name) in REGISTERS {
+        out.extend_from_slice(&[3 + name.len() as u8, id, bits, name.len() as u8]);
+        out.extend_from_slice(name.as_bytes());
+    }
+    Ok((CMD_REGISTERS_AVAILABLE, out))
+}
+
+fn banks_available() -> Vec<u8> {
+    let mut out = (BANKS.len() as u16).to_le_bytes().to_vec();
+    for &(id, name) in BANKS {
+        out.push(3 + name.len() as u8);
+        out.extend_from_slice(&id.to_le_bytes());
+        out.push(name.len() as u8);
+        out.extend_from_slice(name.as_bytes());
+    }
+    out
+}
+
+// ─── Server ──────────────────────────────────────────────────────────────────
+
+/// Serve one client until it disconnects or quits.
+fn serve(stream: TcpStream, device: &Device) {
+    let _ = stream.set_nodelay(true);
+    let peer = stream
+        .peer_addr()
+        .map(|a| a.to_string())
+        .unwrap_or_default();
+    println!("Monitor client connected: {}", peer);
+    let mut session = Session::new(device.clone());
+    let mut reader = &stream;
+    let mut writer = &stream;
+    'requests: while let Ok(req) = read_request(&mut reader) {
+        for frame in session.handle(&req) {
+            if writer.write_all(&frame).is_err() {
+                break 'requests;
+            }
+        }
+        if session.quit {
+            break;
+        }
+    }
+    if let Err(e) = session.release() {
+        log::warn!("monitor: could not resume the CPU: {}", e.detail);
+    }
+    println!("Monitor client disconnected: {}", peer);
+}
+
+/// Blocking entry point for `ultimate64-manager monitor`: serve clients one
+/// at a time until killed.
+pub fn run_forever(bind: SocketAddr, device: Device) -> Result<(), String> {
+    let listener =
+        TcpListener::bind(bind).map_err(|e| format!("Bind monitor on {}: {}", bind, e))?;
+    println!(
+        "VICE binary monitor on {}",
+        listener.local_addr().map_err(|e| e.to_string())?
+    );
+    println!("Press Ctrl+C to stop.");
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => serve(stream, &device),
+            Err(e) => log::warn!("monitor: accept failed: {}", e),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::remote_device::mock::MockDevice;
+
+    fn request(command: u8, body: &[u8]) -> Request {
+        let mut wire = vec![STX, API_VERSION];
+        wire.extend_from_slice(&(body.len() as u32).to_le_bytes());
+        wire.extend_from_slice(&7u32.to_le_bytes());
+        wire.push(command);
+        wire.extend_from_slice(body);
+        read_request(&mut wire.as_slice()).unwrap()
+    }
+
+    /// `(type, error, body)` of a single framed response
+    fn parse(frame: &[u8]) -> (u8, u8, Vec<u8>) {
+        assert_eq!(&frame[..2], &[STX, API_VERSION]);
+        let len = u32::from_le_bytes(frame[2..6].try_into().unwrap()) as usize;
+        assert_eq!(u32::from_le_bytes(frame[8..12].try_into().unwrap()), 7);
+        assert_eq!(frame.len(), 12 + len);
+        (frame[6], frame[7], frame[12..].to_vec())
+    }
+
+    fn session(dev: &MockDevice) -> (Session, tokio::runtime::Runtime) {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let device = Device {
+            connection: Arc::new(Mutex::new(dev.clone())),
+            port64: Port64Client::new("127.0.0.1", None),
+            runtime: runtime.handle().clone(),
+        };
+        (Session::new(device), runtime)
+    }
+
+    #[test]
+    fn memory_get_and_set_go_through_dma() {
+        let dev = MockDevice::new();
+        let (mut s, _rt) = session(&dev);
+
+        // side effects, $C000..$C003, main memspace, bank 0
+        let frames = s.handle(&request(
+            CMD_MEMORY_GET,
+            &[0, 0x00, 0xC0, 0x03, 0xC0, 0, 0, 0],
+        ));
+        assert_eq!(frames.len(), 1);
+        let (kind, error, body) = parse(&frames[0]);
+        assert_eq!((kind, error), (CMD_MEMORY_GET, ERR_OK));
+        assert_eq!(body, vec![4, 0, 0xAA, 0xAA, 0xAA, 0xAA]);
+
+        let frames = s.handle(&request(
+            CMD_MEMORY_SET,
+            &[0, 0x20, 0xD0, 0x21, 0xD0, 0, 0, 0, 0x06, 0x0E],
+        ));
+        assert_eq!(parse(&frames[0]).1, ERR_OK);
+        assert_eq!(dev.reads(), vec![(0xC000, 4)]);
+        assert_eq!(dev.writes(), vec![(0xD020, vec![0x06, 0x0E])]);
+    }
+
+    #[test]
+    fn unsupported_requests_get_error_responses() {
+        let dev = MockDevice::new();
+        let (mut s, _rt) = session(&dev);
+        let error = |s: &mut Session, command: u8, body: &[u8]| {
+            let frames = s.handle(&request(command, body));
+            let (kind, error, _) = parse(&frames[0]);
+            assert_eq!(kind, command);
+            error
+        };
+
+        // Drive 8 memory, a short memory set, a backwards range
+        assert_eq!(
+            error(&mut s, CMD_MEMORY_GET, &[0, 0, 0, 0, 0, 1, 0, 0]),
+            ERR_INVALID_MEMSPACE
+        );
+        assert_eq!(
+            error(&mut s, CMD_MEMORY_SET, &[0, 0, 4, 1, 4, 0, 0, 0, 1]),
+            ERR_CMD_LENGTH
+        );
+        assert_eq!(
+            error(&mut s, CMD_MEMORY_GET, &[0, 1, 0, 0, 0, 0, 0, 0]),
+            ERR_INVALID_PARAMETER
+        );
+        assert_eq!(error(&mut s, CMD_CHECKPOINT_SET, &[]), ERR_FAILURE);
+        assert_eq!(
+            error(&mut s, CMD_CHECKPOINT_DELETE, &[1, 0, 0, 0]),
+            ERR_OBJECT_MISSING
+        );
+        assert_eq!(error(&mut s, CMD_RESET, &[8]), ERR_INVALID_PARAMETER);
+        assert_eq!(error(&mut s, 0x7E, &[]), ERR_INVALID_COMMAND);
+
+        let mut old = request(CMD_PING, &[]);
+        old.api_version = 9;
+        assert_eq!(parse(&s.handle(&old)[0]).1, ERR_API_VERSION);
+        assert!(dev.writes().is_empty());
+    }
+}
@@ -0,0 +1,790 @@
+//! Multi-device workspace: several Ultimate 64s connected at once.
+//!
+//! The rest of the app talks to a single device through
+//! [`crate::tab::TabContext`]. The workspace keeps its own list of members,
+//! each with its own REST client. A subscription polls every member's status
+//! every few seconds. Reset / run / mount / apply-profile are sent to the
+//! selected members in parallel, and each member shows the result of the last
+//! action it received.
+//!
+//! Members are persisted in `profiles::ProfileManager::workspace`. The app resolves
+//! connection-profile and device-profile names (it owns both managers) and
+//! hands them in through [`Workspace::add`] and [`Workspace::apply_profile`].
+
+use crate::device_profile::{self, ConfigTree};
+use crate::launch_script;
+use crate::remote_device::RemoteDevice;
+use iced::{
+    widget::{
+        button, checkbox, column, container, pick_list, row, rule, scrollable, text, text_input,
+        tooltip, Column, Space,
+    },
+    Color, Element, Length, Subscription, Task,
+};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::Mutex;
+use ultimate64::drives::MountMode;
+
+/// Seconds between status polls of every member
+const POLL_SECS: u64 = 5;
+
+/// Disk image extensions that are mounted on drive A and autoloaded by Run
+const DISK_EXTENSIONS: [&str; 5] = ["d64", "d71", "d81", "g64", "g71"];
+
+// ─── Types ───────────────────────────────────────────────────────────────────
+
+/// A workspace member as stored in settings.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkspaceDevice {
+    pub name: String,
+    pub host: String,
+    #[serde(default)]
+    pub password: Option<String>,
+}
+
+/// Result of the latest status poll.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeviceStatus {
+    Connecting,
+    /// "product (firmware)"
+    Online(String),
+    Offline(String),
+}
+
+/// Group actions, recorded next to each member's result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Reset,
+    Run,
+    Mount,
+    ApplyProfile,
+}
+
+impl Action {
+    fn label(self) -> &'static str {
+        match self {
+            Action::Reset => "Reset",
+            Action::Run => "Run",
+            Action::Mount => "Mount",
+            Action::ApplyProfile => "Apply profile",
+        }
+    }
+}
+
+struct Member {
+    id: u64,
+    device: WorkspaceDevice,
+    connection: Option<Arc<Mutex<dyn RemoteDevice>>>,
+    status: DeviceStatus,
+    selected: bool,
+    /// Action still running on this member
+    busy: Option<Action>,
+    /// A status poll has been sent and not answered yet
+    polling: bool,
+    /// Last finished action and its outcome
+    result: Option<(Action, Result<String, String>)>,
+}
+
+#[derive(Debug, Clone)]
+pub enum WorkspaceMessage {
+    HostInputChanged(String),
+    AddHost,
+    /// Add a saved connection profile — resolved by the app
+    AddProfile(String),
+    Remove(u64),
+    ToggleSelected(u64, bool),
+    SelectAll(bool),
+    Poll,
+    Polled(u64, Result<String, String>),
+    Reset,
+    PickFile(Action),
+    FilePicked(Action, Option<PathBuf>),
+    /// Apply a device profile to the selection — resolved by the app
+    ApplyProfile(String),
+    ActionDone(u64, Action, Result<String, String>),
+}
+
+pub struct Workspace {
+    members: Vec<Member>,
+    next_id: u64,
+    host_input: String,
+    status_message: Option<String>,
+}
+
+// ─── State ───────────────────────────────────────────────────────────────────
+
+impl Workspace {
+    /// Restore the saved members. Their first poll comes from the subscription.
+    pub fn new(devices: &[WorkspaceDevice]) -> Self {
+        let mut workspace = Self {
+            members: Vec::new(),
+            next_id: 0,
+            host_input: String::new(),
+            status_message: None,
+        };
+        for device in devices {
+            workspace.push(device.clone());
+        }
+        workspace
+    }
+
+    /// Members in the form they are saved in settings.
+    pub fn devices(&self) -> Vec<WorkspaceDevice> {
+        self.members.iter().map(|m| m.device.clone()).collect()
+    }
+
+    /// Add a member and poll it right away. A host that is already in the
+    /// workspace is refused.
+    pub fn add(&mut self, device: WorkspaceDevice) -> Task<WorkspaceMessage> {
+        if self.members.iter().any(|m| m.device.host == device.host) {
+            self.status_message = Some(format!("{} is already in the workspace", device.host));
+            return Task::none();
+        }
+        self.status_message = Some(format!("Added {}", device.name));
+        let id = self.push(device);
+        self.members
+            .iter_mut()
+            .find(|m| m.id == id)
+            .map(start_poll)
+            .unwrap_or_else(Task::none)
+    }
+
+    fn push(&mut self, device: WorkspaceDevice) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        let (connection, status) = match connect(&device.host, device.password.clone()) {
+            Ok(c) => (Some(c), DeviceStatus::Connecting),
+            Err(e) => (None, DeviceStatus::Offline(e)),
+        };
+        self.members.push(Member {
+            id,
+            device,
+            connection,
+            status,
+            selected: true,
+            busy: None,
+            polling: false,
+            result: None,
+        });
+        id
+    }
+
+    /// Apply a device profile to every selected member. Each member diffs the
+    /// profile against `baseline` (the Profiles tab's stored baseline). With
+    /// no baseline, every setting in the profile is sent.
+    pub fn apply_profile(
+        &mut self,
+        name: &str,
+        path: PathBuf,
+        baseline: Option<ConfigTree>,
+    ) -> Task<WorkspaceMessage> {
+        let baseline = baseline.unwrap_or_default();
+        self.status_message = Some(format!("Applying profile '{}'...", name));
+        self.broadcast(Action::ApplyProfile, |member, connection| {
+            let path = path.clone();
+            let baseline = baseline.clone();
+            let host_url = format!("http://{}", member.device.host);
+            let password = member.device.password.clone();
+            async move {
+                let profile = crate::profile_repo::load_profile_async(path).await?;
+                let diff = device_profile::diff_configs(&baseline, &profile.config);
+                crate::profile_api::apply_profile(
+                    host_url,
+                    &profile,
+                    diff,
+                    password,
+                    Some(connection),
+                    0,
+                )
+                .await
+            }
+        })
+    }
+
+    /// Start `action` on every selected member that has a connection.
+    fn broadcast<F, Fut>(&mut self, action: Action, mut op: F) -> Task<WorkspaceMessage>
+    where
+        F: FnMut(&Member, Arc<Mutex<dyn RemoteDevice>>) -> Fut,
+        Fut: std::future::Future<Output = Result<String, String>> + Send + 'static,
+    {
+        let mut tasks = Vec::new();
+        for member in self.members.iter_mut().filter(|m| m.selected) {
+            let Some(connection) = member.connection.clone() else {
+                member.result = Some((action, Err("Not connected".to_string())));
+                continue;
+            };
+            let id = member.id;
+            tasks.push(Task::perform(op(member, connection), move |r| {
+                WorkspaceMessage::ActionDone(id, action, r)
+            }));
+            member.busy = Some(action);
+            member.result = None;
+        }
+        if tasks.is_empty() {
+            self.status_message = Some("Select at least one connected device".to_string());
+            return Task::none();
+        }
+        Task::batch(tasks)
+    }
+
+    // ─── Update ──────────────────────────────────────────────────────────────
+
+    pub fn update_impl(&mut self, message: WorkspaceMessage) -> Task<WorkspaceMessage> {
+        match message {
+            WorkspaceMessage::HostInputChanged(value) => {
+                self.host_input = value;
+                Task::none()
+            }
+            WorkspaceMessage::AddHost => {
+                let host = self.host_input.trim().to_string();
+                if host.is_empty() {
+                    return Task::none();
+                }
+                self.host_input.clear();
+                self.add(WorkspaceDevice {
+                    name: host.clone(),
+                    host,
+                    password: None,
+                })
+            }
+            // Intercepted by the app, which owns the connection profiles.
+            WorkspaceMessage::AddProfile(_) => Task::none(),
+            WorkspaceMessage::Remove(id) => {
+                self.members.retain(|m| m.id != id);
+                Task::none()
+            }
+            WorkspaceMessage::ToggleSelected(id, selected) => {
+                if let Some(member) = self.members.iter_mut().find(|m| m.id == id) {
+                    member.selected = selected;
+                }
+                Task::none()
+            }
+            WorkspaceMessage::SelectAll(selected) => {
+                for member in &mut self.members {
+                    member.selected = selected;
+                }
+                Task::none()
+            }
+            WorkspaceMessage::Poll => Task::batch(self.members.iter_mut().map(start_poll)),
+            WorkspaceMessage::Polled(id, result) => {
+                if let Some(member) = self.members.iter_mut().find(|m| m.id == id) {
+                    member.polling = false;
+                    member.status = match result {
+                        Ok(info) => DeviceStatus::Online(info),
+                        Err(e) => DeviceStatus::Offline(e),
+                    };
+                }
+                Task::none()
+            }
+            WorkspaceMessage::Reset => {
+                self.status_message = Some("Resetting...".to_string());
+                self.broadcast(Action::Reset, |_, connection| {
+                    on_device(connection, |c| {
+                        c.reset().map_err(|e| format!("Reset failed: {}", e))?;
+                        Ok("Machine reset".to_string())
+                    })
+                })
+            }
+            WorkspaceMessage::PickFile(action) => {
+                let (label, extensions): (&str, Vec<&str>) = if action == Action::Mount {
+                    ("Disk images", DISK_EXTENSIONS.to_vec())
+                } else {
+                    let mut all = vec!["prg", "crt", "sid", launch_script::EXTENSION];
+                    all.extend(DISK_EXTENSIONS);
+                    ("Runnable files", all)
+                };
+                Task::perform(
+                    async move {
+                        rfd::AsyncFileDialog::new()
+                            .add_filter(label, &extensions)
+                            .pick_file()
+                            .await
+                            .map(|f| f.path().to_path_buf())
+                    },
+                    move |path| WorkspaceMessage::FilePicked(action, path),
+                )
+            }
+            WorkspaceMessage::FilePicked(_, None) => Task::none(),
+            WorkspaceMessage::FilePicked(action, Some(path)) => self.send_file(action, path),
+            // Intercepted by the app, which owns the device profiles.
+            WorkspaceMessage::ApplyProfile(_) => Task::none(),
+            WorkspaceMessage::ActionDone(id, action, result) => {
+                if let Some(member) = self.members.iter_mut().find(|m| m.id == id) {
+                    member.busy = None;
+                    member.result = Some((action, result));
+                }
+                if self.members.iter().all(|m| m.busy.is_none()) {
+                    let failed = self
+                        .members
+                        .iter()
+                        .filter(|m| matches!(m.result, Some((a, Err(_))) if a == action))
+                        .count();
+                    self.status_message = Some(if failed == 0 {
+                        format!("{} finished", action.label())
+                    } else {
+                        format!("{} finished, {} device(s) failed", action.label(), failed)
+                    });
+                }
+                Task::none()
+            }
+        }
+    }
+
+    /// Run or mount `path` on the selection. Run prefers a launch script: the
+    /// file itself, or its sidecar.
+    fn send_file(&mut self, action: Action, path: PathBuf) -> Task<WorkspaceMessage> {
+        let name = path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        if action == Action::Mount {
+            self.status_message = Some(format!("Mounting {}...", name));
+            return self.broadcast(action, |_, connection| {
+                let path = path.clone();
+                on_device(connection, move |c| {
+                    mount_file(c, &path)?;
+                    Ok(format!("Mounted {} on drive A", name_of(&path)))
+                })
+            });
+        }
+
+        let script = if has_extension(&path, &[launch_script::EXTENSION]) {
+            Some((path.clone(), None))
+        } else {
+            launch_script::sidecar(&path).map(|s| (s, Some(path.clone())))
+        };
+        self.status_message = Some(format!("Running {}...", name));
+        match script {
+            Some((script, file)) => {
+                let request = match launch_script::read_local(&script, file.as_deref()) {
+                    Ok(r) => r,
+                    Err(e) => {
+                        self.status_message = Some(format!("Launch script: {}", e));
+                        return Task::none();
+                    }
+                };
+                let statements = match launch_script::parse(&request.source) {
+                    Ok(s) => Arc::new(s),
+                    Err(errors) => {
+                        self.status_message = Some(launch_script::describe(&errors));
+                        return Task::none();
+                    }
+                };
+                self.broadcast(action, |member, connection| {
+                    let env = launch_script::Env {
+                        connection,
+                        host: member.device.host.clone(),
+                        password: member.device.password.clone(),
+                        script: request.script.clone(),
+                        file: request.file.clone(),
+                        cancel: Default::default(),
+                    };
+                    let statements = statements.clone();
+                    let name = request.name.clone();
+                    async move {
+                        launch_script::run(&env, &statements, |_| {})
+                            .await
+                            .map_err(|e| e.to_string())?;
+                        Ok(format!("Ran {}", name))
+                    }
+                })
+            }
+            None => self.broadcast(action, |_, connection| {
+                let path = path.clone();
+                on_device(connection, move |c| run_file(c, &path))
+            }),
+        }
+    }
+
+    pub fn subscription(&self) -> Subscription<WorkspaceMessage> {
+        if self.members.is_empty() {
+            Subscription::none()
+        } else {
+            iced::time::every(std::time::Duration::from_secs(POLL_SECS))
+                .map(|_| WorkspaceMessage::Poll)
+        }
+    }
+
+    // ─── View ────────────────────────────────────────────────────────────────
+
+    /// `connection_profiles` and `device_profiles` fill the two pickers.
+    pub fn view(
+        &self,
+        font_size: u32,
+        connection_profiles: Vec<String>,
+        device_profiles: Vec<String>,
+    ) -> Element<'_, WorkspaceMessage> {
+        let fs = crate::styles::FontSizes::from_base(font_size);
+
+        let add_row = row![
+            text("Add:").size(fs.normal),
+            pick_list(
+                connection_profiles,
+                None::<String>,
+                WorkspaceMessage::AddProfile
+            )
+            .placeholder("Connection profile...")
+            .text_size(fs.normal),
+            text_input("Host or IP", &self.host_input)
+                .on_input(WorkspaceMessage::HostInputChanged)
+                .on_submit(WorkspaceMessage::AddHost)
+                .size(fs.normal)
+                .width(Length::Fixed(200.0)),
+            button(text("Add host").size(fs.normal))
+                .on_press(WorkspaceMessage::AddHost)
+                .padding([4, 10]),
+        ]
+        .spacing(8)
+        .align_y(iced::Alignment::Center);
+
+        let any_selected = self.members.iter().any(|m| m.selected);
+        let action_button = |label: &'static str, tip: &'static str, msg: WorkspaceMessage| {
+            tooltip(
+                button(text(label).size(fs.normal))
+                    .on_press_maybe(any_selected.then_some(msg))
+                    .padding([4, 10]),
+                text(tip).size(fs.small),
+                tooltip::Position::Bottom,
+            )
+            .style(crate::styles::subtle_tooltip)
+        };
+        let all_selected = !self.members.is_empty() && self.members.iter().all(|m| m.selected);
+        let actions = row![
+            checkbox(all_selected)
+                .on_toggle(WorkspaceMessage::SelectAll)
+                .size(fs.normal as f32),
+            text("All").size(fs.normal),
+            Space::new().width(10),
+            action_button(
+                "Reset",
+                "Reset the selected machines",
+                WorkspaceMessage::Reset
+            ),
+            action_button(
+                "Run file...",
+                "Run a PRG, CRT, SID, disk image or launch script on the selected devices",
+                WorkspaceMessage::PickFile(Action::Run)
+            ),
+            action_button(
+                "Mount...",
+                "Mount a disk image on drive A of the selected devices",
+                WorkspaceMessage::PickFile(Action::Mount)
+            ),
+            pick_list(
+                device_profiles,
+                None::<String>,
+                WorkspaceMessage::ApplyProfile
+            )
+            .placeholder("Apply profile...")
+            .text_size(fs.normal),
+        ]
+        .spacing(8)
+        .align_y(iced::Alignment::Center);
+
+        let mut list = Column::new().spacing(4);
+        if self.members.is_empty() {
+            list = list.push(
+                text("No devices yet — add a connection profile or a host above.")
+                    .size(fs.normal)
+                    .color(Color::from_rgb(0.6, 0.6, 0.6)),
+            );
+        }
+        for member in &self.members {
+            list = list.push(member_row(member, fs.normal, fs.small));
+        }
+
+        let status = text(self.status_message.clone().unwrap_or_default()).size(fs.small);
+
+        container(
+            column![
+                text("WORKSPACE").size(fs.large),
+                add_row,
+                rule::horizontal(1),
+                actions,
+                scrollable(list).height(Length::Fill),
+                rule::horizontal(1),
+                status,
+            ]
+            .spacing(10),
+        )
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .into()
+    }
+}
+
+fn member_row(member: &Member, size: u32, small: u32) -> Element<'_, WorkspaceMessage> {
+    let id = member.id;
+    let (dot, status, color) = match &member.status {
+        DeviceStatus::Connecting => ("○", "Connecting...".to_string(), (0.6, 0.6, 0.6)),
+        DeviceStatus::Online(info) => ("●", info.clone(), (0.2, 0.8, 0.2)),
+        DeviceStatus::Offline(e) => ("○", e.clone(), (0.8, 0.5, 0.2)),
+    };
+    let result: Element<'_, WorkspaceMessage> = match (&member.busy, &member.result) {
+        (Some(action), _) => text(format!("{}...", action.label())).size(small).into(),
+        (None, Some((action, Ok(msg)))) => text(format!("{}: {}", action.label(), msg))
+            .size(small)
+            .color(Color::from_rgb(0.2, 0.8, 0.2))
+            .into(),
+        (None, Some((action, Err(e)))) => text(format!("{}: {}", action.label(), e))
+            .size(small)
+            .color(Color::from_rgb(0.9, 0.3, 0.3))
+            .into(),
+        (None, None) => Space::new().into(),
+    };
+    container(
+        column![
+            row![
+                checkbox(member.selected)
+                    .on_toggle(move |on| WorkspaceMessage::ToggleSelected(id, on)),
+                text(dot)
+                    .size(size)
+                    .color(Color::from_rgb(color.0, color.1, color.2)),
+                text(&member.device.name)
+                    .size(size)
+                    .width(Length::FillPortion(2)),
+                text(&member.device.host)
+                    .size(small)
+                    .width(Length::FillPortion(2)),
+                text(status).size(small).width(Length::FillPortion(3)),
+                button(text("Remove").size(small))
+                    .on_press(WorkspaceMessage::Remove(id))
+                    .style(button::secondary)
+                    .padding([2, 8]),
+            ]
+            .spacing(8)
+            .align_y(iced::Alignment::Center),
+            result,
+        ]
+        .spacing(2),
+    )
+    .padding([4, 8])
+    .style(crate::styles::section_style)
+    .into()
+}
+
+impl crate::tab::TabController for Workspace {
+    type Message = WorkspaceMessage;
+    /// Members carry their own connections, so the shared context is unused.
+    fn update(
+        &mut self,
+        message: WorkspaceMessage,
+        _ctx: crate::tab::TabContext,
+    ) -> Task<WorkspaceMessage> {
+        self.update_impl(message)
+    }
+}
+
+// ─── Device operations ───────────────────────────────────────────────────────
+
+fn connect(host: &str, password: Option<String>) -> Result<Arc<Mutex<dyn RemoteDevice>>, String> {
+    let parsed = if let Ok(ip) = host.parse::<std::net::Ipv4Addr>() {
+        url::Host::Ipv4(ip)
+    } else if let Ok(ip) = host.parse::<std::net::Ipv6Addr>() {
+        url::Host::Ipv6(ip)
+    } else {
+        url::Host::parse(host).map_err(|e| format!("Invalid host: {}", e))?
+    };
+    let rest = ultimate64::Rest::new(&parsed, password.filter(|p| !p.is_empty()))
+        .map_err(|e| format!("Connection failed: {}", e))?;
+    Ok(Arc::new(Mutex::new(rest)))
+}
+
+/// Poll a member unless an action is running on it or the previous poll has
+/// not come back yet — a slow device would otherwise queue up a poll every
+/// interval behind its device lock.
+fn start_poll(member: &mut Member) -> Task<WorkspaceMessage> {
+    if member.busy.is_some() || member.polling || member.connection.is_none() {
+        return Task::none();
+    }
+    member.polling = true;
+    poll_member(member)
+}
+
+fn poll_member(member: &Member) -> Task<WorkspaceMessage> {
+    let Some(connection) = member.connection.clone() else {
+        return Task::none();
+    };
+    let id = member.id;
+    Task::perform(
+        async move {
+            tokio::time::timeout(
+                std::time::Duration::from_secs(crate::net_utils::REST_TIMEOUT_SECS),
+                on_device(connection, |c| {
+                    c.info()
+                        .map(|info| format!("{} ({})", info.product, info.firmware_version))
+                        .map_err(|e| e.to_string())
+                }),
+            )
+            .await
+            .unwrap_or_else(|_| Err("Timed out".to_string()))
+        },
+        move |result| WorkspaceMessage::Polled(id, result),
+    )
+}
+
+/// Run a blocking device call on the worker pool.
+async fn on_device<T: Send + 'static>(
+    connection: Arc<Mutex<dyn RemoteDevice>>,
+    op: impl FnOnce(&dyn RemoteDevice) -> Result<T, String> + Send + 'static,
+) -> Result<T, String> {
+    tokio::task::spawn_blocking(move || {
+        let conn = connection
+            .lock()
+            .map_err(|_| "Device lock poisoned".to_string())?;
+        op(&*conn)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+fn name_of(path: &Path) -> String {
+    path.file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string()
+}
+
+fn has_extension(path: &Path, extensions: &[&str]) -> bool {
+    path.extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .is_some_and(|e| extensions.contains(&e.as_str()))
+}
+
+fn mount_file(conn: &dyn RemoteDevice, path: &Path) -> Result<(), String> {
+    if !has_extension(path, &DISK_EXTENSIONS) {
+        return Err(format!("{} is not a disk image", name_of(path)));
+    }
+    conn.mount_disk_image(path, "a".to_string(), MountMode::ReadOnly, false)
+        .map_err(|e| format!("Mount failed: {}", e))
+}
+
+/// Run a local file the way the file browser does: PRG / CRT / SID are sent
+/// directly, disk images are mounted on drive A and autoloaded.
+fn run_file(conn: &dyn RemoteDevice, path: &Path) -> Result<String, String> {
+    let name = name_of(path);
+    if has_extension(path, &DISK_EXTENSIONS) {
+        mount_file(conn, path)?;
+        crate::run_ops::autoload_mounted_disk(conn, "8")?;
+        return Ok(format!("Running {}", name));
+    }
+    let ext = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let data = std::fs::read(path).map_err(|e| format!("{}: {}", name, e))?;
+    let sent = match ext.as_str() {
+        "prg" => conn.run_prg(&data),
+        "crt" => conn.run_crt(&data),
+        "sid" => conn.sid_play(&data, None),
+        _ => return Err(format!("Can't run {}", name)),
+    };
+    sent.map_err(|e| format!("Run failed: {}", e))?;
+    Ok(format!("Running {}", name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::remote_device::mock::MockDevice;
+
+    #[test]
+    fn run_file_dispatches_on_extension() {
+        let dir = std::env::temp_dir().join(format!("u64_workspace_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let prg = dir.join("game.PRG");
+        std::fs::write(&prg, [0x01, 0x08, 0x00]).unwrap();
+        let txt = dir.join("notes.txt");
+        std::fs::write(&txt, "hi").unwrap();
+
+        let mock = MockDevice::new();
+        assert_eq!(run_file(&mock, &prg).unwrap(), "Running game.PRG");
+        assert_eq!(mock.calls(), vec!["run_prg(3)"]);
+        assert!(run_file(&mock, &txt).is_err());
+        assert!(mount_file(&mock, &prg).is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn members_are_unique_by_host_and_round_trip() {
+        let device = |name: &str, host: &str| WorkspaceDevice {
+            name: name.to_string(),
+            host: host.to_string(),
+            password: None,
+        };
+        let mut workspace = Workspace::new(&[device("Lab 1", "10.0.0.11")]);
+        let _ = workspace.add(device("Lab 2", "10.0.0.12"));
+        let _ = workspace.add(device("Again", "10.0.0.11"));
+        assert_eq!(
+            workspace.devices(),
+            vec![device("Lab 1", "10.0.0.11"), device("Lab 2", "10.0.0.12")]
+        );
+
+        // Nothing selected: no action starts.
+        let _ = workspace.update_impl(WorkspaceMessage::SelectAll(false));
+        let _ = workspace.update_impl(WorkspaceMessage::Reset);
+        assert!(workspace.members.iter().all(|m| m.busy.is_none()));
+    }
+
+    #[test]
+    fn removed_members_drop_out_of_the_saved_list() {
+        let device = |name: &str, host: &str| WorkspaceDevice {
+            name: name.to_string(),
+            host: host.to_string(),
+            password: None,
+        };
+        let mut workspace = Workspace::new(&[]);
+        let _ = workspace.add(device("Lab 1", "10.0.0.11"));
+        let _ = workspace.add(device("Lab 2", "10.0.0.12"));
+        let first = workspace.members[0].id;
+        let _ = workspace.update_impl(WorkspaceMessage::Remove(first));
+        assert_eq!(workspace.devices(), vec![device("Lab 2", "10.0.0.12")]);
+
+        // The saved list restores the same members.
+        let restored = Workspace::new(&workspace.devices());
+        assert_eq!(restored.devices(), workspace.devices());
+    }
+
+    #[test]
+    fn polls_skip_busy_members_and_polls_in_flight() {
+        let mut workspace = Workspace::new(&[
+            WorkspaceDevice {
+                name: "Lab 1".to_string(),
+                host: "10.0.0.11".to_string(),
+                password: None,
+            },
+            WorkspaceDevice {
+                name: "Lab 2".to_string(),
+                host: "10.0.0.12".to_string(),
+                password: None,
+            },
+        ]);
+        let (a, b) = (workspace.members[0].id, workspace.members[1].id);
+        workspace.members[1].busy = Some(Action::Reset);
+
+        let _ = workspace.update_impl(WorkspaceMessage::Poll);
+        assert!(workspace.members[0].polling);
+        assert!(!workspace.members[1].polling, "busy member was polled");
+
+        // The answer clears the in-flight flag and sets the status.
+        let _ = workspace.update_impl(WorkspaceMessage::Polled(a, Err("Timed out".into())));
+        assert!(!workspace.members[0].polling);
+        assert!(matches!(
+            workspace.members[0].status,
+            DeviceStatus::Offline(_)
+        ));
+
+        // Once the action finishes the member is polled again.
+        let _ = workspace.update_impl(WorkspaceMessage::ActionDone(
+            b,
+            Action::Reset,
+            Ok("Machine reset".into()),
+        ));
+        assert!(workspace.members[1].busy.is_none());
+        let _ = workspace.update_impl(WorkspaceMessage::Poll);
+        assert!(workspace.members[1].polling);
+    }
+}